@@ -14,6 +14,7 @@ LICENSE END */
 
 #include <vector>
 #include <memory>
+#include <chrono>
 
 namespace sixtyfps::cbindgen_private {
 // Workaround https://github.com/eqrion/cbindgen/issues/43
@@ -35,6 +36,9 @@ extern const cbindgen_private::ItemVTable ImageVTable;
 extern const cbindgen_private::ItemVTable PathVTable;
 extern const cbindgen_private::ItemVTable FlickableVTable;
 extern const cbindgen_private::ItemVTable WindowVTable;
+extern const cbindgen_private::ItemVTable FocusScopeVTable;
+extern const cbindgen_private::ItemVTable TextInputVTable;
+extern const cbindgen_private::ItemVTable TimerItemVTable;
 }
 }
 
@@ -46,10 +50,14 @@ using cbindgen_private::ItemVTable;
 using ComponentRef = VRef<private_api::ComponentVTable>;
 using ItemVisitorRefMut = VRefMut<cbindgen_private::ItemVisitorVTable>;
 using cbindgen_private::EasingCurve;
+using cbindgen_private::MouseCursor;
+using cbindgen_private::PointerButton;
 using cbindgen_private::PropertyAnimation;
 using cbindgen_private::Slice;
 using cbindgen_private::TextHorizontalAlignment;
+using cbindgen_private::TextOverflow;
 using cbindgen_private::TextVerticalAlignment;
+using cbindgen_private::TextWrap;
 using cbindgen_private::TraversalOrder;
 
 namespace private_api {
@@ -83,6 +91,28 @@ struct ComponentWindow
                 &inner, VRef<ComponentVTable> { &Component::component_type, c });
     }
 
+    template<typename Component>
+    cbindgen_private::KeyEventResult dispatch_key_event(Component *c,
+                                                        const cbindgen_private::KeyEvent *event) const
+    {
+        return cbindgen_private::sixtyfps_component_window_dispatch_key_event(
+                &inner, VRef<ComponentVTable> { &Component::component_type, c }, event);
+    }
+
+    template<typename Component>
+    void set_focus_item(Component *c, VRef<ItemVTable> item) const
+    {
+        cbindgen_private::sixtyfps_component_window_set_focus_item(
+                &inner, VRef<ComponentVTable> { &Component::component_type, c }, item);
+    }
+
+    template<typename Component>
+    void set_focus_item_at(Component *c, cbindgen_private::Point pos) const
+    {
+        cbindgen_private::sixtyfps_component_window_set_focus_item_at(
+                &inner, VRef<ComponentVTable> { &Component::component_type, c }, pos);
+    }
+
 private:
     cbindgen_private::ComponentWindowOpaque inner;
 };
@@ -90,10 +120,13 @@ private:
 
 using cbindgen_private::BorderRectangle;
 using cbindgen_private::Flickable;
+using cbindgen_private::FocusScope;
 using cbindgen_private::Image;
 using cbindgen_private::Path;
 using cbindgen_private::Rectangle;
 using cbindgen_private::Text;
+using cbindgen_private::TextInput;
+using cbindgen_private::TimerItem;
 using cbindgen_private::TouchArea;
 using cbindgen_private::Window;
 
@@ -115,16 +148,23 @@ constexpr inline ItemTreeNode make_dyn_node(std::uintptr_t offset)
 
 using cbindgen_private::InputEventResult;
 using cbindgen_private::MouseEvent;
+using cbindgen_private::MouseEventType;
 using cbindgen_private::sixtyfps_visit_item_tree;
 namespace private_api {
 template<typename GetDynamic>
 inline InputEventResult process_input_event(ComponentRef component, int64_t &mouse_grabber,
-                                            MouseEvent mouse_event, Slice<ItemTreeNode> tree,
-                                            GetDynamic get_dynamic)
+                                            int64_t &mouse_hover, MouseEvent mouse_event,
+                                            Slice<ItemTreeNode> tree, GetDynamic get_dynamic)
 {
-    if (mouse_grabber != -1) {
-        auto item_index = mouse_grabber & 0xffffffff;
-        auto rep_index = mouse_grabber >> 32;
+    if (mouse_event.what == MouseEventType::MouseWheel) {
+        // The wheel goes to the item under the cursor, regardless of the mouse grab
+        int64_t wheel_target = -1;
+        return cbindgen_private::sixtyfps_process_ungrabbed_mouse_event(component, mouse_event,
+                                                                        &wheel_target);
+    }
+    auto send_to_item = [&](int64_t target, MouseEvent mouse_event) {
+        auto item_index = target & 0xffffffff;
+        auto rep_index = target >> 32;
         auto offset = cbindgen_private::sixtyfps_item_offset(component, tree, item_index);
         mouse_event.pos = { mouse_event.pos.x - offset.x, mouse_event.pos.y - offset.y };
         const auto &item_node = tree.ptr[item_index];
@@ -141,31 +181,85 @@ inline InputEventResult process_input_event(ComponentRef component, int64_t &mou
             break;
         case ItemTreeNode::Tag::DynamicTree: {
             ComponentRef comp = get_dynamic(item_node.dynamic_tree.index, rep_index);
-            result = comp.vtable->input_event(comp, mouse_event);
+            if (comp.vtable)
+                result = comp.vtable->input_event(comp, mouse_event);
         } break;
         }
+        return result;
+    };
+    if (mouse_grabber != -1) {
+        auto result = send_to_item(mouse_grabber, mouse_event);
+        mouse_hover = mouse_grabber;
         if (result != InputEventResult::GrabMouse) {
             mouse_grabber = -1;
         }
         return result;
-    } else {
-        return cbindgen_private::sixtyfps_process_ungrabbed_mouse_event(component, mouse_event,
-                                                                        &mouse_grabber);
     }
+    auto old_hover = mouse_hover;
+    if (mouse_event.what == MouseEventType::MouseExit) {
+        // Only the hovered item needs to know that the mouse left the component
+        mouse_hover = -1;
+        return old_hover != -1 ? send_to_item(old_hover, mouse_event)
+                               : InputEventResult::EventIgnored;
+    }
+    auto result = cbindgen_private::sixtyfps_process_ungrabbed_mouse_event(component, mouse_event,
+                                                                           &mouse_hover);
+    if (old_hover != -1 && old_hover != mouse_hover) {
+        auto exit_event = mouse_event;
+        exit_event.what = MouseEventType::MouseExit;
+        send_to_item(old_hover, exit_event);
+    }
+    if (result == InputEventResult::GrabMouse) {
+        mouse_grabber = mouse_hover;
+    }
+    return result;
 }
 }
 
+using cbindgen_private::KeyboardModifiers;
+using cbindgen_private::KeyCode;
+using cbindgen_private::KeyEvent;
+using cbindgen_private::KeyEventResult;
+using cbindgen_private::KeyEventType;
+
 // layouts:
+using cbindgen_private::box_layout_height_for_width;
+using cbindgen_private::box_layout_info;
+using cbindgen_private::BoxLayoutCellData;
+using cbindgen_private::BoxLayoutData;
+using cbindgen_private::CellAlignment;
+using cbindgen_private::flow_layout_info;
+using cbindgen_private::grid_layout_height_for_width;
 using cbindgen_private::grid_layout_info;
 using cbindgen_private::GridLayoutCellData;
 using cbindgen_private::GridLayoutData;
+using cbindgen_private::HeightForWidth;
+using cbindgen_private::LayoutAlignment;
 using cbindgen_private::LayoutInfo;
 using cbindgen_private::Padding;
 using cbindgen_private::PathLayoutData;
 using cbindgen_private::PathLayoutItemData;
+using cbindgen_private::solve_box_layout;
+using cbindgen_private::solve_flow_layout;
 using cbindgen_private::solve_grid_layout;
 using cbindgen_private::solve_path_layout;
 
+namespace private_api {
+/// Returns a HeightForWidth that calls the function object `f`, which must outlive it
+template<typename F>
+inline HeightForWidth make_height_for_width(const F &f)
+{
+    return { [](const void *data, float width) { return (*static_cast<const F *>(data))(width); },
+             &f };
+}
+
+/// Returns the height that the text needs when it is `width` wide
+inline float text_height_for_width(const Text &text, float width)
+{
+    return cbindgen_private::sixtyfps_text_height_for_width(&text, width);
+}
+}
+
 // models
 
 struct Model
@@ -208,13 +302,17 @@ struct Repeater
     void update_model(Model *model, const Parent *parent) const
     {
         auto &data = const_cast<Repeater *>(this)->data;
-        data.clear();
-        auto count = model->count();
-        for (auto i = 0; i < count; ++i) {
-            auto x = std::make_unique<C>();
-            x->parent = parent;
-            x->update_data(i, model->get(i));
-            data.push_back(std::move(x));
+        // Keep the existing instances and only create or destroy the difference
+        auto count = std::size_t(model->count());
+        if (data.size() > count)
+            data.resize(count);
+        for (std::size_t i = 0; i < count; ++i) {
+            if (i >= data.size()) {
+                auto x = std::make_unique<C>();
+                x->parent = parent;
+                data.push_back(std::move(x));
+            }
+            data[i]->update_data(i, model->get(i));
         }
     }
 
@@ -235,6 +333,15 @@ struct Repeater
         const auto &x = data.at(i);
         return { &C::component_type, x.get() };
     }
+
+    /// Same as item_at, but returns a null reference if there is no component at that index.
+    /// (The model may have changed since the component grabbed the mouse)
+    VRef<private_api::ComponentVTable> try_item_at(int i) const
+    {
+        if (i < 0 || std::size_t(i) >= data.size())
+            return { nullptr, nullptr };
+        return item_at(i);
+    }
 };
 
 Flickable::Flickable()
@@ -246,6 +353,64 @@ Flickable::~Flickable()
     sixtyfps_flickable_data_free(&data);
 }
 
+TimerItem::TimerItem()
+{
+    sixtyfps_timer_item_data_init(&data);
+}
+TimerItem::~TimerItem()
+{
+    sixtyfps_timer_item_data_free(&data);
+}
+void TimerItem::init()
+{
+    sixtyfps_timer_item_init(this);
+}
+
+TextInput::~TextInput()
+{
+    sixtyfps_text_input_drop(this);
+}
+
+using cbindgen_private::TimerMode;
+
+/// A Timer that can call a callback at repeated interval, or once after a given duration
+///
+/// Use the static single_shot function to make a single shot timer
+struct Timer
+{
+    Timer() = default;
+    Timer(const Timer &) = delete;
+    Timer &operator=(const Timer &) = delete;
+    ~Timer() { cbindgen_private::sixtyfps_timer_destroy(id); }
+
+    /// Starts the timer with the given \a mode and \a interval, in order for the \a callback
+    /// to be called when the timer fires. If the timer was already started, it is restarted
+    /// with the new parameters.
+    template<typename F>
+    void start(TimerMode mode, std::chrono::milliseconds interval, F callback)
+    {
+        id = cbindgen_private::sixtyfps_timer_start(
+                id, mode, interval.count(), [](void *data) { (*reinterpret_cast<F *>(data))(); },
+                new F(std::move(callback)), [](void *data) { delete reinterpret_cast<F *>(data); });
+    }
+    /// Stops the previously started timer. Does nothing if the timer has never been started.
+    void stop() { cbindgen_private::sixtyfps_timer_stop(id); }
+    /// Returns true if the timer is running; false otherwise.
+    bool running() const { return cbindgen_private::sixtyfps_timer_running(id); }
+
+    /// Call the callback after the given duration.
+    template<typename F>
+    static void single_shot(std::chrono::milliseconds duration, F callback)
+    {
+        cbindgen_private::sixtyfps_timer_singleshot(
+                duration.count(), [](void *data) { (*reinterpret_cast<F *>(data))(); },
+                new F(std::move(callback)), [](void *data) { delete reinterpret_cast<F *>(data); });
+    }
+
+private:
+    uintptr_t id = uintptr_t(-1);
+};
+
 namespace private_api {
 template<int Major, int Minor, int Patch>
 struct VersionCheckHelper
@@ -61,4 +61,25 @@ void Property<Color>::set_animated_binding(F binding,
             &animation_data);
 }
 
+template<>
+template<typename F, typename Trans>
+void Property<Color>::set_dynamic_animated_binding(F binding, Trans compute_animation_details)
+{
+    struct UserData
+    {
+        F binding;
+        Trans compute_animation_details;
+    };
+    cbindgen_private::sixtyfps_property_set_dynamic_animated_binding_color(
+            &inner,
+            [](void *user_data, Color *value) {
+                *value = reinterpret_cast<UserData *>(user_data)->binding();
+            },
+            new UserData { binding, compute_animation_details },
+            [](void *user_data) { delete reinterpret_cast<UserData *>(user_data); },
+            [](void *user_data, cbindgen_private::PropertyAnimation *animation_data) {
+                *animation_data = reinterpret_cast<UserData *>(user_data)->compute_animation_details();
+            });
+}
+
 }
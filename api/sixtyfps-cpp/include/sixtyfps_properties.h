@@ -9,6 +9,9 @@
 LICENSE END */
 #pragma once
 #include <string_view>
+#include <memory>
+#include <optional>
+#include <type_traits>
 
 namespace sixtyfps {
 namespace cbindgen_private {
@@ -40,7 +43,7 @@ struct Property
     void set(const T &value) const
     {
         this->value = value;
-        cbindgen_private::sixtyfps_property_set_changed(&inner);
+        cbindgen_private::sixtyfps_property_set_changed(&inner, &this->value);
     }
 
     const T &get() const
@@ -57,13 +60,49 @@ struct Property
                 [](void *user_data, void *value) {
                     *reinterpret_cast<T *>(value) = (*reinterpret_cast<F *>(user_data))();
                 },
-                new F(binding), [](void *user_data) { delete reinterpret_cast<F *>(user_data); });
+                new F(binding), [](void *user_data) { delete reinterpret_cast<F *>(user_data); },
+                nullptr, nullptr);
     }
 
     inline void set_animated_value(const T &value,
                                    const cbindgen_private::PropertyAnimation &animation_data);
     template<typename F>
     inline void set_animated_binding(F binding, const cbindgen_private::PropertyAnimation &animation_data);
+    template<typename F, typename Trans>
+    inline void set_dynamic_animated_binding(F binding, Trans compute_animation_details);
+
+    /// Link two properties such that any change to one property is affecting the other
+    /// property as if they were, in fact, a single property.
+    /// The current value of p2 is kept.
+    static void link_two_way(const Property<T> *p1, const Property<T> *p2)
+    {
+        auto common_property = std::make_shared<Property<T>>(p2->get());
+        struct TwoWayBinding
+        {
+            std::shared_ptr<Property<T>> common_property;
+        };
+        auto del_fn = [](void *user_data) { delete reinterpret_cast<TwoWayBinding *>(user_data); };
+        auto call_fn = [](void *user_data, void *value) {
+            *reinterpret_cast<T *>(value) =
+                    reinterpret_cast<TwoWayBinding *>(user_data)->common_property->get();
+        };
+        auto intercept_fn = [](void *user_data, const void *value) {
+            reinterpret_cast<TwoWayBinding *>(user_data)->common_property->set(
+                    *reinterpret_cast<const T *>(value));
+            return true;
+        };
+        auto intercept_binding_fn = [](void *user_data, void *value) {
+            cbindgen_private::sixtyfps_property_set_binding_internal(
+                    &reinterpret_cast<TwoWayBinding *>(user_data)->common_property->inner, value);
+            return true;
+        };
+        cbindgen_private::sixtyfps_property_set_binding(&p1->inner, call_fn,
+                                                       new TwoWayBinding { common_property },
+                                                       del_fn, intercept_fn, intercept_binding_fn);
+        cbindgen_private::sixtyfps_property_set_binding(&p2->inner, call_fn,
+                                                       new TwoWayBinding { common_property },
+                                                       del_fn, intercept_fn, intercept_binding_fn);
+    }
 
 private:
     cbindgen_private::PropertyHandleOpaque inner;
@@ -112,6 +151,67 @@ void Property<float>::set_animated_binding(F binding,
             &animation_data);
 }
 
+template<>
+template<typename F, typename Trans>
+void Property<int32_t>::set_dynamic_animated_binding(F binding, Trans compute_animation_details)
+{
+    struct UserData
+    {
+        F binding;
+        Trans compute_animation_details;
+    };
+    cbindgen_private::sixtyfps_property_set_dynamic_animated_binding_int(
+            &inner,
+            [](void *user_data, int32_t *value) {
+                *value = reinterpret_cast<UserData *>(user_data)->binding();
+            },
+            new UserData { binding, compute_animation_details },
+            [](void *user_data) { delete reinterpret_cast<UserData *>(user_data); },
+            [](void *user_data, cbindgen_private::PropertyAnimation *animation_data) {
+                *animation_data = reinterpret_cast<UserData *>(user_data)->compute_animation_details();
+            });
+}
+
+template<>
+template<typename F, typename Trans>
+void Property<float>::set_dynamic_animated_binding(F binding, Trans compute_animation_details)
+{
+    struct UserData
+    {
+        F binding;
+        Trans compute_animation_details;
+    };
+    cbindgen_private::sixtyfps_property_set_dynamic_animated_binding_float(
+            &inner,
+            [](void *user_data, float *value) {
+                *value = reinterpret_cast<UserData *>(user_data)->binding();
+            },
+            new UserData { binding, compute_animation_details },
+            [](void *user_data) { delete reinterpret_cast<UserData *>(user_data); },
+            [](void *user_data, cbindgen_private::PropertyAnimation *animation_data) {
+                *animation_data = reinterpret_cast<UserData *>(user_data)->compute_animation_details();
+            });
+}
+
+namespace private_api {
+using cbindgen_private::StateInfo;
+
+/// Set on the property a binding that computes the current state with `binding`, and that
+/// remembers the previous state when it changes.
+template<typename F>
+void set_state_binding(const Property<StateInfo> &property, F binding)
+{
+    property.set_binding([binding, info = std::make_shared<StateInfo>()]() {
+        int32_t new_state = binding();
+        if (new_state != info->current_state) {
+            info->previous_state = info->current_state;
+            info->current_state = new_state;
+        }
+        return *info;
+    });
+}
+}
+
 struct PropertyTracker
 {
     PropertyTracker() { cbindgen_private::sixtyfps_property_tracker_init(&inner); }
@@ -136,4 +236,64 @@ private:
     cbindgen_private::PropertyTrackerOpaque inner;
 };
 
+namespace private_api {
+template<typename T, typename = void>
+struct is_equality_comparable : std::false_type
+{
+};
+template<typename T>
+struct is_equality_comparable<
+        T, std::void_t<decltype(std::declval<const T &>() == std::declval<const T &>())>>
+    : std::true_type
+{
+};
+}
+
+/// Calls a handler when the value returned by a function changes.
+/// The handler is called when the changes are processed by the event loop, and not for the
+/// first value.
+struct ChangeTracker
+{
+    ChangeTracker() { cbindgen_private::sixtyfps_change_tracker_init(&inner); }
+    ~ChangeTracker() { cbindgen_private::sixtyfps_change_tracker_drop(&inner); }
+    ChangeTracker(const ChangeTracker &) = delete;
+    ChangeTracker &operator=(const ChangeTracker &) = delete;
+
+    template<typename F, typename H>
+    void init(F eval, H handler) const
+    {
+        using T = std::decay_t<decltype(eval())>;
+        struct Data
+        {
+            F eval;
+            H handler;
+            std::optional<T> value;
+        };
+        cbindgen_private::sixtyfps_change_tracker_start(
+                &inner, new Data { std::move(eval), std::move(handler), {} },
+                [](void *data) { delete reinterpret_cast<Data *>(data); },
+                [](void *data) {
+                    auto d = reinterpret_cast<Data *>(data);
+                    T new_value = d->eval();
+                    bool changed = false;
+                    if (d->value) {
+                        // Values that cannot be compared are always considered as changed
+                        if constexpr (private_api::is_equality_comparable<T>::value)
+                            changed = !(*d->value == new_value);
+                        else
+                            changed = true;
+                    }
+                    d->value = std::move(new_value);
+                    return changed;
+                },
+                [](void *data) {
+                    auto d = reinterpret_cast<Data *>(data);
+                    d->handler(*d->value);
+                });
+    }
+
+private:
+    cbindgen_private::ChangeTrackerOpaque inner;
+};
+
 } // namespace sixtyfps
@@ -9,12 +9,19 @@
 LICENSE END */
 #pragma once
 #include <string_view>
+#include <tuple>
+#include <type_traits>
 #include "sixtyfps_properties_internal.h"
 
 namespace sixtyfps {
 
-// template<typename... Args>
-struct Signal
+/// A Signal that can be connected to a handler. The template argument is the signature of the
+/// handler, for example `Signal<int(int, SharedString)>`
+template<typename = void()>
+struct Signal;
+
+template<typename Ret, typename... Arg>
+struct Signal<Ret(Arg...)>
 {
     Signal() { cbindgen_private::sixtyfps_signal_init(&inner); }
     ~Signal() { cbindgen_private::sixtyfps_signal_drop(&inner); }
@@ -23,22 +30,37 @@ struct Signal
     Signal &operator=(const Signal &) = delete;
 
     template<typename F>
-    void set_handler(F binding)
+    void set_handler(F binding) const
     {
         cbindgen_private::sixtyfps_signal_set_handler(
                 &inner,
-                [](void *user_data) {
-                    (*reinterpret_cast<F *>(user_data))();
+                [](void *user_data, const void *arg, void *ret) {
+                    auto &f = *reinterpret_cast<F *>(user_data);
+                    auto &args = *reinterpret_cast<const Tuple *>(arg);
+                    if constexpr (std::is_void_v<Ret>) {
+                        std::apply(f, args);
+                    } else {
+                        *reinterpret_cast<Ret *>(ret) = std::apply(f, args);
+                    }
                 },
-                new F(std::move(binding)), [](void *user_data) { delete reinterpret_cast<F *>(user_data); });
+                new F(std::move(binding)),
+                [](void *user_data) { delete reinterpret_cast<F *>(user_data); });
     }
 
-    void emit() const
+    Ret emit(const Arg &...arg) const
     {
-        cbindgen_private::sixtyfps_signal_emit(&inner);
+        Tuple tuple { arg... };
+        if constexpr (std::is_void_v<Ret>) {
+            cbindgen_private::sixtyfps_signal_emit(&inner, &tuple, nullptr);
+        } else {
+            Ret r {};
+            cbindgen_private::sixtyfps_signal_emit(&inner, &tuple, &r);
+            return r;
+        }
     }
 
 private:
+    using Tuple = std::tuple<Arg...>;
     cbindgen_private::SignalOpaque inner;
 };
 }
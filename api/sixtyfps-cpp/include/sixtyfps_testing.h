@@ -20,4 +20,30 @@ template<typename Component>
 inline void send_mouse_click(Component &component, float x, float y) {
     cbindgen_private::sixtyfps_send_mouse_click({&Component::component_type, &component}, x, y);
 }
+template<typename Component>
+inline void send_mouse_press(Component &component, float x, float y)
+{
+    cbindgen_private::sixtyfps_send_mouse_press({ &Component::component_type, &component }, x, y);
+}
+template<typename Component>
+inline void send_mouse_release(Component &component, float x, float y)
+{
+    cbindgen_private::sixtyfps_send_mouse_release({ &Component::component_type, &component }, x,
+                                                  y);
+}
+template<typename Component>
+inline void send_mouse_wheel(Component &component, float x, float y, float pixel_delta_x,
+                             float pixel_delta_y, float line_delta_x, float line_delta_y)
+{
+    cbindgen_private::sixtyfps_send_mouse_wheel({ &Component::component_type, &component }, x, y,
+                                                pixel_delta_x, pixel_delta_y, line_delta_x,
+                                                line_delta_y);
+}
+template<typename Component>
+inline void send_keyboard_string_sequence(Component &component,
+                                          const sixtyfps::SharedString &str)
+{
+    cbindgen_private::sixtyfps_send_keyboard_string_sequence(
+            { &Component::component_type, &component }, &str);
+}
 } // namespace sixtyfps
@@ -27,7 +27,13 @@ require.extensions['.60'] =
             let comp = c.create(init_properties);
             let ret = {
                 show() { comp.show() },
-                send_mouse_click(x, y) { comp.send_mouse_click(x, y) }
+                send_mouse_click(x, y) { comp.send_mouse_click(x, y) },
+                send_mouse_press(x, y) { comp.send_mouse_press(x, y) },
+                send_mouse_release(x, y) { comp.send_mouse_release(x, y) },
+                send_mouse_wheel(x, y, pixel_delta_x, pixel_delta_y, line_delta_x, line_delta_y) {
+                    comp.send_mouse_wheel(x, y, pixel_delta_x, pixel_delta_y, line_delta_x, line_delta_y)
+                },
+                send_keyboard_string_sequence(s) { comp.send_keyboard_string_sequence(s) }
             };
             c.properties().forEach(x => {
                 Object.defineProperty(ret, x, {
@@ -38,7 +44,7 @@ require.extensions['.60'] =
             });
             c.signals().forEach(x => {
                 Object.defineProperty(ret, x, {
-                    get() { return function () { comp.emit_signal(x); } },
+                    get() { return function () { return comp.emit_signal(x, [...arguments]); } },
                     enumerable: true,
                 })
             });
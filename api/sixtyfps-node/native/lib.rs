@@ -91,29 +91,40 @@ fn create<'cx>(
                     cx.throw_error(format!("Property {} not found in the component", prop_name))
                 })?
                 .clone();
-            if ty == Type::Signal {
+            if let Type::Signal { return_type, .. } = ty {
                 let _fun = value.downcast_or_throw::<JsFunction, _>(cx)?;
                 let fun_idx = persistent_context.allocate(cx, value);
                 component_type
                     .set_signal_handler(
                         component.borrow(),
                         prop_name.as_str(),
-                        Box::new(move |()| {
+                        Box::new(move |args| {
+                            let ret = core::cell::Cell::new(sixtyfps_interpreter::Value::Void);
+                            let borrow_ret = &ret;
+                            let args = args.to_vec();
+                            let return_type = &return_type;
                             GLOBAL_CONTEXT.with(|cx_fn| {
                                 cx_fn(&move |cx, presistent_context| {
-                                    presistent_context
+                                    let args = args
+                                        .iter()
+                                        .map(|a| to_js_value(a.clone(), cx).unwrap())
+                                        .collect::<Vec<_>>();
+                                    let result = presistent_context
                                         .get(cx, fun_idx)
                                         .unwrap()
                                         .downcast::<JsFunction>()
                                         .unwrap()
-                                        .call::<_, _, JsValue, _>(
-                                            cx,
-                                            JsUndefined::new(),
-                                            std::iter::empty(),
-                                        )
+                                        .call::<_, _, JsValue, _>(cx, JsUndefined::new(), args)
                                         .unwrap();
+                                    if let Some(return_type) = return_type {
+                                        borrow_ret.set(
+                                            to_eval_value(result, (**return_type).clone(), cx)
+                                                .unwrap(),
+                                        );
+                                    }
                                 })
-                            })
+                            });
+                            ret.into_inner()
                         }),
                     )
                     .or_else(|_| cx.throw_error(format!("Cannot set signal")))?;
@@ -146,14 +157,33 @@ fn to_eval_value<'cx>(
         | Type::Native(_)
         | Type::Function { .. }
         | Type::Model
-        | Type::Signal
+        | Type::Signal { .. }
         | Type::Easing
-        | Type::PathElements => cx.throw_error("Cannot convert to a Sixtyfps property value"),
+        | Type::PathElements
+        | Type::ElementReference => cx.throw_error("Cannot convert to a Sixtyfps property value"),
         Type::Float32 | Type::Int32 | Type::Duration | Type::Length | Type::LogicalLength => {
             Ok(Value::Number(val.downcast_or_throw::<JsNumber, _>(cx)?.value()))
         }
         Type::String => Ok(Value::String(val.to_string(cx)?.value().as_str().into())),
-        Type::Color | Type::Array(_) | Type::Object(_) => todo!(),
+        Type::Object { fields, .. } => {
+            let obj = val.downcast_or_throw::<JsObject, _>(cx)?;
+            Ok(Value::Object(
+                fields
+                    .iter()
+                    .map(|(field_name, field_type)| {
+                        Ok((
+                            field_name.clone(),
+                            to_eval_value(
+                                obj.get(cx, field_name.as_str())?,
+                                field_type.clone(),
+                                cx,
+                            )?,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        Type::Color | Type::Array(_) => todo!(),
         Type::Resource => Ok(Value::String(val.to_string(cx)?.value().as_str().into())),
         Type::Bool => Ok(Value::Bool(val.downcast_or_throw::<JsBoolean, _>(cx)?.value())),
         Type::Enumeration(_) => todo!(),
@@ -239,7 +269,7 @@ declare_types! {
             let properties = ct.properties();
             let array = JsArray::new(&mut cx, properties.len() as u32);
             let mut len: u32 = 0;
-            for (p, _) in properties.iter().filter(|(_, prop_type)| **prop_type == Type::Signal) {
+            for (p, _) in properties.iter().filter(|(_, prop_type)| matches!(**prop_type, Type::Signal { .. })) {
                 let prop_name = JsString::new(&mut cx, p);
                 array.set(&mut cx, len, prop_name)?;
                 len = len + 1;
@@ -300,12 +330,37 @@ declare_types! {
             let lock = cx.lock();
             let x = this.borrow(&lock).0.clone();
             let component = x.ok_or(()).or_else(|()| cx.throw_error("Invalid type"))?;
-            run_scoped(&mut cx,this.downcast().unwrap(), || {
+            let ty = component.description().properties()
+                .get(&signal_name)
+                .ok_or(())
+                .or_else(|()| {
+                    cx.throw_error(format!("Signal {} not found in the component", signal_name))
+                })?
+                .clone();
+            let args = if let Type::Signal { args, .. } = ty {
+                let arguments = cx.argument::<JsArray>(1)?.to_vec(&mut cx)?;
+                if arguments.len() != args.len() {
+                    return cx.throw_error(format!(
+                        "{} expect {} arguments, but {} were provided",
+                        signal_name,
+                        args.len(),
+                        arguments.len()
+                    ));
+                }
+                args.into_iter()
+                    .zip(arguments.into_iter())
+                    .map(|(ty, value)| to_eval_value(value, ty, &mut cx))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                return cx.throw_error(format!("{} is not a signal", signal_name));
+            };
+
+            let res = run_scoped(&mut cx,this.downcast().unwrap(), || {
                 component.description()
-                    .emit_signal(component.borrow(), signal_name.as_str())
+                    .emit_signal(component.borrow(), signal_name.as_str(), args.as_slice())
                     .map_err(|()| "Cannot emit signal".to_string())
             })?;
-            Ok(JsUndefined::new().as_value(&mut cx))
+            to_js_value(res, &mut cx)
         }
 
         method send_mouse_click(mut cx) {
@@ -321,6 +376,76 @@ declare_types! {
             })?;
             Ok(JsUndefined::new().as_value(&mut cx))
         }
+
+        method send_mouse_press(mut cx) {
+            let x = cx.argument::<JsNumber>(0)?.value() as f32;
+            let y = cx.argument::<JsNumber>(1)?.value() as f32;
+            let this = cx.this();
+            let lock = cx.lock();
+            let comp = this.borrow(&lock).0.clone();
+            let component = comp.ok_or(()).or_else(|()| cx.throw_error("Invalid type"))?;
+            run_scoped(&mut cx,this.downcast().unwrap(), || {
+                sixtyfps_corelib::tests::sixtyfps_send_mouse_press(component.borrow(), x, y);
+                Ok(())
+            })?;
+            Ok(JsUndefined::new().as_value(&mut cx))
+        }
+
+        method send_mouse_release(mut cx) {
+            let x = cx.argument::<JsNumber>(0)?.value() as f32;
+            let y = cx.argument::<JsNumber>(1)?.value() as f32;
+            let this = cx.this();
+            let lock = cx.lock();
+            let comp = this.borrow(&lock).0.clone();
+            let component = comp.ok_or(()).or_else(|()| cx.throw_error("Invalid type"))?;
+            run_scoped(&mut cx,this.downcast().unwrap(), || {
+                sixtyfps_corelib::tests::sixtyfps_send_mouse_release(component.borrow(), x, y);
+                Ok(())
+            })?;
+            Ok(JsUndefined::new().as_value(&mut cx))
+        }
+
+        method send_mouse_wheel(mut cx) {
+            let x = cx.argument::<JsNumber>(0)?.value() as f32;
+            let y = cx.argument::<JsNumber>(1)?.value() as f32;
+            let pixel_delta_x = cx.argument::<JsNumber>(2)?.value() as f32;
+            let pixel_delta_y = cx.argument::<JsNumber>(3)?.value() as f32;
+            let line_delta_x = cx.argument::<JsNumber>(4)?.value() as f32;
+            let line_delta_y = cx.argument::<JsNumber>(5)?.value() as f32;
+            let this = cx.this();
+            let lock = cx.lock();
+            let comp = this.borrow(&lock).0.clone();
+            let component = comp.ok_or(()).or_else(|()| cx.throw_error("Invalid type"))?;
+            run_scoped(&mut cx,this.downcast().unwrap(), || {
+                sixtyfps_corelib::tests::sixtyfps_send_mouse_wheel(
+                    component.borrow(),
+                    x,
+                    y,
+                    pixel_delta_x,
+                    pixel_delta_y,
+                    line_delta_x,
+                    line_delta_y,
+                );
+                Ok(())
+            })?;
+            Ok(JsUndefined::new().as_value(&mut cx))
+        }
+
+        method send_keyboard_string_sequence(mut cx) {
+            let sequence = cx.argument::<JsString>(0)?.value();
+            let this = cx.this();
+            let lock = cx.lock();
+            let comp = this.borrow(&lock).0.clone();
+            let component = comp.ok_or(()).or_else(|()| cx.throw_error("Invalid type"))?;
+            run_scoped(&mut cx,this.downcast().unwrap(), || {
+                sixtyfps_corelib::tests::sixtyfps_send_keyboard_string_sequence(
+                    component.borrow(),
+                    &sequence.as_str().into(),
+                );
+                Ok(())
+            })?;
+            Ok(JsUndefined::new().as_value(&mut cx))
+        }
     }
 }
 
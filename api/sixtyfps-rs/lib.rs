@@ -101,6 +101,8 @@ The follow table summarizes the entire mapping:
 | `logical_length` | `f32` | At run-time, logical lengths are automatically translated to physical pixels using the device pixel ratio. |
 | `duration` | `i64` | At run-time, durations are always represented as signed 64-bit integers with milisecond precision. |
 | `easing` | [`sixtyfps::EasingCurve`](enum.EasingCurve.html) | |
+| `struct` | A generated struct | The struct has the same name and fields as the one declared in the `.60` file, and implements `Clone`, `PartialEq`, `Debug` and `Default`. |
+| array | [`sixtyfps::ModelHandle`](struct.ModelHandle.html) | For example `[int]` is a `ModelHandle<i32>`. Use a [`sixtyfps::VecModel`](struct.VecModel.html) to get the changes of the data reflected in the repeaters. |
 
 */
 
@@ -113,6 +115,10 @@ pub use sixtyfps_rs_macro::sixtyfps;
 pub(crate) mod repeater;
 
 pub use sixtyfps_corelib::animations::EasingCurve;
+pub use sixtyfps_corelib::eventloop::{Timer, TimerMode};
+pub use sixtyfps_corelib::model::{
+    Model, ModelHandle, ModelNotify, ModelPeer, VecModel, ViewAbstraction,
+};
 pub use sixtyfps_corelib::string::SharedString;
 pub use sixtyfps_corelib::Color;
 
@@ -131,7 +137,8 @@ pub mod re_exports {
         PathArcTo, PathData, PathElement, PathEvent, PathLineTo, Point, Rect, Size,
     };
     pub use sixtyfps_corelib::input::{
-        process_ungrabbed_mouse_event, InputEventResult, MouseEvent,
+        process_ungrabbed_mouse_event, InputEventResult, KeyCode, KeyEvent, KeyEventResult,
+        KeyEventType, KeyboardModifiers, MouseEvent, MouseEventType,
     };
     pub use sixtyfps_corelib::item_tree::{
         item_offset, visit_item_tree, ItemTreeNode, ItemVisitorRefMut, ItemVisitorVTable,
@@ -140,10 +147,16 @@ pub mod re_exports {
     pub use sixtyfps_corelib::items::*;
     pub use sixtyfps_corelib::layout::LayoutInfo;
     pub use sixtyfps_corelib::layout::{
-        grid_layout_info, solve_grid_layout, solve_path_layout, GridLayoutCellData, GridLayoutData,
-        Padding, PathLayoutData, PathLayoutItemData,
+        box_layout_height_for_width, box_layout_info, flow_layout_info,
+        grid_layout_height_for_width, grid_layout_info, solve_box_layout, solve_flow_layout,
+        solve_grid_layout, solve_path_layout, BoxLayoutCellData, BoxLayoutData, CellAlignment,
+        GridLayoutCellData, GridLayoutData, HeightForWidth, LayoutAlignment, Padding,
+        PathLayoutData, PathLayoutItemData,
+    };
+    pub use sixtyfps_corelib::model::{ModelHandle, VecModel};
+    pub use sixtyfps_corelib::properties::{
+        set_state_binding, ChangeTracker, Property, PropertyTracker, StateInfo,
     };
-    pub use sixtyfps_corelib::properties::{Property, PropertyTracker};
     pub use sixtyfps_corelib::signals::Signal;
     pub use sixtyfps_corelib::slice::Slice;
     pub use sixtyfps_corelib::Color;
@@ -173,6 +186,67 @@ pub mod testing {
     ) {
         sixtyfps_corelib::tests::sixtyfps_send_mouse_click(vtable::VRef::new_pin(component), x, y);
     }
+
+    /// Simulate pressing the left mouse button, without releasing it
+    pub fn send_mouse_press<
+        X: vtable::HasStaticVTable<sixtyfps_corelib::component::ComponentVTable>,
+    >(
+        component: core::pin::Pin<&X>,
+        x: f32,
+        y: f32,
+    ) {
+        sixtyfps_corelib::tests::sixtyfps_send_mouse_press(vtable::VRef::new_pin(component), x, y);
+    }
+
+    /// Simulate releasing the left mouse button
+    pub fn send_mouse_release<
+        X: vtable::HasStaticVTable<sixtyfps_corelib::component::ComponentVTable>,
+    >(
+        component: core::pin::Pin<&X>,
+        x: f32,
+        y: f32,
+    ) {
+        sixtyfps_corelib::tests::sixtyfps_send_mouse_release(
+            vtable::VRef::new_pin(component),
+            x,
+            y,
+        );
+    }
+
+    /// Simulate turning the mouse wheel by a number of lines (`line_delta`), or scrolling on a
+    /// trackpad by a number of pixels (`pixel_delta`)
+    pub fn send_mouse_wheel<
+        X: vtable::HasStaticVTable<sixtyfps_corelib::component::ComponentVTable>,
+    >(
+        component: core::pin::Pin<&X>,
+        x: f32,
+        y: f32,
+        pixel_delta: (f32, f32),
+        line_delta: (f32, f32),
+    ) {
+        sixtyfps_corelib::tests::sixtyfps_send_mouse_wheel(
+            vtable::VRef::new_pin(component),
+            x,
+            y,
+            pixel_delta.0,
+            pixel_delta.1,
+            line_delta.0,
+            line_delta.1,
+        );
+    }
+
+    /// Simulate entering a sequence of characters on the keyboard
+    pub fn send_keyboard_string_sequence<
+        X: vtable::HasStaticVTable<sixtyfps_corelib::component::ComponentVTable>,
+    >(
+        component: core::pin::Pin<&X>,
+        sequence: &str,
+    ) {
+        sixtyfps_corelib::tests::sixtyfps_send_keyboard_string_sequence(
+            vtable::VRef::new_pin(component),
+            &sequence.into(),
+        );
+    }
 }
 
 /// Include the code generated with the sixtyfps-build crate from the build script. After calling `sixtyfps_build::compile`
@@ -9,6 +9,8 @@
 LICENSE END */
 use core::cell::RefCell;
 use core::pin::Pin;
+use sixtyfps_corelib::model::{Model, ModelHandle, ViewAbstraction};
+use sixtyfps_corelib::Property;
 use std::rc::Rc;
 
 /// Component that can be instantiated by a repeater.
@@ -18,34 +20,198 @@ pub trait RepeatedComponent: sixtyfps_corelib::component::Component {
 
     /// Update this component at the given index and the given data
     fn update(&self, index: usize, data: Self::Data);
+
+    /// Update the index of this component, when rows were added or removed before it
+    fn update_index(&self, index: usize);
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RepeatedComponentState {
+    /// The item is in a clean state
+    Clean,
+    /// Rows were added or removed before this one, so only its index needs to be refreshed
+    IndexChanged,
+    /// The model data is stale and needs to be refreshed
+    Dirty,
+}
+
+impl RepeatedComponentState {
+    /// Called for the rows that follow the rows that were added or removed
+    fn shift(&mut self) {
+        if *self == RepeatedComponentState::Clean {
+            *self = RepeatedComponentState::IndexChanged;
+        }
+    }
+}
+
+/// The instances of a repeater, kept up to date by the notifications of the model.
+///
+/// An instance is `None` when the row was added to the model, but was not instantiated yet.
+struct RepeaterTracker<C> {
+    components: RefCell<Vec<(RepeatedComponentState, Option<Pin<Rc<C>>>)>>,
+    /// Set by every notification of the model. It is read in [`Repeater::ensure_updated`], so
+    /// that the layouts (and anything else that updates the repeater) are evaluated again.
+    model_changed: Pin<Box<Property<()>>>,
+}
+
+impl<C> RepeaterTracker<C> {
+    fn new(components: Vec<(RepeatedComponentState, Option<Pin<Rc<C>>>)>) -> Rc<Self> {
+        Rc::new(Self {
+            components: RefCell::new(components),
+            model_changed: Box::pin(Property::new(())),
+        })
+    }
+}
+
+impl<C> ViewAbstraction for RepeaterTracker<C> {
+    fn row_changed(&self, row: usize) {
+        if let Some(c) = self.components.borrow_mut().get_mut(row) {
+            c.0 = RepeatedComponentState::Dirty;
+        }
+        self.model_changed.set(());
+    }
+
+    fn row_added(&self, index: usize, count: usize) {
+        let mut components = self.components.borrow_mut();
+        let index = index.min(components.len());
+        components.splice(
+            index..index,
+            core::iter::repeat((RepeatedComponentState::Dirty, None)).take(count),
+        );
+        // The rows that follow were shifted, so their index changed
+        for c in components[index + count..].iter_mut() {
+            c.0.shift();
+        }
+        self.model_changed.set(());
+    }
+
+    fn row_removed(&self, index: usize, count: usize) {
+        let mut components = self.components.borrow_mut();
+        let index = index.min(components.len());
+        let end = (index + count).min(components.len());
+        components.drain(index..end);
+        for c in components[index..].iter_mut() {
+            c.0.shift();
+        }
+        self.model_changed.set(());
+    }
 }
 
 /// This field is put in a component when using the `for` syntax
 /// It helps instantiating the components `C`
-pub struct Repeater<C> {
-    components: RefCell<Vec<Pin<Rc<C>>>>,
+pub struct Repeater<C: RepeatedComponent> {
+    /// A new tracker is created when the model changes, so that the notifications
+    /// of the previous model no longer reach this repeater
+    inner: RefCell<Rc<RepeaterTracker<C>>>,
+    model: RefCell<ModelHandle<C::Data>>,
 }
 
-impl<C> Default for Repeater<C> {
+impl<C: RepeatedComponent> Default for Repeater<C> {
     fn default() -> Self {
-        Repeater { components: Default::default() }
+        Repeater { inner: RefCell::new(RepeaterTracker::new(vec![])), model: Default::default() }
     }
 }
 
 impl<Data, C> Repeater<C>
 where
-    C: RepeatedComponent<Data = Data>,
+    C: RepeatedComponent<Data = Data> + 'static,
 {
+    /// Move the existing instances to a new tracker, that is not attached to any model.
+    fn detach(&self) -> Rc<RepeaterTracker<C>> {
+        let components = core::mem::take(&mut *self.inner.borrow().components.borrow_mut());
+        let tracker = RepeaterTracker::new(components);
+        *self.inner.borrow_mut() = tracker.clone();
+        tracker
+    }
+
     /// Called when the model is changed
+    ///
+    /// The existing instances are kept and updated with the new data, only the
+    /// instances for the rows that were added or removed are created or destroyed.
     pub fn update_model<'a>(&self, data: impl Iterator<Item = Data>, init: impl Fn() -> Pin<Rc<C>>)
     where
         Data: 'a,
     {
-        self.components.borrow_mut().clear();
+        *self.model.borrow_mut() = ModelHandle::default();
+        let tracker = self.detach();
+        let mut components = tracker.components.borrow_mut();
+        let mut count = 0;
         for (i, d) in data.enumerate() {
-            let c = init();
-            c.update(i, d);
-            self.components.borrow_mut().push(c);
+            if i < components.len() {
+                let c = components[i].1.get_or_insert_with(|| init());
+                c.update(i, d);
+                components[i].0 = RepeatedComponentState::Clean;
+            } else {
+                let c = init();
+                c.update(i, d);
+                components.push((RepeatedComponentState::Clean, Some(c)));
+            }
+            count = i + 1;
+        }
+        components.truncate(count);
+    }
+
+    /// Set a model that notifies the repeater about its changes.
+    ///
+    /// The instances are only created or updated in [`Self::ensure_updated`].
+    pub fn set_model(&self, model: ModelHandle<Data>) {
+        let tracker = self.detach();
+        {
+            let mut components = tracker.components.borrow_mut();
+            components.resize_with(model.row_count(), || (RepeatedComponentState::Dirty, None));
+            for c in components.iter_mut() {
+                c.0 = RepeatedComponentState::Dirty;
+            }
+        }
+        let peer: Rc<dyn ViewAbstraction> = tracker;
+        model.attach_peer(Rc::downgrade(&peer));
+        *self.model.borrow_mut() = model;
+    }
+
+    /// Create the instances for the rows that were added to the model, and update the
+    /// instances for the rows that were changed, since the last call.
+    ///
+    /// When called while evaluating a binding or a layout, it will be evaluated again after
+    /// the next change of the model.
+    pub fn ensure_updated(&self, init: impl Fn() -> Pin<Rc<C>>) {
+        let model = self.model.borrow().clone();
+        let tracker = self.inner.borrow().clone();
+        tracker.model_changed.as_ref().get();
+        // The components are not borrowed while the instances are created and updated, nor while
+        // the model is read, because they may call back into this repeater.
+        let rows: Vec<_> = tracker
+            .components
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.0 != RepeatedComponentState::Clean)
+            .map(|(i, c)| (i, c.0, c.1.clone()))
+            .collect();
+        for (i, state, old_instance) in rows {
+            let instance = match (state, &old_instance) {
+                (RepeatedComponentState::IndexChanged, Some(instance)) => {
+                    instance.update_index(i);
+                    instance.clone()
+                }
+                _ => {
+                    let instance = old_instance.clone().unwrap_or_else(|| init());
+                    instance.update(i, model.row_data(i));
+                    instance
+                }
+            };
+            // Only store the result if the row was not changed, moved or removed in the meantime.
+            // Otherwise, the new notification will bring the repeater here again.
+            let mut components = tracker.components.borrow_mut();
+            if let Some(c) = components.get_mut(i) {
+                let same_instance = match (&c.1, &old_instance) {
+                    (Some(a), Some(b)) => core::ptr::eq(&**a, &**b),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if same_instance && c.0 == state {
+                    *c = (RepeatedComponentState::Clean, Some(instance));
+                }
+            }
         }
     }
 
@@ -55,7 +221,12 @@ where
         order: sixtyfps_corelib::item_tree::TraversalOrder,
         mut visitor: sixtyfps_corelib::item_tree::ItemVisitorRefMut,
     ) -> sixtyfps_corelib::item_tree::VisitChildrenResult {
-        for (i, c) in self.components.borrow().iter().enumerate() {
+        let tracker = self.inner.borrow().clone();
+        for (i, c) in tracker.components.borrow().iter().enumerate() {
+            let c = match &c.1 {
+                Some(c) => c,
+                None => continue,
+            };
             if c.as_ref().visit_children_item(-1, order, visitor.borrow_mut()).has_aborted() {
                 return sixtyfps_corelib::item_tree::VisitChildrenResult::abort(i, 0);
             }
@@ -64,21 +235,184 @@ where
     }
 
     /// Forward an input event to a particular item
+    ///
+    /// The event is ignored if there is no longer a component at that index.
     pub fn input_event(
         &self,
         idx: usize,
         event: sixtyfps_corelib::input::MouseEvent,
     ) -> sixtyfps_corelib::input::InputEventResult {
-        self.components.borrow()[idx].as_ref().input_event(event)
+        // Don't keep the vector borrowed while the event is processed: the event handler
+        // might change the model
+        let component = self.inner.borrow().components.borrow().get(idx).and_then(|c| c.1.clone());
+        match component {
+            Some(component) => component.as_ref().input_event(event),
+            None => sixtyfps_corelib::input::InputEventResult::EventIgnored,
+        }
     }
 
     /// Return the amount of item currently in the component
     pub fn len(&self) -> usize {
-        self.components.borrow().len()
+        self.inner.borrow().components.borrow().len()
+    }
+
+    /// Returns a vector containing all the instances
+    ///
+    /// The rows that were not instantiated yet are skipped, so [`Self::ensure_updated`] should
+    /// be called first.
+    pub fn components_vec(&self) -> Vec<Pin<Rc<C>>> {
+        self.inner.borrow().components.borrow().iter().filter_map(|c| c.1.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sixtyfps_corelib::component::Component;
+    use sixtyfps_corelib::input::{InputEventResult, KeyEvent, KeyEventResult, MouseEvent};
+    use sixtyfps_corelib::item_tree::{ItemVisitorRefMut, TraversalOrder, VisitChildrenResult};
+    use sixtyfps_corelib::layout::LayoutInfo;
+    use sixtyfps_corelib::model::VecModel;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct TestComponent {
+        index: Cell<usize>,
+        data: Cell<i32>,
+        update_count: Cell<usize>,
+    }
+
+    impl Component for TestComponent {
+        fn visit_children_item(
+            self: Pin<&Self>,
+            _: isize,
+            _: TraversalOrder,
+            _: ItemVisitorRefMut,
+        ) -> VisitChildrenResult {
+            VisitChildrenResult::CONTINUE
+        }
+        fn layout_info(self: Pin<&Self>) -> LayoutInfo {
+            LayoutInfo::default()
+        }
+        fn compute_layout(self: Pin<&Self>) {}
+        fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
+            InputEventResult::EventIgnored
+        }
+        fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+            KeyEventResult::EventIgnored
+        }
+    }
+
+    impl RepeatedComponent for TestComponent {
+        type Data = i32;
+        fn update(&self, index: usize, data: Self::Data) {
+            self.index.set(index);
+            self.data.set(data);
+            self.update_count.set(self.update_count.get() + 1);
+        }
+        fn update_index(&self, index: usize) {
+            self.index.set(index);
+        }
+    }
+
+    #[test]
+    fn test_repeater_keeps_instances() {
+        let init_count = Cell::new(0);
+        let init = || {
+            init_count.set(init_count.get() + 1);
+            Rc::pin(TestComponent::default())
+        };
+        let check = |instances: &[Pin<Rc<TestComponent>>], data: &[i32]| {
+            assert_eq!(instances.iter().map(|c| c.data.get()).collect::<Vec<_>>(), data);
+            for (i, c) in instances.iter().enumerate() {
+                assert_eq!(c.index.get(), i);
+            }
+        };
+        let same =
+            |a: &Pin<Rc<TestComponent>>, b: &Pin<Rc<TestComponent>>| core::ptr::eq(&**a, &**b);
+
+        let model = Rc::new(VecModel::from(vec![1, 2, 3]));
+        let repeater = Repeater::<TestComponent>::default();
+        repeater.set_model(ModelHandle::new(model.clone()));
+        repeater.ensure_updated(init);
+        assert_eq!(init_count.get(), 3);
+        let old = repeater.components_vec();
+        check(&old, &[1, 2, 3]);
+
+        model.push(4);
+        repeater.ensure_updated(init);
+        assert_eq!(init_count.get(), 4);
+        let instances = repeater.components_vec();
+        check(&instances, &[1, 2, 3, 4]);
+        assert!(old.iter().zip(&instances).all(|(a, b)| same(a, b)));
+
+        model.insert(0, 0);
+        repeater.ensure_updated(init);
+        assert_eq!(init_count.get(), 5);
+        let instances = repeater.components_vec();
+        check(&instances, &[0, 1, 2, 3, 4]);
+        assert!(old.iter().zip(&instances[1..]).all(|(a, b)| same(a, b)));
+        // The shifted instances only got a new index, their data was not updated again
+        assert!(old.iter().all(|c| c.update_count.get() == 1));
+
+        model.remove(2);
+        repeater.ensure_updated(init);
+        assert_eq!(init_count.get(), 5);
+        let instances = repeater.components_vec();
+        check(&instances, &[0, 1, 3, 4]);
+        assert!(same(&instances[1], &old[0]));
+        assert!(same(&instances[2], &old[2]));
+        assert!(same(&instances[3], &old[3]));
+
+        model.set_row_data(1, 10);
+        repeater.ensure_updated(init);
+        assert_eq!(init_count.get(), 5);
+        check(&repeater.components_vec(), &[0, 10, 3, 4]);
+        assert_eq!(old[0].update_count.get(), 2);
+        assert_eq!(old[2].update_count.get(), 1);
+    }
+
+    #[test]
+    fn test_repeater_reentrancy() {
+        let model = Rc::new(VecModel::from(vec![1, 2]));
+        let repeater = Repeater::<TestComponent>::default();
+        repeater.set_model(ModelHandle::new(model.clone()));
+        // Creating an instance reads the repeater, as a binding of the new instance could do
+        let init = || {
+            repeater.components_vec();
+            Rc::pin(TestComponent::default())
+        };
+        repeater.ensure_updated(init);
+        assert_eq!(
+            repeater.components_vec().iter().map(|c| c.data.get()).collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        // A row is added while the instances are created: it is instantiated by the next call
+        let added = Cell::new(false);
+        let init = || {
+            if !added.replace(true) {
+                model.insert(0, 0);
+            }
+            Rc::pin(TestComponent::default())
+        };
+        model.push(3);
+        repeater.ensure_updated(init);
+        repeater.ensure_updated(init);
+        let instances = repeater.components_vec();
+        assert_eq!(instances.iter().map(|c| c.data.get()).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert!(instances.iter().enumerate().all(|(i, c)| c.index.get() == i));
     }
 
-    /// Borrow the internal vector
-    pub fn borrow_item_vec(&self) -> core::cell::Ref<Vec<Pin<Rc<C>>>> {
-        self.components.borrow()
+    #[test]
+    fn test_repeater_model_change_dirties_tracker() {
+        let model = Rc::new(VecModel::from(vec![1]));
+        let repeater = Repeater::<TestComponent>::default();
+        repeater.set_model(ModelHandle::new(model.clone()));
+        let tracker = Box::pin(sixtyfps_corelib::properties::PropertyTracker::default());
+        tracker.as_ref().evaluate(|| repeater.ensure_updated(|| Rc::pin(Default::default())));
+        assert!(!tracker.is_dirty());
+        model.push(2);
+        assert!(tracker.is_dirty());
     }
 }
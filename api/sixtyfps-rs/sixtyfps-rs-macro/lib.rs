@@ -74,6 +74,17 @@ fn fill_token_vec(stream: impl Iterator<Item = TokenTree>, vec: &mut Vec<parser:
                                 last.text = "=>".into();
                                 continue;
                             }
+                            if last.kind == SyntaxKind::LessEqual && prev_spacing == Spacing::Joint
+                            {
+                                last.kind = SyntaxKind::DoubleArrow;
+                                last.text = "<=>".into();
+                                continue;
+                            }
+                            if last.kind == SyntaxKind::Minus && prev_spacing == Spacing::Joint {
+                                last.kind = SyntaxKind::Arrow;
+                                last.text = "->".into();
+                                continue;
+                            }
                         }
                         SyntaxKind::RAngle
                     }
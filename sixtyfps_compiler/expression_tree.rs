@@ -43,6 +43,8 @@ impl Hash for NamedReference {
 /// A function built into the run-time
 pub enum BuiltinFunction {
     GetWindowScaleFactor,
+    /// Gives the focus to the element passed as argument
+    SetFocusItem,
 }
 
 impl BuiltinFunction {
@@ -51,6 +53,10 @@ impl BuiltinFunction {
             BuiltinFunction::GetWindowScaleFactor => {
                 Type::Function { return_type: Box::new(Type::Float32), args: vec![] }
             }
+            BuiltinFunction::SetFocusItem => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![Type::ElementReference],
+            },
         }
     }
 }
@@ -172,9 +178,23 @@ pub enum Expression {
     /// Reference to the signal <name> in the <element>
     PropertyReference(NamedReference),
 
+    /// Reference to the function <name> declared in the <element>
+    FunctionReference(NamedReference),
+
+    /// Reference to the index of the argument of the signal handler (or function) in which
+    /// this expression is.
+    FunctionParameterReference {
+        index: usize,
+        ty: Type,
+    },
+
     /// Reference to a function built into the run-time, implemented natively
     BuiltinFunctionReference(BuiltinFunction),
 
+    /// A reference to a specific element. This isn't possible to create in .60 syntax itself, but intermediate passes may generate this
+    /// type of expression.
+    ElementReference(Weak<RefCell<Element>>),
+
     /// Reference to the index variable of a repeater
     ///
     /// Example: `idx`  in `for xxx[idx] in ...`.   The element is the reference to the
@@ -223,6 +243,7 @@ pub enum Expression {
     /// A function call
     FunctionCall {
         function: Box<Expression>,
+        arguments: Vec<Expression>,
     },
 
     /// A SelfAssignment or an Assignment.  When op is '=' this is a signel assignment.
@@ -272,6 +293,13 @@ pub enum Expression {
     EasingCurve(EasingCurve),
 
     EnumerationValue(EnumerationValue),
+
+    /// A two way binding (`<=>`) to the property of the named reference. This is only valid
+    /// as the binding of a property.
+    /// The optional expression is a binding that is set on the property after it was linked
+    /// (It comes from the binding of an element that was overriding the binding of a
+    /// component when inlining)
+    TwoWayBinding(NamedReference, Option<Box<Expression>>),
 }
 
 impl Default for Expression {
@@ -289,11 +317,18 @@ impl Expression {
             Expression::StringLiteral(_) => Type::String,
             Expression::NumberLiteral(_, unit) => unit.ty(),
             Expression::BoolLiteral(_) => Type::Bool,
-            Expression::SignalReference { .. } => Type::Signal,
+            Expression::SignalReference(NamedReference { element, name }) => {
+                element.upgrade().unwrap().borrow().lookup_property(name)
+            }
             Expression::PropertyReference(NamedReference { element, name }) => {
                 element.upgrade().unwrap().borrow().lookup_property(name)
             }
+            Expression::FunctionReference(NamedReference { element, name }) => {
+                element.upgrade().unwrap().borrow().lookup_property(name)
+            }
+            Expression::FunctionParameterReference { ty, .. } => ty.clone(),
             Expression::BuiltinFunctionReference(funcref) => funcref.ty(),
+            Expression::ElementReference(_) => Type::ElementReference,
             Expression::RepeaterIndexReference { .. } => Type::Int32,
             Expression::RepeaterModelReference { element } => {
                 if let Expression::Cast { from, .. } = element
@@ -314,15 +349,19 @@ impl Expression {
                 }
             }
             Expression::ObjectAccess { base, name } => {
-                if let Type::Object(o) = base.ty() {
-                    o.get(name.as_str()).unwrap_or(&Type::Invalid).clone()
+                if let Type::Object { fields, .. } = base.ty() {
+                    fields.get(name.as_str()).unwrap_or(&Type::Invalid).clone()
                 } else {
                     Type::Invalid
                 }
             }
             Expression::Cast { to, .. } => to.clone(),
             Expression::CodeBlock(sub) => sub.last().map_or(Type::Void, |e| e.ty()),
-            Expression::FunctionCall { function } => function.ty(),
+            Expression::FunctionCall { function, .. } => match function.ty() {
+                Type::Function { return_type, .. } => *return_type,
+                Type::Signal { return_type, .. } => return_type.map_or(Type::Void, |x| *x),
+                _ => Type::Invalid,
+            },
             Expression::SelfAssignment { .. } => Type::Void,
             Expression::ResourceReference { .. } => Type::Resource,
             Expression::Condition { condition: _, true_expr, false_expr } => {
@@ -364,6 +403,9 @@ impl Expression {
             Expression::ReadLocalVariable { ty, .. } => ty.clone(),
             Expression::EasingCurve(_) => Type::Easing,
             Expression::EnumerationValue(value) => Type::Enumeration(value.enumeration.clone()),
+            Expression::TwoWayBinding(NamedReference { element, name }, _) => {
+                element.upgrade().unwrap().borrow().lookup_property(name)
+            }
         }
     }
 
@@ -376,8 +418,11 @@ impl Expression {
             Expression::NumberLiteral(_, _) => {}
             Expression::BoolLiteral(_) => {}
             Expression::SignalReference { .. } => {}
+            Expression::FunctionReference { .. } => {}
             Expression::PropertyReference { .. } => {}
+            Expression::FunctionParameterReference { .. } => {}
             Expression::BuiltinFunctionReference { .. } => {}
+            Expression::ElementReference(_) => {}
             Expression::ObjectAccess { base, .. } => visitor(&**base),
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
@@ -387,7 +432,12 @@ impl Expression {
                     visitor(e)
                 }
             }
-            Expression::FunctionCall { function } => visitor(&**function),
+            Expression::FunctionCall { function, arguments } => {
+                visitor(&**function);
+                for e in arguments {
+                    visitor(e);
+                }
+            }
             Expression::SelfAssignment { lhs, rhs, .. } => {
                 visitor(&**lhs);
                 visitor(&**rhs);
@@ -424,6 +474,11 @@ impl Expression {
             Expression::ReadLocalVariable { .. } => {}
             Expression::EasingCurve(_) => {}
             Expression::EnumerationValue(_) => {}
+            Expression::TwoWayBinding(_, next) => {
+                if let Some(next) = next {
+                    visitor(&**next)
+                }
+            }
         }
     }
 
@@ -435,8 +490,11 @@ impl Expression {
             Expression::NumberLiteral(_, _) => {}
             Expression::BoolLiteral(_) => {}
             Expression::SignalReference { .. } => {}
+            Expression::FunctionReference { .. } => {}
             Expression::PropertyReference { .. } => {}
+            Expression::FunctionParameterReference { .. } => {}
             Expression::BuiltinFunctionReference { .. } => {}
+            Expression::ElementReference(_) => {}
             Expression::ObjectAccess { base, .. } => visitor(&mut **base),
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
@@ -446,7 +504,12 @@ impl Expression {
                     visitor(e)
                 }
             }
-            Expression::FunctionCall { function } => visitor(&mut **function),
+            Expression::FunctionCall { function, arguments } => {
+                visitor(&mut **function);
+                for e in arguments {
+                    visitor(e);
+                }
+            }
             Expression::SelfAssignment { lhs, rhs, .. } => {
                 visitor(&mut **lhs);
                 visitor(&mut **rhs);
@@ -483,6 +546,11 @@ impl Expression {
             Expression::ReadLocalVariable { .. } => {}
             Expression::EasingCurve(_) => {}
             Expression::EnumerationValue(_) => {}
+            Expression::TwoWayBinding(_, next) => {
+                if let Some(next) = next {
+                    visitor(&mut **next)
+                }
+            }
         }
     }
 
@@ -494,8 +562,11 @@ impl Expression {
             Expression::NumberLiteral(_, _) => true,
             Expression::BoolLiteral(_) => true,
             Expression::SignalReference { .. } => false,
+            Expression::FunctionReference { .. } => false,
             Expression::PropertyReference { .. } => false,
+            Expression::FunctionParameterReference { .. } => false,
             Expression::BuiltinFunctionReference { .. } => false,
+            Expression::ElementReference(_) => false,
             Expression::RepeaterIndexReference { .. } => false,
             Expression::RepeaterModelReference { .. } => false,
             Expression::ObjectAccess { base, .. } => base.is_constant(),
@@ -524,6 +595,7 @@ impl Expression {
             Expression::ReadLocalVariable { .. } => false,
             Expression::EasingCurve(_) => true,
             Expression::EnumerationValue(_) => true,
+            Expression::TwoWayBinding(..) => false,
         }
     }
 
@@ -537,6 +609,52 @@ impl Expression {
         let ty = self.ty();
         if ty == target_type {
             self
+        } else if let (true, Type::Array(element_ty)) =
+            (matches!(self, Expression::Array { .. }), &target_type)
+        {
+            // Convert each element of an array literal. This also gives a type to empty arrays.
+            let element_ty = (**element_ty).clone();
+            match self {
+                Expression::Array { values, .. } => Expression::Array {
+                    values: values
+                        .into_iter()
+                        .map(|e| e.maybe_convert_to(element_ty.clone(), node, diag))
+                        .collect(),
+                    element_ty,
+                },
+                _ => unreachable!(),
+            }
+        } else if let (true, Type::Object { fields, .. }) =
+            (matches!(self, Expression::Object { .. }), &target_type)
+        {
+            // Convert each member of an object literal. The members that are not
+            // specified get the default value.
+            match self {
+                Expression::Object { mut values, .. } => {
+                    for k in values.keys() {
+                        if !fields.contains_key(k) {
+                            diag.push_error(
+                                format!("Cannot convert {} to {}: no field {}", ty, target_type, k),
+                                node,
+                            );
+                        }
+                    }
+                    Expression::Object {
+                        values: fields
+                            .iter()
+                            .map(|(k, field_ty)| {
+                                let e = match values.remove(k) {
+                                    Some(e) => e.maybe_convert_to(field_ty.clone(), node, diag),
+                                    None => Expression::default_value_for_type(field_ty),
+                                };
+                                (k.clone(), e)
+                            })
+                            .collect(),
+                        ty: target_type,
+                    }
+                }
+                _ => unreachable!(),
+            }
         } else if ty.can_convert(&target_type) {
             let from = match (ty, &target_type) {
                 (Type::Length, Type::LogicalLength) => Expression::BinaryExpression {
@@ -545,6 +663,7 @@ impl Expression {
                         function: Box::new(Expression::BuiltinFunctionReference(
                             BuiltinFunction::GetWindowScaleFactor,
                         )),
+                        arguments: vec![],
                     }),
                     op: '/',
                 },
@@ -554,6 +673,7 @@ impl Expression {
                         function: Box::new(Expression::BuiltinFunctionReference(
                             BuiltinFunction::GetWindowScaleFactor,
                         )),
+                        arguments: vec![],
                     }),
                     op: '*',
                 },
@@ -575,7 +695,7 @@ impl Expression {
             | Type::Component(_)
             | Type::Builtin(_)
             | Type::Native(_)
-            | Type::Signal
+            | Type::Signal { .. }
             | Type::Function { .. }
             | Type::Void => Expression::Invalid,
             Type::Float32 => Expression::NumberLiteral(0., Unit::None),
@@ -596,9 +716,9 @@ impl Expression {
             Type::Array(element_ty) => {
                 Expression::Array { element_ty: (**element_ty).clone(), values: vec![] }
             }
-            Type::Object(map) => Expression::Object {
+            Type::Object { fields, .. } => Expression::Object {
                 ty: ty.clone(),
-                values: map
+                values: fields
                     .into_iter()
                     .map(|(k, v)| (k.clone(), Expression::default_value_for_type(v)))
                     .collect(),
@@ -608,6 +728,7 @@ impl Expression {
                 Expression::EnumerationValue(enumeration.clone().default_value())
             }
             Type::EnumerationValue(_) => Expression::Invalid,
+            Type::ElementReference => Expression::Invalid,
         }
     }
 }
@@ -663,6 +784,13 @@ pub struct PathElement {
 pub enum EasingCurve {
     Linear,
     CubicBezier(f32, f32, f32, f32),
+    EaseInElastic,
+    EaseOutElastic,
+    EaseInOutElastic,
+    EaseInBounce,
+    EaseOutBounce,
+    EaseInOutBounce,
+    Steps(u32),
     // CubicBesizerNonConst([Box<Expression>; 4]),
     // Custom(Box<dyn Fn(f32)->f32>),
 }
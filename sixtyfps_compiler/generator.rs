@@ -138,3 +138,11 @@ pub fn is_flickable(e: &ElementRc) -> bool {
         _ => false,
     }
 }
+
+/// Timer items need to be initialized once all their bindings are set
+pub fn is_timer(e: &ElementRc) -> bool {
+    match &e.borrow().base_type {
+        crate::typeregister::Type::Native(n) if n.class_name == "TimerItem" => true,
+        _ => false,
+    }
+}
@@ -184,11 +184,15 @@ mod cpp_ast {
 
 use crate::diagnostics::{BuildDiagnostics, CompilerDiagnostic, Spanned};
 use crate::expression_tree::{BuiltinFunction, EasingCurve, Expression, ExpressionSpanned};
-use crate::layout::{gen::LayoutItemCodeGen, Layout, LayoutElement};
-use crate::object_tree::{Component, Element, ElementRc, RepeatedElementInfo};
+use crate::layout::{
+    gen::LayoutItemCodeGen, Layout, LayoutElement, LayoutItem, LayoutItemConstraints, Padding,
+};
+use crate::object_tree::{
+    recurse_elem, Component, Element, ElementRc, PropertyAnimation, RepeatedElementInfo,
+};
 use crate::typeregister::Type;
 use cpp_ast::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 impl CppType for Type {
@@ -204,8 +208,12 @@ impl CppType for Type {
             Type::LogicalLength => Some("float".to_owned()),
             Type::Bool => Some("bool".to_owned()),
             Type::Model => Some("std::shared_ptr<sixtyfps::Model>".to_owned()),
-            Type::Object(o) => {
-                let elem = o.values().map(|v| v.cpp_type()).collect::<Option<Vec<_>>>()?;
+            Type::Object { .. } if *self == Type::state_info() => {
+                Some("sixtyfps::private_api::StateInfo".to_owned())
+            }
+            Type::Object { name: Some(name), .. } => Some(name.clone()),
+            Type::Object { fields, name: None } => {
+                let elem = fields.values().map(|v| v.cpp_type()).collect::<Option<Vec<_>>>()?;
                 // This will produce a tuple
                 Some(format!("std::tuple<{}>", elem.join(", ")))
             }
@@ -243,32 +251,28 @@ fn new_struct_with_bindings(
     )
 }
 
-fn property_animation_code(
-    component: &Rc<Component>,
-    element: &Element,
-    property_name: &str,
-) -> Option<String> {
-    if let Some(animation) = element.property_animations.get(property_name) {
-        Some(new_struct_with_bindings(
-            "sixtyfps::PropertyAnimation",
-            &animation.borrow().bindings,
-            component,
-        ))
-    } else {
-        None
-    }
+/// Returns the code that creates the `PropertyAnimation` struct for the given `animate` element
+fn animation_struct_code(component: &Rc<Component>, animation: &ElementRc) -> String {
+    new_struct_with_bindings("sixtyfps::PropertyAnimation", &animation.borrow().bindings, component)
 }
+
 fn property_set_value_code(
     component: &Rc<Component>,
     element: &Element,
     property_name: &str,
     value_expr: &str,
 ) -> String {
-    if let Some(animation_code) = property_animation_code(component, element, property_name) {
+    let animation = match element.property_animations.get(property_name) {
+        Some(PropertyAnimation::Static(animation)) => Some(animation),
+        // The transitions only animate the bindings, setting a value uses the default animation
+        Some(PropertyAnimation::Transition { default, .. }) => default.as_ref(),
+        None => None,
+    };
+    if let Some(animation) = animation {
         format!(
             "set_animated_value({value}, {animation})",
             value = value_expr,
-            animation = animation_code
+            animation = animation_struct_code(component, animation)
         )
     } else {
         format!("set({})", value_expr)
@@ -281,14 +285,51 @@ fn property_set_binding_code(
     property_name: &str,
     binding_expr: String,
 ) -> String {
-    if let Some(animation_code) = property_animation_code(component, element, property_name) {
-        format!(
-            "set_animated_binding({binding}, {animation})",
+    match element.property_animations.get(property_name) {
+        Some(PropertyAnimation::Static(animation))
+            if animation.borrow().bindings.values().all(|b| b.is_constant()) =>
+        {
+            format!(
+                "set_animated_binding({binding}, {animation})",
+                binding = binding_expr,
+                animation = animation_struct_code(component, animation)
+            )
+        }
+        // The parameters of the animation are evaluated when the animation starts
+        Some(PropertyAnimation::Static(animation)) => format!(
+            "set_dynamic_animated_binding({binding}, [this]() {{
+                [[maybe_unused]] auto self = this;
+                return {animation};
+            }})",
             binding = binding_expr,
-            animation = animation_code
-        )
-    } else {
-        format!("set_binding({})", binding_expr)
+            animation = animation_struct_code(component, animation)
+        ),
+        Some(PropertyAnimation::Transition { state_ref, animations, default }) => {
+            let animations = animations.iter().map(|a| {
+                format!(
+                    "if (state.{field} == {state_id}) return {animation};",
+                    field = if a.is_out { "previous_state" } else { "current_state" },
+                    state_id = a.state_id,
+                    animation = animation_struct_code(component, &a.animation)
+                )
+            });
+            format!(
+                "set_dynamic_animated_binding({binding}, [this]() {{
+                    [[maybe_unused]] auto self = this;
+                    auto state = {state};
+                    {animations}
+                    return {default};
+                }})",
+                binding = binding_expr,
+                state = compile_expression(state_ref, component),
+                animations = animations.collect::<Vec<_>>().join("\n"),
+                default = default.as_ref().map_or_else(
+                    || "sixtyfps::PropertyAnimation{}".to_owned(),
+                    |default| animation_struct_code(component, default)
+                ),
+            )
+        }
+        None => format!("set_binding({})", binding_expr),
     }
 }
 
@@ -302,62 +343,113 @@ fn handle_item(item: &Element, main_struct: &mut Struct, init: &mut Vec<String>)
         }),
     ));
 
+    handle_bindings(item, init);
+}
+
+/// Push in `init` the code that sets the bindings and signal handlers of the element
+fn handle_bindings(item: &Element, init: &mut Vec<String>) {
     let id = &item.id;
-    init.extend(item.bindings.iter().map(|(s, i)| {
-        if matches!(item.lookup_property(s.as_str()), Type::Signal) {
+    for (s, i) in &item.bindings {
+        if let Type::Signal { args, return_type } = item.lookup_property(s.as_str()) {
             let signal_accessor_prefix = if item.property_declarations.contains_key(s) {
                 String::new()
             } else {
                 format!("{id}.", id = id.clone())
             };
+            let params = args.iter().enumerate().map(|(i, ty)| {
+                format!("[[maybe_unused]] {} arg_{}", ty.cpp_type().unwrap_or_default(), i)
+            });
 
-            format!(
+            init.push(format!(
                 "{signal_accessor_prefix}{prop}.set_handler(
-                    [this]() {{
+                    [this]({params}) {{
                         [[maybe_unused]] auto self = this;
-                        {code};
+                        {ret}{code};
                     }});",
                 signal_accessor_prefix = signal_accessor_prefix,
                 prop = s,
+                params = params.collect::<Vec<_>>().join(", "),
+                ret = if return_type.is_some() { "return " } else { "" },
                 code = compile_expression(i, &item.enclosing_component.upgrade().unwrap())
-            )
+            ));
+        } else if matches!(item.lookup_property(s.as_str()), Type::Function { .. }) {
+            // Functions are generated as member functions by declare_properties_and_signals
         } else {
-            let accessor_prefix = if item.property_declarations.contains_key(s) {
-                String::new()
-            } else {
-                format!("{id}.", id = id.clone())
-            };
+            handle_property_binding(item, s, i, init);
+        }
+    }
+}
 
-            let component = &item.enclosing_component.upgrade().unwrap();
+fn handle_property_binding(
+    item: &Element,
+    prop_name: &str,
+    binding_expression: &Expression,
+    init: &mut Vec<String>,
+) {
+    let component = &item.enclosing_component.upgrade().unwrap();
+    let accessor_prefix = if item.property_declarations.contains_key(prop_name) {
+        String::new()
+    } else {
+        format!("{id}.", id = item.id)
+    };
 
-            let init = compile_expression(i, component);
-            if i.is_constant() {
-                format!(
-                    "{accessor_prefix}{cpp_prop}.set({init});",
-                    accessor_prefix = accessor_prefix,
-                    cpp_prop = s,
-                    init = init
-                )
-            } else {
-                let binding_code = format!(
-                    "[this]() {{
-                            [[maybe_unused]] auto self = this;
-                            return {init};
-                        }}",
-                    init = init
-                );
+    if let Expression::TwoWayBinding(nr, next) = binding_expression {
+        let p2 = access_member(&nr.element.upgrade().unwrap(), nr.name.as_str(), component, "self");
+        // The links need to be done before any other binding is set, since linking only
+        // keeps the value (and not the binding) of the second property.
+        // Index 0 is the declaration of `self`.
+        init.insert(
+            1,
+            format!(
+                "sixtyfps::Property<{ty}>::link_two_way(&{accessor_prefix}{cpp_prop}, &{p2});",
+                ty = binding_expression.ty().cpp_type().unwrap_or_default(),
+                accessor_prefix = accessor_prefix,
+                cpp_prop = prop_name,
+                p2 = p2,
+            ),
+        );
+        if let Some(next) = next {
+            handle_property_binding(item, prop_name, next, init);
+        }
+        return;
+    }
 
-                let binding_setter = property_set_binding_code(component, item, s, binding_code);
+    let init_expr = compile_expression(binding_expression, component);
+    if item.lookup_property(prop_name) == Type::state_info() {
+        init.push(format!(
+            "sixtyfps::private_api::set_state_binding({accessor_prefix}{cpp_prop}, [this]() {{
+                    [[maybe_unused]] auto self = this;
+                    return {init};
+                }});",
+            accessor_prefix = accessor_prefix,
+            cpp_prop = prop_name,
+            init = init_expr
+        ));
+    } else if binding_expression.is_constant() {
+        init.push(format!(
+            "{accessor_prefix}{cpp_prop}.set({init});",
+            accessor_prefix = accessor_prefix,
+            cpp_prop = prop_name,
+            init = init_expr
+        ));
+    } else {
+        let binding_code = format!(
+            "[this]() {{
+                    [[maybe_unused]] auto self = this;
+                    return {init};
+                }}",
+            init = init_expr
+        );
 
-                format!(
-                    "{accessor_prefix}{cpp_prop}.{binding_setter};",
-                    accessor_prefix = accessor_prefix,
-                    cpp_prop = s,
-                    binding_setter = binding_setter,
-                )
-            }
-        }
-    }));
+        let binding_setter = property_set_binding_code(component, item, prop_name, binding_code);
+
+        init.push(format!(
+            "{accessor_prefix}{cpp_prop}.{binding_setter};",
+            accessor_prefix = accessor_prefix,
+            cpp_prop = prop_name,
+            binding_setter = binding_setter,
+        ));
+    }
 }
 
 fn handle_repeater(
@@ -403,23 +495,36 @@ fn handle_repeater(
                 init: None,
             }),
         ));
+        // Called before visiting the repeater, and before computing a layout that contains it
+        component_struct.members.push((
+            Access::Private,
+            Declaration::Function(Function {
+                name: format!("update_{}", repeater_id),
+                signature: "() const -> void".to_owned(),
+                statements: Some(vec![
+                    "[[maybe_unused]] auto self = this;".to_owned(),
+                    format!(
+                        "if (self->model_{i}.is_dirty()) {{ self->model_{i}.evaluate([&] {{ self->{id}.update_model({model}, self); }}); }}",
+                        id = repeater_id,
+                        i = repeater_count,
+                        model = model,
+                    ),
+                ]),
+                ..Default::default()
+            }),
+        ));
         children_visitor_cases.push(format!(
             "\n        case {i}: {{
-                if (self->model_{i}.is_dirty()) {{
-                    self->model_{i}.evaluate([&] {{
-                        self->{id}.update_model({model}, self);
-                    }});
-                }}
+                self->update_{id}();
                 return self->{id}.visit(order, visitor);
             }}",
             id = repeater_id,
             i = repeater_count,
-            model = model,
         ));
     }
 
     repeated_input_branch.push(format!(
-        "\n        case {i}: return self->{id}.item_at(rep_index);",
+        "\n        case {i}: return self->{id}.try_item_at(rep_index);",
         i = repeater_count,
         id = repeater_id,
     ));
@@ -447,6 +552,23 @@ pub fn generate(
 
     generate_component(&mut file, component, diag, None);
 
+    // generate_component puts the component first, but the structs and the globals must be
+    // declared before
+    let mut structs_file = File::default();
+    for ty in component.used_structs.borrow().iter() {
+        if let Type::Object { fields, name: Some(name) } = ty {
+            generate_struct(&mut structs_file, name, fields, diag);
+        }
+    }
+    if !component.used_globals.borrow().is_empty() {
+        let mut friends = vec![];
+        component_and_sub_component_ids(component, &mut friends);
+        for global in component.used_globals.borrow().iter() {
+            generate_global(&mut structs_file, global, friends.clone(), diag);
+        }
+    }
+    file.declarations.splice(0..0, structs_file.declarations);
+
     file.declarations.push(Declaration::Var(Var{
         ty: format!(
             "constexpr sixtyfps::private_api::VersionCheckHelper<{}, {}, {}>",
@@ -464,30 +586,162 @@ pub fn generate(
     }
 }
 
-/// Generate the component in `file`.
-///
-/// `sub_components`, if Some, will be filled with all the sub component which needs to be added as friends
-fn generate_component(
+/// Generate the declaration of a struct declared with `struct` in the .60 file, and its
+/// comparison operators
+fn generate_struct(
     file: &mut File,
-    component: &Rc<Component>,
+    name: &str,
+    fields: &BTreeMap<String, Type>,
     diag: &mut BuildDiagnostics,
-    mut sub_components: Option<&mut Vec<String>>,
 ) {
-    let component_id = component_id(component);
-    let mut component_struct = Struct { name: component_id.clone(), ..Default::default() };
+    let members = fields
+        .iter()
+        .map(|(field_name, t)| {
+            let ty = t.cpp_type().unwrap_or_else(|| {
+                let err = CompilerDiagnostic {
+                    message: format!("Cannot map the type of {}::{} to C++", name, field_name),
+                    span: Default::default(),
+                };
+                diag.push_internal_error(err.into());
+                "".into()
+            });
+            (Access::Public, Declaration::Var(Var { ty, name: field_name.clone(), init: None }))
+        })
+        .collect();
+    file.declarations.push(Declaration::Struct(Struct {
+        name: name.into(),
+        members,
+        ..Default::default()
+    }));
+
+    let comparison = if fields.is_empty() {
+        "true".to_owned()
+    } else {
+        fields.keys().map(|k| format!("a.{0} == b.{0}", k)).collect::<Vec<_>>().join(" && ")
+    };
+    file.declarations.push(Declaration::Function(Function {
+        name: "operator==".into(),
+        signature: format!("(const {0} &a, const {0} &b) -> bool", name),
+        statements: Some(vec![format!("return {};", comparison)]),
+        ..Default::default()
+    }));
+    file.declarations.push(Declaration::Function(Function {
+        name: "operator!=".into(),
+        signature: format!("(const {0} &a, const {0} &b) -> bool", name),
+        statements: Some(vec!["return !(a == b);".into()]),
+        ..Default::default()
+    }));
+}
+
+/// Generate the struct for a component declared with `global`. It is instantiated once by
+/// the root component. `friends` are the components that access its properties.
+fn generate_global(
+    file: &mut File,
+    global: &Rc<Component>,
+    friends: Vec<String>,
+    diag: &mut BuildDiagnostics,
+) {
+    let global_id = component_id(global);
+    let mut global_struct = Struct { name: global_id.clone(), friends, ..Default::default() };
+    declare_properties_and_signals(global, &mut global_struct, true, diag);
 
-    let is_root = component.parent_element.upgrade().is_none();
     let mut init = vec!["[[maybe_unused]] auto self = this;".into()];
+    handle_bindings(&*global.root_element.borrow(), &mut init);
+    global_struct.members.push((
+        Access::Public,
+        Declaration::Function(Function {
+            name: global_id,
+            signature: "()".to_owned(),
+            is_constructor_or_destructor: true,
+            statements: Some(init),
+            ..Default::default()
+        }),
+    ));
+
+    file.declarations.push(Declaration::Struct(global_struct));
+}
+
+/// Fill `ids` with the id of the component and of all its repeated sub components
+fn component_and_sub_component_ids(component: &Rc<Component>, ids: &mut Vec<String>) {
+    ids.push(component_id(component));
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        if elem.borrow().repeated.is_some() {
+            component_and_sub_component_ids(elem.borrow().base_type.as_component(), ids);
+        }
+    });
+}
 
+/// Add to the struct the properties and signals declared in the root element of the component,
+/// and their accessors if `expose_accessors` is true
+fn declare_properties_and_signals(
+    component: &Rc<Component>,
+    component_struct: &mut Struct,
+    expose_accessors: bool,
+    diag: &mut BuildDiagnostics,
+) {
     for (cpp_name, property_decl) in component.root_element.borrow().property_declarations.iter() {
-        let ty = if property_decl.property_type == Type::Signal {
-            if property_decl.expose_in_public_api && is_root {
-                let signal_emitter = vec![format!("{}.emit();", cpp_name)];
+        if let Type::Function { args, return_type } = &property_decl.property_type {
+            let params = args
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| {
+                    format!("[[maybe_unused]] {} arg_{}", ty.cpp_type().unwrap_or_default(), i)
+                })
+                .collect::<Vec<_>>();
+            let body = component
+                .root_element
+                .borrow()
+                .bindings
+                .get(cpp_name)
+                .map_or_else(String::new, |e| compile_expression(e, component));
+            let ret = if **return_type == Type::Void { "" } else { "return " };
+            component_struct.members.push((
+                if property_decl.expose_in_public_api && expose_accessors {
+                    Access::Public
+                } else {
+                    Access::Private
+                },
+                Declaration::Function(Function {
+                    name: cpp_name.clone(),
+                    signature: format!(
+                        "({}) -> {}",
+                        params.join(", "),
+                        return_type.cpp_type().unwrap_or_default()
+                    ),
+                    statements: Some(vec![
+                        "[[maybe_unused]] auto self = this;".into(),
+                        format!("{}{};", ret, body),
+                    ]),
+                    ..Default::default()
+                }),
+            ));
+            continue;
+        }
+        let ty = if let Type::Signal { args, return_type } = &property_decl.property_type {
+            let param_types =
+                args.iter().map(|t| t.cpp_type().unwrap_or_default()).collect::<Vec<_>>();
+            let return_type =
+                return_type.as_ref().map_or("void".into(), |t| t.cpp_type().unwrap_or_default());
+            if property_decl.expose_in_public_api && expose_accessors {
+                let signal_emitter = vec![format!(
+                    "return {}.emit({});",
+                    cpp_name,
+                    (0..args.len()).map(|i| format!("arg_{}", i)).collect::<Vec<_>>().join(", ")
+                )];
                 component_struct.members.push((
                     Access::Public,
                     Declaration::Function(Function {
                         name: format!("emit_{}", cpp_name),
-                        signature: "()".into(),
+                        signature: format!(
+                            "({}) -> {}",
+                            param_types
+                                .iter()
+                                .enumerate()
+                                .map(|(i, ty)| format!("{} arg_{}", ty, i))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            return_type
+                        ),
                         statements: Some(signal_emitter),
                         ..Default::default()
                     }),
@@ -507,7 +761,7 @@ fn generate_component(
                 ));
             }
 
-            "sixtyfps::Signal".into()
+            format!("sixtyfps::Signal<{}({})>", return_type, param_types.join(", "))
         } else {
             let cpp_type = property_decl.property_type.cpp_type().unwrap_or_else(|| {
                 let err = CompilerDiagnostic {
@@ -519,7 +773,7 @@ fn generate_component(
                 "".into()
             });
 
-            if property_decl.expose_in_public_api && is_root {
+            if property_decl.expose_in_public_api && expose_accessors {
                 let prop_getter: Vec<String> = vec![format!("return {}.get();", cpp_name)];
 
                 component_struct.members.push((
@@ -560,6 +814,24 @@ fn generate_component(
             Declaration::Var(Var { ty, name: cpp_name.clone(), init: None }),
         ));
     }
+}
+
+/// Generate the component in `file`.
+///
+/// `sub_components`, if Some, will be filled with all the sub component which needs to be added as friends
+fn generate_component(
+    file: &mut File,
+    component: &Rc<Component>,
+    diag: &mut BuildDiagnostics,
+    mut sub_components: Option<&mut Vec<String>>,
+) {
+    let component_id = component_id(component);
+    let mut component_struct = Struct { name: component_id.clone(), ..Default::default() };
+
+    let is_root = component.parent_element.upgrade().is_none();
+    let mut init = vec!["[[maybe_unused]] auto self = this;".into()];
+
+    declare_properties_and_signals(component, &mut component_struct, is_root, diag);
 
     if !is_root {
         let parent_element = component.parent_element.upgrade().unwrap();
@@ -675,13 +947,35 @@ fn generate_component(
                 statements: Some(vec!["window.run(this);".into()]),
                 ..Default::default()
             }),
-        ))
+        ));
+
+        for global in component.used_globals.borrow().iter() {
+            let global_id = self::component_id(global);
+            component_struct.members.push((
+                Access::Private,
+                Declaration::Var(Var {
+                    ty: global_id.clone(),
+                    name: format!("global_{}_instance", global_id),
+                    ..Var::default()
+                }),
+            ));
+            component_struct.members.push((
+                Access::Public,
+                Declaration::Function(Function {
+                    name: format!("global_{}", global_id),
+                    signature: format!("() -> {} &", global_id),
+                    statements: Some(vec![format!("return global_{}_instance;", global_id)]),
+                    ..Default::default()
+                }),
+            ));
+        }
     }
 
     let mut children_visitor_cases = vec![];
     let mut repeated_input_branch = vec![];
     let mut tree_array = vec![];
     let mut repeater_count = 0;
+    let mut timer_init = vec![];
     super::build_array_helper(component, |item_rc, children_offset, is_flickable_rect| {
         let item = item_rc.borrow();
         if is_flickable_rect {
@@ -724,9 +1018,37 @@ fn generate_component(
                 children_offset,
             ));
             handle_item(&*item, &mut component_struct, &mut init);
+            if super::is_timer(item_rc) {
+                timer_init.push(format!("{}.init();", item.id));
+            }
         }
     });
 
+    for (i, (nr, handler)) in component.root_element.borrow().change_callbacks.iter().enumerate() {
+        let change_tracker_id = format!("change_tracker_{}", i);
+        component_struct.members.push((
+            Access::Private,
+            Declaration::Var(Var {
+                ty: "sixtyfps::ChangeTracker".to_owned(),
+                name: change_tracker_id.clone(),
+                init: None,
+            }),
+        ));
+        init.push(format!(
+            "{id}.init([this]() {{
+                    [[maybe_unused]] auto self = this;
+                    return {value};
+                }}, [this](const auto &) {{
+                    [[maybe_unused]] auto self = this;
+                    {handler};
+                }});",
+            id = change_tracker_id,
+            value = compile_expression(&Expression::PropertyReference(nr.clone()), component),
+            handler = compile_expression(handler, component),
+        ));
+    }
+    init.append(&mut timer_init);
+
     component_struct.members.push((
         Access::Public,
         Declaration::Function(Function {
@@ -803,6 +1125,14 @@ fn generate_component(
             init: Some("-1".into()),
         }),
     ));
+    component_struct.members.push((
+        Access::Private,
+        Declaration::Var(Var {
+            ty: "int64_t".into(),
+            name: "mouse_hover".into(),
+            init: Some("-1".into()),
+        }),
+    ));
     component_struct.members.push((
         Access::Private,
         Declaration::Function(Function {
@@ -811,11 +1141,44 @@ fn generate_component(
                 "(sixtyfps::ComponentRef component, sixtyfps::MouseEvent mouse_event) -> sixtyfps::InputEventResult"
                     .into(),
             is_static: true,
+            statements: Some(
+                std::iter::once(format!(
+                    "    auto self = reinterpret_cast<{}*>(component.instance);",
+                    component_id
+                ))
+                // Clicking on an item gives it the focus. Only the root component receives the mouse press,
+                // the repeated components get the events forwarded by their parent.
+                .chain(if is_root {
+                    Some("if (mouse_event.what == sixtyfps::MouseEventType::MousePressed) self->window.set_focus_item_at(self, mouse_event.pos);".into())
+                } else {
+                    None
+                })
+                .chain(vec![
+                    "return sixtyfps::private_api::process_input_event(component, self->mouse_grabber, self->mouse_hover, mouse_event, item_tree(), [self](int dyn_index, [[maybe_unused]] int rep_index) {".into(),
+                    format!("    switch(dyn_index) {{ {} }};", repeated_input_branch.join("")),
+                    "    return sixtyfps::ComponentRef{nullptr, nullptr};\n});".into(),
+                ])
+                .collect(),
+            ),
+            ..Default::default()
+        }),
+    ));
+
+    component_struct.members.push((
+        Access::Private,
+        Declaration::Function(Function {
+            name: "key_event".into(),
+            signature:
+                "(sixtyfps::ComponentRef component, const sixtyfps::KeyEvent *key_event) -> sixtyfps::KeyEventResult"
+                    .into(),
+            is_static: true,
             statements: Some(vec![
                 format!("    auto self = reinterpret_cast<{}*>(component.instance);", component_id),
-                "return sixtyfps::private_api::process_input_event(component, self->mouse_grabber, mouse_event, item_tree(), [self](int dyn_index, [[maybe_unused]] int rep_index) {".into(),
-                format!("    switch(dyn_index) {{ {} }};", repeated_input_branch.join("")),
-                "    return sixtyfps::ComponentRef{nullptr, nullptr};\n});".into(),
+                format!(
+                    "return {}.dispatch_key_event({}, key_event);",
+                    window_ref_expression(component),
+                    root_component_expression(component)
+                ),
             ]),
             ..Default::default()
         }),
@@ -848,7 +1211,9 @@ fn generate_component(
     declarations.push(Declaration::Var(Var {
         ty: "const sixtyfps::private_api::ComponentVTable".to_owned(),
         name: format!("{}::component_type", component_id),
-        init: Some("{ visit_children, nullptr, compute_layout, input_event }".to_owned()),
+        init: Some(
+            "{ visit_children, nullptr, compute_layout, input_event, key_event }".to_owned(),
+        ),
     }));
 
     declarations.append(&mut file.declarations);
@@ -886,6 +1251,14 @@ fn access_member(
         } else {
             format!("{}->{}.{}", component_cpp, e.id.as_str(), name)
         }
+    } else if enclosing_component.is_global && component.parent_element.upgrade().is_none() {
+        // The globals are owned by the root component
+        format!(
+            "{}->global_{}_instance.{}",
+            component_cpp,
+            component_id(&enclosing_component),
+            name
+        )
     } else {
         access_member(
             element,
@@ -903,15 +1276,20 @@ fn access_member(
     }
 }
 
-/// Return an expression that gets the window
-fn window_ref_expression(component: &Rc<Component>) -> String {
+/// Return an expression that gets the pointer to the root component
+fn root_component_expression(component: &Rc<Component>) -> String {
     let mut root_component = component.clone();
     let mut component_cpp = "self".to_owned();
     while let Some(p) = root_component.parent_element.upgrade() {
         root_component = p.borrow().enclosing_component.upgrade().unwrap();
         component_cpp = format!("{}->parent", component_cpp);
     }
-    format!("{}->window", component_cpp)
+    component_cpp
+}
+
+/// Return an expression that gets the window
+fn window_ref_expression(component: &Rc<Component>) -> String {
+    format!("{}->window", root_component_expression(component))
 }
 
 fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Component>) -> String {
@@ -932,11 +1310,21 @@ fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Com
                 access_member(&element.upgrade().unwrap(), name.as_str(), component, "self");
             format!(r#"{}.emit()"#, access)
         }
+        Expression::FunctionParameterReference { index, .. } => format!("arg_{}", index),
+        Expression::FunctionReference(_) => {
+            panic!("internal error: function references are only valid in a function call")
+        }
         Expression::BuiltinFunctionReference(funcref) => match funcref {
             BuiltinFunction::GetWindowScaleFactor => {
                 format!("{}.scale_factor()", window_ref_expression(component))
             }
+            BuiltinFunction::SetFocusItem => {
+                panic!("internal error: SetFocusItem is handled directly in the FunctionCall")
+            }
         },
+        Expression::ElementReference(_) => {
+            panic!("internal error: element references are only valid as function arguments")
+        }
         Expression::RepeaterIndexReference { element } => {
             let access = access_member(
                 &element.upgrade().unwrap().borrow().base_type.as_component().root_element,
@@ -959,16 +1347,17 @@ fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Com
             format!("auto {} = {};", name, compile_expression(value, component))
         }
         Expression::ReadLocalVariable { name, .. } => name.clone(),
-        Expression::ObjectAccess { base, name } => {
-            let index = if let Type::Object(ty) = base.ty() {
-                ty.keys()
+        Expression::ObjectAccess { base, name } => match base.ty() {
+            Type::Object { fields, name: None } => {
+                let index = fields
+                    .keys()
                     .position(|k| k == name)
-                    .expect("Expression::ObjectAccess: Cannot find a key in an object")
-            } else {
-                panic!("Expression::ObjectAccess's base expression is not an Object type")
-            };
-            format!("std::get<{}>({})", index, compile_expression(base, component))
-        }
+                    .expect("Expression::ObjectAccess: Cannot find a key in an object");
+                format!("std::get<{}>({})", index, compile_expression(base, component))
+            }
+            Type::Object { .. } => format!("({}).{}", compile_expression(base, component), name),
+            _ => panic!("Expression::ObjectAccess's base expression is not an Object type"),
+        },
         Expression::Cast { from, to } => {
             let f = compile_expression(&*from, component);
             match (from.ty(), to) {
@@ -989,13 +1378,41 @@ fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Com
 
             format!("[&]{{ {} }}()", x.join(";"))
         }
-        Expression::FunctionCall { function } => {
-            if matches!(function.ty(), Type::Signal | Type::Function{..}) {
-                compile_expression(&*function, component)
-            } else {
-                format!("\n#error the function `{:?}` is not a signal\n", function)
+        Expression::FunctionCall { function, arguments } => match &**function {
+            Expression::BuiltinFunctionReference(BuiltinFunction::SetFocusItem) => {
+                let focus_item = match arguments.as_slice() {
+                    [Expression::ElementReference(focus_item)] => focus_item.upgrade().unwrap(),
+                    _ => panic!("internal error: SetFocusItem takes an element as argument"),
+                };
+                format!(
+                    "{}.set_focus_item({}, {{ &sixtyfps::private_api::{}, &{}{} }})",
+                    window_ref_expression(component),
+                    root_component_expression(component),
+                    focus_item.borrow().base_type.as_native().vtable_symbol,
+                    access_member(&focus_item, "", component, "self"),
+                    focus_item.borrow().id,
+                )
             }
-        }
+            Expression::SignalReference(NamedReference { element, name }) => {
+                let access =
+                    access_member(&element.upgrade().unwrap(), name.as_str(), component, "self");
+                let args = arguments.iter().map(|e| compile_expression(e, component));
+                format!("{}.emit({})", access, args.collect::<Vec<_>>().join(", "))
+            }
+            Expression::FunctionReference(NamedReference { element, name }) => {
+                let access =
+                    access_member(&element.upgrade().unwrap(), name.as_str(), component, "self");
+                let args = arguments.iter().map(|e| compile_expression(e, component));
+                format!("{}({})", access, args.collect::<Vec<_>>().join(", "))
+            }
+            _ => {
+                if matches!(function.ty(), Type::Function { .. }) {
+                    compile_expression(&*function, component)
+                } else {
+                    format!("\n#error the function `{:?}` is not a signal\n", function)
+                }
+            }
+        },
         Expression::SelfAssignment { lhs, rhs, op } => match &**lhs {
             Expression::PropertyReference(NamedReference { element, name }) => {
                 let access =
@@ -1067,17 +1484,28 @@ fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Com
             )
         }
         Expression::Object { ty, values } => {
-            if let Type::Object(ty) = ty {
-                let elem = ty
-                    .keys()
-                    .map(|k| {
+            if let Type::Object { fields, name } = ty {
+                let elem = fields
+                    .iter()
+                    .map(|(k, t)| {
                         values
                             .get(k)
-                            .map(|e| compile_expression(e, component))
+                            .map(|e| {
+                                let e = compile_expression(e, component);
+                                match t.cpp_type() {
+                                    // Avoid narrowing errors in the brace initialization
+                                    Some(ty) if name.is_some() => format!("{}({})", ty, e),
+                                    _ => e,
+                                }
+                            })
                             .unwrap_or_else(|| "(Error: missing member in object)".to_owned())
                     })
                     .collect::<Vec<String>>();
-                format!("std::make_tuple({})", elem.join(", "))
+                if let Some(name) = name {
+                    format!("{}{{{}}}", name, elem.join(", "))
+                } else {
+                    format!("std::make_tuple({})", elem.join(", "))
+                }
             } else {
                 panic!("Expression::Object is not a Type::Object")
             }
@@ -1088,10 +1516,31 @@ fn compile_expression(e: &crate::expression_tree::Expression, component: &Rc<Com
             "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::CubicBezier, {}, {}, {}, {})",
             a, b, c, d
         ),
+        Expression::EasingCurve(EasingCurve::EaseInElastic) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseInElastic)".into()
+        }
+        Expression::EasingCurve(EasingCurve::EaseOutElastic) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseOutElastic)".into()
+        }
+        Expression::EasingCurve(EasingCurve::EaseInOutElastic) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseInOutElastic)".into()
+        }
+        Expression::EasingCurve(EasingCurve::EaseInBounce) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseInBounce)".into()
+        }
+        Expression::EasingCurve(EasingCurve::EaseOutBounce) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseOutBounce)".into()
+        }
+        Expression::EasingCurve(EasingCurve::EaseInOutBounce) => {
+            "sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::EaseInOutBounce)".into()
+        }
+        Expression::EasingCurve(EasingCurve::Steps(steps)) => {
+            format!("sixtyfps::EasingCurve(sixtyfps::EasingCurve::Tag::Steps, {})", steps)
+        }
         Expression::EnumerationValue(value) => {
             format!("sixtyfps::{}::{}", value.enumeration.name, value.to_string())
         }
-        Expression::Uncompiled(_) => panic!(),
+        Expression::Uncompiled(_) | Expression::TwoWayBinding(..) => panic!(),
         Expression::Invalid => format!("\n#error invalid expression\n"),
     }
 }
@@ -1122,6 +1571,28 @@ impl LayoutItemCodeGen<CppLanguageLayoutGen> for Layout {
                 "sixtyfps::grid_layout_info(&{}, {}, &{})",
                 cell_ref_variable, spacing, padding
             ),
+            LayoutTreeItem::BoxLayout { layout, spacing, cell_ref_variable, padding, .. }
+                if layout.wrap =>
+            {
+                format!(
+                    "sixtyfps::flow_layout_info(&{}, {}, &{}, {})",
+                    cell_ref_variable,
+                    spacing,
+                    padding,
+                    compile_expression(&layout.rect.width_reference, component)
+                )
+            }
+            LayoutTreeItem::BoxLayout {
+                layout,
+                spacing,
+                cell_ref_variable,
+                padding,
+                alignment,
+                ..
+            } => format!(
+                "sixtyfps::box_layout_info(&{}, {}, &{}, {}, {})",
+                cell_ref_variable, spacing, padding, alignment, layout.is_horizontal
+            ),
             LayoutTreeItem::PathLayout(_) => todo!(),
         }
     }
@@ -1160,6 +1631,8 @@ impl LayoutItemCodeGen<CppLanguageLayoutGen> for LayoutElement {
                             std::min(layout_info.max_width, element_info.max_width),
                             std::max(layout_info.min_height, element_info.min_height),
                             std::min(layout_info.max_height, element_info.max_height),
+                            element_info.horizontal_stretch,
+                            element_info.vertical_stretch,
                         }};
                 }}()"#,
                     layout_info, element_info
@@ -1170,6 +1643,202 @@ impl LayoutItemCodeGen<CppLanguageLayoutGen> for LayoutElement {
     }
 }
 
+/// Returns the layout info of an item in a layout, with the constraints explicitly set on it
+fn layout_info_with_constraints<'a>(
+    item: &'a LayoutItem,
+    constraints: &LayoutItemConstraints,
+    layout_tree: &mut Vec<LayoutTreeItem<'a>>,
+    component: &Rc<Component>,
+) -> String {
+    let mut layout_info = item.get_layout_info_ref(layout_tree, component);
+    if constraints.has_explicit_restrictions() {
+        layout_info = format!("[&]{{ auto layout_info = {};", layout_info);
+        for (expr, name) in constraints.for_each_restrictions().iter() {
+            if let Some(e) = expr {
+                layout_info +=
+                    &format!(" layout_info.{} = {};", name, compile_expression(&e, component));
+            }
+        }
+        layout_info += " return layout_info; }()";
+    }
+    layout_info
+}
+
+/// Adds the declaration of the spacing and the padding variables of a layout to the creation
+/// code, and returns the code to access them.
+fn compile_spacing_and_padding(
+    spacing: Option<&Expression>,
+    padding: &Padding,
+    layout_index: usize,
+    component: &Rc<Component>,
+    creation_code: &mut Vec<String>,
+) -> (String, String) {
+    let spacing = if let Some(spacing) = spacing {
+        let variable = format!("spacing_{}", layout_index);
+        creation_code.push(format!(
+            "auto {} = {};",
+            variable,
+            compile_expression(spacing, component)
+        ));
+        variable
+    } else {
+        "0.".into()
+    };
+
+    let padding_variable = format!("padding_{}", layout_index);
+    let padding_prop = |expr| {
+        if let Some(expr) = expr {
+            compile_expression(expr, component)
+        } else {
+            "0.".into()
+        }
+    };
+    creation_code.push(format!(
+        "sixtyfps::Padding {} = {{ {}, {}, {}, {} }};",
+        padding_variable,
+        padding_prop(padding.left.as_ref()),
+        padding_prop(padding.right.as_ref()),
+        padding_prop(padding.top.as_ref()),
+        padding_prop(padding.bottom.as_ref()),
+    ));
+    (spacing, padding_variable)
+}
+
+/// Returns the code that adds a BoxLayoutCellData to the `vector` for each instance of the
+/// repeated element
+fn push_repeated_box_layout_cells(elem: &ElementRc, vector: &str) -> String {
+    let sub_component = elem.borrow().base_type.as_component().clone();
+    let root_element = sub_component.root_element.clone();
+    let root_id = root_element.borrow().id.clone();
+    let (vtable_symbol, class_name) = {
+        let root_element = root_element.borrow();
+        let native = root_element.base_type.as_native();
+        (native.vtable_symbol.clone(), native.class_name.clone())
+    };
+
+    let prop_ref = |n: &str| {
+        if root_element.borrow().lookup_property(n) == Type::Length {
+            format!("&sub_comp->{}.{}", root_id, n)
+        } else {
+            "nullptr".to_owned()
+        }
+    };
+    let mut constraints = String::new();
+    for (name, prop) in &[
+        ("min_width", "minimum_width"),
+        ("max_width", "maximum_width"),
+        ("min_height", "minimum_height"),
+        ("max_height", "maximum_height"),
+        ("horizontal_stretch", "horizontal_stretch"),
+        ("vertical_stretch", "vertical_stretch"),
+    ] {
+        if root_element.borrow().property_declarations.contains_key(*prop) {
+            constraints += &format!(
+                " layout_info.{} = {}.get();",
+                name,
+                access_member(&root_element, prop, &sub_component, "sub_comp")
+            );
+        }
+    }
+
+    // The data of the HeightForWidth is the Text item, which lives as long as the instance
+    let height_for_width = if crate::layout::is_wrapped_text(&root_element) {
+        format!(
+            "{{ [](const void *text, float width) {{ return sixtyfps::private_api::text_height_for_width(*static_cast<const sixtyfps::Text *>(text), width); }}, &sub_comp->{} }}",
+            root_id
+        )
+    } else {
+        "{}".to_owned()
+    };
+
+    // Make sure the instances exist and match the model before reading them
+    let update = if elem.borrow().repeated.as_ref().map_or(false, |r| !r.model.is_constant()) {
+        format!("self->update_repeater_{}();\n    ", elem.borrow().id)
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"{update}for (auto &&sub_comp : self->repeater_{id}.data) {{
+        auto layout_info = sixtyfps::private_api::{vt}.layouting_info({{&sixtyfps::private_api::{vt}, const_cast<sixtyfps::{ty}*>(&sub_comp->{root_id})}});{constraints}
+        {vector}.push_back({{ layout_info, {x}, {y}, {w}, {h}, {hfw} }});
+    }}"#,
+        update = update,
+        id = elem.borrow().id,
+        vt = vtable_symbol,
+        ty = class_name,
+        root_id = root_id,
+        constraints = constraints,
+        vector = vector,
+        x = prop_ref("x"),
+        y = prop_ref("y"),
+        w = prop_ref("width"),
+        h = prop_ref("height"),
+        hfw = height_for_width,
+    )
+}
+
+/// Returns the code that declares a lambda computing the minimum height of the item for a
+/// given width, and the HeightForWidth that calls it. Returns None when the height of the item
+/// does not depend on its width.
+fn height_for_width(item: &LayoutItem, layout_tree: &[LayoutTreeItem]) -> Option<(String, String)> {
+    if !item.has_height_for_width() {
+        return None;
+    }
+    let (variable, function) = match (item, item.layout()) {
+        (LayoutItem::Element(LayoutElement { element, .. }), None) => {
+            let id = element.borrow().id.clone();
+            (
+                format!("height_for_width_{}", id),
+                format!("sixtyfps::private_api::text_height_for_width(self->{}, width)", id),
+            )
+        }
+        (_, layout) => {
+            let layout = layout?;
+            layout_tree.iter().enumerate().find_map(|(index, tree_item)| {
+                let function = match (tree_item, layout) {
+                    (
+                        LayoutTreeItem::GridLayout { grid, cell_ref_variable, spacing, padding, .. },
+                        Layout::GridLayout(layout),
+                    ) if std::ptr::eq(*grid, layout) => format!(
+                        "sixtyfps::grid_layout_height_for_width(&{}, {}, &{}, width)",
+                        cell_ref_variable, spacing, padding
+                    ),
+                    (
+                        LayoutTreeItem::BoxLayout {
+                            layout: box_layout,
+                            cell_ref_variable,
+                            spacing,
+                            padding,
+                            alignment,
+                            ..
+                        },
+                        Layout::BoxLayout(layout),
+                    ) if std::ptr::eq(*box_layout, layout) => {
+                        if layout.wrap {
+                            format!(
+                                "sixtyfps::flow_layout_info(&{}, {}, &{}, width).min_height",
+                                cell_ref_variable, spacing, padding
+                            )
+                        } else {
+                            format!(
+                                "sixtyfps::box_layout_height_for_width(&{}, {}, &{}, {}, {}, width)",
+                                cell_ref_variable, spacing, padding, alignment, layout.is_horizontal
+                            )
+                        }
+                    }
+                    _ => return None,
+                };
+                Some((format!("height_for_width_{}", index), function))
+            })?
+        }
+    };
+    Some((
+        format!("    auto {} = [&](float width) {{ return {}; }};", variable, function),
+        format!("sixtyfps::private_api::make_height_for_width({})", variable),
+    ))
+}
+
 fn collect_layouts_recursively<'a, 'b>(
     layout_tree: &'b mut Vec<LayoutTreeItem<'a>>,
     layout: &'a Layout,
@@ -1178,35 +1847,43 @@ fn collect_layouts_recursively<'a, 'b>(
     match layout {
         Layout::GridLayout(grid_layout) => {
             let mut creation_code = Vec::new();
+            let mut height_for_width_code = Vec::new();
 
             for cell in &grid_layout.elems {
-                let mut layout_info = cell.item.get_layout_info_ref(layout_tree, component);
-                if cell.has_explicit_restrictions() {
-                    layout_info = format!("[&]{{ auto layout_info = {};", layout_info);
-                    for (expr, name) in cell.for_each_restrictions().iter() {
-                        if let Some(e) = expr {
-                            layout_info += &format!(
-                                " layout_info.{} = {};",
-                                name,
-                                compile_expression(&e, component)
-                            );
-                        }
+                let layout_info = layout_info_with_constraints(
+                    &cell.item,
+                    &cell.constraints,
+                    layout_tree,
+                    component,
+                );
+
+                let alignment = |expr: &Option<Box<Expression>>| match expr {
+                    Some(e) => compile_expression(&e, component),
+                    None => "sixtyfps::CellAlignment::stretch".to_owned(),
+                };
+                let height_for_width = match height_for_width(&cell.item, layout_tree) {
+                    Some((code, height_for_width)) => {
+                        height_for_width_code.push(code);
+                        height_for_width
                     }
-                    layout_info += " return layout_info; }()";
-                }
+                    None => "{}".to_owned(),
+                };
 
                 let get_property_ref = LayoutItemCodeGen::<CppLanguageLayoutGen>::get_property_ref;
                 creation_code.push(format!(
-                    "        {{ {c}, {r}, {cs}, {rs}, {li}, {x}, {y}, {w}, {h} }},",
+                    "        {{ {c}, {r}, {cs}, {rs}, {li}, {ha}, {va}, {x}, {y}, {w}, {h}, {hfw} }},",
                     c = cell.col,
                     r = cell.row,
                     cs = cell.colspan,
                     rs = cell.rowspan,
                     li = layout_info,
+                    ha = alignment(&cell.horizontal_alignment),
+                    va = alignment(&cell.vertical_alignment),
                     x = get_property_ref(&cell.item, "x"),
                     y = get_property_ref(&cell.item, "y"),
                     w = get_property_ref(&cell.item, "width"),
-                    h = get_property_ref(&cell.item, "height")
+                    h = get_property_ref(&cell.item, "height"),
+                    hfw = height_for_width,
                 ));
             }
             let cell_ref_variable = format!("cells_{}", layout_tree.len()).to_owned();
@@ -1214,44 +1891,114 @@ fn collect_layouts_recursively<'a, 'b>(
                 0,
                 format!("    sixtyfps::GridLayoutCellData {}_data[] = {{", cell_ref_variable,),
             );
+            creation_code.splice(0..0, height_for_width_code);
             creation_code.push("    };".to_owned());
             creation_code.push(format!(
                 "    const sixtyfps::Slice<sixtyfps::GridLayoutCellData> {cv}{{{cv}_data, std::size({cv}_data)}};",
                 cv = cell_ref_variable
             ));
-            let spacing = if let Some(spacing) = &grid_layout.spacing {
-                let variable = format!("spacing_{}", layout_tree.len());
+            let (spacing, padding) = compile_spacing_and_padding(
+                grid_layout.spacing.as_ref(),
+                &grid_layout.padding,
+                layout_tree.len(),
+                component,
+                &mut creation_code,
+            );
+
+            layout_tree.push(LayoutTreeItem::GridLayout {
+                grid: grid_layout,
+                spacing,
+                padding,
+                var_creation_code: creation_code.join("\n"),
+                cell_ref_variable,
+            })
+        }
+        Layout::BoxLayout(box_layout) => {
+            let cell_ref_variable = format!("cells_{}", layout_tree.len());
+            let is_static = box_layout.is_static();
+            let mut creation_code = Vec::new();
+            let mut height_for_width_code = Vec::new();
+            if is_static {
                 creation_code.push(format!(
-                    "auto {} = {};",
-                    variable,
-                    compile_expression(spacing, component)
+                    "    sixtyfps::BoxLayoutCellData {}_data[] = {{",
+                    cell_ref_variable
                 ));
-                variable
             } else {
-                "0.".into()
-            };
+                creation_code.push(format!(
+                    "    std::vector<sixtyfps::BoxLayoutCellData> {}_data;",
+                    cell_ref_variable
+                ));
+            }
 
-            let padding = format!("padding_{}", layout_tree.len());
-            let padding_prop = |expr| {
-                if let Some(expr) = expr {
-                    compile_expression(expr, component)
+            for cell in &box_layout.elems {
+                if let Some(elem) = cell.repeated_element() {
+                    creation_code.push(push_repeated_box_layout_cells(
+                        elem,
+                        &format!("{}_data", cell_ref_variable),
+                    ));
+                    continue;
+                }
+                let layout_info = layout_info_with_constraints(
+                    &cell.item,
+                    &cell.constraints,
+                    layout_tree,
+                    component,
+                );
+                let height_for_width = match height_for_width(&cell.item, layout_tree) {
+                    Some((code, height_for_width)) => {
+                        height_for_width_code.push(code);
+                        height_for_width
+                    }
+                    None => "{}".to_owned(),
+                };
+                let get_property_ref = LayoutItemCodeGen::<CppLanguageLayoutGen>::get_property_ref;
+                let cell_data = format!(
+                    "{{ {li}, {x}, {y}, {w}, {h}, {hfw} }}",
+                    li = layout_info,
+                    x = get_property_ref(&cell.item, "x"),
+                    y = get_property_ref(&cell.item, "y"),
+                    w = get_property_ref(&cell.item, "width"),
+                    h = get_property_ref(&cell.item, "height"),
+                    hfw = height_for_width,
+                );
+                if is_static {
+                    creation_code.push(format!("        {},", cell_data));
                 } else {
-                    "0.".into()
+                    creation_code
+                        .push(format!("    {}_data.push_back({});", cell_ref_variable, cell_data));
                 }
-            };
+            }
+            if is_static {
+                creation_code.push("    };".to_owned());
+            }
+            creation_code.splice(0..0, height_for_width_code);
             creation_code.push(format!(
-                "sixtyfps::Padding {} = {{ {}, {}, {}, {} }};",
-                padding,
-                padding_prop(grid_layout.padding.left.as_ref()),
-                padding_prop(grid_layout.padding.right.as_ref()),
-                padding_prop(grid_layout.padding.top.as_ref()),
-                padding_prop(grid_layout.padding.bottom.as_ref()),
+                "    const sixtyfps::Slice<sixtyfps::BoxLayoutCellData> {cv}{{std::data({cv}_data), std::size({cv}_data)}};",
+                cv = cell_ref_variable
             ));
 
-            layout_tree.push(LayoutTreeItem::GridLayout {
-                grid: grid_layout,
+            let (spacing, padding) = compile_spacing_and_padding(
+                box_layout.spacing.as_ref(),
+                &box_layout.padding,
+                layout_tree.len(),
+                component,
+                &mut creation_code,
+            );
+            let alignment = format!("alignment_{}", layout_tree.len());
+            creation_code.push(format!(
+                "auto {} = {};",
+                alignment,
+                box_layout.alignment.as_ref().map_or_else(
+                    || "sixtyfps::LayoutAlignment::stretch".to_owned(),
+                    |expr| compile_expression(expr, component)
+                )
+            ));
+
+            layout_tree.push(LayoutTreeItem::BoxLayout {
+                layout: box_layout,
                 spacing,
                 padding,
+                alignment,
                 var_creation_code: creation_code.join("\n"),
                 cell_ref_variable,
             })
@@ -1287,6 +2034,44 @@ impl<'a> LayoutTreeItem<'a> {
                 code_stream.push("    sixtyfps::solve_grid_layout(&grid);".to_owned());
                 code_stream.push("    } ".into());
             }
+            LayoutTreeItem::BoxLayout {
+                layout,
+                spacing,
+                cell_ref_variable,
+                padding,
+                alignment,
+                ..
+            } => {
+                code_stream.push("    { ".into());
+                code_stream.push(format!(
+                    "    auto width = {};",
+                    compile_expression(&layout.rect.width_reference, component)
+                ));
+                code_stream.push(format!(
+                    "    auto height = {};",
+                    compile_expression(&layout.rect.height_reference, component)
+                ));
+                code_stream.push("    sixtyfps::BoxLayoutData box { ".into());
+                code_stream.push(format!(
+                    "        width, height, {}, {}, {}, &{}, {},",
+                    compile_expression(&layout.rect.x_reference, component),
+                    compile_expression(&layout.rect.y_reference, component),
+                    spacing,
+                    padding,
+                    alignment,
+                ));
+                code_stream.push(format!("        {cv}", cv = cell_ref_variable).to_owned());
+                code_stream.push("    };".to_owned());
+                if layout.wrap {
+                    code_stream.push("    sixtyfps::solve_flow_layout(&box);".to_owned());
+                } else {
+                    code_stream.push(format!(
+                        "    sixtyfps::solve_box_layout(&box, {});",
+                        layout.is_horizontal
+                    ));
+                }
+                code_stream.push("    } ".into());
+            }
             LayoutTreeItem::PathLayout(path_layout) => {
                 code_stream.push("{".to_owned());
 
@@ -1416,7 +2201,10 @@ fn compute_layout(component: &Rc<Component>) -> Vec<String> {
         collect_layouts_recursively(&mut inverse_layout_tree, layout, component);
 
         res.extend(inverse_layout_tree.iter().filter_map(|layout| match layout {
-            LayoutTreeItem::GridLayout { var_creation_code, .. } => Some(var_creation_code.clone()),
+            LayoutTreeItem::GridLayout { var_creation_code, .. }
+            | LayoutTreeItem::BoxLayout { var_creation_code, .. } => {
+                Some(var_creation_code.clone())
+            }
             LayoutTreeItem::PathLayout(_) => None,
         }));
 
@@ -14,8 +14,10 @@ use crate::diagnostics::{BuildDiagnostics, CompilerDiagnostic, Spanned};
 use crate::expression_tree::{
     BuiltinFunction, EasingCurve, Expression, NamedReference, OperatorClass, Path,
 };
-use crate::layout::{gen::LayoutItemCodeGen, Layout, LayoutElement};
-use crate::object_tree::{Component, ElementRc};
+use crate::layout::{
+    gen::LayoutItemCodeGen, Layout, LayoutElement, LayoutItem, LayoutItemConstraints, Padding,
+};
+use crate::object_tree::{Component, ElementRc, PropertyAnimation};
 use crate::typeregister::Type;
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -35,14 +37,26 @@ fn rust_type(
         Type::LogicalLength => Ok(quote!(f32)),
         Type::Bool => Ok(quote!(bool)),
         Type::Resource => Ok(quote!(sixtyfps::re_exports::Resource)),
-        Type::Object(o) => {
-            let elem = o.values().map(|v| rust_type(v, span)).collect::<Result<Vec<_>, _>>()?;
+        Type::Object { .. } if *ty == Type::state_info() => {
+            Ok(quote!(sixtyfps::re_exports::StateInfo))
+        }
+        Type::Object { name: Some(name), .. } => {
+            let name = quote::format_ident!("{}", name);
+            Ok(quote!(#name))
+        }
+        Type::Object { fields, name: None } => {
+            let elem =
+                fields.values().map(|v| rust_type(v, span)).collect::<Result<Vec<_>, _>>()?;
             // This will produce a tuple
             Ok(quote!((#(#elem,)*)))
         }
         Type::Array(o) => {
             let inner = rust_type(&o, span)?;
-            Ok(quote!(sixtyfps::re_exports::SharedArray<#inner>))
+            Ok(quote!(sixtyfps::re_exports::ModelHandle<#inner>))
+        }
+        Type::Enumeration(e) => {
+            let e = quote::format_ident!("{}", e.name);
+            Ok(quote!(sixtyfps::re_exports::#e))
         }
         _ => Err(CompilerDiagnostic {
             message: format!("Cannot map property type {} to Rust", ty),
@@ -69,16 +83,98 @@ pub fn generate(component: &Rc<Component>, diag: &mut BuildDiagnostics) -> Optio
         env!("CARGO_PKG_VERSION_MINOR"),
         env!("CARGO_PKG_VERSION_PATCH"),
     );
+    let used_structs = component.used_structs.borrow();
+    let struct_names = used_structs.iter().filter_map(|ty| match ty {
+        Type::Object { name: Some(name), .. } => Some(quote::format_ident!("{}", name)),
+        _ => None,
+    });
+    let structs = used_structs.iter().filter_map(|ty| generate_struct(ty, diag));
+    let used_globals = component.used_globals.borrow();
+    let global_names = used_globals.iter().map(|g| component_id(g));
+    let globals = used_globals.iter().filter_map(|g| generate_global(g, diag)).collect::<Vec<_>>();
     Some(quote! {
         #[allow(non_snake_case)]
         mod #compo_module {
+             #(#structs)*
+             #(#globals)*
              #compo
              const _THE_SAME_VERSION_MUST_BE_USED_FOR_THE_COMPILER_AND_THE_RUNTIME : sixtyfps::#version_check = sixtyfps::#version_check;
         }
-        pub use #compo_module::#compo_id;
+        pub use #compo_module::{#compo_id #(, #struct_names)* #(, #global_names)* };
+    })
+}
+
+/// Generate the definition of a struct declared with `struct` in the .60 file
+fn generate_struct(ty: &Type, diag: &mut BuildDiagnostics) -> Option<TokenStream> {
+    let (fields, name) = match ty {
+        Type::Object { fields, name: Some(name) } => (fields, name),
+        _ => return None,
+    };
+    let name = quote::format_ident!("{}", name);
+    let field_names = fields.keys().map(|k| quote::format_ident!("{}", k));
+    let field_types = fields.values().map(|t| {
+        rust_type(t, &Default::default()).unwrap_or_else(|err| {
+            diag.push_internal_error(err.into());
+            quote!(())
+        })
+    });
+    Some(quote! {
+        #[derive(Default, PartialEq, Debug, Clone)]
+        pub struct #name {
+            #(pub #field_names : #field_types),*
+        }
     })
 }
 
+/// Generate the struct for a component declared with `global`. It is instantiated once by
+/// the root component.
+fn generate_global(global: &Rc<Component>, diag: &mut BuildDiagnostics) -> Option<TokenStream> {
+    let mut declared_property_vars = vec![];
+    let mut declared_property_types = vec![];
+    let mut declared_signals = vec![];
+    let mut declared_signals_types = vec![];
+    let mut property_and_signal_accessors: Vec<TokenStream> = vec![];
+    declare_properties_and_signals(
+        global,
+        &mut declared_property_vars,
+        &mut declared_property_types,
+        &mut declared_signals,
+        &mut declared_signals_types,
+        &mut property_and_signal_accessors,
+        diag,
+    );
+
+    let mut init = Vec::new();
+    handle_bindings(&global.root_element, global, &mut init);
+
+    if diag.has_error() {
+        return None;
+    }
+
+    let global_id = component_id(global);
+    Some(quote!(
+        #[derive(sixtyfps::re_exports::FieldOffsets, Default)]
+        #[const_field_offset(sixtyfps::re_exports::const_field_offset)]
+        #[repr(C)]
+        #[pin]
+        pub struct #global_id {
+            #(#declared_property_vars : sixtyfps::re_exports::Property<#declared_property_types>,)*
+            #(#declared_signals : sixtyfps::re_exports::Signal<#declared_signals_types>,)*
+        }
+
+        impl #global_id {
+            fn new() -> core::pin::Pin<std::rc::Rc<Self>> {
+                #![allow(unused)]
+                use sixtyfps::re_exports::*;
+                let self_pinned = std::rc::Rc::pin(Self::default());
+                #(#init)*
+                self_pinned
+            }
+            #(#property_and_signal_accessors)*
+        }
+    ))
+}
+
 /// Generate the rust code for the given component.
 ///
 /// Fill the diagnostic in case of error.
@@ -90,75 +186,17 @@ fn generate_component(
     let mut declared_property_vars = vec![];
     let mut declared_property_types = vec![];
     let mut declared_signals = vec![];
+    let mut declared_signals_types = vec![];
     let mut property_and_signal_accessors: Vec<TokenStream> = vec![];
-    for (prop_name, property_decl) in component.root_element.borrow().property_declarations.iter() {
-        let prop_ident = quote::format_ident!("{}", prop_name);
-        if property_decl.property_type == Type::Signal {
-            declared_signals.push(prop_ident.clone());
-            if property_decl.expose_in_public_api {
-                let emitter_ident = quote::format_ident!("emit_{}", prop_name);
-                property_and_signal_accessors.push(
-                    quote!(
-                        #[allow(dead_code)]
-                        pub fn #emitter_ident(self: ::core::pin::Pin<&Self>) {
-                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).emit(())
-                        }
-                    )
-                    .into(),
-                );
-                let on_ident = quote::format_ident!("on_{}", prop_name);
-                property_and_signal_accessors.push(
-                    quote!(
-                        #[allow(dead_code)]
-                        pub fn #on_ident(self: ::core::pin::Pin<&Self>, f: impl Fn() + 'static) {
-                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).set_handler(move |()|f())
-                        }
-                    )
-                    .into(),
-                );
-            }
-        } else {
-            declared_property_vars.push(prop_ident.clone());
-            let rust_property_type =
-                rust_type(&property_decl.property_type, &property_decl.type_node.span())
-                    .unwrap_or_else(|err| {
-                        diag.push_internal_error(err.into());
-                        quote!().into()
-                    });
-            declared_property_types.push(rust_property_type.clone());
-
-            if property_decl.expose_in_public_api {
-                let getter_ident = quote::format_ident!("get_{}", prop_name);
-                let setter_ident = quote::format_ident!("set_{}", prop_name);
-
-                property_and_signal_accessors.push(
-                    quote!(
-                        #[allow(dead_code)]
-                        pub fn #getter_ident(self: ::core::pin::Pin<&Self>) -> #rust_property_type {
-                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).get()
-                        }
-                    )
-                    .into(),
-                );
-
-                let set_value = property_set_value_tokens(
-                    component,
-                    &component.root_element,
-                    prop_name,
-                    quote!(value),
-                );
-                property_and_signal_accessors.push(
-                    quote!(
-                        #[allow(dead_code)]
-                        pub fn #setter_ident(&self, value: #rust_property_type) {
-                            Self::FIELD_OFFSETS.#prop_ident.apply(self).#set_value
-                        }
-                    )
-                    .into(),
-                );
-            }
-        }
-    }
+    declare_properties_and_signals(
+        component,
+        &mut declared_property_vars,
+        &mut declared_property_types,
+        &mut declared_signals,
+        &mut declared_signals_types,
+        &mut property_and_signal_accessors,
+        diag,
+    );
 
     if diag.has_error() {
         return None;
@@ -173,10 +211,14 @@ fn generate_component(
     let mut repeated_element_components = Vec::new();
     let mut repeated_dynmodel_names = Vec::new();
     let mut repeated_visit_branch = Vec::new();
+    let mut repeater_update_functions = Vec::new();
     let mut repeated_input_branch = Vec::new();
     let mut init = Vec::new();
+    let mut timer_init = Vec::new();
     let mut maybe_window_field_decl = None;
     let mut maybe_window_field_init = None;
+    let mut global_field_names = Vec::new();
+    let mut global_types = Vec::new();
     super::build_array_helper(component, |item_rc, children_index, is_flickable_rect| {
         let item = item_rc.borrow();
         if is_flickable_rect {
@@ -208,6 +250,7 @@ fn generate_component(
                      impl sixtyfps::re_exports::RepeatedComponent for #rep_component_id {
                         type Data = ();
                         fn update(&self, _: usize, _: Self::Data) { }
+                        fn update_index(&self, _: usize) { }
                     }
                 }
             } else {
@@ -227,6 +270,9 @@ fn generate_component(
                             self.index.set(index);
                             self.model_data.set(data)
                         }
+                        fn update_index(&self, index: usize) {
+                            self.index.set(index);
+                        }
                     }
                 }
             });
@@ -236,33 +282,72 @@ fn generate_component(
                 model = quote!((if #model {Some(())} else {None}).iter().cloned())
             }
 
-            if repeated.model.is_constant() {
+            // Arrays are models that notify the repeater of their changes
+            let is_array_model = matches!(&repeated.model,
+                Expression::Cast { from, .. } if matches!(from.ty(), Type::Array(_)));
+
+            let update_fn = quote::format_ident!("update_{}", repeater_id);
+            let mut update_code = quote!();
+            if is_array_model {
+                let ensure_updated = quote! {
+                    self_pinned.#repeater_id.ensure_updated(|| {
+                        #rep_component_id::new(self_pinned.self_weak.get().unwrap().clone())
+                    });
+                };
+                if repeated.model.is_constant() {
+                    init.push(quote! {
+                        self_pinned.#repeater_id.set_model(#model);
+                    });
+                    update_code = ensure_updated;
+                } else {
+                    let model_name = quote::format_ident!("model_{}", repeater_index);
+                    update_code = quote! {
+                        if self_pinned.#model_name.is_dirty() {
+                            #component_id::FIELD_OFFSETS.#model_name.apply_pin(self_pinned).evaluate(|| {
+                                let _self = self_pinned.clone();
+                                self_pinned.#repeater_id.set_model(#model);
+                            });
+                        }
+                        #ensure_updated
+                    };
+                    repeated_dynmodel_names.push(model_name);
+                }
+            } else if repeated.model.is_constant() {
                 init.push(quote! {
                     self_pinned.#repeater_id.update_model(#model, || {
                         #rep_component_id::new(self_pinned.self_weak.get().unwrap().clone())
                     });
                 });
-                repeated_visit_branch.push(quote!(
-                    #repeater_index => self_pinned.#repeater_id.visit(order, visitor),
-                ));
             } else {
                 let model_name = quote::format_ident!("model_{}", repeater_index);
-                repeated_visit_branch.push(quote!(
-                    #repeater_index => {
-                        if self_pinned.#model_name.is_dirty() {
-                            #component_id::FIELD_OFFSETS.#model_name.apply_pin(self_pinned).evaluate(|| {
-                                let _self = self_pinned.clone();
-                                self_pinned.#repeater_id.update_model(#model, || {
-                                    #rep_component_id::new(self_pinned.self_weak.get().unwrap().clone())
-                                });
+                update_code = quote! {
+                    if self_pinned.#model_name.is_dirty() {
+                        #component_id::FIELD_OFFSETS.#model_name.apply_pin(self_pinned).evaluate(|| {
+                            let _self = self_pinned.clone();
+                            self_pinned.#repeater_id.update_model(#model, || {
+                                #rep_component_id::new(self_pinned.self_weak.get().unwrap().clone())
                             });
-                        }
-                        self_pinned.#repeater_id.visit(order, visitor)
+                        });
                     }
-                ));
+                };
                 repeated_dynmodel_names.push(model_name);
             }
 
+            // The repeater is brought up to date before it is visited or laid out
+            repeater_update_functions.push(quote!(
+                fn #update_fn(self: ::core::pin::Pin<&Self>) {
+                    #![allow(unused)]
+                    let self_pinned = self;
+                    #update_code
+                }
+            ));
+            repeated_visit_branch.push(quote!(
+                #repeater_index => {
+                    self_pinned.#update_fn();
+                    self_pinned.#repeater_id.visit(order, visitor)
+                }
+            ));
+
             repeated_input_branch.push(quote!(
                 #repeater_index => self.#repeater_id.input_event(rep_index, event),
             ));
@@ -287,55 +372,46 @@ fn generate_component(
                     children_index: #children_index,
                 }
             ));
-            for (k, binding_expression) in &item.bindings {
-                let rust_property_ident = quote::format_ident!("{}", k);
-                let rust_property_accessor_prefix = if item.property_declarations.contains_key(k) {
-                    proc_macro2::TokenStream::new()
-                } else {
-                    quote!(#field_name.)
-                };
-                let rust_property = quote!(#rust_property_accessor_prefix#rust_property_ident);
-                let tokens_for_expression = compile_expression(binding_expression, &component);
-
-                if matches!(item.lookup_property(k.as_str()), Type::Signal) {
-                    init.push(quote!(
-                        self_pinned.#rust_property.set_handler({
-                            let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
-                            move |()| {
-                                let self_pinned = self_weak.upgrade().unwrap();
-                                let _self = self_pinned.as_ref();
-                                #tokens_for_expression;
-                            }
-                        });
-                    ));
-                } else {
-                    let setter = if binding_expression.is_constant() {
-                        quote!(set((#tokens_for_expression) as _))
-                    } else {
-                        property_set_binding_tokens(
-                            component,
-                            &item_rc,
-                            k,
-                            quote!({
-                                let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
-                                move || {
-                                    let self_pinned = self_weak.upgrade().unwrap();
-                                    let _self = self_pinned.as_ref();
-                                    (#tokens_for_expression) as _
-                                }
-                            }),
-                        )
-                    };
-                    init.push(quote!(
-                        self_pinned.#rust_property.#setter;
-                    ));
-                }
+            handle_bindings(item_rc, component, &mut init);
+            if super::is_timer(item_rc) {
+                timer_init.push(quote!(
+                    #component_id::FIELD_OFFSETS.#field_name.apply_pin(self_pinned.as_ref()).init();
+                ));
             }
             item_names.push(field_name);
             item_types.push(quote::format_ident!("{}", item.base_type.as_native().class_name));
         }
     });
 
+    let mut change_tracker_names = Vec::new();
+    for (nr, handler) in &component.root_element.borrow().change_callbacks {
+        let change_tracker_name =
+            quote::format_ident!("change_tracker_{}", change_tracker_names.len());
+        let prop_value = compile_expression(&Expression::PropertyReference(nr.clone()), component);
+        let handler = compile_expression(handler, component);
+        init.push(quote!(
+            self_pinned.#change_tracker_name.init(
+                {
+                    let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                    move || {
+                        let self_pinned = self_weak.upgrade().unwrap();
+                        let _self = self_pinned.as_ref();
+                        #prop_value
+                    }
+                },
+                {
+                    let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                    move |_| {
+                        let self_pinned = self_weak.upgrade().unwrap();
+                        let _self = self_pinned.as_ref();
+                        #handler;
+                    }
+                },
+            );
+        ));
+        change_tracker_names.push(change_tracker_name);
+    }
+
     let resource_symbols: Vec<proc_macro2::TokenStream> = if component.embed_file_resources.get() {
         component
             .referenced_file_resources
@@ -399,6 +475,17 @@ fn generate_component(
                 sixtyfps::re_exports::PinWeak::downgrade(self)
             }
         });
+        for global in component.used_globals.borrow().iter() {
+            let global_id = component_id(global);
+            let global_field = quote::format_ident!("global_{}", global_id);
+            global_field_names.push(global_field.clone());
+            global_types.push(global_id.clone());
+            property_and_signal_accessors.push(quote! {
+                pub fn #global_field(self: core::pin::Pin<&Self>) -> core::pin::Pin<&#global_id> {
+                    self.get_ref().#global_field.as_ref()
+                }
+            });
+        }
         visibility = Some(quote!(pub));
     };
 
@@ -411,6 +498,18 @@ fn generate_component(
         return None;
     }
 
+    let root_component = root_component_expression(component);
+    let window_ref = window_ref_expression(component);
+    // Clicking on an item gives it the focus. Only the root component receives the mouse press,
+    // the repeated components get the events forwarded by their parent.
+    let focus_on_press = if component.parent_element.upgrade().is_none() {
+        Some(quote!(if let MouseEventType::MousePressed = mouse_event.what {
+            self.as_ref().window.set_focus_item_at(VRef::new_pin(self), mouse_event.pos);
+        }))
+    } else {
+        None
+    };
+
     let drop_impl = {
         let guarded_window_ref = {
             let mut root_component = component.clone();
@@ -444,12 +543,15 @@ fn generate_component(
         #visibility struct #component_id {
             #(#item_names : sixtyfps::re_exports::#item_types,)*
             #(#declared_property_vars : sixtyfps::re_exports::Property<#declared_property_types>,)*
-            #(#declared_signals : sixtyfps::re_exports::Signal<()>,)*
+            #(#declared_signals : sixtyfps::re_exports::Signal<#declared_signals_types>,)*
             #(#repeated_element_names : sixtyfps::re_exports::Repeater<#repeated_element_components>,)*
             #(#repeated_dynmodel_names : sixtyfps::re_exports::PropertyTracker,)*
+            #(#change_tracker_names : sixtyfps::re_exports::ChangeTracker,)*
             self_weak: sixtyfps::re_exports::OnceCell<sixtyfps::re_exports::PinWeak<#component_id>>,
             #(parent : sixtyfps::re_exports::PinWeak<#parent_component_type>,)*
             mouse_grabber: ::core::cell::Cell<sixtyfps::re_exports::VisitChildrenResult>,
+            mouse_hover: ::core::cell::Cell<sixtyfps::re_exports::VisitChildrenResult>,
+            #(#global_field_names : core::pin::Pin<std::rc::Rc<#global_types>>,)*
             #maybe_window_field_decl
         }
 
@@ -470,14 +572,18 @@ fn generate_component(
 
             fn input_event(self: ::core::pin::Pin<&Self>, mouse_event : sixtyfps::re_exports::MouseEvent) -> sixtyfps::re_exports::InputEventResult {
                 use sixtyfps::re_exports::*;
-                let mouse_grabber = self.mouse_grabber.get();
+                #focus_on_press
+                if let MouseEventType::MouseWheel = mouse_event.what {
+                    // The wheel goes to the item under the cursor, regardless of the mouse grab
+                    return process_ungrabbed_mouse_event(VRef::new_pin(self), mouse_event).0;
+                }
                 #[allow(unused)]
-                let (status, new_grab) = if let Some((item_index, rep_index)) = mouse_grabber.aborted_indexes() {
+                let send_to_item = |item_index: usize, rep_index: usize, mouse_event: MouseEvent| {
                     let tree = Self::item_tree();
                     let offset = item_offset(self, tree, item_index);
                     let mut event = mouse_event.clone();
                     event.pos -= offset.to_vector();
-                    let res = match tree[item_index] {
+                    match tree[item_index] {
                         ItemTreeNode::Item { item, .. } => {
                             item.apply_pin(self).as_ref().input_event(event)
                         }
@@ -487,18 +593,45 @@ fn generate_component(
                                 _ => panic!("invalid index {}", index),
                             }
                         }
-                    };
-                    match res {
-                        InputEventResult::GrabMouse => (res, mouse_grabber),
-                        _ => (res, VisitChildrenResult::CONTINUE),
                     }
-                } else {
-                    process_ungrabbed_mouse_event(VRef::new_pin(self), mouse_event)
                 };
-                self.mouse_grabber.set(new_grab);
+                let mouse_grabber = self.mouse_grabber.get();
+                if let Some((item_index, rep_index)) = mouse_grabber.aborted_indexes() {
+                    let res = send_to_item(item_index, rep_index, mouse_event);
+                    self.mouse_hover.set(mouse_grabber);
+                    if res != InputEventResult::GrabMouse {
+                        self.mouse_grabber.set(VisitChildrenResult::CONTINUE);
+                    }
+                    return res;
+                }
+                let old_hover = self.mouse_hover.get();
+                if let MouseEventType::MouseExit = mouse_event.what {
+                    // Only the hovered item needs to know that the mouse left the component
+                    self.mouse_hover.set(VisitChildrenResult::CONTINUE);
+                    return match old_hover.aborted_indexes() {
+                        Some((item_index, rep_index)) => send_to_item(item_index, rep_index, mouse_event),
+                        None => InputEventResult::EventIgnored,
+                    };
+                }
+                let (status, item) = process_ungrabbed_mouse_event(VRef::new_pin(self), mouse_event);
+                if item != old_hover {
+                    if let Some((item_index, rep_index)) = old_hover.aborted_indexes() {
+                        send_to_item(item_index, rep_index, MouseEvent::new(mouse_event.pos, MouseEventType::MouseExit));
+                    }
+                }
+                self.mouse_hover.set(item);
+                if status == InputEventResult::GrabMouse {
+                    self.mouse_grabber.set(item);
+                }
                 status
             }
 
+            fn key_event(self: ::core::pin::Pin<&Self>, event : &sixtyfps::re_exports::KeyEvent) -> sixtyfps::re_exports::KeyEventResult {
+                use sixtyfps::re_exports::*;
+                let _self = self;
+                #window_ref.dispatch_key_event(VRef::new_pin(#root_component), event)
+            }
+
             #layouts
         }
 
@@ -515,19 +648,25 @@ fn generate_component(
                     #(#declared_signals : ::core::default::Default::default(),)*
                     #(#repeated_element_names : ::core::default::Default::default(),)*
                     #(#repeated_dynmodel_names : ::core::default::Default::default(),)*
+                    #(#change_tracker_names : ::core::default::Default::default(),)*
                     self_weak : ::core::default::Default::default(),
                     #(parent : parent as sixtyfps::re_exports::PinWeak::<#parent_component_type>,)*
                     mouse_grabber: ::core::cell::Cell::new(sixtyfps::re_exports::VisitChildrenResult::CONTINUE),
+                    mouse_hover: ::core::cell::Cell::new(sixtyfps::re_exports::VisitChildrenResult::CONTINUE),
+                    #(#global_field_names : #global_types::new(),)*
                     #maybe_window_field_init
                 };
                 let self_pinned = std::rc::Rc::pin(self_);
                 self_pinned.self_weak.set(PinWeak::downgrade(self_pinned.clone())).map_err(|_|())
                     .expect("Can only be pinned once");
                 #(#init)*
+                #(#timer_init)*
                 self_pinned
             }
             #(#property_and_signal_accessors)*
 
+            #(#repeater_update_functions)*
+
             fn item_tree() -> &'static [sixtyfps::re_exports::ItemTreeNode<Self>] {
                 use sixtyfps::re_exports::*;
                 // FIXME: ideally this should be a const
@@ -543,6 +682,253 @@ fn generate_component(
     ))
 }
 
+/// Push in `init` the code that sets the bindings and signal handlers of the element
+fn handle_bindings(item_rc: &ElementRc, component: &Rc<Component>, init: &mut Vec<TokenStream>) {
+    let item = item_rc.borrow();
+    let field_name = quote::format_ident!("{}", item.id);
+    for (k, binding_expression) in &item.bindings {
+        let rust_property_ident = quote::format_ident!("{}", k);
+        let rust_property_accessor_prefix = if item.property_declarations.contains_key(k) {
+            proc_macro2::TokenStream::new()
+        } else {
+            quote!(#field_name.)
+        };
+        let rust_property = quote!(#rust_property_accessor_prefix#rust_property_ident);
+
+        if let Type::Signal { return_type, .. } = item.lookup_property(k.as_str()) {
+            let tokens_for_expression = compile_expression(binding_expression, &component);
+            let tokens_for_expression = if return_type.is_some() {
+                quote!((#tokens_for_expression) as _)
+            } else {
+                quote!(#tokens_for_expression;)
+            };
+            init.push(quote!(
+                self_pinned.#rust_property.set_handler({
+                    let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                    move |args| {
+                        let self_pinned = self_weak.upgrade().unwrap();
+                        let _self = self_pinned.as_ref();
+                        #tokens_for_expression
+                    }
+                });
+            ));
+        } else if matches!(item.lookup_property(k.as_str()), Type::Function { .. }) {
+            // Functions are generated as methods by declare_properties_and_signals
+        } else {
+            handle_property_binding(item_rc, component, k, rust_property, binding_expression, init);
+        }
+    }
+}
+
+fn handle_property_binding(
+    item_rc: &ElementRc,
+    component: &Rc<Component>,
+    prop_name: &str,
+    rust_property: TokenStream,
+    binding_expression: &Expression,
+    init: &mut Vec<TokenStream>,
+) {
+    if let Expression::TwoWayBinding(nr, next) = binding_expression {
+        let p1 = access_member(item_rc, prop_name, component, quote!(_self), false);
+        let p2 = access_member(
+            &nr.element.upgrade().unwrap(),
+            nr.name.as_str(),
+            component,
+            quote!(_self),
+            false,
+        );
+        init.push(quote!({
+            let _self = self_pinned.as_ref();
+            sixtyfps::re_exports::Property::link_two_way(#p1, #p2);
+        }));
+        if let Some(next) = next {
+            handle_property_binding(item_rc, component, prop_name, rust_property, next, init);
+        }
+        return;
+    }
+
+    let tokens_for_expression = compile_expression(binding_expression, &component);
+    if item_rc.borrow().lookup_property(prop_name) == Type::state_info() {
+        let p = access_member(item_rc, prop_name, component, quote!(_self), false);
+        init.push(quote!({
+            let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+            let _self = self_pinned.as_ref();
+            sixtyfps::re_exports::set_state_binding(#p, move || {
+                let self_pinned = self_weak.upgrade().unwrap();
+                let _self = self_pinned.as_ref();
+                (#tokens_for_expression) as _
+            });
+        }));
+        return;
+    }
+    let setter = if binding_expression.is_constant() {
+        quote!(set((#tokens_for_expression) as _))
+    } else {
+        property_set_binding_tokens(
+            component,
+            item_rc,
+            prop_name,
+            quote!({
+                let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                move || {
+                    let self_pinned = self_weak.upgrade().unwrap();
+                    let _self = self_pinned.as_ref();
+                    (#tokens_for_expression) as _
+                }
+            }),
+        )
+    };
+    init.push(quote!(
+        self_pinned.#rust_property.#setter;
+    ));
+}
+
+/// Fill the vectors with the properties and signals declared in the root element of the
+/// component, and their accessors if they are exposed in the public API
+fn declare_properties_and_signals(
+    component: &Rc<Component>,
+    declared_property_vars: &mut Vec<proc_macro2::Ident>,
+    declared_property_types: &mut Vec<TokenStream>,
+    declared_signals: &mut Vec<proc_macro2::Ident>,
+    declared_signals_types: &mut Vec<TokenStream>,
+    property_and_signal_accessors: &mut Vec<TokenStream>,
+    diag: &mut BuildDiagnostics,
+) {
+    for (prop_name, property_decl) in component.root_element.borrow().property_declarations.iter() {
+        let prop_ident = quote::format_ident!("{}", prop_name);
+        if let Type::Signal { args, return_type } = &property_decl.property_type {
+            let signal_args = args
+                .iter()
+                .map(|a| {
+                    rust_type(a, &property_decl.type_node.span()).unwrap_or_else(|err| {
+                        diag.push_internal_error(err.into());
+                        quote!()
+                    })
+                })
+                .collect::<Vec<_>>();
+            let return_type = return_type
+                .as_ref()
+                .map(|a| {
+                    rust_type(a, &property_decl.type_node.span()).unwrap_or_else(|err| {
+                        diag.push_internal_error(err.into());
+                        quote!()
+                    })
+                })
+                .unwrap_or(quote!(()));
+            declared_signals.push(prop_ident.clone());
+            declared_signals_types.push(quote!((#(#signal_args,)*), #return_type));
+            if property_decl.expose_in_public_api {
+                let args_name = (0..signal_args.len())
+                    .map(|i| quote::format_ident!("arg_{}", i))
+                    .collect::<Vec<_>>();
+                let args_index = (0..signal_args.len()).map(proc_macro2::Literal::usize_unsuffixed);
+                let emitter_ident = quote::format_ident!("emit_{}", prop_name);
+                property_and_signal_accessors.push(
+                    quote!(
+                        #[allow(dead_code)]
+                        pub fn #emitter_ident(self: ::core::pin::Pin<&Self>, #(#args_name : #signal_args,)*) -> #return_type {
+                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).emit(&(#(#args_name,)*))
+                        }
+                    )
+                    .into(),
+                );
+                let on_ident = quote::format_ident!("on_{}", prop_name);
+                property_and_signal_accessors.push(
+                    quote!(
+                        #[allow(dead_code, unused)]
+                        pub fn #on_ident(self: ::core::pin::Pin<&Self>, f: impl Fn(#(#signal_args),*) -> #return_type + 'static) {
+                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).set_handler(
+                                move |args| f(#(args.#args_index.clone()),*)
+                            )
+                        }
+                    )
+                    .into(),
+                );
+            }
+        } else if let Type::Function { args, return_type } = &property_decl.property_type {
+            let span = property_decl.type_node.span();
+            let arg_types = args
+                .iter()
+                .map(|a| {
+                    rust_type(a, &span).unwrap_or_else(|err| {
+                        diag.push_internal_error(err.into());
+                        quote!()
+                    })
+                })
+                .collect::<Vec<_>>();
+            let args_name =
+                (0..args.len()).map(|i| quote::format_ident!("arg_{}", i)).collect::<Vec<_>>();
+            let body = component
+                .root_element
+                .borrow()
+                .bindings
+                .get(prop_name)
+                .map_or_else(TokenStream::new, |e| compile_expression(e, component));
+            let (return_type, body) = if **return_type == Type::Void {
+                (quote!(()), quote!(#body;))
+            } else {
+                let ty = rust_type(return_type, &span).unwrap_or_else(|err| {
+                    diag.push_internal_error(err.into());
+                    quote!()
+                });
+                (ty, quote!((#body) as _))
+            };
+            let visibility =
+                if property_decl.expose_in_public_api { quote!(pub) } else { quote!() };
+            property_and_signal_accessors.push(quote!(
+                #[allow(dead_code)]
+                #visibility fn #prop_ident(self: ::core::pin::Pin<&Self>, #(#args_name : #arg_types,)*) -> #return_type {
+                    #![allow(unused)]
+                    use sixtyfps::re_exports::*;
+                    let _self = self;
+                    let args = (#(#args_name,)*);
+                    #body
+                }
+            ));
+        } else {
+            declared_property_vars.push(prop_ident.clone());
+            let rust_property_type =
+                rust_type(&property_decl.property_type, &property_decl.type_node.span())
+                    .unwrap_or_else(|err| {
+                        diag.push_internal_error(err.into());
+                        quote!().into()
+                    });
+            declared_property_types.push(rust_property_type.clone());
+
+            if property_decl.expose_in_public_api {
+                let getter_ident = quote::format_ident!("get_{}", prop_name);
+                let setter_ident = quote::format_ident!("set_{}", prop_name);
+
+                property_and_signal_accessors.push(
+                    quote!(
+                        #[allow(dead_code)]
+                        pub fn #getter_ident(self: ::core::pin::Pin<&Self>) -> #rust_property_type {
+                            Self::FIELD_OFFSETS.#prop_ident.apply_pin(self).get()
+                        }
+                    )
+                    .into(),
+                );
+
+                let set_value = property_set_value_tokens(
+                    component,
+                    &component.root_element,
+                    prop_name,
+                    quote!(value),
+                );
+                property_and_signal_accessors.push(
+                    quote!(
+                        #[allow(dead_code)]
+                        pub fn #setter_ident(&self, value: #rust_property_type) {
+                            Self::FIELD_OFFSETS.#prop_ident.apply(self).#set_value
+                        }
+                    )
+                    .into(),
+                );
+            }
+        }
+    }
+}
+
 /// Return an identifier suitable for this component
 fn component_id(component: &Component) -> proc_macro2::Ident {
     if component.id.is_empty() {
@@ -557,30 +943,23 @@ fn component_id(component: &Component) -> proc_macro2::Ident {
     }
 }
 
-fn property_animation_tokens(
-    component: &Rc<Component>,
-    element: &ElementRc,
-    property_name: &str,
-) -> Option<TokenStream> {
-    if let Some(animation) = element.borrow().property_animations.get(property_name) {
-        let bindings: Vec<TokenStream> = animation
-            .borrow()
-            .bindings
-            .iter()
-            .map(|(prop, initializer)| {
-                let prop_ident = quote::format_ident!("{}", prop);
-                let initializer = compile_expression(initializer, component);
-                quote!(#prop_ident: #initializer as _)
-            })
-            .collect();
+/// Returns the code that creates the `PropertyAnimation` struct for the given `animate` element
+fn animation_struct_tokens(component: &Rc<Component>, animation: &ElementRc) -> TokenStream {
+    let bindings: Vec<TokenStream> = animation
+        .borrow()
+        .bindings
+        .iter()
+        .map(|(prop, initializer)| {
+            let prop_ident = quote::format_ident!("{}", prop);
+            let initializer = compile_expression(initializer, component);
+            quote!(#prop_ident: #initializer as _)
+        })
+        .collect();
 
-        Some(quote!(&sixtyfps::re_exports::PropertyAnimation{
-            #(#bindings, )*
-            ..::core::default::Default::default()
-        }))
-    } else {
-        None
-    }
+    quote!(sixtyfps::re_exports::PropertyAnimation{
+        #(#bindings, )*
+        ..::core::default::Default::default()
+    })
 }
 
 fn property_set_value_tokens(
@@ -589,8 +968,15 @@ fn property_set_value_tokens(
     property_name: &str,
     value_tokens: TokenStream,
 ) -> TokenStream {
-    if let Some(animation_tokens) = property_animation_tokens(component, element, property_name) {
-        quote!(set_animated_value(#value_tokens, #animation_tokens))
+    let animation = match element.borrow().property_animations.get(property_name) {
+        Some(PropertyAnimation::Static(animation)) => Some(animation.clone()),
+        // The transitions only animate the bindings, setting a value uses the default animation
+        Some(PropertyAnimation::Transition { default, .. }) => default.clone(),
+        None => None,
+    };
+    if let Some(animation) = animation {
+        let animation_tokens = animation_struct_tokens(component, &animation);
+        quote!(set_animated_value(#value_tokens, &#animation_tokens))
     } else {
         quote!(set(#value_tokens))
     }
@@ -602,10 +988,48 @@ fn property_set_binding_tokens(
     property_name: &str,
     binding_tokens: TokenStream,
 ) -> TokenStream {
-    if let Some(animation_tokens) = property_animation_tokens(component, element, property_name) {
-        quote!(set_animated_binding(#binding_tokens, #animation_tokens))
-    } else {
-        quote!(set_binding(#binding_tokens))
+    match element.borrow().property_animations.get(property_name) {
+        Some(PropertyAnimation::Static(animation)) => {
+            let animation_tokens = animation_struct_tokens(component, animation);
+            if animation.borrow().bindings.values().all(|b| b.is_constant()) {
+                quote!(set_animated_binding(#binding_tokens, &#animation_tokens))
+            } else {
+                // The parameters of the animation are evaluated when the animation starts
+                quote!(set_dynamic_animated_binding(#binding_tokens, {
+                    let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                    move || {
+                        let self_pinned = self_weak.upgrade().unwrap();
+                        let _self = self_pinned.as_ref();
+                        #animation_tokens
+                    }
+                }))
+            }
+        }
+        Some(PropertyAnimation::Transition { state_ref, animations, default }) => {
+            let state_tokens = compile_expression(state_ref, component);
+            let animations = animations.iter().map(|a| {
+                let state_field =
+                    if a.is_out { quote!(previous_state) } else { quote!(current_state) };
+                let state_id = a.state_id;
+                let animation_tokens = animation_struct_tokens(component, &a.animation);
+                quote!(if state.#state_field == #state_id { return #animation_tokens; })
+            });
+            let default_tokens = default.as_ref().map_or_else(
+                || quote!(::core::default::Default::default()),
+                |default| animation_struct_tokens(component, default),
+            );
+            quote!(set_dynamic_animated_binding(#binding_tokens, {
+                let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_pinned.clone());
+                move || {
+                    let self_pinned = self_weak.upgrade().unwrap();
+                    let _self = self_pinned.as_ref();
+                    let state = #state_tokens;
+                    #(#animations)*
+                    #default_tokens
+                }
+            }))
+        }
+        None => quote!(set_binding(#binding_tokens)),
     }
 }
 
@@ -639,6 +1063,12 @@ fn access_member(
                 .apply_pin(#component_rust)
             )
         }
+    } else if enclosing_component.is_global && component.parent_element.upgrade().is_none() {
+        // The globals are owned by the root component
+        let global_id = component_id(&enclosing_component);
+        let global_field = quote::format_ident!("global_{}", global_id);
+        let name_ident = quote::format_ident!("{}", name);
+        quote!(#global_id::FIELD_OFFSETS.#name_ident.apply_pin(#component_rust.#global_field.as_ref()))
     } else {
         access_member(
             element,
@@ -657,15 +1087,54 @@ fn access_member(
     }
 }
 
-/// Return an expression that gets the window
-fn window_ref_expression(component: &Rc<Component>) -> TokenStream {
+/// Return an expression that gets the component in which the function `name` of the element is
+/// declared, followed by the name of the function, so it can be called
+fn access_function(
+    element: &ElementRc,
+    name: &str,
+    component: &Rc<Component>,
+    component_rust: TokenStream,
+) -> TokenStream {
+    let enclosing_component = element.borrow().enclosing_component.upgrade().unwrap();
+    let name_ident = quote::format_ident!("{}", name);
+    if Rc::ptr_eq(component, &enclosing_component) {
+        quote!(#component_rust.#name_ident)
+    } else if enclosing_component.is_global && component.parent_element.upgrade().is_none() {
+        let global_id = component_id(&enclosing_component);
+        let global_field = quote::format_ident!("global_{}", global_id);
+        quote!(#component_rust.#global_field.as_ref().#name_ident)
+    } else {
+        access_function(
+            element,
+            name,
+            &component
+                .parent_element
+                .upgrade()
+                .unwrap()
+                .borrow()
+                .enclosing_component
+                .upgrade()
+                .unwrap(),
+            quote!(#component_rust.parent.upgrade().unwrap().as_ref()),
+        )
+    }
+}
+
+/// Return an expression that gets the root component
+fn root_component_expression(component: &Rc<Component>) -> TokenStream {
     let mut root_component = component.clone();
     let mut component_rust = quote!(_self);
     while let Some(p) = root_component.parent_element.upgrade() {
         root_component = p.borrow().enclosing_component.upgrade().unwrap();
         component_rust = quote!(#component_rust.parent.upgrade().unwrap().as_ref());
     }
-    quote!(#component_rust.as_ref().window)
+    component_rust
+}
+
+/// Return an expression that gets the window
+fn window_ref_expression(component: &Rc<Component>) -> TokenStream {
+    let root_component = root_component_expression(component);
+    quote!(#root_component.as_ref().window)
 }
 
 fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream {
@@ -683,7 +1152,7 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
                     quote!(sixtyfps::re_exports::SharedString::from(format!("{}", #f).as_str()))
                 }
                 (Type::Float32, Type::Model) | (Type::Int32, Type::Model) => quote!((0..#f as i32)),
-                (Type::Array(_), Type::Model) => quote!(#f.iter().cloned()),
+                (Type::Array(_), Type::Model) => f,
                 (Type::Float32, Type::Color) => {
                     quote!(sixtyfps::re_exports::Color::from(#f as u32))
                 }
@@ -705,7 +1174,13 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
                 let window_ref = window_ref_expression(component);
                 quote!(#window_ref.scale_factor())
             }
+            BuiltinFunction::SetFocusItem => {
+                panic!("internal error: SetFocusItem is handled directly in the FunctionCall")
+            }
         },
+        Expression::ElementReference(_) => {
+            panic!("internal error: element references are only valid as function arguments")
+        }
         Expression::RepeaterIndexReference { element } => {
             let access = access_member(
                 &element.upgrade().unwrap().borrow().base_type.as_component().root_element,
@@ -726,18 +1201,23 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
             );
             quote!(#access.get())
         }
-        Expression::ObjectAccess { base, name } => {
-            let index = if let Type::Object(ty) = base.ty() {
-                ty.keys()
+        Expression::ObjectAccess { base, name } => match base.ty() {
+            Type::Object { fields, name: None } => {
+                let index = fields
+                    .keys()
                     .position(|k| k == name)
-                    .expect("Expression::ObjectAccess: Cannot find a key in an object")
-            } else {
-                panic!("Expression::ObjectAccess's base expression is not an Object type")
-            };
-            let index = proc_macro2::Literal::usize_unsuffixed(index);
-            let base_e = compile_expression(base, component);
-            quote!((#base_e).#index )
-        }
+                    .expect("Expression::ObjectAccess: Cannot find a key in an object");
+                let index = proc_macro2::Literal::usize_unsuffixed(index);
+                let base_e = compile_expression(base, component);
+                quote!((#base_e).#index )
+            }
+            Type::Object { .. } => {
+                let name = quote::format_ident!("{}", name);
+                let base_e = compile_expression(base, component);
+                quote!((#base_e).#name)
+            }
+            _ => panic!("Expression::ObjectAccess's base expression is not an Object type"),
+        },
         Expression::CodeBlock(sub) => {
             let map = sub.iter().map(|e| compile_expression(e, &component));
             quote!({ #(#map);* })
@@ -750,16 +1230,59 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
                 quote!(_self),
                 false,
             );
-            quote!(#access.emit(()))
+            quote!(#access.emit(&()))
         }
-        Expression::FunctionCall { function } => {
-            if matches!(function.ty(), Type::Signal | Type::Function{..}) {
-                compile_expression(function, &component)
-            } else {
-                let error = format!("the function {:?} is not a signal", e);
-                quote!(compile_error! {#error})
-            }
+        Expression::FunctionParameterReference { index, .. } => {
+            let i = proc_macro2::Literal::usize_unsuffixed(*index);
+            quote! {args.#i.clone()}
         }
+        Expression::FunctionReference(_) => {
+            panic!("internal error: function references are only valid in a function call")
+        }
+        Expression::FunctionCall { function, arguments } => match &**function {
+            Expression::BuiltinFunctionReference(BuiltinFunction::SetFocusItem) => {
+                let focus_item = match arguments.as_slice() {
+                    [Expression::ElementReference(focus_item)] => focus_item.upgrade().unwrap(),
+                    _ => panic!("internal error: SetFocusItem takes an element as argument"),
+                };
+                let item_id = focus_item.borrow().id.clone();
+                let item = access_member(&focus_item, &item_id, component, quote!(_self), true);
+                let window_ref = window_ref_expression(component);
+                let root_component = root_component_expression(component);
+                quote!(
+                    #window_ref.set_focus_item(VRef::new_pin(#root_component), VRef::new_pin(#item))
+                )
+            }
+            Expression::SignalReference(NamedReference { element, name, .. }) => {
+                let access = access_member(
+                    &element.upgrade().unwrap(),
+                    name.as_str(),
+                    component,
+                    quote!(_self),
+                    false,
+                );
+                let a = arguments.iter().map(|a| compile_expression(a, &component));
+                quote!(#access.emit(&(#((#a) as _,)*)))
+            }
+            Expression::FunctionReference(NamedReference { element, name }) => {
+                let f = access_function(
+                    &element.upgrade().unwrap(),
+                    name.as_str(),
+                    component,
+                    quote!(_self),
+                );
+                let a = arguments.iter().map(|a| compile_expression(a, &component));
+                quote!(#f(#((#a) as _),*))
+            }
+            _ => {
+                if matches!(function.ty(), Type::Function { .. }) {
+                    compile_expression(function, &component)
+                } else {
+                    let error = format!("the function {:?} is not a signal", e);
+                    quote!(compile_error! {#error})
+                }
+            }
+        },
         Expression::SelfAssignment { lhs, rhs, op } => match &**lhs {
             Expression::PropertyReference(NamedReference { element, name }) => {
                 let lhs = access_member(
@@ -851,20 +1374,27 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
         Expression::Array { values, element_ty } => {
             let rust_element_ty = rust_type(&element_ty, &Default::default()).unwrap();
             let val = values.iter().map(|e| compile_expression(e, component));
-            // FIXME: we don't need to allocate a SharedArray in the model case.
-            quote!(sixtyfps::re_exports::SharedArray::<#rust_element_ty>::from(&[#(#val as _),*]))
+            quote!(sixtyfps::re_exports::ModelHandle::new(
+                std::rc::Rc::new(sixtyfps::re_exports::VecModel::<#rust_element_ty>::from(vec![#(#val as _),*]))
+            ))
         }
         Expression::Object { ty, values } => {
-            if let Type::Object(ty) = ty {
-                let elem = ty.iter().map(|(k, t)| {
+            if let Type::Object { fields, name } = ty {
+                let elem = fields.iter().map(|(k, t)| {
                     values.get(k).map(|e| {
                         let ce = compile_expression(e, component);
                         let t = rust_type(t, &Default::default()).unwrap_or_default();
                         quote!(#ce as #t)
                     })
                 });
-                // This will produce a tuple
-                quote!((#(#elem,)*))
+                if let Some(name) = name {
+                    let name = quote::format_ident!("{}", name);
+                    let keys = fields.keys().map(|k| quote::format_ident!("{}", k));
+                    quote!(#name { #(#keys: #elem,)* })
+                } else {
+                    // This will produce a tuple
+                    quote!((#(#elem,)*))
+                }
             } else {
                 panic!("Expression::Object is not a Type::Object")
             }
@@ -885,11 +1415,35 @@ fn compile_expression(e: &Expression, component: &Rc<Component>) -> TokenStream
         Expression::EasingCurve(EasingCurve::CubicBezier(a, b, c, d)) => {
             quote!(sixtyfps::re_exports::EasingCurve::CubicBezier([#a, #b, #c, #d]))
         }
+        Expression::EasingCurve(EasingCurve::EaseInElastic) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseInElastic)
+        }
+        Expression::EasingCurve(EasingCurve::EaseOutElastic) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseOutElastic)
+        }
+        Expression::EasingCurve(EasingCurve::EaseInOutElastic) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseInOutElastic)
+        }
+        Expression::EasingCurve(EasingCurve::EaseInBounce) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseInBounce)
+        }
+        Expression::EasingCurve(EasingCurve::EaseOutBounce) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseOutBounce)
+        }
+        Expression::EasingCurve(EasingCurve::EaseInOutBounce) => {
+            quote!(sixtyfps::re_exports::EasingCurve::EaseInOutBounce)
+        }
+        Expression::EasingCurve(EasingCurve::Steps(steps)) => {
+            quote!(sixtyfps::re_exports::EasingCurve::Steps(#steps))
+        }
         Expression::EnumerationValue(value) => {
             let base_ident = quote::format_ident!("{}", value.enumeration.name);
             let value_ident = quote::format_ident!("{}", value.to_string());
             quote!(sixtyfps::re_exports::#base_ident::#value_ident)
         }
+        Expression::TwoWayBinding(..) => {
+            quote!(compile_error! {"two way binding is only supported as a binding"})
+        }
     }
 }
 
@@ -919,6 +1473,39 @@ impl LayoutItemCodeGen<RustLanguageLayoutGen> for Layout {
             LayoutTreeItem::GridLayout { cell_ref_variable, spacing, padding, .. } => {
                 quote!(grid_layout_info(&Slice::from_slice(&#cell_ref_variable), #spacing, #padding))
             }
+            LayoutTreeItem::BoxLayout {
+                layout,
+                cell_ref_variable,
+                spacing,
+                padding,
+                alignment,
+                ..
+            } if layout.wrap => {
+                let width = compile_expression(&*layout.rect.width_reference, component);
+                quote!(flow_layout_info(
+                    &Slice::from_slice(&#cell_ref_variable),
+                    #spacing,
+                    #padding,
+                    #width
+                ))
+            }
+            LayoutTreeItem::BoxLayout {
+                layout,
+                cell_ref_variable,
+                spacing,
+                padding,
+                alignment,
+                ..
+            } => {
+                let is_horizontal = layout.is_horizontal;
+                quote!(box_layout_info(
+                    &Slice::from_slice(&#cell_ref_variable),
+                    #spacing,
+                    #padding,
+                    #alignment,
+                    #is_horizontal
+                ))
+            }
             LayoutTreeItem::PathLayout(_) => todo!(),
         }
     }
@@ -951,6 +1538,230 @@ impl LayoutItemCodeGen<RustLanguageLayoutGen> for LayoutElement {
     }
 }
 
+/// Returns the layout info of an item in a layout, with the constraints explicitly set on it
+fn layout_info_with_constraints<'a>(
+    item: &'a LayoutItem,
+    constraints: &LayoutItemConstraints,
+    layout_tree: &mut Vec<LayoutTreeItem<'a>>,
+    component: &Rc<Component>,
+) -> TokenStream {
+    let layout_info = item.get_layout_info_ref(layout_tree, component);
+    if !constraints.has_explicit_restrictions() {
+        return layout_info;
+    }
+    let (name, expr): (Vec<_>, Vec<_>) = constraints
+        .for_each_restrictions()
+        .iter()
+        .filter_map(|(e, s)| {
+            e.as_ref().map(|e| (quote::format_ident!("{}", s), compile_expression(&e, component)))
+        })
+        .unzip();
+    quote!({
+        let mut layout_info = #layout_info;
+         #(layout_info.#name = #expr;)*
+        layout_info })
+}
+
+/// Returns the code for the spacing and the padding of a layout, and the code that declares
+/// the spacing variable
+fn compile_spacing_and_padding(
+    spacing: Option<&Expression>,
+    padding: &Padding,
+    layout_index: usize,
+    component: &Rc<Component>,
+) -> (TokenStream, Option<TokenStream>, TokenStream) {
+    let (spacing, spacing_creation_code) = if let Some(spacing) = spacing {
+        let variable = quote::format_ident!("spacing_{}", layout_index);
+        let spacing_code = compile_expression(spacing, component);
+        (quote!(#variable), Some(quote!(let #variable = #spacing_code;)))
+    } else {
+        (quote!(0.), None)
+    };
+    let padding_prop = |expr| {
+        if let Some(expr) = expr {
+            compile_expression(expr, component)
+        } else {
+            quote!(0.)
+        }
+    };
+    let left = padding_prop(padding.left.as_ref());
+    let right = padding_prop(padding.right.as_ref());
+    let top = padding_prop(padding.top.as_ref());
+    let bottom = padding_prop(padding.bottom.as_ref());
+    let padding = quote!(&sixtyfps::re_exports::Padding {
+        left: #left,
+        right: #right,
+        top: #top,
+        bottom: #bottom,
+    });
+    (spacing, spacing_creation_code, padding)
+}
+
+/// Returns the code that pushes a BoxLayoutCellData in the `cell_ref_variable` vector for each
+/// instance of the repeated element
+fn push_repeated_box_layout_cells(
+    elem: &ElementRc,
+    cell_ref_variable: &TokenStream,
+) -> TokenStream {
+    let repeater_id = quote::format_ident!("repeater_{}", elem.borrow().id);
+    let sub_component = elem.borrow().base_type.as_component().clone();
+    let sub_component_id = component_id(&sub_component);
+    let root_element = sub_component.root_element.clone();
+    let root_id = quote::format_ident!("{}", root_element.borrow().id);
+
+    let prop_ref = |n: &str| {
+        if root_element.borrow().lookup_property(n) == Type::Length {
+            let n = quote::format_ident!("{}", n);
+            quote! {Some(&sub_comp.#root_id.#n)}
+        } else {
+            quote! {None}
+        }
+    };
+    let (x, y, width, height) =
+        (prop_ref("x"), prop_ref("y"), prop_ref("width"), prop_ref("height"));
+
+    let (name, expr): (Vec<_>, Vec<_>) = [
+        ("min_width", "minimum_width"),
+        ("max_width", "maximum_width"),
+        ("min_height", "minimum_height"),
+        ("max_height", "maximum_height"),
+        ("horizontal_stretch", "horizontal_stretch"),
+        ("vertical_stretch", "vertical_stretch"),
+    ]
+    .iter()
+    .filter(|(_, prop)| root_element.borrow().property_declarations.contains_key(*prop))
+    .map(|(name, prop)| {
+        let accessor =
+            access_member(&root_element, prop, &sub_component, quote!(sub_comp.as_ref()), false);
+        (quote::format_ident!("{}", name), quote!(#accessor.get()))
+    })
+    .unzip();
+
+    let update_fn = quote::format_ident!("update_{}", repeater_id);
+
+    // The closures are kept in a vector that lives as long as the cells that refer to them
+    let (height_for_width_code, loop_header, height_for_width) =
+        if crate::layout::is_wrapped_text(&root_element) {
+            let height_for_width_vec = quote::format_ident!("height_for_width_{}", repeater_id);
+            (
+                quote! {
+                    let #height_for_width_vec = internal_vec.iter().map(|sub_comp| {
+                        move |width: f32| #sub_component_id::FIELD_OFFSETS.#root_id
+                            .apply_pin(sub_comp.as_ref())
+                            .height_for_width(width)
+                    }).collect::<Vec<_>>();
+                },
+                quote!((index, sub_comp) in internal_vec.iter().enumerate()),
+                quote!(HeightForWidth::new(&#height_for_width_vec[index])),
+            )
+        } else {
+            (quote!(), quote!(sub_comp in &*internal_vec), quote!(Default::default()))
+        };
+
+    quote! {
+        self.#update_fn();
+        let internal_vec = self.#repeater_id.components_vec();
+        #height_for_width_code
+        for #loop_header {
+            let mut layout_info = #sub_component_id::FIELD_OFFSETS.#root_id
+                .apply_pin(sub_comp.as_ref())
+                .layouting_info();
+            #(layout_info.#name = #expr;)*
+            #cell_ref_variable.push(BoxLayoutCellData {
+                x: #x,
+                y: #y,
+                width: #width,
+                height: #height,
+                constraint: layout_info,
+                height_for_width: #height_for_width,
+            });
+        }
+    }
+}
+
+/// Returns the code that declares a closure computing the minimum height of the item for a
+/// given width, and the HeightForWidth that calls it. Returns None when the height of the item
+/// does not depend on its width.
+fn height_for_width(
+    item: &LayoutItem,
+    layout_tree: &[LayoutTreeItem],
+) -> Option<(TokenStream, TokenStream)> {
+    if !item.has_height_for_width() {
+        return None;
+    }
+    let (variable, function) = match (item, item.layout()) {
+        (LayoutItem::Element(LayoutElement { element, .. }), None) => {
+            let e = quote::format_ident!("{}", element.borrow().id);
+            (
+                quote::format_ident!("height_for_width_{}", e),
+                quote!(|width: f32| Self::FIELD_OFFSETS.#e.apply_pin(self).height_for_width(width)),
+            )
+        }
+        (_, layout) => {
+            let layout = layout?;
+            layout_tree.iter().enumerate().find_map(|(index, tree_item)| {
+                let function = match (tree_item, layout) {
+                    (
+                        LayoutTreeItem::GridLayout {
+                            grid,
+                            cell_ref_variable,
+                            spacing,
+                            padding,
+                            ..
+                        },
+                        Layout::GridLayout(layout),
+                    ) if std::ptr::eq(*grid, layout) => quote!(|width: f32| {
+                        grid_layout_height_for_width(
+                            &Slice::from_slice(&#cell_ref_variable),
+                            #spacing,
+                            #padding,
+                            width,
+                        )
+                    }),
+                    (
+                        LayoutTreeItem::BoxLayout {
+                            layout: box_layout,
+                            cell_ref_variable,
+                            spacing,
+                            padding,
+                            alignment,
+                            ..
+                        },
+                        Layout::BoxLayout(layout),
+                    ) if std::ptr::eq(*box_layout, layout) => {
+                        if layout.wrap {
+                            quote!(|width: f32| {
+                                flow_layout_info(
+                                    &Slice::from_slice(&#cell_ref_variable),
+                                    #spacing,
+                                    #padding,
+                                    width,
+                                )
+                                .min_height
+                            })
+                        } else {
+                            let is_horizontal = layout.is_horizontal;
+                            quote!(|width: f32| {
+                                box_layout_height_for_width(
+                                    &Slice::from_slice(&#cell_ref_variable),
+                                    #spacing,
+                                    #padding,
+                                    #alignment,
+                                    #is_horizontal,
+                                    width,
+                                )
+                            })
+                        }
+                    }
+                    _ => return None,
+                };
+                Some((quote::format_ident!("height_for_width_{}", index), function))
+            })?
+        }
+    };
+    Some((quote!(let #variable = #function;), quote!(HeightForWidth::new(&#variable))))
+}
+
 fn collect_layouts_recursively<'a, 'b>(
     layout_tree: &'b mut Vec<LayoutTreeItem<'a>>,
     layout: &'a Layout,
@@ -959,6 +1770,7 @@ fn collect_layouts_recursively<'a, 'b>(
     let get_property_ref = LayoutItemCodeGen::<RustLanguageLayoutGen>::get_property_ref;
     match layout {
         Layout::GridLayout(grid_layout) => {
+            let mut height_for_width_code = vec![];
             let cells: Vec<TokenStream> = grid_layout
                 .elems
                 .iter()
@@ -969,25 +1781,25 @@ fn collect_layouts_recursively<'a, 'b>(
                     let y = get_property_ref(&cell.item, "y");
                     let (col, row, colspan, rowspan) =
                         (cell.col, cell.row, cell.colspan, cell.rowspan);
-                    let mut layout_info = cell.item.get_layout_info_ref(layout_tree, component);
-                    if cell.has_explicit_restrictions() {
-                        let (name, expr): (Vec<_>, Vec<_>) = cell
-                            .for_each_restrictions()
-                            .iter()
-                            .filter_map(|(e, s)| {
-                                e.as_ref().map(|e| {
-                                    (
-                                        quote::format_ident!("{}", s),
-                                        compile_expression(&e, component),
-                                    )
-                                })
-                            })
-                            .unzip();
-                        layout_info = quote!({
-                            let mut layout_info = #layout_info;
-                             #(layout_info.#name = #expr;)*
-                            layout_info });
-                    }
+                    let layout_info = layout_info_with_constraints(
+                        &cell.item,
+                        &cell.constraints,
+                        layout_tree,
+                        component,
+                    );
+                    let alignment = |expr: &Option<Box<Expression>>| match expr {
+                        Some(e) => compile_expression(&e, component),
+                        None => quote!(sixtyfps::re_exports::CellAlignment::stretch),
+                    };
+                    let horizontal_alignment = alignment(&cell.horizontal_alignment);
+                    let vertical_alignment = alignment(&cell.vertical_alignment);
+                    let height_for_width = match height_for_width(&cell.item, layout_tree) {
+                        Some((creation_code, height_for_width)) => {
+                            height_for_width_code.push(creation_code);
+                            height_for_width
+                        }
+                        None => quote!(Default::default()),
+                    };
 
                     quote!(GridLayoutCellData {
                         x: #x,
@@ -999,38 +1811,24 @@ fn collect_layouts_recursively<'a, 'b>(
                         colspan: #colspan,
                         rowspan: #rowspan,
                         constraint: #layout_info,
+                        horizontal_alignment: #horizontal_alignment,
+                        vertical_alignment: #vertical_alignment,
+                        height_for_width: #height_for_width,
                     })
                 })
                 .collect();
 
             let cell_ref_variable = quote::format_ident!("cells_{}", layout_tree.len());
-            let cell_creation_code = quote!(let #cell_ref_variable = [#( #cells ),*];);
-            let (spacing, spacing_creation_code) = if let Some(spacing) = &grid_layout.spacing {
-                let variable = quote::format_ident!("spacing_{}", layout_tree.len());
-                let spacing_code = compile_expression(spacing, component);
-                (quote!(#variable), Some(quote!(let #variable = #spacing_code;)))
-            } else {
-                (quote!(0.), None)
-            };
-            let padding = {
-                let padding_prop = |expr| {
-                    if let Some(expr) = expr {
-                        compile_expression(expr, component)
-                    } else {
-                        quote!(0.)
-                    }
-                };
-                let left = padding_prop(grid_layout.padding.left.as_ref());
-                let right = padding_prop(grid_layout.padding.right.as_ref());
-                let top = padding_prop(grid_layout.padding.top.as_ref());
-                let bottom = padding_prop(grid_layout.padding.bottom.as_ref());
-                quote!(&sixtyfps::re_exports::Padding {
-                    left: #left,
-                    right: #right,
-                    top: #top,
-                    bottom: #bottom,
-                })
+            let cell_creation_code = quote! {
+                #(#height_for_width_code)*
+                let #cell_ref_variable = [#( #cells ),*];
             };
+            let (spacing, spacing_creation_code, padding) = compile_spacing_and_padding(
+                grid_layout.spacing.as_ref(),
+                &grid_layout.padding,
+                layout_tree.len(),
+                component,
+            );
             layout_tree.push(
                 LayoutTreeItem::GridLayout {
                     grid: grid_layout,
@@ -1042,6 +1840,92 @@ fn collect_layouts_recursively<'a, 'b>(
                 .into(),
             );
         }
+        Layout::BoxLayout(box_layout) => {
+            let cell_ref_variable = quote::format_ident!("cells_{}", layout_tree.len());
+            let mut cell_creation_code = quote!();
+            let mut height_for_width_code = vec![];
+            let mut cells = vec![];
+            let mut repeated_count = quote!();
+            for cell in &box_layout.elems {
+                if let Some(elem) = cell.repeated_element() {
+                    let repeater_id = quote::format_ident!("repeater_{}", elem.borrow().id);
+                    repeated_count = quote!(#repeated_count + self.#repeater_id.len());
+                    let push_code =
+                        push_repeated_box_layout_cells(elem, &quote!(#cell_ref_variable));
+                    cell_creation_code = quote!(#cell_creation_code #push_code);
+                    continue;
+                }
+                let width = get_property_ref(&cell.item, "width");
+                let height = get_property_ref(&cell.item, "height");
+                let x = get_property_ref(&cell.item, "x");
+                let y = get_property_ref(&cell.item, "y");
+                let layout_info = layout_info_with_constraints(
+                    &cell.item,
+                    &cell.constraints,
+                    layout_tree,
+                    component,
+                );
+                let height_for_width = match height_for_width(&cell.item, layout_tree) {
+                    Some((creation_code, height_for_width)) => {
+                        height_for_width_code.push(creation_code);
+                        height_for_width
+                    }
+                    None => quote!(Default::default()),
+                };
+                let cell = quote!(BoxLayoutCellData {
+                    x: #x,
+                    y: #y,
+                    width: #width,
+                    height: #height,
+                    constraint: #layout_info,
+                    height_for_width: #height_for_width,
+                });
+                if box_layout.is_static() {
+                    cells.push(cell);
+                } else {
+                    cell_creation_code =
+                        quote!(#cell_creation_code #cell_ref_variable.push(#cell););
+                }
+            }
+            let cell_creation_code = if box_layout.is_static() {
+                quote! {
+                    #(#height_for_width_code)*
+                    let #cell_ref_variable = [#( #cells ),*];
+                }
+            } else {
+                let fixed_count = box_layout.elems.len()
+                    - box_layout.elems.iter().filter(|c| c.repeated_element().is_some()).count();
+                quote! {
+                    #(#height_for_width_code)*
+                    let mut #cell_ref_variable = Vec::with_capacity(#fixed_count #repeated_count);
+                    #cell_creation_code
+                }
+            };
+            let (spacing, spacing_creation_code, padding) = compile_spacing_and_padding(
+                box_layout.spacing.as_ref(),
+                &box_layout.padding,
+                layout_tree.len(),
+                component,
+            );
+            let alignment_variable = quote::format_ident!("alignment_{}", layout_tree.len());
+            let alignment_code = if let Some(expr) = &box_layout.alignment {
+                compile_expression(expr, component)
+            } else {
+                quote!(LayoutAlignment::stretch)
+            };
+            layout_tree.push(LayoutTreeItem::BoxLayout {
+                layout: box_layout,
+                var_creation_code: quote! {
+                    #cell_creation_code
+                    #spacing_creation_code
+                    let #alignment_variable = #alignment_code;
+                },
+                cell_ref_variable: quote!(#cell_ref_variable),
+                spacing,
+                padding,
+                alignment: quote!(#alignment_variable),
+            });
+        }
         Layout::PathLayout(layout) => layout_tree.push(layout.into()),
     }
     layout_tree.last().unwrap()
@@ -1068,6 +1952,35 @@ impl<'a> LayoutTreeItem<'a> {
                     });
                 });
             }
+            LayoutTreeItem::BoxLayout {
+                layout,
+                cell_ref_variable,
+                spacing,
+                padding,
+                alignment,
+                ..
+            } => {
+                let x_pos = compile_expression(&*layout.rect.x_reference, component);
+                let y_pos = compile_expression(&*layout.rect.y_reference, component);
+                let width = compile_expression(&*layout.rect.width_reference, component);
+                let height = compile_expression(&*layout.rect.height_reference, component);
+                let data = quote!(&BoxLayoutData {
+                    width: #width,
+                    height: #height,
+                    x: #x_pos as _,
+                    y: #y_pos as _,
+                    cells: Slice::from_slice(&#cell_ref_variable),
+                    spacing: #spacing,
+                    padding: #padding,
+                    alignment: #alignment,
+                });
+                if layout.wrap {
+                    code_stream.push(quote!(solve_flow_layout(#data);));
+                } else {
+                    let is_horizontal = layout.is_horizontal;
+                    code_stream.push(quote!(solve_box_layout(#data, #is_horizontal);));
+                }
+            }
             LayoutTreeItem::PathLayout(path_layout) => {
                 let path_layout_item_data =
                     |elem: &ElementRc, elem_rs: TokenStream, component_rust: TokenStream| {
@@ -1122,6 +2035,7 @@ impl<'a> LayoutTreeItem<'a> {
                     for elem in &path_layout.elements {
                         if elem.borrow().repeated.is_some() {
                             let repeater_id = quote::format_ident!("repeater_{}", elem.borrow().id);
+                            let update_fn = quote::format_ident!("update_{}", repeater_id);
                             repeated_count = quote!(#repeated_count + self.#repeater_id.len());
                             let root_element =
                                 elem.borrow().base_type.as_component().root_element.clone();
@@ -1133,7 +2047,8 @@ impl<'a> LayoutTreeItem<'a> {
                             );
                             push_code = quote! {
                                 #push_code
-                                let internal_vec = self.#repeater_id.borrow_item_vec();
+                                self.#update_fn();
+                                let internal_vec = self.#repeater_id.components_vec();
                                 for sub_comp in &*internal_vec {
                                     items_vec.push(#e)
                                 }
@@ -1187,7 +2102,10 @@ fn compute_layout(component: &Rc<Component>) -> TokenStream {
         collect_layouts_recursively(&mut inverse_layout_tree, layout, component);
 
         layouts.extend(inverse_layout_tree.iter().filter_map(|layout| match layout {
-            LayoutTreeItem::GridLayout { var_creation_code, .. } => Some(var_creation_code.clone()),
+            LayoutTreeItem::GridLayout { var_creation_code, .. }
+            | LayoutTreeItem::BoxLayout { var_creation_code, .. } => {
+                Some(var_creation_code.clone())
+            }
             LayoutTreeItem::PathLayout(_) => None,
         }));
 
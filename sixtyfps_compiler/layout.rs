@@ -17,6 +17,7 @@ use std::rc::Rc;
 #[derive(Debug, derive_more::From)]
 pub enum Layout {
     GridLayout(GridLayout),
+    BoxLayout(BoxLayout),
     PathLayout(PathLayout),
 }
 
@@ -24,15 +25,28 @@ impl Layout {
     pub fn rect(&self) -> &LayoutRect {
         match self {
             Layout::GridLayout(g) => &g.rect,
+            Layout::BoxLayout(g) => &g.rect,
             Layout::PathLayout(p) => &p.rect,
         }
     }
+
+    /// Returns true if the minimum height of this layout depends on its width
+    pub fn has_height_for_width(&self) -> bool {
+        match self {
+            Layout::GridLayout(grid) => grid.elems.iter().any(|e| e.item.has_height_for_width()),
+            Layout::BoxLayout(box_layout) => {
+                box_layout.wrap || box_layout.elems.iter().any(|e| e.item.has_height_for_width())
+            }
+            Layout::PathLayout(_) => false,
+        }
+    }
 }
 
 impl ExpressionFieldsVisitor for Layout {
     fn visit_expressions(&mut self, visitor: &mut impl FnMut(&mut Expression)) {
         match self {
             Layout::GridLayout(grid) => grid.visit_expressions(visitor),
+            Layout::BoxLayout(box_layout) => box_layout.visit_expressions(visitor),
             Layout::PathLayout(path) => path.visit_expressions(visitor),
         }
     }
@@ -59,6 +73,51 @@ pub enum LayoutItem {
     Layout(Box<Layout>),
 }
 
+impl LayoutItem {
+    /// Returns the layout that places this item's children, if any.
+    pub fn layout(&self) -> Option<&Layout> {
+        match self {
+            LayoutItem::Element(element) => element.layout.as_ref(),
+            LayoutItem::Layout(layout) => Some(&**layout),
+        }
+    }
+
+    /// Returns true if the minimum height of this item depends on its width: a Text that may be
+    /// word wrapped, a FlowLayout, or a layout containing such items.
+    pub fn has_height_for_width(&self) -> bool {
+        match self {
+            LayoutItem::Element(LayoutElement { element, layout: None }) => {
+                is_wrapped_text(element)
+            }
+            _ => self.layout().map_or(false, Layout::has_height_for_width),
+        }
+    }
+}
+
+/// Returns true if the element is a Text that may be word wrapped, whose minimum height
+/// depends on its width. For a repeated element, this is about the root of the instances.
+pub fn is_wrapped_text(element: &ElementRc) -> bool {
+    let element = element.borrow();
+    match &element.base_type {
+        Type::Native(n) => n.class_name == "Text" && element.bindings.contains_key("wrap"),
+        // The repeater_component pass made the repeated element the component of its instances
+        Type::Component(c) if element.repeated.is_some() => is_wrapped_text(&c.root_element),
+        _ => false,
+    }
+}
+
+impl ExpressionFieldsVisitor for LayoutItem {
+    fn visit_expressions(&mut self, visitor: &mut impl FnMut(&mut Expression)) {
+        match self {
+            LayoutItem::Element(element) => {
+                element.layout.as_mut().map(|layout| layout.visit_expressions(visitor));
+                // The expressions of element.element are traversed through the regular element tree traversal
+            }
+            LayoutItem::Layout(layout) => layout.visit_expressions(visitor),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LayoutRect {
     pub width_reference: Box<Expression>,
@@ -117,26 +176,25 @@ impl ExpressionFieldsVisitor for LayoutRect {
     }
 }
 
-/// An element in a GridLayout
-#[derive(Debug)]
-pub struct GridLayoutElement {
-    pub col: u16,
-    pub row: u16,
-    pub colspan: u16,
-    pub rowspan: u16,
-    pub item: LayoutItem,
+/// The size constraints and stretch factors explicitly set on an item of a layout
+#[derive(Debug, Default)]
+pub struct LayoutItemConstraints {
     pub minimum_width: Option<Box<Expression>>,
     pub maximum_width: Option<Box<Expression>>,
     pub minimum_height: Option<Box<Expression>>,
     pub maximum_height: Option<Box<Expression>>,
+    pub horizontal_stretch: Option<Box<Expression>>,
+    pub vertical_stretch: Option<Box<Expression>>,
 }
 
-impl GridLayoutElement {
+impl LayoutItemConstraints {
     pub fn has_explicit_restrictions(&self) -> bool {
         self.minimum_width.is_some()
             || self.maximum_width.is_some()
             || self.minimum_height.is_some()
             || self.maximum_height.is_some()
+            || self.horizontal_stretch.is_some()
+            || self.vertical_stretch.is_some()
     }
 
     /*pub fn for_each_restrictions(&self, mut f: impl FnMut(&str, &Expression)) {
@@ -145,16 +203,43 @@ impl GridLayoutElement {
         self.minimum_height.map(|e| f("minimum_height", &e));
         self.maximum_height.map(|e| f("maximum_height", &e));
     }*/
-    pub fn for_each_restrictions<'a>(&'a self) -> [(&Option<Box<Expression>>, &'static str); 4] {
+    pub fn for_each_restrictions<'a>(&'a self) -> [(&Option<Box<Expression>>, &'static str); 6] {
         [
             (&self.minimum_width, "min_width"),
             (&self.maximum_width, "max_width"),
             (&self.minimum_height, "min_height"),
             (&self.maximum_height, "max_height"),
+            (&self.horizontal_stretch, "horizontal_stretch"),
+            (&self.vertical_stretch, "vertical_stretch"),
         ]
     }
 }
 
+impl ExpressionFieldsVisitor for LayoutItemConstraints {
+    fn visit_expressions(&mut self, visitor: &mut impl FnMut(&mut Expression)) {
+        self.maximum_width.as_mut().map(|e| visitor(&mut *e));
+        self.minimum_width.as_mut().map(|e| visitor(&mut *e));
+        self.maximum_height.as_mut().map(|e| visitor(&mut *e));
+        self.minimum_height.as_mut().map(|e| visitor(&mut *e));
+        self.horizontal_stretch.as_mut().map(|e| visitor(&mut *e));
+        self.vertical_stretch.as_mut().map(|e| visitor(&mut *e));
+    }
+}
+
+/// An element in a GridLayout
+#[derive(Debug)]
+pub struct GridLayoutElement {
+    pub col: u16,
+    pub row: u16,
+    pub colspan: u16,
+    pub rowspan: u16,
+    pub item: LayoutItem,
+    pub constraints: LayoutItemConstraints,
+    /// The alignment of the item within its cell, if set
+    pub horizontal_alignment: Option<Box<Expression>>,
+    pub vertical_alignment: Option<Box<Expression>>,
+}
+
 #[derive(Debug)]
 pub struct Padding {
     pub left: Option<Expression>,
@@ -188,22 +273,73 @@ impl ExpressionFieldsVisitor for GridLayout {
     fn visit_expressions(&mut self, visitor: &mut impl FnMut(&mut Expression)) {
         self.rect.visit_expressions(visitor);
         for cell in &mut self.elems {
-            match &mut cell.item {
-                LayoutItem::Element(element) => {
-                    element.layout.as_mut().map(|layout| layout.visit_expressions(visitor));
-                    // The expressions of element.element are traversed through the regular element tree traversal
-                }
-                LayoutItem::Layout(layout) => layout.visit_expressions(visitor),
+            cell.item.visit_expressions(visitor);
+            cell.constraints.visit_expressions(visitor);
+            cell.horizontal_alignment.as_mut().map(|e| visitor(&mut *e));
+            cell.vertical_alignment.as_mut().map(|e| visitor(&mut *e));
+        }
+        self.spacing.as_mut().map(|e| visitor(&mut *e));
+        self.padding.visit_expressions(visitor);
+    }
+}
+
+/// An element in a BoxLayout
+///
+/// When the element is repeated, the constraints are not set and the generators use the
+/// properties of the root element of the repeated component instead.
+#[derive(Debug)]
+pub struct BoxLayoutElement {
+    pub item: LayoutItem,
+    pub constraints: LayoutItemConstraints,
+}
+
+impl BoxLayoutElement {
+    /// Returns the repeated element if this element is the placeholder of a repeater
+    pub fn repeated_element(&self) -> Option<&ElementRc> {
+        match &self.item {
+            LayoutItem::Element(LayoutElement { element, .. })
+                if element.borrow().repeated.is_some() =>
+            {
+                Some(element)
             }
+            _ => None,
         }
-        for e in &mut self.elems {
-            e.maximum_width.as_mut().map(|e| visitor(&mut *e));
-            e.minimum_width.as_mut().map(|e| visitor(&mut *e));
-            e.maximum_height.as_mut().map(|e| visitor(&mut *e));
-            e.minimum_height.as_mut().map(|e| visitor(&mut *e));
+    }
+}
+
+/// Internal representation of a HorizontalLayout, a VerticalLayout or a FlowLayout
+#[derive(Debug)]
+pub struct BoxLayout {
+    /// When true, this is a HorizontalLayout, otherwise a VerticalLayout
+    pub is_horizontal: bool,
+    /// When true, this is a FlowLayout: the elements are placed horizontally and wrap to the
+    /// next line when the width is exceeded
+    pub wrap: bool,
+    pub elems: Vec<BoxLayoutElement>,
+    pub rect: LayoutRect,
+
+    pub spacing: Option<Expression>,
+    pub padding: Padding,
+    pub alignment: Option<Expression>,
+}
+
+impl BoxLayout {
+    /// Returns true if all the elements of this layout are known at compile time
+    pub fn is_static(&self) -> bool {
+        self.elems.iter().all(|e| e.repeated_element().is_none())
+    }
+}
+
+impl ExpressionFieldsVisitor for BoxLayout {
+    fn visit_expressions(&mut self, visitor: &mut impl FnMut(&mut Expression)) {
+        self.rect.visit_expressions(visitor);
+        for cell in &mut self.elems {
+            cell.item.visit_expressions(visitor);
+            cell.constraints.visit_expressions(visitor);
         }
         self.spacing.as_mut().map(|e| visitor(&mut *e));
         self.padding.visit_expressions(visitor);
+        self.alignment.as_mut().map(|e| visitor(&mut *e));
     }
 }
 
@@ -240,6 +376,14 @@ pub mod gen {
             var_creation_code: L::CompiledCode,
             cell_ref_variable: L::CompiledCode,
         },
+        BoxLayout {
+            layout: &'a BoxLayout,
+            spacing: L::CompiledCode,
+            padding: L::CompiledCode,
+            alignment: L::CompiledCode,
+            var_creation_code: L::CompiledCode,
+            cell_ref_variable: L::CompiledCode,
+        },
         PathLayout(&'a PathLayout),
     }
 
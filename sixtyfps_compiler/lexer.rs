@@ -90,9 +90,19 @@ pub fn lex_string(text: &str) -> usize {
     if !text.starts_with('"') {
         return 0;
     }
-    let end = text[1..].find('"').unwrap_or(0) + 2;
-    assert!(!text[..end].contains("\\"), "escape code not yet supported");
-    end
+    let mut chars = text.char_indices().skip(1);
+    while let Some((offset, ch)) = chars.next() {
+        match ch {
+            '"' => return offset + 1,
+            // The escaped character cannot end the string
+            '\\' => {
+                chars.next();
+            }
+            _ => (),
+        }
+    }
+    // Unterminated
+    0
 }
 
 pub fn lex_number(text: &str) -> usize {
@@ -230,4 +240,12 @@ fn basic_lexer_test() {
             (crate::parser::SyntaxKind::Star, "*"),
         ],
     );
+    compare(
+        r#""a\"b\\"+"\n""#,
+        &[
+            (crate::parser::SyntaxKind::StringLiteral, r#""a\"b\\""#),
+            (crate::parser::SyntaxKind::Plus, "+"),
+            (crate::parser::SyntaxKind::StringLiteral, r#""\n""#),
+        ],
+    );
 }
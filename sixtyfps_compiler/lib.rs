@@ -45,6 +45,7 @@ mod passes {
     }
 
     pub mod collect_resources;
+    pub mod collect_structs;
     pub mod compile_paths;
     pub mod deduplicate_property_read;
     pub mod inlining;
@@ -117,11 +118,15 @@ pub fn run_passes(
     passes::materialize_fake_properties::materialize_fake_properties(&doc.root_component);
     passes::collect_resources::collect_resources(&doc.root_component);
     doc.root_component.embed_file_resources.set(compiler_config.embed_resources);
-    passes::lower_states::lower_states(&doc.root_component, diag);
+    passes::lower_states::lower_states(&doc.root_component, &doc.local_registry, diag);
     passes::deduplicate_property_read::deduplicate_property_read(&doc.root_component);
     passes::repeater_component::process_repeater_components(&doc.root_component);
     passes::move_declarations::move_declarations(&doc.root_component);
+    for global in doc.root_component.used_globals.borrow().iter() {
+        passes::move_declarations::move_declarations(global);
+    }
     passes::resolve_native_classes::resolve_native_classes(&doc.root_component);
+    passes::collect_structs::collect_structs(&doc.root_component);
 }
 
 mod library {
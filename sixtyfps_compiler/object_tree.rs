@@ -13,7 +13,9 @@ LICENSE END */
 
 use crate::diagnostics::{FileDiagnostics, Spanned, SpannedWithSourceFile};
 use crate::expression_tree::{Expression, ExpressionSpanned, NamedReference};
-use crate::parser::{syntax_nodes, SyntaxKind, SyntaxNodeWithSourceFile};
+use crate::parser::{
+    syntax_nodes, SyntaxKind, SyntaxNodeWithSourceFile, SyntaxTokenWithSourceFile,
+};
 use crate::typeregister::{Type, TypeRegister};
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
@@ -39,6 +41,40 @@ impl Document {
 
         let mut local_registry = TypeRegister::new(parent_registry);
 
+        // A struct can use the structs declared after it: the names of all the structs are
+        // collected first, and each struct is resolved once the structs it uses are resolved.
+        let mut structs_to_resolve = node
+            .StructDeclaration()
+            .chain(node.ExportsList().flat_map(|export| export.StructDeclaration()))
+            .map(|n| {
+                let name =
+                    n.DeclaredIdentifier().child_text(SyntaxKind::Identifier).unwrap_or_default();
+                let used_names = used_type_names(&n.ObjectType());
+                (name, n, used_names)
+            })
+            .collect::<Vec<_>>();
+        while !structs_to_resolve.is_empty() {
+            let is_pending =
+                |used: &String| structs_to_resolve.iter().any(|(name, ..)| name == used);
+            let next = structs_to_resolve
+                .iter()
+                .position(|(_, _, used_names)| !used_names.iter().any(is_pending))
+                .unwrap_or_else(|| {
+                    // The remaining structs use each other, resolve the first one anyway
+                    let (name, n, _) = &structs_to_resolve[0];
+                    if let Some(name_token) =
+                        n.DeclaredIdentifier().child_token(SyntaxKind::Identifier)
+                    {
+                        diag.push_error(format!("Recursive struct '{}'", name), &name_token);
+                    }
+                    0
+                });
+            let (name, n, _) = structs_to_resolve.remove(next);
+            let ty =
+                type_struct_from_node(n.ObjectType(), diag, &local_registry, Some(name.clone()));
+            local_registry.insert_type_with_name(ty, name);
+        }
+
         let inner_components = node
             .Component()
             .chain(node.ExportsList().flat_map(|export| export.Component()))
@@ -49,11 +85,16 @@ impl Document {
             })
             .collect::<Vec<_>>();
 
-        let exports = Exports::from_node(&node, &inner_components, &parent_registry, diag);
+        let exports = Exports::from_node(&node, &inner_components, &local_registry, diag);
 
         Document {
             // FIXME: one should use the `component` hint instead of always returning the last
-            root_component: inner_components.last().cloned().unwrap_or_default(),
+            root_component: inner_components
+                .iter()
+                .rev()
+                .find(|c| !c.is_global)
+                .cloned()
+                .unwrap_or_default(),
 
             inner_components,
 
@@ -63,7 +104,7 @@ impl Document {
         }
     }
 
-    pub fn exports(&self) -> &Vec<(String, Rc<Component>)> {
+    pub fn exports(&self) -> &Vec<(String, Type)> {
         &self.exports.0
     }
 }
@@ -98,6 +139,20 @@ pub struct Component {
     /// When creating this component and inserting "children", append them to the children of
     /// the element pointer to by this field.
     pub child_insertion_point: Option<ElementRc>,
+
+    /// The structs declared with `struct` that are used by this component and its repeated
+    /// components. A struct comes after the structs used by its fields.
+    /// Only set on the root component, by the collect_structs pass.
+    pub used_structs: RefCell<Vec<Type>>,
+
+    /// True if this component was declared with `global Name := { ... }`: it has no items and
+    /// is instantiated once per root component.
+    pub is_global: bool,
+
+    /// The global components whose properties or signals are used by this component.
+    /// After the resolving pass, the root component also contains the globals used by all the
+    /// components it uses.
+    pub used_globals: RefCell<Vec<Rc<Component>>>,
 }
 
 impl Component {
@@ -107,18 +162,32 @@ impl Component {
         tr: &TypeRegister,
     ) -> Rc<Self> {
         let mut child_insertion_point = None;
+        let (id, is_global) = component_name_from_node(&node);
         let mut c = Component {
-            id: node.child_text(SyntaxKind::Identifier).unwrap_or_default(),
+            id: id.unwrap_or_default(),
             root_element: Element::from_node(
                 node.Element(),
                 "root".into(),
-                Type::Invalid,
+                if is_global { Type::Void } else { Type::Invalid },
                 &mut child_insertion_point,
                 diag,
                 tr,
             ),
+            is_global,
             ..Default::default()
         };
+        if is_global {
+            let root = c.root_element.borrow();
+            if let Some(child) = root.children.first() {
+                diag.push_error(
+                    "A global component cannot have sub elements".into(),
+                    &*child.borrow(),
+                );
+            }
+            if !root.states.is_empty() || !root.transitions.is_empty() {
+                diag.push_error("A global component cannot have states".into(), &node.Element());
+            }
+        }
         c.child_insertion_point = child_insertion_point;
         let c = Rc::new(c);
         let weak = Rc::downgrade(&c);
@@ -129,6 +198,21 @@ impl Component {
     }
 }
 
+/// Returns the name of the component, and true if it is declared as `global Name := { ... }`
+fn component_name_from_node(node: &syntax_nodes::Component) -> (Option<String>, bool) {
+    let identifiers = node
+        .children_with_tokens()
+        .filter(|n| n.kind() == SyntaxKind::Identifier)
+        .filter_map(|n| n.into_token())
+        .map(|t| t.text().to_string())
+        .collect::<Vec<_>>();
+    match identifiers.as_slice() {
+        [global, name] if global == "global" => (Some(name.clone()), true),
+        [name] => (Some(name.clone()), false),
+        _ => (None, false),
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct PropertyDeclaration {
     pub property_type: Type,
@@ -156,7 +240,7 @@ pub struct Element {
 
     pub property_declarations: HashMap<String, PropertyDeclaration>,
 
-    pub property_animations: HashMap<String, ElementRc>,
+    pub property_animations: HashMap<String, PropertyAnimation>,
 
     /// Tis element is part of a `for <xxx> in <model>:
     pub repeated: Option<RepeatedElementInfo>,
@@ -164,6 +248,10 @@ pub struct Element {
     pub states: Vec<State>,
     pub transitions: Vec<Transition>,
 
+    /// The `changed prop => { ... }` handlers, with the property they watch.
+    /// The move_declarations pass moves them all to the root element of the component.
+    pub change_callbacks: Vec<(NamedReference, Expression)>,
+
     pub child_of_layout: bool,
 
     /// The AST node, if available
@@ -205,36 +293,37 @@ impl Element {
         diag: &mut FileDiagnostics,
         tr: &TypeRegister,
     ) -> ElementRc {
-        let base = QualifiedTypeName::from_node(node.QualifiedName());
-        let mut r = Element {
-            id,
-            base_type: match parent_type.lookup_type_for_child_element(&base.to_string(), tr) {
+        let (base, base_type) = if let Some(base_node) = node.QualifiedName() {
+            let base = QualifiedTypeName::from_node(base_node.clone());
+            let base_type = match parent_type.lookup_type_for_child_element(&base.to_string(), tr) {
+                Ok(Type::Component(c)) if c.is_global => {
+                    diag.push_error(
+                        format!("Cannot create an instance of the global component '{}'", base),
+                        &base_node,
+                    );
+                    return ElementRc::default();
+                }
                 Ok(ty) => ty,
                 Err(err) => {
-                    diag.push_error(err, &node.QualifiedName());
+                    diag.push_error(err, &base_node);
                     return ElementRc::default();
                 }
-            },
-            node: Some(node.clone()),
-            ..Default::default()
+            };
+            (base, base_type)
+        } else {
+            // Only the root element of a global has no base type (parent_type is Void)
+            if parent_type != Type::Void {
+                diag.push_error("Expected a base type for the element".into(), &node);
+                return ElementRc::default();
+            }
+            (QualifiedTypeName::default(), Type::Void)
         };
-        assert!(r.base_type.is_object_type());
+        let mut r = Element { id, base_type, node: Some(node.clone()), ..Default::default() };
+        assert!(r.base_type.is_object_type() || r.base_type == Type::Void);
 
         for prop_decl in node.PropertyDeclaration() {
-            let qualified_type_node = prop_decl.QualifiedName();
-            let qualified_type = QualifiedTypeName::from_node(qualified_type_node.clone());
-
-            let prop_type = tr.lookup_qualified(&qualified_type.members);
-
-            match prop_type {
-                Type::Invalid => {
-                    diag.push_error(
-                        format!("Unknown property type '{}'", qualified_type.to_string()),
-                        &qualified_type_node,
-                    );
-                }
-                _ => (),
-            };
+            let type_node = prop_decl.Type();
+            let prop_type = type_from_node(type_node.clone(), diag, tr);
 
             let prop_name_token =
                 prop_decl.DeclaredIdentifier().child_token(SyntaxKind::Identifier).unwrap();
@@ -251,12 +340,20 @@ impl Element {
                 prop_name.clone(),
                 PropertyDeclaration {
                     property_type: prop_type,
-                    type_node: Some(qualified_type_node.into()),
+                    type_node: Some(type_node.into()),
                     ..Default::default()
                 },
             );
 
             if let Some(csn) = prop_decl.BindingExpression() {
+                if r.bindings
+                    .insert(prop_name.clone(), ExpressionSpanned::new_uncompiled(csn.into()))
+                    .is_some()
+                {
+                    diag.push_error("Duplicated property binding".into(), &prop_name_token);
+                }
+            }
+            if let Some(csn) = prop_decl.TwoWayBinding() {
                 if r.bindings
                     .insert(prop_name, ExpressionSpanned::new_uncompiled(csn.into()))
                     .is_some()
@@ -266,16 +363,56 @@ impl Element {
             }
         }
 
-        r.parse_bindings(&base, node.Binding(), diag);
+        for func in node.Function() {
+            let name_token = func.DeclaredIdentifier().child_token(SyntaxKind::Identifier).unwrap();
+            let name = name_token.text().to_string();
+            if !matches!(r.lookup_property(&name), Type::Invalid) {
+                diag.push_error(format!("Cannot override '{}'", name), &name_token);
+                continue;
+            }
+            let args =
+                func.ArgumentDeclaration().map(|a| type_from_node(a.Type(), diag, tr)).collect();
+            let return_type = Box::new(
+                func.ReturnType()
+                    .map_or(Type::Void, |ret_ty| type_from_node(ret_ty.Type(), diag, tr)),
+            );
+            r.property_declarations.insert(
+                name.clone(),
+                PropertyDeclaration {
+                    property_type: Type::Function { return_type, args },
+                    type_node: Some(func.clone().into()),
+                    ..Default::default()
+                },
+            );
+            r.bindings.insert(name, ExpressionSpanned::new_uncompiled(func.into()));
+        }
+
+        r.parse_bindings(
+            &base,
+            node.Binding().filter_map(|b| {
+                Some((b.child_token(SyntaxKind::Identifier)?, b.BindingExpression().into()))
+            }),
+            diag,
+        );
+        r.parse_bindings(
+            &base,
+            node.TwoWayBinding()
+                .filter_map(|b| Some((b.child_token(SyntaxKind::Identifier)?, b.into()))),
+            diag,
+        );
 
         for sig_decl in node.SignalDeclaration() {
             let name_token =
                 sig_decl.DeclaredIdentifier().child_token(SyntaxKind::Identifier).unwrap();
             let name = name_token.text().to_string();
+            let args = sig_decl.Type().map(|node_ty| type_from_node(node_ty, diag, tr)).collect();
+            let return_type = sig_decl
+                .ReturnType()
+                .map(|ret_ty| Box::new(type_from_node(ret_ty.Type(), diag, tr)));
             r.property_declarations.insert(
                 name,
                 PropertyDeclaration {
-                    property_type: Type::Signal,
+                    property_type: Type::Signal { return_type, args },
                     type_node: Some(sig_decl.into()),
                     ..Default::default()
                 },
@@ -289,11 +426,24 @@ impl Element {
             };
             let name = name_token.text().to_string();
             let prop_type = r.lookup_property(&name);
-            if !matches!(prop_type, Type::Signal) {
+            if let Type::Signal { args, .. } = prop_type {
+                let num_arg = con_node.DeclaredIdentifier().count();
+                if num_arg > args.len() {
+                    diag.push_error(
+                        format!(
+                            "'{}' only has {} arguments, but {} were provided",
+                            name,
+                            args.len(),
+                            num_arg
+                        ),
+                        &name_token,
+                    );
+                }
+            } else {
                 diag.push_error(format!("'{}' is not a signal in {}", name, base), &name_token);
             }
             if r.bindings
-                .insert(name, ExpressionSpanned::new_uncompiled(con_node.CodeBlock().into()))
+                .insert(name, ExpressionSpanned::new_uncompiled(con_node.clone().into()))
                 .is_some()
             {
                 diag.push_error("Duplicated signal".into(), &name_token);
@@ -319,7 +469,7 @@ impl Element {
                             tr,
                         ) {
                             if r.property_animations
-                                .insert(prop_name.clone(), anim_element)
+                                .insert(prop_name.clone(), PropertyAnimation::Static(anim_element))
                                 .is_some()
                             {
                                 diag.push_error("Duplicated animation".into(), &prop_name_token)
@@ -418,15 +568,30 @@ impl Element {
         }
 
         for trs in node.Transitions().flat_map(|s| s.Transition()) {
-            if let Some(star) = trs.child_token(SyntaxKind::Star) {
-                diag.push_error("TODO: catch-all not yet implemented".into(), &star);
-            };
+            let mut catch_all_animation = None;
+            for anim in trs.PropertyAnimation() {
+                if anim.child_token(SyntaxKind::Star).is_some() {
+                    let anim_type = tr.property_animation_type_for_property(Type::Float32);
+                    let anim_element = new_animation_element(&anim, anim_type, diag);
+                    if catch_all_animation.replace(anim_element).is_some() {
+                        diag.push_error(
+                            "Duplicated animation".into(),
+                            &anim.child_token(SyntaxKind::Star).unwrap(),
+                        );
+                    }
+                }
+            }
+            let state_id =
+                trs.DeclaredIdentifier().child_text(SyntaxKind::Identifier).unwrap_or_default();
+            if !r.borrow().states.iter().any(|s| s.id == state_id) {
+                diag.push_error(
+                    format!("State '{}' does not exist", state_id),
+                    &trs.DeclaredIdentifier(),
+                );
+            }
             let trans = Transition {
                 is_out: trs.child_text(SyntaxKind::Identifier).unwrap_or_default() == "out",
-                state_id: trs
-                    .DeclaredIdentifier()
-                    .child_text(SyntaxKind::Identifier)
-                    .unwrap_or_default(),
+                state_id,
                 property_animations: trs
                     .PropertyAnimation()
                     .flat_map(|pa| pa.QualifiedName().map(move |qn| (pa.clone(), qn)))
@@ -441,10 +606,29 @@ impl Element {
                             .map(|anim_element| (ne, anim_element))
                     })
                     .collect(),
+                catch_all_animation,
             };
             r.borrow_mut().transitions.push(trans);
         }
 
+        for ch in node.ChangeHandler() {
+            if r.borrow().base_type == Type::Void {
+                diag.push_error(
+                    "Change handlers are not supported in global components".into(),
+                    &ch.child_token(SyntaxKind::Identifier).unwrap(),
+                );
+                continue;
+            }
+            let (ne, prop_type) = lookup_property_from_qualified_name(ch.QualifiedName(), &r, diag);
+            if !prop_type.is_property_type() {
+                debug_assert!(diag.has_error()); // Error should have been reported already
+                continue;
+            }
+            r.borrow_mut()
+                .change_callbacks
+                .push((ne, Expression::Uncompiled(ch.CodeBlock().into())));
+        }
+
         r
     }
 
@@ -518,40 +702,90 @@ impl Element {
         self.node.as_ref().map(|n| n.span()).unwrap_or_default()
     }
 
+    /// Add the bindings. The iterator gives the name of the property and the node of the
+    /// binding expression (either a BindingExpression or a TwoWayBinding)
     fn parse_bindings(
         &mut self,
         base: &QualifiedTypeName,
-        bindings: impl Iterator<Item = syntax_nodes::Binding>,
+        bindings: impl Iterator<Item = (SyntaxTokenWithSourceFile, SyntaxNodeWithSourceFile)>,
         diag: &mut FileDiagnostics,
     ) {
-        for b in bindings {
-            let name_token = match b.child_token(SyntaxKind::Identifier) {
-                Some(x) => x,
-                None => continue,
-            };
+        for (name_token, b) in bindings {
             let name = name_token.text().to_string();
             let prop_type = self.lookup_property(&name);
             if !prop_type.is_property_type() {
                 diag.push_error(
                     match prop_type {
                         Type::Invalid => format!("Unknown property {} in {}", name, base),
-                        Type::Signal => format!("'{}' is a signal. Use `=>` to connect", name),
+                        Type::Signal { .. } => {
+                            format!("'{}' is a signal. Use `=>` to connect", name)
+                        }
                         _ => format!("Cannot assing to {} in {}", name, base),
                     },
                     &name_token,
                 );
             }
-            if self
-                .bindings
-                .insert(name, ExpressionSpanned::new_uncompiled(b.BindingExpression().into()))
-                .is_some()
-            {
+            if self.bindings.insert(name, ExpressionSpanned::new_uncompiled(b)).is_some() {
                 diag.push_error("Duplicated property binding".into(), &name_token);
             }
         }
     }
 }
 
+/// Resolve the type of a property declaration. Unknown types are reported as errors.
+fn type_from_node(node: syntax_nodes::Type, diag: &mut FileDiagnostics, tr: &TypeRegister) -> Type {
+    if let Some(qualified_type_node) = node.QualifiedName() {
+        let qualified_type = QualifiedTypeName::from_node(qualified_type_node.clone());
+        let prop_type = tr.lookup_qualified(&qualified_type.members);
+        if prop_type == Type::Invalid {
+            diag.push_error(
+                format!("Unknown property type '{}'", qualified_type.to_string()),
+                &qualified_type_node,
+            );
+        }
+        prop_type
+    } else if let Some(object_node) = node.ObjectType() {
+        type_struct_from_node(object_node, diag, tr, None)
+    } else if let Some(array_node) = node.ArrayType() {
+        match type_from_node(array_node.Type(), diag, tr) {
+            Type::Invalid => Type::Invalid,
+            element_ty => Type::Array(Box::new(element_ty)),
+        }
+    } else {
+        // The parser already reported an error
+        Type::Invalid
+    }
+}
+
+/// The names of the types used by the fields of an object type, and of its nested types
+fn used_type_names(object_node: &syntax_nodes::ObjectType) -> Vec<String> {
+    object_node
+        .node
+        .descendants()
+        .filter(|n| n.kind() == SyntaxKind::QualifiedName)
+        .map(|n| n.text().to_string().trim().to_owned())
+        .collect()
+}
+
+/// Resolve an object type. `name` is set for the types declared with `struct`.
+fn type_struct_from_node(
+    object_node: syntax_nodes::ObjectType,
+    diag: &mut FileDiagnostics,
+    tr: &TypeRegister,
+    name: Option<String>,
+) -> Type {
+    let fields = object_node
+        .ObjectTypeMember()
+        .map(|member| {
+            (
+                member.child_text(SyntaxKind::Identifier).unwrap_or_default(),
+                type_from_node(member.Type(), diag, tr),
+            )
+        })
+        .collect();
+    Type::Object { fields, name }
+}
+
 fn animation_element_from_node(
     anim: &syntax_nodes::PropertyAnimation,
     prop_name: &syntax_nodes::QualifiedName,
@@ -567,16 +801,30 @@ fn animation_element_from_node(
         );
         None
     } else {
-        let base = QualifiedTypeName {
-            members: vec![anim_type.as_builtin().native_class.class_name.clone()],
-        };
-        let mut anim_element =
-            Element { id: "".into(), base_type: anim_type, node: None, ..Default::default() };
-        anim_element.parse_bindings(&base, anim.Binding(), diag);
-        Some(Rc::new(RefCell::new(anim_element)))
+        Some(new_animation_element(anim, anim_type, diag))
     }
 }
 
+/// Create the element for the `animate` node, `anim_type` must be the builtin animation type
+fn new_animation_element(
+    anim: &syntax_nodes::PropertyAnimation,
+    anim_type: Type,
+    diag: &mut FileDiagnostics,
+) -> ElementRc {
+    let base =
+        QualifiedTypeName { members: vec![anim_type.as_builtin().native_class.class_name.clone()] };
+    let mut anim_element =
+        Element { id: "".into(), base_type: anim_type, node: None, ..Default::default() };
+    anim_element.parse_bindings(
+        &base,
+        anim.Binding().filter_map(|b| {
+            Some((b.child_token(SyntaxKind::Identifier)?, b.BindingExpression().into()))
+        }),
+        diag,
+    );
+    Rc::new(RefCell::new(anim_element))
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct QualifiedTypeName {
     members: Vec<String>,
@@ -697,22 +945,62 @@ pub fn visit_element_expressions(
     }
     elem.borrow_mut().states = states;
 
-    let property_animations = std::mem::take(&mut elem.borrow_mut().property_animations);
-    for anim_elem in property_animations.values() {
-        let mut bindings = std::mem::take(&mut anim_elem.borrow_mut().bindings);
-        for (name, expr) in &mut bindings {
-            vis(expr, &|| anim_elem.borrow().lookup_property(name));
+    let mut property_animations = std::mem::take(&mut elem.borrow_mut().property_animations);
+    for anim in property_animations.values_mut() {
+        match anim {
+            PropertyAnimation::Static(anim_elem) => {
+                visit_animation_expressions(anim_elem, &mut vis)
+            }
+            PropertyAnimation::Transition { state_ref, animations, default } => {
+                vis(state_ref, &|| Type::state_info());
+                for a in animations.iter() {
+                    visit_animation_expressions(&a.animation, &mut vis)
+                }
+                if let Some(default) = default {
+                    visit_animation_expressions(default, &mut vis)
+                }
+            }
         }
-        anim_elem.borrow_mut().bindings = bindings;
     }
     elem.borrow_mut().property_animations = property_animations;
+
+    let transitions = std::mem::take(&mut elem.borrow_mut().transitions);
+    for t in &transitions {
+        for (_, anim_elem) in &t.property_animations {
+            visit_animation_expressions(anim_elem, &mut vis)
+        }
+        if let Some(anim_elem) = &t.catch_all_animation {
+            visit_animation_expressions(anim_elem, &mut vis)
+        }
+    }
+    elem.borrow_mut().transitions = transitions;
+
+    let mut change_callbacks = std::mem::take(&mut elem.borrow_mut().change_callbacks);
+    for (_, e) in &mut change_callbacks {
+        vis(e, &|| Type::Void);
+    }
+    elem.borrow_mut().change_callbacks = change_callbacks;
+}
+
+fn visit_animation_expressions(
+    anim_elem: &ElementRc,
+    vis: &mut impl FnMut(&mut Expression, &dyn Fn() -> Type),
+) {
+    let mut bindings = std::mem::take(&mut anim_elem.borrow_mut().bindings);
+    for (name, expr) in &mut bindings {
+        vis(expr, &|| anim_elem.borrow().lookup_property(name));
+    }
+    anim_elem.borrow_mut().bindings = bindings;
 }
 
 pub fn visit_all_named_references(elem: &ElementRc, mut vis: impl FnMut(&mut NamedReference)) {
     fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedReference)) {
         expr.visit_mut(|sub| recurse_expression(sub, vis));
         match expr {
-            Expression::PropertyReference(r) | Expression::SignalReference(r) => vis(r),
+            Expression::PropertyReference(r)
+            | Expression::SignalReference(r)
+            | Expression::FunctionReference(r)
+            | Expression::TwoWayBinding(r, _) => vis(r),
             // This is not really a named reference, but the result is the same, it need to be updated
             // FIXME: this should probably be lowered into a PropertyReference
             Expression::RepeaterModelReference { element }
@@ -722,6 +1010,12 @@ pub fn visit_all_named_references(elem: &ElementRc, mut vis: impl FnMut(&mut Nam
                 debug_assert!(nc.element.upgrade().unwrap().borrow().repeated.is_some());
                 *element = nc.element;
             }
+            // Same as above, the element might be moved by the passes
+            Expression::ElementReference(element) => {
+                let mut nc = NamedReference { element: element.clone(), name: "$element".into() };
+                vis(&mut nc);
+                *element = nc.element;
+            }
             _ => {}
         }
     }
@@ -740,6 +1034,11 @@ pub fn visit_all_named_references(elem: &ElementRc, mut vis: impl FnMut(&mut Nam
         }
     }
     elem.borrow_mut().transitions = transitions;
+    let mut change_callbacks = std::mem::take(&mut elem.borrow_mut().change_callbacks);
+    for (r, _) in &mut change_callbacks {
+        vis(r);
+    }
+    elem.borrow_mut().change_callbacks = change_callbacks;
 }
 
 #[derive(Debug, Clone)]
@@ -755,6 +1054,34 @@ pub struct Transition {
     pub is_out: bool,
     pub state_id: String,
     pub property_animations: Vec<(NamedReference, ElementRc)>,
+    /// The `animate *`, which applies to all the properties changed by the state
+    pub catch_all_animation: Option<ElementRc>,
+}
+
+/// The animation of a property
+#[derive(Debug, Clone)]
+pub enum PropertyAnimation {
+    /// Declared with `animate` in the element
+    Static(ElementRc),
+    /// Set by the lower_states pass for the properties animated in a transition.
+    /// The animation is selected when the animation starts, depending on the state.
+    Transition {
+        /// Reference to the state property, of type `Type::state_info()`
+        state_ref: Expression,
+        animations: Vec<TransitionPropertyAnimation>,
+        /// The animation used when no transition matches (the `animate` in the element, if any)
+        default: Option<ElementRc>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct TransitionPropertyAnimation {
+    /// The index of the state, as computed by the lower_states pass
+    pub state_id: i32,
+    /// false for 'to', true for 'out'
+    pub is_out: bool,
+    /// The `animate` element
+    pub animation: ElementRc,
 }
 
 #[derive(Debug, Clone)]
@@ -764,13 +1091,13 @@ pub struct NamedExport {
 }
 
 #[derive(Default, Debug, derive_more::Deref)]
-pub struct Exports(Vec<(String, Rc<Component>)>);
+pub struct Exports(Vec<(String, Type)>);
 
 impl Exports {
     pub fn from_node(
         doc: &syntax_nodes::Document,
         inner_components: &Vec<Rc<Component>>,
-        type_registry: &Rc<RefCell<TypeRegister>>,
+        type_registry: &TypeRegister,
         diag: &mut FileDiagnostics,
     ) -> Self {
         let mut exports = doc
@@ -804,7 +1131,7 @@ impl Exports {
 
         exports.extend(doc.ExportsList().flat_map(|exports| exports.Component()).filter_map(
             |component| {
-                let name = match component.child_text(SyntaxKind::Identifier) {
+                let name = match component_name_from_node(&component).0 {
                     Some(name) => name,
                     None => {
                         diag.push_error(
@@ -818,6 +1145,13 @@ impl Exports {
             },
         ));
 
+        exports.extend(
+            doc.ExportsList().flat_map(|exports| exports.StructDeclaration()).filter_map(|st| {
+                let name = st.DeclaredIdentifier().child_text(SyntaxKind::Identifier)?;
+                Some(NamedExport { internal_name: name.clone(), exported_name: name })
+            }),
+        );
+
         if exports.is_empty() {
             let internal_name = inner_components.last().cloned().unwrap_or_default().id.clone();
             exports.push(NamedExport {
@@ -826,34 +1160,13 @@ impl Exports {
             })
         }
 
-        let imported_names = doc
-            .ImportSpecifier()
-            .map(|import| crate::typeloader::ImportedName::extract_imported_names(&import))
-            .flatten()
-            .collect::<Vec<_>>();
-
-        let resolve_export_to_inner_component_or_import = |export: &NamedExport| {
+        let resolve_export_to_inner_component_or_type = |export: &NamedExport| {
             if let Some(local_comp) = inner_components.iter().find(|c| c.id == export.internal_name)
             {
-                local_comp.clone()
+                Type::Component(local_comp.clone())
             } else {
-                imported_names
-                    .iter()
-                    .find_map(|import| {
-                        if import.internal_name == export.internal_name {
-                            Some(
-                                type_registry
-                                    .borrow()
-                                    .lookup_element(&import.internal_name)
-                                    .unwrap()
-                                    .as_component()
-                                    .clone(),
-                            )
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap()
+                // A struct, or an imported type
+                type_registry.lookup_element(&export.internal_name).unwrap()
             }
         };
 
@@ -863,7 +1176,7 @@ impl Exports {
                 .map(|export| {
                     (
                         export.exported_name.clone(),
-                        resolve_export_to_inner_component_or_import(export),
+                        resolve_export_to_inner_component_or_type(export),
                     )
                 })
                 .collect(),
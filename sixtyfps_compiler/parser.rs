@@ -241,12 +241,14 @@ declare_syntax! {
         MinusEqual -> "-=",
         StarEqual -> "*=",
         DivEqual -> "/=",
+        DoubleArrow -> "<=>",
         LessEqual -> "<=",
         GreaterEqual -> ">=",
         EqualEqual -> "==",
         NotEqual -> "!=",
         ColonEqual -> ":=",
         FatArrow -> "=>",
+        Arrow -> "->",
         OrOr -> "||",
         AndAnd -> "&&",
         LBrace -> "{",
@@ -272,20 +274,43 @@ declare_syntax! {
     }
     // syntax kind
     {
-        Document -> [ *Component, *ExportsList, *ImportSpecifier ],
+        Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration ],
+        /// `Foo := Base { ... }` or `global Foo := { ... }`
         Component -> [ Element ],
         /// Note: This is in fact the same as Component as far as the parser is concerned
         SubElement -> [ Element ],
-        Element -> [ QualifiedName, *PropertyDeclaration, *Binding, *SignalConnection,
-                     *SignalDeclaration, *SubElement, *RepeatedElement, *PropertyAnimation,
-                     *States, *Transitions, ?ChildrenPlaceholder ],
+        /// The QualifiedName is only missing for the root element of a `global`
+        Element -> [ ?QualifiedName, *PropertyDeclaration, *Binding, *SignalConnection,
+                     *SignalDeclaration, *Function, *ChangeHandler, *SubElement, *RepeatedElement,
+                     *PropertyAnimation, *TwoWayBinding, *States, *Transitions,
+                     ?ChildrenPlaceholder ],
         RepeatedElement -> [ ?DeclaredIdentifier, ?RepeatedIndex, Expression , Element],
         RepeatedIndex -> [],
         ConditionalElement -> [ Expression , Element],
-        SignalDeclaration -> [ DeclaredIdentifier ],
-        SignalConnection -> [ CodeBlock ],
-        /// Declaration of a propery. QualifiedName is the type.
-        PropertyDeclaration-> [ QualifiedName , DeclaredIdentifier, ?BindingExpression ],
+        /// `signal foo(type, type) -> type;`
+        SignalDeclaration -> [ DeclaredIdentifier, *Type, ?ReturnType ],
+        /// `-> type`  (but without the ->)
+        ReturnType -> [ Type ],
+        /// `foo(a, b) => { ... }`
+        SignalConnection -> [ *DeclaredIdentifier, CodeBlock ],
+        /// `function foo(a: type, b: type) -> type { ... }`
+        Function -> [ DeclaredIdentifier, *ArgumentDeclaration, ?ReturnType, CodeBlock ],
+        /// `changed foo => { ... }`, the QualifiedName is the property
+        ChangeHandler -> [ QualifiedName, CodeBlock ],
+        /// `a: type` in the argument list of a function
+        ArgumentDeclaration -> [ DeclaredIdentifier, Type ],
+        /// Declaration of a propery.
+        PropertyDeclaration-> [ Type , DeclaredIdentifier, ?BindingExpression, ?TwoWayBinding ],
+        /// The type of a property: either a QualifiedName, an ObjectType or an ArrayType
+        Type -> [ ?QualifiedName, ?ObjectType, ?ArrayType ],
+        /// `{ foo: string, bar: int }`
+        ObjectType ->[ *ObjectTypeMember ],
+        /// `foo: type`
+        ObjectTypeMember -> [ Type ],
+        /// `[ type ]`
+        ArrayType -> [ Type ],
+        /// `struct Foo { ... }`
+        StructDeclaration -> [DeclaredIdentifier, ObjectType],
         /// QualifiedName are the properties name
         PropertyAnimation-> [ *QualifiedName, *Binding ],
         /// wraps Identifiers, like `Rectangle` or `SomeModule.SomeType`
@@ -297,6 +322,8 @@ declare_syntax! {
         /// the right-hand-side of a binding
         // Fixme: the test should be a or
         BindingExpression-> [ ?CodeBlock, ?Expression ],
+        /// `foo <=> bar;` (the identifier is not there within a PropertyDeclaration)
+        TwoWayBinding -> [ Expression ],
         CodeBlock-> [ *Expression ],
         // FIXME: the test should test that as alternative rather than several of them (but it can also be a literal)
         Expression-> [ ?Expression, ?BangExpression, ?FunctionCallExpression, ?SelfAssignment,
@@ -304,8 +331,8 @@ declare_syntax! {
                        ?UnaryOpExpression, ?CodeBlock],
         /// `foo!bar`
         BangExpression -> [Expression],
-        /// expression()
-        FunctionCallExpression -> [Expression],
+        /// `expression(arg1, arg2)`
+        FunctionCallExpression -> [*Expression],
         /// `expression += expression`
         SelfAssignment -> [2 Expression],
         /// `condition ? first : second`
@@ -331,7 +358,7 @@ declare_syntax! {
         /// There is an idientfier "to" or "out", the DeclaredIdentifier is the state name
         Transition -> [DeclaredIdentifier, *PropertyAnimation],
         /// Export a set of declared components by name
-        ExportsList -> [ *ExportSpecifier, *Component ],
+        ExportsList -> [ *ExportSpecifier, *Component, *StructDeclaration ],
         /// Declare the first identifier to be exported, either under its name or instead
         /// under the name of the second identifier.
         ExportSpecifier -> [ ExportIdentifier, ?ExportName ],
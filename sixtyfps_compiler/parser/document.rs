@@ -18,6 +18,8 @@ use super::statements::parse_statement;
 /// component Comp := Base {}  Type := Base {}
 /// Type := Base {} export { Type }
 /// import { Base } from "somewhere"; Type := Base {}
+/// struct Foo { foo: foo } Type := Base {}
+/// export struct Foo { foo: foo } Type := Base {}
 /// ```
 pub fn parse_document(p: &mut impl Parser) -> bool {
     let mut p = p.start_node(SyntaxKind::Document);
@@ -34,6 +36,11 @@ pub fn parse_document(p: &mut impl Parser) -> bool {
                     return false;
                 }
             }
+            "struct" if p.nth(1) == SyntaxKind::Identifier => {
+                if !parse_struct_declaration(&mut *p) {
+                    return false;
+                }
+            }
             _ => {
                 if p.peek().as_str() == "component" && p.nth(1) != SyntaxKind::ColonEqual {
                     p.expect(SyntaxKind::Identifier);
@@ -56,13 +63,26 @@ pub fn parse_document(p: &mut impl Parser) -> bool {
 /// Type := Base { }
 /// Type := Base { prop: value; }
 /// Type := Base { SubElement { } }
+/// global Struct := { property<int> xx; }
 /// ```
 pub fn parse_component(p: &mut impl Parser) -> bool {
     let mut p = p.start_node(SyntaxKind::Component);
+    let is_global = p.peek().as_str() == "global" && p.nth(1) == SyntaxKind::Identifier;
+    if is_global {
+        p.consume();
+    }
     if !(p.expect(SyntaxKind::Identifier) && p.expect(SyntaxKind::ColonEqual)) {
         return false;
     }
 
+    if is_global && p.nth(0) == SyntaxKind::LBrace {
+        // The root element of a global has no base type
+        let mut p = p.start_node(SyntaxKind::Element);
+        p.consume(); // "{"
+        parse_element_content(&mut *p);
+        return p.expect(SyntaxKind::RBrace);
+    }
+
     if !parse_element(&mut *p) {
         return false;
     }
@@ -92,7 +112,10 @@ pub fn parse_element(p: &mut impl Parser) -> bool {
 /// for xx in model: Sub {}
 /// if (condition) : Sub {}
 /// clicked => {}
+/// edited(text) => {}
 /// signal foobar;
+/// signal changed(int, string);
+/// function double(x: int) -> int { x * 2 }
 /// property<int> width;
 /// animate someProp { }
 /// animate * { }
@@ -107,12 +130,19 @@ fn parse_element_content(p: &mut impl Parser) {
                 SyntaxKind::Colon => parse_property_binding(&mut *p),
                 SyntaxKind::ColonEqual | SyntaxKind::LBrace => parse_sub_element(&mut *p),
                 SyntaxKind::FatArrow => parse_signal_connection(&mut *p),
+                SyntaxKind::DoubleArrow => parse_two_way_binding(&mut *p),
                 SyntaxKind::Identifier if p.peek().as_str() == "for" => {
                     parse_repeated_element(&mut *p);
                 }
                 SyntaxKind::Identifier if p.peek().as_str() == "signal" => {
                     parse_signal_declaration(&mut *p);
                 }
+                SyntaxKind::Identifier if p.peek().as_str() == "function" => {
+                    parse_function(&mut *p);
+                }
+                SyntaxKind::Identifier if p.peek().as_str() == "changed" => {
+                    parse_change_handler(&mut *p);
+                }
                 SyntaxKind::Identifier | SyntaxKind::Star if p.peek().as_str() == "animate" => {
                     parse_property_animation(&mut *p);
                 }
@@ -122,6 +152,7 @@ fn parse_element_content(p: &mut impl Parser) {
                 SyntaxKind::LParent if p.peek().as_str() == "if" => {
                     parse_if_element(&mut *p);
                 }
+                SyntaxKind::LParent => parse_signal_connection(&mut *p),
                 SyntaxKind::LBracket if p.peek().as_str() == "states" => {
                     parse_states(&mut *p);
                 }
@@ -295,13 +326,41 @@ pub fn parse_code_block(p: &mut impl Parser) {
     p.expect(SyntaxKind::RBrace);
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,TwoWayBinding
+/// foo <=> bar;
+/// foo <=> bar.baz;
+/// ```
+fn parse_two_way_binding(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::TwoWayBinding);
+    p.consume(); // the identifier
+    p.expect(SyntaxKind::DoubleArrow);
+    parse_expression(&mut *p);
+    p.expect(SyntaxKind::Semicolon);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,SignalConnection
 /// clicked => {}
+/// clicked() => { foo; }
+/// mouse_move(x, y) => {}
+/// mouse_move(x, y, ) => { bar; goo; }
 /// ```
 fn parse_signal_connection(p: &mut impl Parser) {
     let mut p = p.start_node(SyntaxKind::SignalConnection);
     p.consume(); // the identifier
+    if p.test(SyntaxKind::LParent) {
+        while p.nth(0) != SyntaxKind::RParent {
+            {
+                let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+                p.expect(SyntaxKind::Identifier);
+            }
+            if !p.test(SyntaxKind::Comma) {
+                break;
+            }
+        }
+        p.expect(SyntaxKind::RParent);
+    }
     p.expect(SyntaxKind::FatArrow);
     parse_code_block(&mut *p);
 }
@@ -309,6 +368,12 @@ fn parse_signal_connection(p: &mut impl Parser) {
 #[cfg_attr(test, parser_test)]
 /// ```test,SignalDeclaration
 /// signal foobar;
+/// signal my_signal();
+/// signal foo(int, string);
+/// signal one_arg({ a: string, b: string});
+/// signal end_coma(a, b, c,);
+/// signal with_return(a, b) -> int;
+/// signal with_return2({a: string}) -> { a: string };
 /// ```
 /// Must consume at least one token
 fn parse_signal_declaration(p: &mut impl Parser) {
@@ -319,20 +384,87 @@ fn parse_signal_declaration(p: &mut impl Parser) {
         let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
         p.expect(SyntaxKind::Identifier);
     }
+    if p.test(SyntaxKind::LParent) {
+        while p.nth(0) != SyntaxKind::RParent {
+            parse_type(&mut *p);
+            if !p.test(SyntaxKind::Comma) {
+                break;
+            }
+        }
+        p.expect(SyntaxKind::RParent);
+        if p.test(SyntaxKind::Arrow) {
+            let mut p = p.start_node(SyntaxKind::ReturnType);
+            parse_type(&mut *p);
+        }
+    }
     p.expect(SyntaxKind::Semicolon);
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,ChangeHandler
+/// changed value => { }
+/// changed foo.bar => { root.baz(self.value); }
+/// ```
+fn parse_change_handler(p: &mut impl Parser) {
+    debug_assert_eq!(p.peek().as_str(), "changed");
+    let mut p = p.start_node(SyntaxKind::ChangeHandler);
+    p.consume(); // "changed"
+    parse_qualified_name(&mut *p);
+    p.expect(SyntaxKind::FatArrow);
+    parse_code_block(&mut *p);
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,Function
+/// function foobar() { }
+/// function add(a: int, b: int) -> int { a + b }
+/// function end_coma(a: string, b: {x: int},) -> string { a }
+/// ```
+fn parse_function(p: &mut impl Parser) {
+    debug_assert_eq!(p.peek().as_str(), "function");
+    let mut p = p.start_node(SyntaxKind::Function);
+    p.consume(); // "function"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    if p.expect(SyntaxKind::LParent) {
+        while p.nth(0) != SyntaxKind::RParent {
+            {
+                let mut p = p.start_node(SyntaxKind::ArgumentDeclaration);
+                {
+                    let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+                    p.expect(SyntaxKind::Identifier);
+                }
+                p.expect(SyntaxKind::Colon);
+                parse_type(&mut *p);
+            }
+            if !p.test(SyntaxKind::Comma) {
+                break;
+            }
+        }
+        p.expect(SyntaxKind::RParent);
+    }
+    if p.test(SyntaxKind::Arrow) {
+        let mut p = p.start_node(SyntaxKind::ReturnType);
+        parse_type(&mut *p);
+    }
+    parse_code_block(&mut *p);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,PropertyDeclaration
 /// property<int> foobar;
 /// property<string> text: "Something";
+/// property<[int]> numbers: [1, 2, 3];
+/// property<int> alias <=> foo.bar;
 /// ```
 fn parse_property_declaration(p: &mut impl Parser) {
     debug_assert_eq!(p.peek().as_str(), "property");
     let mut p = p.start_node(SyntaxKind::PropertyDeclaration);
     p.consume(); // property
     p.expect(SyntaxKind::LAngle);
-    parse_qualified_name(&mut *p);
+    parse_type(&mut *p);
     p.expect(SyntaxKind::RAngle);
     {
         let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
@@ -341,11 +473,91 @@ fn parse_property_declaration(p: &mut impl Parser) {
     if p.nth(0) == SyntaxKind::Colon {
         p.consume();
         parse_binding_expression(&mut *p);
+    } else if p.nth(0) == SyntaxKind::DoubleArrow {
+        let mut p = p.start_node(SyntaxKind::TwoWayBinding);
+        p.consume();
+        parse_expression(&mut *p);
+        p.expect(SyntaxKind::Semicolon);
     } else {
         p.expect(SyntaxKind::Semicolon);
     }
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,Type
+/// string
+/// [int]
+/// [[Some.Type]]
+/// { a: int, b: [string] }
+/// ```
+fn parse_type(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::Type);
+    match p.nth(0) {
+        SyntaxKind::LBracket => parse_array_type(&mut *p),
+        SyntaxKind::LBrace => parse_object_type(&mut *p),
+        _ => {
+            parse_qualified_name(&mut *p);
+        }
+    }
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,ObjectType
+/// { a: string, b: int }
+/// { a: string, b: { c: bool }, }
+/// ```
+fn parse_object_type(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::ObjectType);
+    p.expect(SyntaxKind::LBrace);
+    while !p.test(SyntaxKind::RBrace) {
+        if !parse_object_type_member(&mut *p)
+            || (p.nth(0) != SyntaxKind::RBrace && !p.expect(SyntaxKind::Comma))
+        {
+            p.until(SyntaxKind::RBrace);
+            return;
+        }
+    }
+}
+
+fn parse_object_type_member(p: &mut impl Parser) -> bool {
+    let mut p = p.start_node(SyntaxKind::ObjectTypeMember);
+    if !(p.expect(SyntaxKind::Identifier) && p.expect(SyntaxKind::Colon)) {
+        return false;
+    }
+    parse_type(&mut *p);
+    true
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,StructDeclaration
+/// struct Foo { a: string, b: int }
+/// ```
+fn parse_struct_declaration(p: &mut impl Parser) -> bool {
+    debug_assert_eq!(p.peek().as_str(), "struct");
+    let mut p = p.start_node(SyntaxKind::StructDeclaration);
+    p.consume(); // "struct"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        if !p.expect(SyntaxKind::Identifier) {
+            return false;
+        }
+    }
+    parse_object_type(&mut *p);
+    true
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,ArrayType
+/// [int]
+/// [[string]]
+/// ```
+fn parse_array_type(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::ArrayType);
+    p.expect(SyntaxKind::LBracket);
+    parse_type(&mut *p);
+    p.expect(SyntaxKind::RBracket);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,PropertyAnimation
 /// animate x { duration: 1000; }
@@ -462,6 +674,7 @@ fn parse_transitions(p: &mut impl Parser) {
 /// to pressed : {}
 /// to pressed: { animate x { duration: 88ms; } }
 /// out pressed: { animate x { duration: 88ms; } }
+/// to pressed { animate * { duration: 88ms; } }
 /// ```
 fn parse_transition(p: &mut impl Parser) -> bool {
     if !matches!(p.peek().as_str(), "to" | "out") {
@@ -474,7 +687,8 @@ fn parse_transition(p: &mut impl Parser) -> bool {
         let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
         p.expect(SyntaxKind::Identifier);
     }
-    p.expect(SyntaxKind::Colon);
+    // The colon is optional
+    p.test(SyntaxKind::Colon);
     if !p.expect(SyntaxKind::LBrace) {
         return false;
     }
@@ -525,6 +739,8 @@ fn parse_export(p: &mut impl Parser) -> bool {
                 }
             }
         }
+    } else if p.peek().as_str() == "struct" {
+        parse_struct_declaration(&mut *p)
     } else {
         return parse_component(&mut *p);
     }
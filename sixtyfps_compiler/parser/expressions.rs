@@ -22,6 +22,7 @@ use super::prelude::*;
 /// img!"something"
 /// some_id.some_property
 /// function_call()
+/// function_call(hello, world)
 /// cond ? first : second
 /// call_cond() ? first : second
 /// (nested()) ? (ok) : (other.ko)
@@ -102,7 +103,13 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
             }
             let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::FunctionCallExpression);
 
-            p.consume();
+            p.consume(); // '('
+            while p.nth(0) != SyntaxKind::RParent {
+                parse_expression(&mut *p);
+                if !p.test(SyntaxKind::Comma) {
+                    break;
+                }
+            }
             p.expect(SyntaxKind::RParent);
         }
         _ => {}
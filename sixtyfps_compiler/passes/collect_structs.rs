@@ -0,0 +1,83 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2020 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2020 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+Collect the structs declared with `struct` that are used by a component and its repeated components,
+so the generators can declare them
+ */
+
+use crate::expression_tree::Expression;
+use crate::object_tree::*;
+use crate::typeregister::Type;
+use std::rc::Rc;
+
+pub fn collect_structs(component: &Rc<Component>) {
+    let mut used_structs = vec![];
+    for global in component.used_globals.borrow().iter() {
+        collect_structs_in_component(global, &mut used_structs);
+    }
+    collect_structs_in_component(component, &mut used_structs);
+    *component.used_structs.borrow_mut() = used_structs;
+}
+
+fn collect_structs_in_component(component: &Rc<Component>, used_structs: &mut Vec<Type>) {
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        for decl in elem.borrow().property_declarations.values() {
+            visit_type(&decl.property_type, used_structs);
+        }
+        visit_element_expressions(elem, |e, _| visit_expression(e, used_structs));
+        if elem.borrow().repeated.is_some() {
+            if let Type::Component(c) = &elem.borrow().base_type {
+                collect_structs_in_component(c, used_structs);
+            }
+        }
+    })
+}
+
+fn visit_expression(e: &Expression, used_structs: &mut Vec<Type>) {
+    visit_type(&e.ty(), used_structs);
+    e.visit(|e| visit_expression(e, used_structs));
+}
+
+/// Add the named structs in `ty` to `used_structs`, after the structs used by their fields
+fn visit_type(ty: &Type, used_structs: &mut Vec<Type>) {
+    match ty {
+        Type::Object { fields, name } => {
+            if name.is_some() && used_structs.contains(ty) {
+                return;
+            }
+            if *ty == Type::state_info() {
+                // Declared in the runtime library
+                return;
+            }
+            for field_ty in fields.values() {
+                visit_type(field_ty, used_structs);
+            }
+            if name.is_some() {
+                used_structs.push(ty.clone());
+            }
+        }
+        Type::Array(element_ty) => visit_type(element_ty, used_structs),
+        Type::Signal { args, return_type } => {
+            for arg in args {
+                visit_type(arg, used_structs);
+            }
+            if let Some(return_type) = return_type {
+                visit_type(return_type, used_structs);
+            }
+        }
+        Type::Function { args, return_type } => {
+            for arg in args {
+                visit_type(arg, used_structs);
+            }
+            visit_type(return_type, used_structs);
+        }
+        _ => {}
+    }
+}
@@ -17,11 +17,18 @@ use std::{cell::RefCell, collections::HashMap};
 pub fn deduplicate_property_read(component: &Component) {
     recurse_elem(&component.root_element, &(), &mut |elem, _| {
         visit_element_expressions(elem, |expr, ty| {
-            if ty() == Type::Signal {
+            if matches!(ty(), Type::Signal { .. }) {
                 // Signal handler can't be optimizes because they can have side effect.
                 // But that's fine as they also do not register dependencies
                 return;
             }
+            if let Expression::TwoWayBinding(_, next) = expr {
+                // Only the binding set on the linked property can be optimized
+                if let Some(next) = next {
+                    process_expression(next, &mut Default::default());
+                }
+                return;
+            }
             process_expression(expr, &mut Default::default());
         })
     })
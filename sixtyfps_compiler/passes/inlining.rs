@@ -9,10 +9,14 @@
 LICENSE END */
 //! Inline each object_tree::Component within the main Component
 
-use crate::{expression_tree::NamedReference, object_tree::*, typeregister::Type};
+use crate::{
+    expression_tree::{Expression, NamedReference},
+    object_tree::*,
+    typeregister::Type,
+};
 use by_address::ByAddress;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 use std::rc::Rc;
 
 pub fn inline(doc: &Document) {
@@ -61,6 +65,9 @@ fn inline_element(
     elem_mut.property_animations.extend(
         inlined_component.root_element.borrow().property_animations.iter().map(clone_tuple),
     );
+    elem_mut
+        .change_callbacks
+        .extend(inlined_component.root_element.borrow().change_callbacks.iter().cloned());
 
     // Map the old element to the new
     let mut mapping = HashMap::new();
@@ -93,14 +100,22 @@ fn inline_element(
 
     elem_mut.children = new_children;
 
-    elem_mut.bindings.extend(
-        inlined_component
-            .root_element
-            .borrow()
-            .bindings
-            .iter()
-            .map(|(k, val)| (k.clone(), val.clone())),
-    );
+    for (k, val) in inlined_component.root_element.borrow().bindings.iter() {
+        match elem_mut.bindings.entry(k.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(val.clone());
+            }
+            Entry::Occupied(mut entry) => {
+                // The binding of the element overrides the one of the component, but a
+                // two way binding within the component must still be kept.
+                if let Expression::TwoWayBinding(nr, _) = &val.expression {
+                    let overriding = core::mem::take(&mut entry.get_mut().expression);
+                    entry.get_mut().expression =
+                        Expression::TwoWayBinding(nr.clone(), Some(Box::new(overriding)));
+                }
+            }
+        }
+    }
 
     core::mem::drop(elem_mut);
 
@@ -124,7 +139,7 @@ fn duplicate_element_with_mapping(
         property_animations: elem
             .property_animations
             .iter()
-            .map(|(k, v)| (k.clone(), duplicate_element_with_mapping(v, mapping, root_component)))
+            .map(|(k, v)| (k.clone(), duplicate_property_animation(v, mapping, root_component)))
             .collect(),
         // We will do the fixup of the bindings later
         bindings: elem.bindings.clone(),
@@ -142,6 +157,7 @@ fn duplicate_element_with_mapping(
             .iter()
             .map(|t| duplicate_transition(t, mapping, root_component))
             .collect(),
+        change_callbacks: elem.change_callbacks.clone(),
         child_of_layout: elem.child_of_layout,
     }));
     mapping.insert(element_key(element.clone()), new.clone());
@@ -172,5 +188,41 @@ fn duplicate_transition(
                 (r.clone(), duplicate_element_with_mapping(anim, mapping, root_component))
             })
             .collect(),
+        catch_all_animation: t
+            .catch_all_animation
+            .as_ref()
+            .map(|anim| duplicate_element_with_mapping(anim, mapping, root_component)),
+    }
+}
+
+fn duplicate_property_animation(
+    v: &PropertyAnimation,
+    mapping: &mut HashMap<ByAddress<ElementRc>, ElementRc>,
+    root_component: &Rc<Component>,
+) -> PropertyAnimation {
+    match v {
+        PropertyAnimation::Static(a) => {
+            PropertyAnimation::Static(duplicate_element_with_mapping(a, mapping, root_component))
+        }
+        PropertyAnimation::Transition { state_ref, animations, default } => {
+            PropertyAnimation::Transition {
+                state_ref: state_ref.clone(),
+                animations: animations
+                    .iter()
+                    .map(|a| TransitionPropertyAnimation {
+                        state_id: a.state_id,
+                        is_out: a.is_out,
+                        animation: duplicate_element_with_mapping(
+                            &a.animation,
+                            mapping,
+                            root_component,
+                        ),
+                    })
+                    .collect(),
+                default: default
+                    .as_ref()
+                    .map(|a| duplicate_element_with_mapping(a, mapping, root_component)),
+            }
+        }
     }
 }
@@ -68,7 +68,6 @@ fn lower_grid_layout(
             let row_children = std::mem::take(&mut layout_child.borrow_mut().children);
             for x in row_children {
                 grid.add_element(x, &mut row, &mut col, diag, &component, collected_children);
-                col += 1;
             }
             component.optimized_elements.borrow_mut().push(layout_child.clone());
         } else {
@@ -80,7 +79,6 @@ fn lower_grid_layout(
                 &component,
                 collected_children,
             );
-            col += 1;
         }
     }
     component.optimized_elements.borrow_mut().push(grid_layout_element.clone());
@@ -91,6 +89,59 @@ fn lower_grid_layout(
     }
 }
 
+fn lower_box_layout(
+    component: &Rc<Component>,
+    rect: LayoutRect,
+    layout_element: &ElementRc,
+    collected_children: &mut Vec<ElementRc>,
+    diag: &mut BuildDiagnostics,
+) -> Option<Layout> {
+    let (is_horizontal, wrap) = if let Type::Builtin(be) = &layout_element.borrow().base_type {
+        match be.native_class.class_name.as_str() {
+            "HorizontalLayout" => (true, false),
+            "FlowLayout" => (true, true),
+            _ => (false, false),
+        }
+    } else {
+        unreachable!()
+    };
+    let mut layout = BoxLayout {
+        is_horizontal,
+        wrap,
+        elems: Default::default(),
+        rect,
+        spacing: binding_reference(layout_element, "spacing"),
+        padding: Padding {
+            left: binding_reference(layout_element, "padding_left"),
+            right: binding_reference(layout_element, "padding_right"),
+            top: binding_reference(layout_element, "padding_top"),
+            bottom: binding_reference(layout_element, "padding_bottom"),
+        },
+        alignment: binding_reference(layout_element, "alignment"),
+    };
+
+    let layout_children = std::mem::take(&mut layout_element.borrow_mut().children);
+    for layout_child in layout_children {
+        check_no_layout_properties(&layout_child, diag);
+        let is_repeated = layout_child.borrow().repeated.is_some();
+        // The constraints of a repeated element are read from the repeated component
+        let constraints = if is_repeated {
+            LayoutItemConstraints::default()
+        } else {
+            layout_item_constraints(&layout_child)
+        };
+        if let Some(item) = create_layout_item(component, layout_child, collected_children, diag) {
+            layout.elems.push(BoxLayoutElement { item, constraints });
+        }
+    }
+    component.optimized_elements.borrow_mut().push(layout_element.clone());
+    if !layout.elems.is_empty() {
+        Some(layout.into())
+    } else {
+        None
+    }
+}
+
 fn lower_path_layout(
     component: &Rc<Component>,
     rect: LayoutRect,
@@ -148,6 +199,11 @@ fn layout_parse_function(
         assert!(be.native_class.class_name != "Row"); // Caught at element lookup time
         if be.native_class.class_name == "GridLayout" {
             return Some(&lower_grid_layout);
+        } else if matches!(
+            be.native_class.class_name.as_str(),
+            "HorizontalLayout" | "VerticalLayout" | "FlowLayout"
+        ) {
+            return Some(&lower_box_layout);
         } else if be.native_class.class_name == "PathLayout" {
             return Some(&lower_path_layout);
         }
@@ -236,49 +292,75 @@ impl GridLayout {
             *row = r;
         }
 
-        let layout_item = if let Some(nested_layout_parser) = layout_parse_function(&item_element) {
-            let layout_rect = LayoutRect::install_on_element(&item_element);
+        let (item_col, item_row) = (*col, *row);
+        // The next item is placed after the columns spanned by this one
+        *col += colspan.max(1);
 
-            if let Some(layout) = nested_layout_parser(
-                component,
-                layout_rect,
-                &item_element,
-                collected_children,
-                diag,
-            ) {
-                Box::new(layout).into()
-            } else {
-                return;
-            }
-        } else {
-            item_element.borrow_mut().child_of_layout = true;
-            collected_children.push(item_element.clone());
-            let element = item_element.clone();
-            let layout = {
-                let mut layouts = lower_element_layout(component, &element, diag);
-                if layouts.is_empty() {
-                    None
-                } else {
-                    Some(layouts.remove(0))
-                }
+        let constraints = layout_item_constraints(&item_element);
+        let horizontal_alignment = find_expression("horizontal_cell_alignment", &item_element);
+        let vertical_alignment = find_expression("vertical_cell_alignment", &item_element);
+        let layout_item =
+            match create_layout_item(component, item_element, collected_children, diag) {
+                Some(item) => item,
+                None => return,
             };
-            LayoutElement { element, layout }.into()
-        };
 
         self.elems.push(GridLayoutElement {
-            col: *col,
-            row: *row,
+            col: item_col,
+            row: item_row,
             colspan,
             rowspan,
             item: layout_item,
-            minimum_width: find_expression("minimum_width", &item_element),
-            maximum_width: find_expression("maximum_width", &item_element),
-            minimum_height: find_expression("minimum_height", &item_element),
-            maximum_height: find_expression("maximum_height", &item_element),
+            constraints,
+            horizontal_alignment,
+            vertical_alignment,
         });
     }
 }
 
+/// Create the LayoutItem for an element that is a child of a layout.
+/// Returns None if the element is itself an empty layout.
+fn create_layout_item(
+    component: &Rc<Component>,
+    item_element: ElementRc,
+    collected_children: &mut Vec<ElementRc>,
+    diag: &mut BuildDiagnostics,
+) -> Option<LayoutItem> {
+    if let Some(nested_layout_parser) = layout_parse_function(&item_element) {
+        let layout_rect = LayoutRect::install_on_element(&item_element);
+
+        nested_layout_parser(component, layout_rect, &item_element, collected_children, diag)
+            .map(|layout| Box::new(layout).into())
+    } else {
+        item_element.borrow_mut().child_of_layout = true;
+        collected_children.push(item_element.clone());
+        let element = item_element;
+        let layout = if element.borrow().repeated.is_some() {
+            // The layouts within a repeated element are lowered when visiting that element
+            None
+        } else {
+            let mut layouts = lower_element_layout(component, &element, diag);
+            if layouts.is_empty() {
+                None
+            } else {
+                Some(layouts.remove(0))
+            }
+        };
+        Some(LayoutElement { element, layout }.into())
+    }
+}
+
+fn layout_item_constraints(item_element: &ElementRc) -> LayoutItemConstraints {
+    LayoutItemConstraints {
+        minimum_width: find_expression("minimum_width", item_element),
+        maximum_width: find_expression("maximum_width", item_element),
+        minimum_height: find_expression("minimum_height", item_element),
+        maximum_height: find_expression("maximum_height", item_element),
+        horizontal_stretch: find_expression("horizontal_stretch", item_element),
+        vertical_stretch: find_expression("vertical_stretch", item_element),
+    }
+}
+
 fn find_expression(name: &str, item_element: &ElementRc) -> Option<Box<Expression>> {
     item_element.borrow().bindings.get(name).map(|_| property_reference(item_element, name))
 }
@@ -309,7 +391,15 @@ fn eval_const_expr(
 
 fn check_no_layout_properties(item: &ElementRc, diag: &mut BuildDiagnostics) {
     for (prop, expr) in item.borrow().bindings.iter() {
-        if matches!(prop.as_ref(), "col" | "row" | "colspan" | "rowspan") {
+        if matches!(
+            prop.as_ref(),
+            "col"
+                | "row"
+                | "colspan"
+                | "rowspan"
+                | "horizontal_cell_alignment"
+                | "vertical_cell_alignment"
+        ) {
             diag.push_error(format!("{} used outside of a GridLayout", prop), expr);
         }
     }
@@ -7,19 +7,29 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
-//! Pass that create a state property, and change all the binding to depend on that property
+//! Pass that create a state property, and change all the binding to depend on that property.
+//! The transitions are lowered into animations that depend on that property.
 
 use crate::diagnostics::BuildDiagnostics;
 use crate::expression_tree::*;
 use crate::object_tree::*;
-use crate::typeregister::Type;
+use crate::typeregister::{Type, TypeRegister};
+use by_address::ByAddress;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
-pub fn lower_states(component: &Rc<Component>, diag: &mut BuildDiagnostics) {
-    recurse_elem(&component.root_element, &(), &mut |elem, _| lower_state_in_element(elem, diag));
+pub fn lower_states(component: &Rc<Component>, tr: &TypeRegister, diag: &mut BuildDiagnostics) {
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        lower_state_in_element(elem, tr, diag)
+    });
 }
 
-fn lower_state_in_element(root_element: &ElementRc, _diag: &mut BuildDiagnostics) {
+fn lower_state_in_element(
+    root_element: &ElementRc,
+    tr: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) {
     if root_element.borrow().states.is_empty() {
         return;
     }
@@ -28,43 +38,146 @@ fn lower_state_in_element(root_element: &ElementRc, _diag: &mut BuildDiagnostics
         element: Rc::downgrade(root_element),
         name: state_property.clone(),
     });
+    let current_state = Expression::ObjectAccess {
+        base: Box::new(state_property_ref.clone()),
+        name: "current_state".into(),
+    };
     let mut state_value = Expression::NumberLiteral(0., Unit::None);
+    let mut states_id = HashMap::new();
+    let mut changed_properties = HashMap::new();
     let states = std::mem::take(&mut root_element.borrow_mut().states);
     for (idx, state) in states.into_iter().enumerate().rev() {
+        let state_id = (idx + 1) as i32;
+        states_id.insert(state.id, state_id);
         if let Some(condition) = &state.condition {
             state_value = Expression::Condition {
                 condition: Box::new(condition.clone()),
-                true_expr: Box::new(Expression::NumberLiteral((idx + 1) as _, Unit::None)),
+                true_expr: Box::new(Expression::NumberLiteral(state_id as _, Unit::None)),
                 false_expr: Box::new(std::mem::take(&mut state_value)),
             };
         }
+        let mut changed_in_state = vec![];
         for (ne, expr) in state.property_changes {
             let e = ne.element.upgrade().unwrap();
             let property_expr = expression_for_property(&e, ne.name.as_str());
-            e.borrow_mut().bindings.insert(
-                ne.name,
-                Expression::Condition {
-                    condition: Box::new(Expression::BinaryExpression {
-                        lhs: Box::new(state_property_ref.clone()),
-                        rhs: Box::new(Expression::NumberLiteral((idx + 1) as _, Unit::None)),
-                        op: '=',
-                    }),
-                    true_expr: Box::new(expr),
-                    false_expr: Box::new(property_expr),
+            let state_binding = |false_expr| Expression::Condition {
+                condition: Box::new(Expression::BinaryExpression {
+                    lhs: Box::new(current_state.clone()),
+                    rhs: Box::new(Expression::NumberLiteral(state_id as _, Unit::None)),
+                    op: '=',
+                }),
+                true_expr: Box::new(expr),
+                false_expr: Box::new(false_expr),
+            };
+            let new_binding = match property_expr {
+                // Keep the two way binding: the state changes the binding that is set on it
+                Expression::TwoWayBinding(nr, next) => {
+                    let ty = e.borrow().lookup_property(&ne.name);
+                    let false_expr =
+                        next.map_or_else(|| Expression::default_value_for_type(&ty), |next| *next);
+                    Expression::TwoWayBinding(nr, Some(Box::new(state_binding(false_expr))))
                 }
-                .into(),
-            );
+                property_expr => state_binding(property_expr),
+            };
+            e.borrow_mut().bindings.insert(ne.name.clone(), new_binding.into());
+            changed_in_state.push(ne);
         }
+        changed_properties.insert(state_id, changed_in_state);
     }
     root_element.borrow_mut().property_declarations.insert(
         state_property.clone(),
         PropertyDeclaration {
-            property_type: Type::Int32,
+            property_type: Type::state_info(),
             type_node: None,
             expose_in_public_api: false,
         },
     );
-    root_element.borrow_mut().bindings.insert(state_property.clone(), state_value.into());
+    root_element.borrow_mut().bindings.insert(state_property, state_value.into());
+
+    lower_transitions_in_element(
+        root_element,
+        state_property_ref,
+        &states_id,
+        &changed_properties,
+        tr,
+        diag,
+    );
+}
+
+/// Replace the animations of the properties animated in the transitions of `root_element`
+/// by a `PropertyAnimation::Transition`, which selects the animation depending on the state.
+fn lower_transitions_in_element(
+    root_element: &ElementRc,
+    state_property_ref: Expression,
+    states_id: &HashMap<String, i32>,
+    changed_properties: &HashMap<i32, Vec<NamedReference>>,
+    tr: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) {
+    let transitions = std::mem::take(&mut root_element.borrow_mut().transitions);
+    let mut animated_properties =
+        HashMap::<(ByAddress<ElementRc>, String), Vec<TransitionPropertyAnimation>>::new();
+    for transition in transitions {
+        let state_id = match states_id.get(&transition.state_id) {
+            Some(state_id) => *state_id,
+            None => continue, // The error was reported when creating the object tree
+        };
+        let is_out = transition.is_out;
+        let mut add_animation = |ne: &NamedReference, animation: ElementRc| {
+            let key = (ByAddress(ne.element.upgrade().unwrap()), ne.name.clone());
+            animated_properties.entry(key).or_default().push(TransitionPropertyAnimation {
+                state_id,
+                is_out,
+                animation,
+            });
+        };
+        for (ne, animation) in transition.property_animations {
+            add_animation(&ne, animation);
+        }
+        if let Some(animation) = transition.catch_all_animation {
+            for ne in changed_properties.get(&state_id).into_iter().flatten() {
+                // Only the properties that can be animated get the catch-all animation
+                let property_type =
+                    ne.element.upgrade().unwrap().borrow().lookup_property(&ne.name);
+                if !matches!(
+                    tr.property_animation_type_for_property(property_type),
+                    Type::Builtin(..)
+                ) {
+                    continue;
+                }
+                // Each property gets its own copy, as the passes may change the bindings
+                let animation = Element {
+                    base_type: animation.borrow().base_type.clone(),
+                    bindings: animation.borrow().bindings.clone(),
+                    ..Default::default()
+                };
+                add_animation(ne, Rc::new(RefCell::new(animation)));
+            }
+        }
+    }
+
+    for ((ByAddress(element), name), animations) in animated_properties {
+        let previous_animation = element.borrow_mut().property_animations.remove(&name);
+        let default = match previous_animation {
+            None => None,
+            Some(PropertyAnimation::Static(anim)) => Some(anim),
+            Some(PropertyAnimation::Transition { .. }) => {
+                diag.push_error(
+                    format!("'{}' is animated in the transitions of several elements", name),
+                    &*element.borrow(),
+                );
+                continue;
+            }
+        };
+        element.borrow_mut().property_animations.insert(
+            name,
+            PropertyAnimation::Transition {
+                state_ref: state_property_ref.clone(),
+                animations,
+                default,
+            },
+        );
+    }
 }
 
 /// Returns a suitable unique name for the "state" property
@@ -35,6 +35,9 @@ pub fn move_declarations(component: &Rc<Component>) {
 
     let mut new_root_bindings = HashMap::new();
     let mut new_root_property_animations = HashMap::new();
+    // The change callbacks refer to their property with a NamedReference, so they can be
+    // gathered in the root element regardless of the element they were declared in.
+    let mut new_root_change_callbacks = Vec::new();
 
     let move_bindings_and_animations = &mut |elem: &ElementRc| {
         visit_all_named_references(elem, fixup_reference);
@@ -76,6 +79,11 @@ pub fn move_declarations(component: &Rc<Component>) {
             }
         }
         elem.borrow_mut().property_animations = new_property_animations;
+
+        if !Rc::ptr_eq(elem, &component.root_element) {
+            new_root_change_callbacks
+                .extend(core::mem::take(&mut elem.borrow_mut().change_callbacks));
+        }
     };
 
     recurse_elem(&component.root_element, &(), &mut |e, _| move_bindings_and_animations(e));
@@ -103,6 +111,7 @@ pub fn move_declarations(component: &Rc<Component>) {
         r.property_declarations = decl.property_declarations;
         r.bindings.extend(new_root_bindings.into_iter());
         r.property_animations.extend(new_root_property_animations.into_iter());
+        r.change_callbacks.extend(new_root_change_callbacks.into_iter());
     }
 
     // By now, the optimized item should be unused
@@ -114,6 +123,10 @@ pub fn move_declarations(component: &Rc<Component>) {
 fn fixup_reference(NamedReference { element, name }: &mut NamedReference) {
     let e = element.upgrade().unwrap();
     let component = e.borrow().enclosing_component.upgrade().unwrap();
+    if component.is_global {
+        // The properties of a global stay in the global
+        return;
+    }
     if e.borrow().property_declarations.contains_key(name) {
         *name = map_name(&e, name.as_str());
         *element = Rc::downgrade(&component.root_element);
@@ -126,7 +139,10 @@ fn map_name(e: &ElementRc, s: &str) -> String {
 
 fn fixup_bindings(val: &mut Expression, comp: &Rc<Component>) {
     match val {
-        Expression::PropertyReference(nr) | Expression::SignalReference(nr) => fixup_reference(nr),
+        Expression::PropertyReference(nr)
+        | Expression::SignalReference(nr)
+        | Expression::FunctionReference(nr)
+        | Expression::TwoWayBinding(nr, _) => fixup_reference(nr),
         _ => {}
     };
     val.visit_mut(|sub| fixup_bindings(sub, comp))
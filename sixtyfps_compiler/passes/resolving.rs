@@ -18,7 +18,7 @@ use crate::diagnostics::BuildDiagnostics;
 use crate::expression_tree::*;
 use crate::object_tree::*;
 use crate::parser::{syntax_nodes, SyntaxKind, SyntaxNodeWithSourceFile};
-use crate::typeregister::Type;
+use crate::typeregister::{Type, TypeRegister};
 use by_address::ByAddress;
 use std::{collections::HashMap, collections::HashSet, rc::Rc};
 
@@ -68,14 +68,24 @@ fn resolve_expression(
     expr: &mut Expression,
     property_type: Type,
     scope: &ComponentScope,
+    type_register: &TypeRegister,
     diag: &mut BuildDiagnostics,
 ) {
     if let Expression::Uncompiled(node) = expr {
-        let mut lookup_ctx = LookupCtx { property_type, component_scope: &scope.0, diag };
+        let mut lookup_ctx = LookupCtx {
+            property_type,
+            component_scope: &scope.0,
+            type_register,
+            diag,
+            arguments: vec![],
+        };
 
         let new_expr = match node.kind() {
+            SyntaxKind::SignalConnection => {
+                Expression::from_signal_connection(node.clone().into(), &mut lookup_ctx)
+            }
+            SyntaxKind::Function => Expression::from_function(node.clone().into(), &mut lookup_ctx),
             SyntaxKind::CodeBlock => {
-                //FIXME: proper signal suport (node is a codeblock)
                 Expression::from_codeblock_node(node.clone().into(), &mut lookup_ctx)
             }
             SyntaxKind::Expression => {
@@ -86,6 +96,9 @@ fn resolve_expression(
             SyntaxKind::BindingExpression => {
                 Expression::from_binding_expression_node(node.clone(), &mut lookup_ctx)
             }
+            SyntaxKind::TwoWayBinding => {
+                Expression::from_two_way_binding_node(node.clone().into(), &mut lookup_ctx)
+            }
             _ => {
                 debug_assert!(diag.has_error());
                 Expression::Invalid
@@ -109,12 +122,23 @@ pub fn resolve_expressions(doc: &Document, diag: &mut BuildDiagnostics) {
 
             scope.0.push(elem.clone());
             visit_element_expressions(elem, |expr, property_type| {
-                resolve_expression(expr, property_type(), &scope, diag)
+                resolve_expression(expr, property_type(), &scope, &doc.local_registry, diag)
             });
             scope.0.pop();
             scope
         })
     }
+
+    // The root component instantiates the globals used by all the components it uses
+    let mut used_globals = doc.root_component.used_globals.borrow().clone();
+    for component in all_components.iter() {
+        for global in component.used_globals.borrow().iter() {
+            if !used_globals.iter().any(|g| Rc::ptr_eq(g, global)) {
+                used_globals.push(global.clone());
+            }
+        }
+    }
+    *doc.root_component.used_globals.borrow_mut() = used_globals;
 }
 
 /// Contains information which allow to lookup identifier in expressions
@@ -126,8 +150,14 @@ struct LookupCtx<'a> {
     /// Here is the stack in which id applies
     component_scope: &'a [ElementRc],
 
+    /// The type register of the document, used to look up the globals
+    type_register: &'a TypeRegister,
+
     /// Somewhere to report diagnostics
     diag: &'a mut BuildDiagnostics,
+
+    /// The name of the arguments of the signal handler or function
+    arguments: Vec<String>,
 }
 
 fn find_element_by_id(roots: &[ElementRc], name: &str) -> Option<ElementRc> {
@@ -169,6 +199,15 @@ fn find_parent_element(e: &ElementRc) -> Option<ElementRc> {
     recurse(&root, e)
 }
 
+/// Returns true if the element is, or inherits from, a builtin element that can have the focus
+fn is_focusable(e: &ElementRc) -> bool {
+    match &e.borrow().base_type {
+        Type::Builtin(b) => b.native_class.lookup_property("has_focus") == Some(Type::Bool),
+        Type::Component(c) => is_focusable(&c.root_element),
+        _ => false,
+    }
+}
+
 impl Expression {
     fn from_binding_expression_node(node: SyntaxNodeWithSourceFile, ctx: &mut LookupCtx) -> Self {
         debug_assert_eq!(node.kind(), SyntaxKind::BindingExpression);
@@ -183,6 +222,79 @@ impl Expression {
         e.maybe_convert_to(ctx.property_type.clone(), &node, &mut ctx.diag)
     }
 
+    fn from_two_way_binding_node(node: syntax_nodes::TwoWayBinding, ctx: &mut LookupCtx) -> Self {
+        let e = Self::from_expression_node(node.Expression(), ctx);
+        let ty = e.ty();
+        match e {
+            Expression::PropertyReference(nr) => {
+                if ty != ctx.property_type
+                    && ty != Type::Invalid
+                    && ctx.property_type != Type::Invalid
+                {
+                    ctx.diag.push_error(
+                        format!(
+                            "The property does not have the same type as the bound property ({} != {})",
+                            ctx.property_type, ty
+                        ),
+                        &node,
+                    );
+                }
+                let is_global = nr
+                    .element
+                    .upgrade()
+                    .and_then(|e| e.borrow().enclosing_component.upgrade())
+                    .map_or(false, |c| c.is_global);
+                if is_global {
+                    ctx.diag.push_error(
+                        "Cannot create a two way binding to a property of a global".into(),
+                        &node,
+                    );
+                    return Expression::Invalid;
+                }
+                Expression::TwoWayBinding(nr, None)
+            }
+            Expression::Invalid => Expression::Invalid,
+            _ => {
+                ctx.diag.push_error(
+                    "The expression in a two way binding must be a property reference".into(),
+                    &node,
+                );
+                Expression::Invalid
+            }
+        }
+    }
+
+    fn from_signal_connection(
+        node: syntax_nodes::SignalConnection,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        ctx.arguments = node
+            .DeclaredIdentifier()
+            .map(|x| x.child_text(SyntaxKind::Identifier).unwrap_or_default())
+            .collect();
+        let body = Self::from_codeblock_node(node.CodeBlock(), ctx);
+        match ctx.property_type.clone() {
+            Type::Signal { return_type: Some(return_type), .. } => {
+                body.maybe_convert_to(*return_type, &node, &mut ctx.diag)
+            }
+            _ => body,
+        }
+    }
+
+    fn from_function(node: syntax_nodes::Function, ctx: &mut LookupCtx) -> Expression {
+        ctx.arguments = node
+            .ArgumentDeclaration()
+            .map(|x| x.DeclaredIdentifier().child_text(SyntaxKind::Identifier).unwrap_or_default())
+            .collect();
+        let body = Self::from_codeblock_node(node.CodeBlock(), ctx);
+        match ctx.property_type.clone() {
+            Type::Function { return_type, .. } if *return_type != Type::Void => {
+                body.maybe_convert_to(*return_type, &node, &mut ctx.diag)
+            }
+            _ => body,
+        }
+    }
+
     fn from_codeblock_node(node: syntax_nodes::CodeBlock, ctx: &mut LookupCtx) -> Expression {
         debug_assert_eq!(node.kind(), SyntaxKind::CodeBlock);
         Expression::CodeBlock(
@@ -231,13 +343,7 @@ impl Expression {
                 })
             })
             .or_else(|| {
-                node.FunctionCallExpression().map(|n| Expression::FunctionCall {
-                    function: Box::new(
-                        n.child_node(SyntaxKind::Expression)
-                            .map(|n| Self::from_expression_node(n.into(), ctx))
-                            .unwrap_or(Expression::Invalid),
-                    ),
-                })
+                node.FunctionCallExpression().map(|n| Self::from_function_call_node(n, ctx))
             })
             .or_else(|| node.SelfAssignment().map(|n| Self::from_self_assignement_node(n, ctx)))
             .or_else(|| node.BinaryExpression().map(|n| Self::from_binary_expression_node(n, ctx)))
@@ -302,6 +408,192 @@ impl Expression {
         }
     }
 
+    fn from_function_call_node(
+        node: syntax_nodes::FunctionCallExpression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let mut sub_expr = node.Expression();
+        let function = match sub_expr.next() {
+            Some(function) => function,
+            None => {
+                debug_assert!(ctx.diag.has_error());
+                return Expression::Invalid;
+            }
+        };
+        if let Some(call) =
+            function.QualifiedName().and_then(|n| Self::from_member_function_call(n, ctx))
+        {
+            if matches!(ctx.property_type, Type::Function { .. }) {
+                ctx.diag.push_error("Cannot set the focus in a pure function".into(), &node);
+            }
+            return call;
+        }
+        if matches!(ctx.property_type, Type::Easing) {
+            if let Some(curve) = function
+                .QualifiedName()
+                .and_then(|n| Self::from_easing_function_call(n, &node, ctx))
+            {
+                return curve;
+            }
+        }
+        let function = Self::from_expression_node(function, ctx);
+        if matches!(function, Expression::SignalReference(_))
+            && matches!(ctx.property_type, Type::Function { .. })
+        {
+            ctx.diag.push_error("Cannot emit a signal in a pure function".into(), &node);
+        }
+        let arguments: Vec<_> =
+            sub_expr.map(|n| (Self::from_expression_node(n.clone(), ctx), n)).collect();
+        let arguments = match function.ty() {
+            Type::Signal { args, .. } | Type::Function { args, .. } => {
+                if arguments.len() != args.len() {
+                    ctx.diag.push_error(
+                        format!(
+                            "The signal or function expects {} arguments, but {} are provided",
+                            args.len(),
+                            arguments.len()
+                        ),
+                        &node,
+                    );
+                    arguments.into_iter().map(|x| x.0).collect()
+                } else {
+                    arguments
+                        .into_iter()
+                        .zip(args.iter())
+                        .map(|((e, n), ty)| e.maybe_convert_to(ty.clone(), &n, &mut ctx.diag))
+                        .collect()
+                }
+            }
+            Type::Invalid => arguments.into_iter().map(|x| x.0).collect(),
+            _ => {
+                ctx.diag.push_error("The expression is not a function".into(), &node);
+                arguments.into_iter().map(|x| x.0).collect()
+            }
+        };
+        Expression::FunctionCall { function: Box::new(function), arguments }
+    }
+
+    /// Lookup calls to the functions of builtin elements, such as `some_element.focus()`.
+    /// Returns None if the qualified name does not refer to such a function.
+    fn from_member_function_call(
+        node: syntax_nodes::QualifiedName,
+        ctx: &mut LookupCtx,
+    ) -> Option<Expression> {
+        let identifiers = node
+            .children_with_tokens()
+            .filter(|n| n.kind() == SyntaxKind::Identifier)
+            .filter_map(|n| n.into_token())
+            .collect::<Vec<_>>();
+        let (element_id, function_name) = match identifiers.as_slice() {
+            [element_id, function_name] => (element_id, function_name),
+            _ => return None,
+        };
+        if function_name.text().as_str() != "focus" {
+            return None;
+        }
+        let elem = match element_id.text().as_str() {
+            "self" => ctx.component_scope.last().cloned(),
+            "parent" => ctx.component_scope.last().and_then(find_parent_element),
+            id => find_element_by_id(ctx.component_scope, id),
+        }?;
+        if elem.borrow().lookup_property(function_name.text().as_str()) != Type::Invalid {
+            return None;
+        }
+        if !is_focusable(&elem) {
+            ctx.diag.push_error(
+                format!("Element '{}' cannot have the focus", element_id.text()),
+                function_name,
+            );
+            return Some(Expression::Invalid);
+        }
+        Some(Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(BuiltinFunction::SetFocusItem)),
+            arguments: vec![Expression::ElementReference(Rc::downgrade(&elem))],
+        })
+    }
+
+    /// Lookup the easing curves that take arguments, such as `cubic_bezier(0.1, 0.7, 1.0, 0.1)`
+    /// or `steps(4)`. Returns None if the qualified name does not refer to such a curve.
+    fn from_easing_function_call(
+        name: syntax_nodes::QualifiedName,
+        node: &syntax_nodes::FunctionCallExpression,
+        ctx: &mut LookupCtx,
+    ) -> Option<Expression> {
+        let identifiers = name
+            .children_with_tokens()
+            .filter(|n| n.kind() == SyntaxKind::Identifier)
+            .filter_map(|n| n.into_token())
+            .collect::<Vec<_>>();
+        let function_name = match identifiers.as_slice() {
+            [function_name] => function_name.text().to_string(),
+            _ => return None,
+        };
+        let expected_arguments = match function_name.as_str() {
+            "cubic_bezier" => 4,
+            "steps" => 1,
+            _ => return None,
+        };
+
+        let mut values = Vec::with_capacity(expected_arguments);
+        let mut has_error = false;
+        // The first sub expression is the function name
+        for n in node.Expression().skip(1) {
+            let e = Self::from_expression_node(n.clone(), ctx);
+            match constant_number(&e) {
+                Some(v) => values.push(v),
+                None => {
+                    if e.ty() != Type::Invalid {
+                        ctx.diag.push_error(
+                            format!("Arguments of {} must be number literals", function_name),
+                            &n,
+                        );
+                    }
+                    has_error = true;
+                }
+            }
+        }
+        if has_error {
+            return Some(Expression::Invalid);
+        }
+        if values.len() != expected_arguments {
+            ctx.diag.push_error(
+                format!(
+                    "{} expects {} arguments, but {} are provided",
+                    function_name,
+                    expected_arguments,
+                    values.len()
+                ),
+                node,
+            );
+            return Some(Expression::Invalid);
+        }
+
+        let curve = match values.as_slice() {
+            [x1, y1, x2, y2] => {
+                if !(0.0..=1.0).contains(x1) || !(0.0..=1.0).contains(x2) {
+                    ctx.diag.push_error(
+                        "The x values of cubic_bezier must be between 0 and 1".into(),
+                        node,
+                    );
+                    return Some(Expression::Invalid);
+                }
+                EasingCurve::CubicBezier(*x1 as f32, *y1 as f32, *x2 as f32, *y2 as f32)
+            }
+            [steps] => {
+                if *steps < 1. || steps.fract() != 0. {
+                    ctx.diag.push_error(
+                        "The argument of steps must be a positive integer".into(),
+                        node,
+                    );
+                    return Some(Expression::Invalid);
+                }
+                EasingCurve::Steps(*steps as u32)
+            }
+            _ => unreachable!(),
+        };
+        Some(Expression::EasingCurve(curve))
+    }
+
     /// Perform the lookup
     fn from_qualified_name_node(node: SyntaxNodeWithSourceFile, ctx: &mut LookupCtx) -> Self {
         debug_assert_eq!(node.kind(), SyntaxKind::QualifiedName);
@@ -321,6 +613,17 @@ impl Expression {
 
         let first_str = first.text().as_str();
 
+        if let Some(index) = ctx.arguments.iter().position(|x| x == first_str) {
+            let ty = match &ctx.property_type {
+                Type::Signal { args, .. } | Type::Function { args, .. } => {
+                    args.get(index).cloned().unwrap_or_default()
+                }
+                _ => panic!("There should only be argument within signal handler or function"),
+            };
+            let expression = Expression::FunctionParameterReference { index, ty };
+            return maybe_lookup_object(expression, it, ctx);
+        }
+
         let elem_opt = match first_str {
             "self" => ctx.component_scope.last().cloned(),
             "parent" => ctx.component_scope.last().and_then(find_parent_element),
@@ -344,7 +647,7 @@ impl Expression {
                     name: prop_name.text().to_string(),
                 });
                 return maybe_lookup_object(prop, it, ctx);
-            } else if matches!(p, Type::Signal) {
+            } else if matches!(p, Type::Signal { .. }) {
                 if let Some(x) = it.next() {
                     ctx.diag.push_error("Cannot access fields of signal".into(), &x)
                 }
@@ -352,6 +655,14 @@ impl Expression {
                     element: Rc::downgrade(&elem),
                     name: prop_name.to_string(),
                 });
+            } else if matches!(p, Type::Function { .. }) {
+                if let Some(x) = it.next() {
+                    ctx.diag.push_error("Cannot access fields of function".into(), &x)
+                }
+                return Self::FunctionReference(NamedReference {
+                    element: Rc::downgrade(&elem),
+                    name: prop_name.to_string(),
+                });
             } else {
                 ctx.diag.push_error(format!("Cannot access property '{}'", prop_name), &prop_name);
                 return Self::Invalid;
@@ -375,7 +686,7 @@ impl Expression {
                     name: first_str.to_string(),
                 });
                 return maybe_lookup_object(prop, it, ctx);
-            } else if matches!(property, Type::Signal) {
+            } else if matches!(property, Type::Signal { .. }) {
                 if let Some(x) = it.next() {
                     ctx.diag.push_error("Cannot access fields of signal".into(), &x)
                 }
@@ -383,11 +694,25 @@ impl Expression {
                     element: Rc::downgrade(&elem),
                     name: first_str.to_string(),
                 });
+            } else if matches!(property, Type::Function { .. }) {
+                if let Some(x) = it.next() {
+                    ctx.diag.push_error("Cannot access fields of function".into(), &x)
+                }
+                return Self::FunctionReference(NamedReference {
+                    element: Rc::downgrade(&elem),
+                    name: first_str.to_string(),
+                });
             } else if property.is_object_type() {
                 todo!("Continue lookling up");
             }
         }
 
+        if let Ok(Type::Component(global)) = ctx.type_register.lookup_element(first_str) {
+            if global.is_global {
+                return Self::from_global_lookup(global, first_str, it, &node, ctx);
+            }
+        }
+
         if it.next().is_some() {
             ctx.diag.push_error(format!("Cannot access id '{}'", first_str), &node);
             return Expression::Invalid;
@@ -419,7 +744,23 @@ impl Expression {
                     "ease_in" => Some(EasingCurve::CubicBezier(0.42, 0.0, 1.0, 1.0)),
                     "ease_in_out" => Some(EasingCurve::CubicBezier(0.42, 0.0, 0.58, 1.0)),
                     "ease_out" => Some(EasingCurve::CubicBezier(0.0, 0.0, 0.58, 1.0)),
-                    "cubic_bezier" => todo!("Not yet implemented"),
+                    // The back curves are also CSS cubic-bezier functions, from https://easings.net
+                    "ease_in_back" => Some(EasingCurve::CubicBezier(0.36, 0.0, 0.66, -0.56)),
+                    "ease_out_back" => Some(EasingCurve::CubicBezier(0.34, 1.56, 0.64, 1.0)),
+                    "ease_in_out_back" => Some(EasingCurve::CubicBezier(0.68, -0.6, 0.32, 1.6)),
+                    "ease_in_elastic" => Some(EasingCurve::EaseInElastic),
+                    "ease_out_elastic" => Some(EasingCurve::EaseOutElastic),
+                    "ease_in_out_elastic" => Some(EasingCurve::EaseInOutElastic),
+                    "ease_in_bounce" => Some(EasingCurve::EaseInBounce),
+                    "ease_out_bounce" => Some(EasingCurve::EaseOutBounce),
+                    "ease_in_out_bounce" => Some(EasingCurve::EaseInOutBounce),
+                    "cubic_bezier" | "steps" => {
+                        ctx.diag.push_error(
+                            format!("'{}' must be called with arguments", first_str),
+                            &node,
+                        );
+                        return Self::Invalid;
+                    }
                     _ => None,
                 };
                 if let Some(curve) = value {
@@ -439,12 +780,79 @@ impl Expression {
         Self::Invalid
     }
 
+    /// Lookup of a property or signal of a global component: `Settings.theme`
+    fn from_global_lookup(
+        global: Rc<Component>,
+        global_name: &str,
+        mut it: impl Iterator<Item = crate::parser::SyntaxTokenWithSourceFile>,
+        node: &SyntaxNodeWithSourceFile,
+        ctx: &mut LookupCtx,
+    ) -> Self {
+        let prop_name = if let Some(second) = it.next() {
+            second
+        } else {
+            ctx.diag.push_error(format!("Cannot take reference of global '{}'", global_name), node);
+            return Self::Invalid;
+        };
+
+        let component = ctx
+            .component_scope
+            .first()
+            .and_then(|root| root.borrow().enclosing_component.upgrade())
+            .unwrap();
+        if component.is_global {
+            ctx.diag.push_error("A global component cannot access another global".into(), node);
+            return Self::Invalid;
+        }
+        {
+            let mut used_globals = component.used_globals.borrow_mut();
+            if !used_globals.iter().any(|g| Rc::ptr_eq(g, &global)) {
+                used_globals.push(global.clone());
+            }
+        }
+
+        let elem = &global.root_element;
+        let p = elem.borrow().lookup_property(prop_name.text().as_str());
+        if p.is_property_type() {
+            let prop = Self::PropertyReference(NamedReference {
+                element: Rc::downgrade(elem),
+                name: prop_name.text().to_string(),
+            });
+            maybe_lookup_object(prop, it, ctx)
+        } else if matches!(p, Type::Signal { .. }) {
+            if let Some(x) = it.next() {
+                ctx.diag.push_error("Cannot access fields of signal".into(), &x)
+            }
+            Self::SignalReference(NamedReference {
+                element: Rc::downgrade(elem),
+                name: prop_name.to_string(),
+            })
+        } else if matches!(p, Type::Function { .. }) {
+            if let Some(x) = it.next() {
+                ctx.diag.push_error("Cannot access fields of function".into(), &x)
+            }
+            Self::FunctionReference(NamedReference {
+                element: Rc::downgrade(elem),
+                name: prop_name.to_string(),
+            })
+        } else {
+            ctx.diag.push_error(
+                format!("'{}' is not a property of global '{}'", prop_name, global_name),
+                &prop_name,
+            );
+            Self::Invalid
+        }
+    }
+
     fn from_self_assignement_node(
         node: syntax_nodes::SelfAssignment,
         ctx: &mut LookupCtx,
     ) -> Expression {
         let (lhs_n, rhs_n) = node.Expression();
         let lhs = Self::from_expression_node(lhs_n.into(), ctx);
+        if matches!(ctx.property_type, Type::Function { .. }) {
+            ctx.diag.push_error("Cannot assign to a property in a pure function".into(), &node);
+        }
         let op = None
             .or(node.child_token(SyntaxKind::PlusEqual).and(Some('+')))
             .or(node.child_token(SyntaxKind::MinusEqual).and(Some('-')))
@@ -490,7 +898,16 @@ impl Expression {
 
         let (lhs_n, rhs_n) = node.Expression();
         let lhs = Self::from_expression_node(lhs_n.clone().into(), ctx);
-        let rhs = Self::from_expression_node(rhs_n.clone().into(), ctx);
+        let rhs = match lhs.ty() {
+            // Allow `touch.pressed_button == right`: the values are looked up in the enumeration
+            lhs_ty @ Type::Enumeration(_) if operator_class(op) == OperatorClass::ComparisonOp => {
+                let property_type = std::mem::replace(&mut ctx.property_type, lhs_ty);
+                let rhs = Self::from_expression_node(rhs_n.clone().into(), ctx);
+                ctx.property_type = property_type;
+                rhs
+            }
+            _ => Self::from_expression_node(rhs_n.clone().into(), ctx),
+        };
 
         let expected_ty = match operator_class(op) {
             OperatorClass::ComparisonOp => {
@@ -619,7 +1036,10 @@ impl Expression {
                 )
             })
             .collect();
-        let ty = Type::Object(values.iter().map(|(k, v)| (k.clone(), v.ty())).collect());
+        let ty = Type::Object {
+            fields: values.iter().map(|(k, v)| (k.clone(), v.ty())).collect(),
+            name: None,
+        };
         Expression::Object { ty, values }
     }
 
@@ -650,8 +1070,8 @@ fn maybe_lookup_object(
 ) -> Expression {
     while let Some(next) = it.next() {
         match base.ty() {
-            Type::Object(obj) => {
-                if obj.get(next.text().as_str()).is_some() {
+            Type::Object { fields, .. } => {
+                if fields.get(next.text().as_str()).is_some() {
                     base = Expression::ObjectAccess {
                         base: Box::new(std::mem::replace(&mut base, Expression::Invalid)),
                         name: next.to_string(),
@@ -727,8 +1147,31 @@ fn unescape_string(string: &str) -> Option<String> {
         return None;
     }
     let string = &string[1..(string.len() - 1)];
-    // TODO: remove slashes
-    return Some(string.into());
+    let mut result = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next()? {
+            'n' => result.push('\n'),
+            '"' => result.push('"'),
+            '\\' => result.push('\\'),
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// Returns the value of a number literal without unit, possibly negated
+fn constant_number(e: &Expression) -> Option<f64> {
+    match e {
+        Expression::NumberLiteral(v, Unit::None) => Some(*v),
+        Expression::UnaryOp { sub, op: '-' } => constant_number(sub).map(|v| -v),
+        Expression::UnaryOp { sub, op: '+' } => constant_number(sub),
+        _ => None,
+    }
 }
 
 fn parse_number_literal(s: String) -> Result<Expression, String> {
@@ -767,3 +1210,12 @@ fn test_parse_number_literal() {
     assert_eq!(doit("12.12oo"), wrong_unit);
     assert_eq!(doit("12.12€"), wrong_unit);
 }
+
+#[test]
+fn test_unescape_string() {
+    assert_eq!(unescape_string(r#""hello""#), Some("hello".into()));
+    assert_eq!(unescape_string(r#""a\nb""#), Some("a\nb".into()));
+    assert_eq!(unescape_string(r#""\"\\""#), Some("\"\\".into()));
+    assert_eq!(unescape_string(r#""\x""#), None);
+    assert_eq!(unescape_string("hello"), None);
+}
@@ -117,12 +117,18 @@ fn load_dependency<'a>(
         &dependency_registry,
     );
 
+    if !dependency_diagnostics.has_error() {
+        // Resolve the expressions with the types of this document, so the globals it uses are
+        // found even if they are not imported by the importer
+        crate::passes::resolving::resolve_expressions(&doc, build_diagnostics);
+    }
+
     let exports = doc.exports();
 
     for import_name in imported_types.type_names {
-        let imported_type = exports.iter().find_map(|(export_name, component)| {
+        let imported_type = exports.iter().find_map(|(export_name, ty)| {
             if import_name.external_name == *export_name {
-                Some(component.clone())
+                Some(ty.clone())
             } else {
                 None
             }
@@ -143,7 +149,9 @@ fn load_dependency<'a>(
             }
         };
 
-        registry_to_populate.borrow_mut().add_with_name(import_name.internal_name, imported_type);
+        registry_to_populate
+            .borrow_mut()
+            .insert_type_with_name(imported_type, import_name.internal_name);
     }
 
     build_diagnostics.add(dependency_diagnostics);
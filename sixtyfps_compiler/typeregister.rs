@@ -20,7 +20,10 @@ pub enum Type {
     Builtin(Rc<BuiltinElement>),
     Native(Rc<NativeClass>),
 
-    Signal,
+    Signal {
+        return_type: Option<Box<Type>>,
+        args: Vec<Type>,
+    },
     Function {
         return_type: Box<Type>,
         args: Vec<Type>,
@@ -41,10 +44,16 @@ pub enum Type {
     Easing,
 
     Array(Box<Type>),
-    Object(BTreeMap<String, Type>),
+    /// An object with the given fields. The name is set for the `struct` declared in .60 files
+    Object {
+        fields: BTreeMap<String, Type>,
+        name: Option<String>,
+    },
 
     Enumeration(Rc<Enumeration>),
     EnumerationValue(EnumerationValue),
+
+    ElementReference,
 }
 
 impl core::cmp::PartialEq for Type {
@@ -55,7 +64,10 @@ impl core::cmp::PartialEq for Type {
             (Type::Component(a), Type::Component(b)) => Rc::ptr_eq(a, b),
             (Type::Builtin(a), Type::Builtin(b)) => Rc::ptr_eq(a, b),
             (Type::Native(a), Type::Native(b)) => Rc::ptr_eq(a, b),
-            (Type::Signal, Type::Signal) => true,
+            (
+                Type::Signal { args: lhs_args, return_type: lhs_ret },
+                Type::Signal { args: rhs_args, return_type: rhs_ret },
+            ) => lhs_args == rhs_args && lhs_ret == rhs_ret,
             (
                 Type::Function { return_type: lhs_rt, args: lhs_args },
                 Type::Function { return_type: rhs_rt, args: rhs_args },
@@ -70,11 +82,15 @@ impl core::cmp::PartialEq for Type {
             (Type::Resource, Type::Resource) => true,
             (Type::Bool, Type::Bool) => true,
             (Type::Array(a), Type::Array(b)) => a == b,
-            (Type::Object(a), Type::Object(b)) => a == b,
+            (
+                Type::Object { fields: a, name: a_name },
+                Type::Object { fields: b, name: b_name },
+            ) => a == b && a_name == b_name,
             (Type::Model, Type::Model) => true,
             (Type::PathElements, Type::PathElements) => true,
             (Type::Easing, Type::Easing) => true,
             (Type::Enumeration(lhs), Type::Enumeration(rhs)) => lhs == rhs,
+            (Type::ElementReference, Type::ElementReference) => true,
             _ => false,
         }
     }
@@ -88,7 +104,23 @@ impl Display for Type {
             Type::Component(c) => c.id.fmt(f),
             Type::Builtin(b) => b.native_class.class_name.fmt(f),
             Type::Native(b) => b.class_name.fmt(f),
-            Type::Signal => write!(f, "signal"),
+            Type::Signal { args, return_type } => {
+                write!(f, "signal")?;
+                if !args.is_empty() {
+                    write!(f, "(")?;
+                    for (i, arg) in args.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ",")?;
+                        }
+                        write!(f, "{}", arg)?;
+                    }
+                    write!(f, ")")?
+                }
+                if let Some(rt) = return_type {
+                    write!(f, "-> {}", rt)?;
+                }
+                Ok(())
+            }
             Type::Function { return_type, args } => {
                 write!(f, "function(")?;
                 for (i, arg) in args.iter().enumerate() {
@@ -110,9 +142,10 @@ impl Display for Type {
             Type::Bool => write!(f, "bool"),
             Type::Model => write!(f, "model"),
             Type::Array(t) => write!(f, "[{}]", t),
-            Type::Object(t) => {
+            Type::Object { name: Some(name), .. } => write!(f, "{}", name),
+            Type::Object { fields, name: None } => {
                 write!(f, "{{ ")?;
-                for (k, v) in t {
+                for (k, v) in fields {
                     write!(f, "{}: {},", k, v)?;
                 }
                 write!(f, "}}")
@@ -123,6 +156,7 @@ impl Display for Type {
             Type::EnumerationValue(value) => {
                 write!(f, "enum {}::{}", value.enumeration.name, value.to_string())
             }
+            Type::ElementReference => write!(f, "element ref"),
         }
     }
 }
@@ -148,10 +182,26 @@ impl Type {
                 | Self::Model
                 | Self::Easing
                 | Self::Enumeration(_)
-                | Self::Object(_)
+                | Self::Object { .. }
+                | Self::Array(_)
         )
     }
 
+    /// The type of the state property created by the lower_states pass, which holds the current
+    /// and the previous state. It is the `StateInfo` struct of the runtime library.
+    pub fn state_info() -> Type {
+        Type::Object {
+            fields: [
+                ("current_state".to_owned(), Type::Int32),
+                ("previous_state".to_owned(), Type::Int32),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            name: Some("StateInfo".into()),
+        }
+    }
+
     pub fn lookup_property(&self, name: &str) -> Type {
         match self {
             Type::Component(c) => c.root_element.borrow().lookup_property(name),
@@ -407,6 +457,10 @@ pub fn reserved_property(name: &str) -> Type {
         ("row", Type::Int32),
         ("colspan", Type::Int32),
         ("rowspan", Type::Int32),
+        ("horizontal_stretch", Type::Float32),
+        ("vertical_stretch", Type::Float32),
+        ("horizontal_cell_alignment", Type::Enumeration(cell_alignment_enum())),
+        ("vertical_cell_alignment", Type::Enumeration(cell_alignment_enum())),
     ]
     .iter()
     {
@@ -417,6 +471,16 @@ pub fn reserved_property(name: &str) -> Type {
     Type::Invalid
 }
 
+/// The enumeration for the alignment of an item within its cell in a GridLayout
+fn cell_alignment_enum() -> Rc<Enumeration> {
+    thread_local!(static CELL_ALIGNMENT: Rc<Enumeration> = Rc::new(Enumeration {
+        name: "CellAlignment".to_owned(),
+        values: ["stretch", "start", "center", "end"].iter().map(|x| x.to_string()).collect(),
+        default_value: 0,
+    }));
+    CELL_ALIGNMENT.with(|e| e.clone())
+}
+
 #[derive(Debug, Default)]
 pub struct TypeRegister {
     /// The set of types.
@@ -457,6 +521,26 @@ impl TypeRegister {
             declare_enum("TextHorizontalAlignment", &["align_left", "align_center", "align_right"]);
         let text_vertical_alignment =
             declare_enum("TextVerticalAlignment", &["align_top", "align_center", "align_bottom"]);
+        let text_wrap = declare_enum("TextWrap", &["no_wrap", "word_wrap"]);
+        let text_overflow = declare_enum("TextOverflow", &["clip", "elide"]);
+        let pointer_button = declare_enum("PointerButton", &["none", "left", "right", "middle"]);
+        let layout_alignment = declare_enum(
+            "LayoutAlignment",
+            &["stretch", "center", "start", "end", "space_between", "space_around"],
+        );
+        let mouse_cursor = declare_enum(
+            "MouseCursor",
+            &[
+                "cursor_default",
+                "cursor_pointer",
+                "cursor_text",
+                "cursor_move",
+                "cursor_ew_resize",
+                "cursor_ns_resize",
+                "cursor_nesw_resize",
+                "cursor_nwse_resize",
+            ],
+        );
 
         let native_class = |tr: &mut TypeRegister, name: &str, properties: &[(&str, Type)]| {
             let native = Rc::new(NativeClass::new_with_properties(
@@ -508,6 +592,8 @@ impl TypeRegister {
                 ("color", Type::Color),
                 ("horizontal_alignment", Type::Enumeration(text_horizontal_alignment)),
                 ("vertical_alignment", Type::Enumeration(text_vertical_alignment)),
+                ("wrap", Type::Enumeration(text_wrap)),
+                ("overflow", Type::Enumeration(text_overflow)),
                 ("x", Type::Length),
                 ("y", Type::Length),
                 ("width", Type::Length),
@@ -515,6 +601,28 @@ impl TypeRegister {
             ],
         );
 
+        native_class(
+            &mut r,
+            "TextInput",
+            &[
+                ("text", Type::String),
+                ("font_family", Type::String),
+                ("font_size", Type::Length),
+                ("color", Type::Color),
+                ("selection_background_color", Type::Color),
+                ("x", Type::Length),
+                ("y", Type::Length),
+                ("width", Type::Length),
+                ("height", Type::Length),
+                ("cursor_position", Type::Int32),
+                ("anchor_position", Type::Int32),
+                ("text_cursor_width", Type::Length),
+                ("has_focus", Type::Bool),
+                ("accepted", Type::Signal { return_type: None, args: vec![] }),
+                ("edited", Type::Signal { return_type: None, args: vec![] }),
+            ],
+        );
+
         native_class(
             &mut r,
             "TouchArea",
@@ -528,7 +636,16 @@ impl TypeRegister {
                 ("mouse_y", Type::Length),
                 ("pressed_x", Type::Length),
                 ("pressed_y", Type::Length),
-                ("clicked", Type::Signal),
+                ("pressed_button", Type::Enumeration(pointer_button)),
+                ("shift_modifier", Type::Bool),
+                ("control_modifier", Type::Bool),
+                ("alt_modifier", Type::Bool),
+                ("meta_modifier", Type::Bool),
+                ("has_hover", Type::Bool),
+                ("mouse_cursor", Type::Enumeration(mouse_cursor)),
+                ("clicked", Type::Signal { return_type: None, args: vec![] }),
+                ("double_clicked", Type::Signal { return_type: None, args: vec![] }),
+                ("long_pressed", Type::Signal { return_type: None, args: vec![] }),
             ],
         );
 
@@ -545,6 +662,36 @@ impl TypeRegister {
 
         native_class(&mut r, "Window", &[("width", Type::Length), ("height", Type::Length)]);
 
+        native_class(
+            &mut r,
+            "FocusScope",
+            &[
+                ("x", Type::Length),
+                ("y", Type::Length),
+                ("width", Type::Length),
+                ("height", Type::Length),
+                ("key", Type::String),
+                ("text", Type::String),
+                ("has_focus", Type::Bool),
+                ("key_pressed", Type::Signal { return_type: None, args: vec![] }),
+                ("key_released", Type::Signal { return_type: None, args: vec![] }),
+            ],
+        );
+
+        // The native class is called TimerItem so it does not clash with the sixtyfps::Timer API
+        let timer = Rc::new(NativeClass::new_with_properties(
+            "TimerItem",
+            [
+                ("interval", Type::Duration),
+                ("running", Type::Bool),
+                ("repeat", Type::Bool),
+                ("triggered", Type::Signal { return_type: None, args: vec![] }),
+            ]
+            .iter()
+            .map(|(n, t)| (n.to_string(), t.clone())),
+        ));
+        r.types.insert("Timer".to_owned(), Type::Builtin(Rc::new(BuiltinElement::new(timer))));
+
         let mut grid_layout = BuiltinElement::new(Rc::new(NativeClass::new("GridLayout")));
         grid_layout.properties.insert("spacing".to_owned(), Type::Length);
         grid_layout.properties.insert("padding_left".to_owned(), Type::Length);
@@ -561,6 +708,19 @@ impl TypeRegister {
 
         r.types.insert("GridLayout".to_owned(), Type::Builtin(Rc::new(grid_layout)));
 
+        for name in &["HorizontalLayout", "VerticalLayout", "FlowLayout"] {
+            let mut box_layout = BuiltinElement::new(Rc::new(NativeClass::new(name)));
+            box_layout.properties.insert("spacing".to_owned(), Type::Length);
+            box_layout.properties.insert("padding_left".to_owned(), Type::Length);
+            box_layout.properties.insert("padding_right".to_owned(), Type::Length);
+            box_layout.properties.insert("padding_top".to_owned(), Type::Length);
+            box_layout.properties.insert("padding_bottom".to_owned(), Type::Length);
+            box_layout
+                .properties
+                .insert("alignment".to_owned(), Type::Enumeration(layout_alignment.clone()));
+            r.types.insert(name.to_string(), Type::Builtin(Rc::new(box_layout)));
+        }
+
         let mut path_class = NativeClass::new("Path");
         path_class.properties.insert("x".to_owned(), Type::Length);
         path_class.properties.insert("y".to_owned(), Type::Length);
@@ -636,6 +796,7 @@ impl TypeRegister {
         property_animation.properties.insert("duration".to_owned(), Type::Duration);
         property_animation.properties.insert("easing".to_owned(), Type::Easing);
         property_animation.properties.insert("loop_count".to_owned(), Type::Int32);
+        property_animation.properties.insert("delay".to_owned(), Type::Duration);
         let mut property_animation = BuiltinElement::new(Rc::new(property_animation));
         property_animation.is_non_item_type = true;
         r.property_animation_type = Type::Builtin(Rc::new(property_animation));
@@ -660,7 +821,7 @@ impl TypeRegister {
                 ("height", Type::Length),
                 ("text", Type::String),
                 ("pressed", Type::Bool),
-                ("clicked", Type::Signal),
+                ("clicked", Type::Signal { return_type: None, args: vec![] }),
             ],
         );
         native_class(
@@ -673,7 +834,7 @@ impl TypeRegister {
                 ("height", Type::Length),
                 ("text", Type::String),
                 ("checked", Type::Bool),
-                ("toggled", Type::Signal),
+                ("toggled", Type::Signal { return_type: None, args: vec![] }),
             ],
         );
         native_class(
@@ -700,6 +861,17 @@ impl TypeRegister {
                 ("max", Type::Float32),
             ],
         );
+        native_class(
+            &mut r,
+            "QtStyleLineEdit",
+            &[
+                ("x", Type::Length),
+                ("y", Type::Length),
+                ("width", Type::Length),
+                ("height", Type::Length),
+                ("focused", Type::Bool),
+            ],
+        );
 
         Rc::new(RefCell::new(r))
     }
@@ -770,6 +942,10 @@ impl TypeRegister {
         self.types.insert(name, Type::Component(comp));
     }
 
+    pub fn insert_type_with_name(&mut self, t: Type, name: String) {
+        self.types.insert(name, t);
+    }
+
     pub fn property_animation_type_for_property(&self, property_type: Type) -> Type {
         if self.supported_property_animation_types.contains(&property_type.to_string()) {
             self.property_animation_type.clone()
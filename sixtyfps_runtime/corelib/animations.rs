@@ -19,6 +19,21 @@ pub enum EasingCurve {
     Linear,
     /// A Cubic bezier curve, with its 4 parameter
     CubicBezier([f32; 4]),
+    /// An elastic curve that oscillates with a growing amplitude before reaching the end value
+    EaseInElastic,
+    /// An elastic curve that overshoots the end value and oscillates around it
+    EaseOutElastic,
+    /// The combination of [`Self::EaseInElastic`] and [`Self::EaseOutElastic`]
+    EaseInOutElastic,
+    /// A curve that bounces at the start value before moving to the end value
+    EaseInBounce,
+    /// A curve that bounces on the end value
+    EaseOutBounce,
+    /// The combination of [`Self::EaseInBounce`] and [`Self::EaseOutBounce`]
+    EaseInOutBounce,
+    /// A step function dividing the animation in the given number of intervals,
+    /// like the CSS `steps(n)` function (which is the same as `steps(n, jump-end)`)
+    Steps(u32),
     //Custom(Box<dyn Fn(f32) -> f32>),
 }
 
@@ -38,10 +53,11 @@ pub struct AnimationDriver {
 
 impl Default for AnimationDriver {
     fn default() -> Self {
+        let now = instant::Instant::now();
         AnimationDriver {
             active_animations: Cell::default(),
-            global_instant: Box::pin(crate::Property::new(instant::Instant::now())),
-            initial_instant: instant::Instant::now(),
+            global_instant: Box::pin(crate::Property::new(now)),
+            initial_instant: now,
         }
     }
 }
@@ -69,6 +85,18 @@ impl AnimationDriver {
     pub fn current_tick(&self) -> instant::Instant {
         self.global_instant.as_ref().get()
     }
+
+    /// The current instant that is to be used for animation and timers,
+    /// without registering a dependency to the current binding
+    pub(crate) fn current_tick_untracked(&self) -> instant::Instant {
+        self.global_instant.as_ref().get_untracked()
+    }
+
+    /// The time elapsed between the creation of the driver and the current instant
+    /// using this function register the current binding as a dependency
+    pub fn elapsed(&self) -> instant::Duration {
+        self.current_tick() - self.initial_instant
+    }
 }
 
 thread_local!(pub(crate) static CURRENT_ANIMATION_DRIVER : AnimationDriver = AnimationDriver::default());
@@ -84,7 +112,7 @@ pub fn easing_curve(curve: &EasingCurve, value: f32) -> f32 {
     match curve {
         EasingCurve::Linear => value,
         EasingCurve::CubicBezier([a, b, c, d]) => {
-            if !(0.0..=1.0).contains(a) && !(0.0..=1.0).contains(c) {
+            if !(0.0..=1.0).contains(a) || !(0.0..=1.0).contains(c) {
                 return value;
             };
             let curve = lyon::algorithms::geom::cubic_bezier::CubicBezierSegment {
@@ -96,7 +124,83 @@ pub fn easing_curve(curve: &EasingCurve, value: f32) -> f32 {
             let curve = curve.assume_monotonic();
             curve.y(curve.solve_t_for_x(value, 0.0..1.0, 0.01))
         }
+        EasingCurve::EaseInElastic => ease_in_elastic(value),
+        EasingCurve::EaseOutElastic => 1. - ease_in_elastic(1. - value),
+        EasingCurve::EaseInOutElastic => {
+            if value < 0.5 {
+                ease_in_elastic(value * 2.) / 2.
+            } else {
+                1. - ease_in_elastic(2. - value * 2.) / 2.
+            }
+        }
+        EasingCurve::EaseInBounce => 1. - ease_out_bounce(1. - value),
+        EasingCurve::EaseOutBounce => ease_out_bounce(value),
+        EasingCurve::EaseInOutBounce => {
+            if value < 0.5 {
+                (1. - ease_out_bounce(1. - value * 2.)) / 2.
+            } else {
+                (1. + ease_out_bounce(value * 2. - 1.)) / 2.
+            }
+        }
+        EasingCurve::Steps(steps) => {
+            if *steps == 0 {
+                return value;
+            }
+            let steps = *steps as f32;
+            (value.max(0.) * steps).floor().min(steps) / steps
+        }
+    }
+}
+
+// The formulas of the elastic and bounce curves are the ones from https://easings.net
+
+fn ease_in_elastic(value: f32) -> f32 {
+    if value <= 0. {
+        return 0.;
+    }
+    if value >= 1. {
+        return 1.;
+    }
+    let c4 = 2. * core::f32::consts::PI / 3.;
+    -(2f32.powf(10. * value - 10.)) * ((value * 10. - 10.75) * c4).sin()
+}
+
+fn ease_out_bounce(value: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if value < 1. / D1 {
+        N1 * value * value
+    } else if value < 2. / D1 {
+        let value = value - 1.5 / D1;
+        N1 * value * value + 0.75
+    } else if value < 2.5 / D1 {
+        let value = value - 2.25 / D1;
+        N1 * value * value + 0.9375
+    } else {
+        let value = value - 2.625 / D1;
+        N1 * value * value + 0.984375
+    }
+}
+
+#[test]
+fn easing_curve_bounds() {
+    for curve in &[
+        EasingCurve::Linear,
+        EasingCurve::CubicBezier([0.68, -0.6, 0.32, 1.6]),
+        EasingCurve::EaseInElastic,
+        EasingCurve::EaseOutElastic,
+        EasingCurve::EaseInOutElastic,
+        EasingCurve::EaseInBounce,
+        EasingCurve::EaseOutBounce,
+        EasingCurve::EaseInOutBounce,
+        EasingCurve::Steps(4),
+    ] {
+        assert!(easing_curve(curve, 0.).abs() < 0.001, "{:?}", curve);
+        assert!((easing_curve(curve, 1.) - 1.).abs() < 0.001, "{:?}", curve);
     }
+    assert_eq!(easing_curve(&EasingCurve::Steps(4), 0.2), 0.);
+    assert_eq!(easing_curve(&EasingCurve::Steps(4), 0.3), 0.25);
+    assert_eq!(easing_curve(&EasingCurve::Steps(4), 0.99), 0.75);
 }
 
 /*
@@ -11,7 +11,7 @@ LICENSE END */
 
 //! This module contains the basic datastructures that are exposed to the C API
 
-use crate::input::{InputEventResult, MouseEvent};
+use crate::input::{InputEventResult, KeyEvent, KeyEventResult, MouseEvent};
 use crate::item_tree::{ItemVisitorVTable, TraversalOrder, VisitChildrenResult};
 use crate::layout::LayoutInfo;
 use vtable::*;
@@ -39,6 +39,10 @@ pub struct ComponentVTable {
     /// input event
     pub input_event:
         extern "C" fn(core::pin::Pin<VRef<ComponentVTable>>, MouseEvent) -> InputEventResult,
+
+    /// key event
+    pub key_event:
+        extern "C" fn(core::pin::Pin<VRef<ComponentVTable>>, &KeyEvent) -> KeyEventResult,
 }
 
 /// Alias for `vtable::VRef<ComponentVTable>` which represent a pointer to a `dyn Component` with
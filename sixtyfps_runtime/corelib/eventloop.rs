@@ -16,7 +16,11 @@ use std::{
 };
 use vtable::*;
 
-use crate::{input::MouseEventType, properties::PropertyTracker};
+use crate::input::{
+    KeyCode, KeyEvent, KeyEventResult, KeyEventType, KeyboardModifiers, MouseEventType,
+};
+use crate::items::PointerButton;
+use crate::properties::PropertyTracker;
 #[cfg(not(target_arch = "wasm32"))]
 use winit::platform::desktop::EventLoopExtDesktop;
 
@@ -26,8 +30,42 @@ pub trait GenericWindow {
         &self,
         pos: winit::dpi::PhysicalPosition<f64>,
         what: MouseEventType,
+        button: PointerButton,
+        modifiers: KeyboardModifiers,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+    );
+    /// Sends a MouseWheel event to the item under the cursor.
+    fn process_mouse_wheel(
+        &self,
+        pos: winit::dpi::PhysicalPosition<f64>,
+        delta: winit::event::MouseScrollDelta,
+        modifiers: KeyboardModifiers,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+    );
+    fn process_key_input(
+        &self,
+        event: &KeyEvent,
         component: core::pin::Pin<crate::component::ComponentRef>,
     );
+    /// Delivers the key event to the item that has the focus, or moves the focus on Tab and Shift+Tab.
+    /// This is called by the component when it receives a key event.
+    fn dispatch_key_event(
+        &self,
+        event: &KeyEvent,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+    ) -> KeyEventResult;
+    /// Gives the focus to the item, if it accepts it. The component is the root component of the window.
+    fn set_focus_item(
+        &self,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+        item: Pin<ItemRef>,
+    );
+    /// Gives the focus to the topmost item at the given position that accepts it.
+    fn set_focus_item_at(
+        &self,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+        pos: crate::graphics::Point,
+    );
     fn with_platform_window(&self, callback: &dyn Fn(&winit::window::Window));
     fn map_window(self: Rc<Self>, event_loop: &EventLoop, root_item: Pin<ItemRef>);
     fn unmap_window(self: Rc<Self>);
@@ -79,6 +117,33 @@ impl ComponentWindow {
     ) {
         self.0.clone().free_graphics_resources(component);
     }
+
+    /// Delivers the key event to the item of the component that has the focus.
+    pub fn dispatch_key_event(
+        &self,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+        event: &KeyEvent,
+    ) -> KeyEventResult {
+        self.0.dispatch_key_event(event, component)
+    }
+
+    /// Gives the focus to the item, if it accepts it. The component is the root component of the window.
+    pub fn set_focus_item(
+        &self,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+        item: Pin<ItemRef>,
+    ) {
+        self.0.set_focus_item(component, item)
+    }
+
+    /// Gives the focus to the topmost item at the given position that accepts it.
+    pub fn set_focus_item_at(
+        &self,
+        component: core::pin::Pin<crate::component::ComponentRef>,
+        pos: crate::graphics::Point,
+    ) {
+        self.0.set_focus_item_at(component, pos)
+    }
 }
 
 thread_local! {
@@ -97,6 +162,338 @@ pub(crate) fn unregister_window(id: winit::window::WindowId) {
     })
 }
 
+/// The mode of a [`Timer`]
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimerMode {
+    /// The timer is triggered once, and then stops
+    SingleShot,
+    /// The timer is triggered each time the interval elapses, until it is stopped
+    Repeated,
+}
+
+struct TimerData {
+    timeout: instant::Instant,
+    interval: core::time::Duration,
+    mode: TimerMode,
+    running: bool,
+    /// The timers created with [`Timer::single_shot`] are not owned by a `Timer` and are removed
+    /// once triggered
+    removed_when_triggered: bool,
+    /// None while the callback is being invoked
+    callback: Option<Box<dyn FnMut()>>,
+}
+
+impl TimerData {
+    fn new(
+        mode: TimerMode,
+        interval: core::time::Duration,
+        callback: Box<dyn FnMut()>,
+        removed_when_triggered: bool,
+    ) -> Self {
+        // A repeated timer with a null interval would be triggered forever
+        let interval = if mode == TimerMode::Repeated {
+            interval.max(core::time::Duration::from_millis(1))
+        } else {
+            interval
+        };
+        Self {
+            timeout: current_tick() + interval,
+            interval,
+            mode,
+            running: true,
+            removed_when_triggered,
+            callback: Some(callback),
+        }
+    }
+}
+
+/// The timers, indexed by the id stored in `Timer`
+#[derive(Default)]
+struct TimerList {
+    timers: Vec<Option<TimerData>>,
+}
+
+impl TimerList {
+    fn insert(&mut self, data: TimerData) -> usize {
+        if let Some(id) = self.timers.iter().position(Option::is_none) {
+            self.timers[id] = Some(data);
+            id
+        } else {
+            self.timers.push(Some(data));
+            self.timers.len() - 1
+        }
+    }
+}
+
+thread_local!(static CURRENT_TIMERS: RefCell<TimerList> = Default::default());
+
+/// The timers use the time of the animation driver, so that they can be mocked in tests
+fn current_tick() -> instant::Instant {
+    crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.current_tick_untracked())
+}
+
+/// A timer invokes a callback in the event loop once a given interval has elapsed.
+///
+/// The timer is stopped when it is dropped.
+///
+/// ```ignore
+/// let timer = Timer::default();
+/// timer.start(TimerMode::Repeated, std::time::Duration::from_secs(1), move || {
+///     println!("one more second");
+/// });
+/// ```
+#[derive(Default)]
+pub struct Timer {
+    id: std::cell::Cell<Option<usize>>,
+}
+
+impl Timer {
+    /// Starts the timer with the given mode and interval. The callback is invoked each time the
+    /// timer is triggered. If the timer was already started, it is restarted with the new
+    /// parameters and callback.
+    pub fn start(
+        &self,
+        mode: TimerMode,
+        interval: core::time::Duration,
+        callback: impl FnMut() + 'static,
+    ) {
+        let data = TimerData::new(mode, interval, Box::new(callback), false);
+        let previous = CURRENT_TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            match self.id.get() {
+                Some(id) => timers.timers[id].replace(data),
+                None => {
+                    self.id.set(Some(timers.insert(data)));
+                    None
+                }
+            }
+        });
+        // Only dropped once the list is no longer borrowed, as the callback may own timers
+        drop(previous);
+    }
+
+    /// Invokes the callback once, after the given duration has elapsed.
+    pub fn single_shot(duration: core::time::Duration, callback: impl FnOnce() + 'static) {
+        let mut callback = Some(callback);
+        let data = TimerData::new(
+            TimerMode::SingleShot,
+            duration,
+            Box::new(move || {
+                if let Some(callback) = callback.take() {
+                    callback()
+                }
+            }),
+            true,
+        );
+        CURRENT_TIMERS.with(|timers| timers.borrow_mut().insert(data));
+    }
+
+    /// Stops the timer. It can be restarted with [`Self::start`].
+    pub fn stop(&self) {
+        if let Some(id) = self.id.get() {
+            CURRENT_TIMERS.with(|timers| {
+                if let Some(Some(timer)) = timers.borrow_mut().timers.get_mut(id) {
+                    timer.running = false;
+                }
+            })
+        }
+    }
+
+    /// Returns true if the timer is started and was not yet triggered (for single shot timers)
+    /// or stopped.
+    pub fn running(&self) -> bool {
+        self.id.get().map_or(false, |id| {
+            CURRENT_TIMERS.with(|timers| {
+                matches!(timers.borrow().timers.get(id), Some(Some(timer)) if timer.running)
+            })
+        })
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.get() {
+            let data = CURRENT_TIMERS
+                .try_with(|timers| timers.borrow_mut().timers.get_mut(id).and_then(Option::take));
+            // Only dropped once the list is no longer borrowed, as the callback may own timers
+            drop(data);
+        }
+    }
+}
+
+/// Invokes the callbacks of the timers whose timeout is reached, in the order of their timeout.
+/// Returns true if any timer was triggered.
+pub(crate) fn process_timers() -> bool {
+    let now = current_tick();
+    let mut triggered = false;
+    loop {
+        let next = CURRENT_TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let (id, _) = timers
+                .timers
+                .iter()
+                .enumerate()
+                .filter_map(|(id, timer)| {
+                    let timer = timer.as_ref()?;
+                    if timer.running && timer.callback.is_some() && timer.timeout <= now {
+                        Some((id, timer.timeout))
+                    } else {
+                        None
+                    }
+                })
+                .min_by_key(|(_, timeout)| *timeout)?;
+            if timers.timers[id].as_ref().unwrap().removed_when_triggered {
+                let timer = timers.timers[id].take().unwrap();
+                return Some((id, timer.callback.unwrap(), true));
+            }
+            let timer = timers.timers[id].as_mut().unwrap();
+            match timer.mode {
+                TimerMode::SingleShot => timer.running = false,
+                TimerMode::Repeated => timer.timeout += timer.interval,
+            }
+            Some((id, timer.callback.take().unwrap(), false))
+        });
+        let (id, mut callback, removed) = match next {
+            Some(next) => next,
+            None => break,
+        };
+        triggered = true;
+        callback();
+        if !removed {
+            // Put the callback back, unless the timer was restarted or dropped by the callback
+            let callback =
+                CURRENT_TIMERS.with(|timers| match timers.borrow_mut().timers.get_mut(id) {
+                    Some(Some(timer)) if timer.callback.is_none() => {
+                        timer.callback = Some(callback);
+                        None
+                    }
+                    _ => Some(callback),
+                });
+            drop(callback);
+        }
+        // The callback may have changed properties that start or stop other timers
+        process_change_watchers();
+    }
+    triggered
+}
+
+/// Returns the instant at which the next timer is triggered, if any timer is running
+pub(crate) fn next_timer_timeout() -> Option<instant::Instant> {
+    CURRENT_TIMERS.with(|timers| {
+        timers
+            .borrow()
+            .timers
+            .iter()
+            .filter_map(|timer| timer.as_ref().filter(|timer| timer.running).map(|t| t.timeout))
+            .min()
+    })
+}
+
+/// An object that reacts to changes of properties, such as the `Timer` element or the
+/// [`ChangeTracker`](crate::properties::ChangeTracker).
+///
+/// Reacting to a change cannot be done while the bindings are evaluated, so the registered
+/// watchers are processed at a defined point of the event loop (see [`process_change_watchers`])
+pub(crate) trait ChangeWatcher {
+    /// Called at every iteration of the event loop. The implementation should check its
+    /// `PropertyTracker` to know whether anything changed.
+    /// Returns true if a handler was called, which may have changed other properties.
+    fn process_changes(&self) -> bool;
+}
+
+thread_local!(static CHANGE_WATCHERS: RefCell<Vec<Weak<dyn ChangeWatcher>>> = Default::default());
+
+/// Registers the watcher so that it is processed until it is dropped
+pub(crate) fn register_change_watcher(watcher: Weak<dyn ChangeWatcher>) {
+    CHANGE_WATCHERS.with(|watchers| watchers.borrow_mut().push(watcher))
+}
+
+/// Calls [`ChangeWatcher::process_changes`] for every registered watcher.
+/// Returns true if any handler was called.
+pub(crate) fn process_change_watchers() -> bool {
+    let watchers = CHANGE_WATCHERS.with(|watchers| {
+        let mut watchers = watchers.borrow_mut();
+        watchers.retain(|w| w.strong_count() > 0);
+        watchers.clone()
+    });
+    let mut handler_called = false;
+    for watcher in watchers {
+        if let Some(watcher) = watcher.upgrade() {
+            handler_called |= watcher.process_changes();
+        }
+    }
+    handler_called
+}
+
+#[test]
+fn timer_test() {
+    use std::cell::Cell;
+    let count = Rc::new(Cell::new(0));
+    let timer = Timer::default();
+    let c = count.clone();
+    timer.start(TimerMode::Repeated, core::time::Duration::from_millis(100), move || {
+        c.set(c.get() + 1)
+    });
+    let single_shot_triggered = Rc::new(Cell::new(false));
+    let s = single_shot_triggered.clone();
+    Timer::single_shot(core::time::Duration::from_millis(250), move || s.set(true));
+    assert!(timer.running());
+
+    crate::tests::sixtyfps_mock_elapsed_time(50);
+    assert_eq!(count.get(), 0);
+    crate::tests::sixtyfps_mock_elapsed_time(50);
+    assert_eq!(count.get(), 1);
+    crate::tests::sixtyfps_mock_elapsed_time(220);
+    assert_eq!(count.get(), 3);
+    assert!(single_shot_triggered.get());
+
+    timer.stop();
+    assert!(!timer.running());
+    crate::tests::sixtyfps_mock_elapsed_time(300);
+    assert_eq!(count.get(), 3);
+
+    let c = count.clone();
+    timer.start(TimerMode::SingleShot, core::time::Duration::from_millis(100), move || {
+        c.set(c.get() + 10)
+    });
+    crate::tests::sixtyfps_mock_elapsed_time(300);
+    assert_eq!(count.get(), 13);
+    assert!(!timer.running());
+
+    timer.start(TimerMode::Repeated, core::time::Duration::from_millis(100), || {
+        panic!("dropped timers are not triggered")
+    });
+    drop(timer);
+    crate::tests::sixtyfps_mock_elapsed_time(300);
+}
+
+fn request_redraw_of_all_windows() {
+    ALL_WINDOWS.with(|windows| {
+        windows.borrow().values().for_each(|window| {
+            if let Some(window) = window.upgrade() {
+                window.request_redraw();
+            }
+        })
+    })
+}
+
+fn process_key_input(
+    window_id: winit::window::WindowId,
+    event: &KeyEvent,
+    component: core::pin::Pin<crate::component::ComponentRef>,
+) {
+    ALL_WINDOWS.with(|windows| {
+        if let Some(Some(window)) =
+            windows.borrow().get(&window_id).map(|weakref| weakref.upgrade())
+        {
+            window.process_key_input(event, component);
+            // FIXME: remove this, it should be based on actual changes rather than this
+            window.request_redraw();
+        }
+    });
+}
+
 pub struct EventLoop {
     winit_loop: winit::event_loop::EventLoop<()>,
 }
@@ -113,12 +510,23 @@ impl EventLoop {
 
         let mut cursor_pos = winit::dpi::PhysicalPosition::new(0., 0.);
         let mut pressed = false;
+        let mut modifiers = KeyboardModifiers::default();
+        // winit reports the text of a key press in a separate ReceivedCharacter event that follows
+        // the KeyboardInput event, so the key press is only delivered once the text is known.
+        let mut pending_key_press: Option<(winit::window::WindowId, KeyEvent)> = None;
         let mut run_fn = move |event: Event<()>,
                                _: &EventLoopWindowTarget<()>,
                                control_flow: &mut ControlFlow| {
             *control_flow = ControlFlow::Wait;
 
             match event {
+                winit::event::Event::NewEvents(_) => {
+                    crate::animations::update_animations();
+                    if process_timers() {
+                        // FIXME: remove this, it should be based on actual changes rather than this
+                        request_redraw_of_all_windows();
+                    }
+                }
                 winit::event::Event::WindowEvent {
                     event: winit::event::WindowEvent::CloseRequested,
                     ..
@@ -129,6 +537,15 @@ impl EventLoop {
                         if let Some(Some(window)) =
                             windows.borrow().get(&id).map(|weakref| weakref.upgrade())
                         {
+                            if crate::input::take_due_mouse_move() {
+                                window.process_mouse_input(
+                                    cursor_pos,
+                                    MouseEventType::MouseMoved,
+                                    PointerButton::none,
+                                    modifiers,
+                                    component,
+                                );
+                            }
                             if layout_listener.as_ref().is_dirty() {
                                 layout_listener
                                     .as_ref()
@@ -175,7 +592,7 @@ impl EventLoop {
 
                 winit::event::Event::WindowEvent {
                     ref window_id,
-                    event: winit::event::WindowEvent::MouseInput { state, .. },
+                    event: winit::event::WindowEvent::MouseInput { state, button, .. },
                     ..
                 } => {
                     crate::animations::update_animations();
@@ -193,7 +610,31 @@ impl EventLoop {
                                     MouseEventType::MouseReleased
                                 }
                             };
-                            window.process_mouse_input(cursor_pos, what, component);
+                            let button = match button {
+                                winit::event::MouseButton::Left => PointerButton::left,
+                                winit::event::MouseButton::Right => PointerButton::right,
+                                winit::event::MouseButton::Middle => PointerButton::middle,
+                                winit::event::MouseButton::Other(_) => PointerButton::none,
+                            };
+                            window.process_mouse_input(
+                                cursor_pos, what, button, modifiers, component,
+                            );
+                            // FIXME: remove this, it should be based on actual changes rather than this
+                            window.request_redraw();
+                        }
+                    });
+                }
+                winit::event::Event::WindowEvent {
+                    ref window_id,
+                    event: winit::event::WindowEvent::MouseWheel { delta, .. },
+                    ..
+                } => {
+                    crate::animations::update_animations();
+                    ALL_WINDOWS.with(|windows| {
+                        if let Some(Some(window)) =
+                            windows.borrow().get(&window_id).map(|weakref| weakref.upgrade())
+                        {
+                            window.process_mouse_wheel(cursor_pos, delta, modifiers, component);
                             // FIXME: remove this, it should be based on actual changes rather than this
                             window.request_redraw();
                         }
@@ -222,7 +663,13 @@ impl EventLoop {
                                 }
                                 winit::event::TouchPhase::Moved => MouseEventType::MouseMoved,
                             };
-                            window.process_mouse_input(cursor_pos, what, component);
+                            window.process_mouse_input(
+                                cursor_pos,
+                                what,
+                                PointerButton::left,
+                                modifiers,
+                                component,
+                            );
                             // FIXME: remove this, it should be based on actual changes rather than this
                             window.request_redraw();
                         }
@@ -242,6 +689,8 @@ impl EventLoop {
                             window.process_mouse_input(
                                 cursor_pos,
                                 MouseEventType::MouseMoved,
+                                PointerButton::none,
+                                modifiers,
                                 component,
                             );
                             // FIXME: remove this, it should be based on actual changes rather than this
@@ -249,14 +698,15 @@ impl EventLoop {
                         }
                     });
                 }
+                // When the cursor leaves the window, the hovered items need to know.
                 // On the html canvas, we don't get the mouse move or release event when outside the canvas. So we have no choice but canceling the event
-                #[cfg(target_arch = "wasm32")]
+                // while on desktop the press continues outside of the window.
                 winit::event::Event::WindowEvent {
                     ref window_id,
                     event: winit::event::WindowEvent::CursorLeft { .. },
                     ..
                 } => {
-                    if pressed {
+                    if cfg!(target_arch = "wasm32") || !pressed {
                         crate::animations::update_animations();
                         ALL_WINDOWS.with(|windows| {
                             if let Some(Some(window)) =
@@ -266,6 +716,8 @@ impl EventLoop {
                                 window.process_mouse_input(
                                     cursor_pos,
                                     MouseEventType::MouseExit,
+                                    PointerButton::none,
+                                    modifiers,
                                     component,
                                 );
                                 // FIXME: remove this, it should be based on actual changes rather than this
@@ -275,23 +727,97 @@ impl EventLoop {
                     }
                 }
 
+                winit::event::Event::WindowEvent {
+                    event: winit::event::WindowEvent::ModifiersChanged(state),
+                    ..
+                } => {
+                    modifiers = state.into();
+                }
+                winit::event::Event::WindowEvent {
+                    window_id,
+                    event: winit::event::WindowEvent::KeyboardInput { input, .. },
+                } => {
+                    crate::animations::update_animations();
+                    if let Some((window_id, event)) = pending_key_press.take() {
+                        process_key_input(window_id, &event, component);
+                    }
+                    let event = KeyEvent {
+                        event_type: match input.state {
+                            winit::event::ElementState::Pressed => KeyEventType::KeyPressed,
+                            winit::event::ElementState::Released => KeyEventType::KeyReleased,
+                        },
+                        key: input.virtual_keycode.map_or(KeyCode::Unknown, KeyCode::from),
+                        text: Default::default(),
+                        modifiers,
+                    };
+                    match event.event_type {
+                        KeyEventType::KeyPressed => pending_key_press = Some((window_id, event)),
+                        KeyEventType::KeyReleased => {
+                            process_key_input(window_id, &event, component)
+                        }
+                    }
+                }
+                winit::event::Event::WindowEvent {
+                    window_id,
+                    event: winit::event::WindowEvent::ReceivedCharacter(ch),
+                } => {
+                    // Control characters are already represented by the key code
+                    if !ch.is_control() {
+                        let mut text = [0u8; 4];
+                        let text = ch.encode_utf8(&mut text);
+                        if let Some((_, event)) =
+                            pending_key_press.as_mut().filter(|(id, _)| *id == window_id)
+                        {
+                            event.text.push_str(text);
+                        } else {
+                            crate::animations::update_animations();
+                            if let Some((window_id, event)) = pending_key_press.take() {
+                                process_key_input(window_id, &event, component);
+                            }
+                            let event = KeyEvent {
+                                event_type: KeyEventType::KeyPressed,
+                                key: KeyCode::Unknown,
+                                text: (&*text).into(),
+                                modifiers,
+                            };
+                            process_key_input(window_id, &event, component);
+                        }
+                    }
+                }
+                winit::event::Event::MainEventsCleared => {
+                    if let Some((window_id, event)) = pending_key_press.take() {
+                        process_key_input(window_id, &event, component);
+                    }
+                }
+
                 _ => (),
             }
 
             if *control_flow != winit::event_loop::ControlFlow::Exit {
+                if process_change_watchers() {
+                    // The handlers may have changed properties. Redrawing also means that
+                    // there will be another iteration to process the changes they caused.
+                    request_redraw_of_all_windows();
+                }
+                if layout_listener.as_ref().is_dirty() {
+                    // Something the layout depends on changed, such as the model of a repeater
+                    request_redraw_of_all_windows();
+                }
                 crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| {
                     if !driver.has_active_animations() {
+                        if let Some(timeout) = next_timer_timeout() {
+                            // The timers use the time of the animation driver, which is not
+                            // the real time when the animations are slowed down
+                            let now = driver.current_tick_untracked();
+                            let delay =
+                                if timeout > now { timeout - now } else { Default::default() };
+                            *control_flow = ControlFlow::WaitUntil(instant::Instant::now() + delay);
+                        }
                         return;
                     }
                     *control_flow = ControlFlow::Poll;
                     //println!("Scheduling a redraw due to active animations");
-                    ALL_WINDOWS.with(|windows| {
-                        windows.borrow().values().for_each(|window| {
-                            if let Some(window) = window.upgrade() {
-                                window.request_redraw();
-                            }
-                        })
-                    })
+                    request_redraw_of_all_windows();
                 })
             }
         };
@@ -384,4 +910,114 @@ pub mod ffi {
         let window = &*(handle as *const ComponentWindow);
         window.free_graphics_resources(component)
     }
+
+    /// Delivers the key event to the item of the component that has the focus.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_dispatch_key_event(
+        handle: *const ComponentWindowOpaque,
+        component: Pin<VRef<ComponentVTable>>,
+        event: &KeyEvent,
+    ) -> KeyEventResult {
+        let window = &*(handle as *const ComponentWindow);
+        window.dispatch_key_event(component, event)
+    }
+
+    /// Gives the focus to the item, if it accepts it.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_set_focus_item(
+        handle: *const ComponentWindowOpaque,
+        component: Pin<VRef<ComponentVTable>>,
+        item: Pin<VRef<ItemVTable>>,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        window.set_focus_item(component, item)
+    }
+
+    /// Gives the focus to the topmost item at the given position that accepts it.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_set_focus_item_at(
+        handle: *const ComponentWindowOpaque,
+        component: Pin<VRef<ComponentVTable>>,
+        pos: crate::graphics::Point,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        window.set_focus_item_at(component, pos)
+    }
+
+    struct WrapFn {
+        callback: extern "C" fn(*mut c_void),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    }
+
+    impl Drop for WrapFn {
+        fn drop(&mut self) {
+            if let Some(x) = self.drop_user_data {
+                x(self.user_data)
+            }
+        }
+    }
+
+    /// Returns the `Timer` for the given id, where `usize::MAX` means that the timer
+    /// was not started yet
+    fn timer_from_id(id: usize) -> Timer {
+        Timer { id: std::cell::Cell::new(if id == usize::MAX { None } else { Some(id) }) }
+    }
+
+    /// Starts the timer with the given id, or a new timer if the id is `usize::MAX`.
+    /// Returns the id of the timer.
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_timer_start(
+        id: usize,
+        mode: TimerMode,
+        duration: u64,
+        callback: extern "C" fn(*mut c_void),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    ) -> usize {
+        let wrap = WrapFn { callback, user_data, drop_user_data };
+        let timer = timer_from_id(id);
+        timer.start(mode, core::time::Duration::from_millis(duration), move || {
+            (wrap.callback)(wrap.user_data)
+        });
+        // Taking the id prevents the timer from being stopped when dropped
+        timer.id.take().unwrap()
+    }
+
+    /// Invokes the callback once, after the given duration in milliseconds.
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_timer_singleshot(
+        duration: u64,
+        callback: extern "C" fn(*mut c_void),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    ) {
+        let wrap = WrapFn { callback, user_data, drop_user_data };
+        Timer::single_shot(core::time::Duration::from_millis(duration), move || {
+            (wrap.callback)(wrap.user_data)
+        });
+    }
+
+    /// Stops the timer and releases its resources.
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_timer_destroy(id: usize) {
+        drop(timer_from_id(id));
+    }
+
+    /// Stops the timer.
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_timer_stop(id: usize) {
+        let timer = timer_from_id(id);
+        timer.stop();
+        timer.id.take();
+    }
+
+    /// Returns whether the timer is running.
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_timer_running(id: usize) -> bool {
+        let timer = timer_from_id(id);
+        let running = timer.running();
+        timer.id.take();
+        running
+    }
 }
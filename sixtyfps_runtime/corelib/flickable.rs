@@ -23,8 +23,15 @@ struct FlickableDataInnter {
     pressed_pos: Point,
     pressed_time: Option<Instant>,
     pressed_viewport_pos: Point,
+    /// The position the viewport is animated to after a wheel event, and when that animation ends
+    wheel_target: Option<(Point, Instant)>,
 }
 
+/// The number of pixels the viewport is moved for one line of mouse wheel scrolling
+const WHEEL_LINE_HEIGHT: f32 = 20.;
+/// The duration of the animation when scrolling with a mouse wheel
+const WHEEL_ANIMATION_DURATION: i32 = 100;
+
 #[derive(Default, Debug)]
 pub struct FlickableData {
     inner: RefCell<FlickableDataInnter>,
@@ -85,6 +92,43 @@ impl FlickableData {
                         .set(new_pos.y);
                 }
             }
+            MouseEventType::MouseWheel => {
+                let now = crate::animations::current_tick();
+                let x = (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
+                    .apply_pin(flick);
+                let y = (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
+                    .apply_pin(flick);
+                let current_pos = match inner.wheel_target {
+                    // Start from the target of the running animation so fast wheel turns add up
+                    Some((target, end)) if end > now => target,
+                    _ => Point::new(x.get(), y.get()),
+                };
+                if event.line_delta != Point::new(0., 0.) {
+                    // Classic wheels scroll by steps, animate them to make it smooth
+                    let new_pos = ensure_in_bound(
+                        flick,
+                        current_pos + event.line_delta.to_vector() * WHEEL_LINE_HEIGHT,
+                    );
+                    let anim = PropertyAnimation {
+                        duration: WHEEL_ANIMATION_DURATION,
+                        easing: EasingCurve::CubicBezier([0.0, 0.0, 0.58, 1.0]),
+                        ..PropertyAnimation::default()
+                    };
+                    x.set_animated_value(new_pos.x, &anim);
+                    y.set_animated_value(new_pos.y, &anim);
+                    inner.wheel_target = Some((
+                        new_pos,
+                        now + core::time::Duration::from_millis(WHEEL_ANIMATION_DURATION as u64),
+                    ));
+                } else {
+                    // Trackpads already send many small and smooth deltas
+                    let new_pos =
+                        ensure_in_bound(flick, current_pos + event.pixel_delta.to_vector());
+                    x.set(new_pos.x);
+                    y.set(new_pos.y);
+                    inner.wheel_target = None;
+                }
+            }
         }
     }
 }
@@ -105,3 +149,39 @@ fn ensure_in_bound(flick: Pin<&Flickable>, p: Point) -> Point {
     let max = Point::new(0., 0.);
     p.max(min).min(max)
 }
+
+#[test]
+fn test_flickable_wheel() {
+    use crate::items::Item;
+
+    let flick = Box::pin(Flickable::default());
+    let flick = flick.as_ref();
+    Flickable::FIELD_OFFSETS.width.apply_pin(flick).set(100.);
+    Flickable::FIELD_OFFSETS.height.apply_pin(flick).set(100.);
+    let viewport_y =
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y).apply_pin(flick);
+    let wheel = |pixel_delta_y: f32, line_delta_y: f32| {
+        let mut event = MouseEvent::new(Point::new(50., 50.), MouseEventType::MouseWheel);
+        event.pixel_delta = Point::new(0., pixel_delta_y);
+        event.line_delta = Point::new(0., line_delta_y);
+        flick.input_event(event);
+    };
+
+    // A line of the mouse wheel scrolls by 20px, with an animation
+    wheel(0., -1.);
+    crate::tests::sixtyfps_mock_elapsed_time(200);
+    assert_eq!(viewport_y.get(), -20.);
+
+    // Trackpads scroll by pixels
+    wheel(-50., 0.);
+    assert_eq!(viewport_y.get(), -70.);
+
+    // Scrolling past the end stops at the end
+    wheel(-5000., 0.);
+    assert_eq!(viewport_y.get(), -900.);
+
+    // Scrolling past the start stops at the start
+    wheel(0., 100.);
+    crate::tests::sixtyfps_mock_elapsed_time(200);
+    assert_eq!(viewport_y.get(), 0.);
+}
@@ -28,6 +28,20 @@ impl Font {
         text_metrics.width() as _
     }
 
+    /// Returns the clusters of `text`, in visual order. The canvas does not expose the shaping,
+    /// so each character is measured as a left-to-right cluster.
+    pub fn visual_clusters(&self, text: &str) -> Vec<super::VisualCluster> {
+        let mut x = 0.;
+        text.char_indices()
+            .map(|(start, ch)| {
+                let end = start + ch.len_utf8();
+                let width = self.text_width(&text[start..end]);
+                x += width;
+                super::VisualCluster { start, end, x: x - width, width, rtl: false }
+            })
+            .collect()
+    }
+
     pub fn font_height(&self) -> f32 {
         self.pixel_size
     }
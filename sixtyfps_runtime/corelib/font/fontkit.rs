@@ -15,37 +15,205 @@ use pathfinder_geometry::{
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct GlyphMetrics {
     pub advance: f32,
 }
 
+/// A glyph of a text shaped with [`Font::shape_text`]
+#[derive(Clone, Debug)]
+pub struct ShapedGlyph {
+    pub glyph_id: u32,
+    /// The byte offset in the text of the first character that produced this glyph
+    pub cluster: usize,
+    /// The horizontal distance between the origin of this glyph and of the next one
+    pub x_advance: f32,
+    /// The offset at which the glyph is drawn from its origin (y pointing up)
+    pub x_offset: f32,
+    pub y_offset: f32,
+    /// True when the glyph is part of a right-to-left run
+    pub rtl: bool,
+}
+
+/// A glyph rasterized with [`Font::rasterize_glyph`]
+pub struct RasterizedGlyph {
+    pub image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    /// The horizontal position of the left of the image, relative to the origin of the glyph.
+    /// It is negative for the glyphs that are drawn before their origin, such as combining marks.
+    pub left: f32,
+}
+
 pub struct Font {
     pub pixel_size: f32,
     font: font_kit::font::Font,
+    /// The font parsed for the shaping. It borrows `_font_data`: the `'static` lifetime is a lie.
+    /// The fields are dropped in declaration order, so this field must stay before `_font_data`.
+    face: Option<rustybuzz::Face<'static>>,
+    /// The content of the font file, that must not be modified or dropped while `face` exists
+    _font_data: Option<Arc<Vec<u8>>>,
     metrics: font_kit::metrics::Metrics,
     glyph_metrics_cache: RefCell<HashMap<u32, GlyphMetrics>>,
+    /// The glyphs of the most recently shaped texts, the most recently used last
+    shaped_text_cache: RefCell<Vec<(String, Rc<[ShapedGlyph]>)>>,
 }
 
+/// The number of texts whose glyphs are kept in `Font::shaped_text_cache`
+const SHAPED_TEXT_CACHE_SIZE: usize = 64;
+
 impl Font {
-    pub fn string_to_glyphs<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (char, u32)> + 'a {
-        text.chars().map(move |ch| {
-            (
-                ch,
-                self.font.glyph_for_char(ch).unwrap_or_else(|| {
-                    self.font
-                        .glyph_for_char('\u{FFFD}')
-                        .unwrap_or_else(|| self.font.glyph_for_char('?').unwrap())
-                }),
-            )
+    fn glyph_for_char(&self, ch: char) -> u32 {
+        self.font.glyph_for_char(ch).unwrap_or_else(|| {
+            self.font
+                .glyph_for_char('\u{FFFD}')
+                .unwrap_or_else(|| self.font.glyph_for_char('?').unwrap())
         })
     }
 
+    /// Converts `text` to glyphs, applying the ligatures, the kerning and the contextual forms
+    /// of the font, such as the joining of Arabic letters.
+    ///
+    /// The text is split into runs of the same direction with the Unicode bidirectional
+    /// algorithm, and the glyphs are returned in visual order, from left to right.
+    ///
+    /// The same texts are measured and drawn again and again, so the result is cached.
+    pub fn shape_text(&self, text: &str) -> Rc<[ShapedGlyph]> {
+        let mut cache = self.shaped_text_cache.borrow_mut();
+        if let Some(index) = cache.iter().position(|(cached_text, _)| cached_text == text) {
+            let entry = cache.remove(index);
+            let glyphs = entry.1.clone();
+            cache.push(entry);
+            return glyphs;
+        }
+        let glyphs: Rc<[ShapedGlyph]> = self.shape_text_uncached(text).into();
+        if cache.len() >= SHAPED_TEXT_CACHE_SIZE {
+            cache.remove(0);
+        }
+        cache.push((text.to_owned(), glyphs.clone()));
+        glyphs
+    }
+
+    fn shape_text_uncached(&self, text: &str) -> Vec<ShapedGlyph> {
+        let face = match &self.face {
+            Some(face) => face,
+            // Without access to the font tables, map the characters to glyphs one by one
+            None => {
+                return text
+                    .char_indices()
+                    .map(|(cluster, ch)| {
+                        let glyph_id = self.glyph_for_char(ch);
+                        ShapedGlyph {
+                            glyph_id,
+                            cluster,
+                            x_advance: self.glyph_metrics(glyph_id).advance,
+                            x_offset: 0.,
+                            y_offset: 0.,
+                            rtl: false,
+                        }
+                    })
+                    .collect()
+            }
+        };
+
+        let mut glyphs = Vec::with_capacity(text.len());
+        // A text without right-to-left characters is a single left-to-right run
+        if !text.chars().any(is_rtl) {
+            self.shape_run(face, text, 0..text.len(), false, &mut glyphs);
+            return glyphs;
+        }
+
+        let bidi_info = unicode_bidi::BidiInfo::new(text, None);
+        for paragraph in &bidi_info.paragraphs {
+            let (levels, runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                self.shape_run(face, text, run, rtl, &mut glyphs);
+            }
+        }
+        glyphs
+    }
+
+    /// Shapes the `run` of `text`, and appends its glyphs to `glyphs` in visual order
+    fn shape_run(
+        &self,
+        face: &rustybuzz::Face,
+        text: &str,
+        run: std::ops::Range<usize>,
+        rtl: bool,
+        glyphs: &mut Vec<ShapedGlyph>,
+    ) {
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(&text[run.clone()]);
+        buffer.set_direction(if rtl {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+        buffer.guess_segment_properties();
+
+        // The glyphs of a right-to-left run are already in visual order
+        let scale = self.font_units_to_pixel_size();
+        let output = rustybuzz::shape(face, &[], buffer);
+        glyphs.extend(output.glyph_infos().iter().zip(output.glyph_positions()).map(
+            |(info, position)| {
+                let cluster = run.start + info.cluster as usize;
+                // The glyph 0 (.notdef) is used for the characters missing from the font.
+                // Show the same replacement glyph as when the text is not shaped.
+                if info.codepoint == 0 {
+                    let glyph_id = self.glyph_for_char(text[cluster..].chars().next().unwrap());
+                    return ShapedGlyph {
+                        glyph_id,
+                        cluster,
+                        x_advance: self.glyph_metrics(glyph_id).advance,
+                        x_offset: 0.,
+                        y_offset: 0.,
+                        rtl,
+                    };
+                }
+                ShapedGlyph {
+                    glyph_id: info.codepoint,
+                    cluster,
+                    x_advance: position.x_advance as f32 * scale,
+                    x_offset: position.x_offset as f32 * scale,
+                    y_offset: position.y_offset as f32 * scale,
+                    rtl,
+                }
+            },
+        ));
+    }
+
     pub fn text_width(&self, text: &str) -> f32 {
-        self.string_to_glyphs(text)
-            .map(|(_, glyph)| self.glyph_metrics(glyph))
-            .fold(0., |width, glyph| width + glyph.advance)
+        self.shape_text(text).iter().map(|glyph| glyph.x_advance).sum()
+    }
+
+    /// Returns the clusters of the shaped `text`, in visual order
+    pub fn visual_clusters(&self, text: &str) -> Vec<super::VisualCluster> {
+        let glyphs = self.shape_text(text);
+        let mut starts: Vec<usize> = glyphs.iter().map(|glyph| glyph.cluster).collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let mut clusters: Vec<super::VisualCluster> = Vec::with_capacity(starts.len());
+        let mut x = 0.;
+        for glyph in glyphs.iter() {
+            match clusters.last_mut() {
+                Some(cluster) if cluster.start == glyph.cluster => cluster.width += glyph.x_advance,
+                _ => {
+                    let index = starts.binary_search(&glyph.cluster).unwrap();
+                    clusters.push(super::VisualCluster {
+                        start: glyph.cluster,
+                        end: starts.get(index + 1).copied().unwrap_or(text.len()),
+                        x,
+                        width: glyph.x_advance,
+                        rtl: glyph.rtl,
+                    })
+                }
+            }
+            x += glyph.x_advance;
+        }
+        clusters
     }
 
     pub fn font_height(&self) -> f32 {
@@ -81,16 +249,35 @@ impl Font {
         (self.metrics.ascent - self.metrics.descent + 1.) * self.font_units_to_pixel_size()
     }
 
-    pub fn rasterize_glyph(&self, glyph_id: u32) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    /// Renders the glyph in an image, or returns None when the glyph does not draw anything,
+    /// as is the case of the whitespace.
+    pub fn rasterize_glyph(&self, glyph_id: u32) -> Option<RasterizedGlyph> {
         let baseline_y = self.ascent();
         let hinting = font_kit::hinting::HintingOptions::None;
         let raster_opts = font_kit::canvas::RasterizationOptions::GrayscaleAa;
 
         // ### TODO: #8 use tight bounding box for glyphs stored in texture atlas
+        // The image covers the advance of the glyph, extended to the parts that are drawn
+        // outside of it, such as the combining marks that have no advance.
         let glyph_height = self.height();
-        let glyph_width = self.glyph_metrics(glyph_id).advance;
+        let advance = self.glyph_metrics(glyph_id).advance;
+        let bounds = self
+            .font
+            .raster_bounds(
+                glyph_id,
+                self.pixel_size,
+                Transform2F::from_translation(Vector2F::new(0., baseline_y)),
+                hinting,
+                raster_opts,
+            )
+            .unwrap();
+        if bounds.width() <= 0 || bounds.height() <= 0 {
+            return None;
+        }
+        let left = bounds.min_x().min(0);
+        let right = (bounds.max_x() as f32).max(advance);
         let mut canvas = font_kit::canvas::Canvas::new(
-            Vector2I::new(glyph_width.ceil() as i32, glyph_height.ceil() as i32),
+            Vector2I::new((right - left as f32).ceil() as i32, glyph_height.ceil() as i32),
             font_kit::canvas::Format::A8,
         );
         self.font
@@ -98,17 +285,19 @@ impl Font {
                 &mut canvas,
                 glyph_id,
                 self.pixel_size,
-                Transform2F::from_translation(Vector2F::new(0., baseline_y)),
+                Transform2F::from_translation(Vector2F::new(-left as f32, baseline_y)),
                 hinting,
                 raster_opts,
             )
             .unwrap();
 
-        image::ImageBuffer::from_fn(canvas.size.x() as u32, canvas.size.y() as u32, |x, y| {
-            let idx = (x as usize) + (y as usize) * canvas.stride;
-            let alpha = canvas.pixels[idx];
-            image::Rgba::<u8>::from_channels(0, 0, 0, alpha)
-        })
+        let image =
+            image::ImageBuffer::from_fn(canvas.size.x() as u32, canvas.size.y() as u32, |x, y| {
+                let idx = (x as usize) + (y as usize) * canvas.stride;
+                let alpha = canvas.pixels[idx];
+                image::Rgba::<u8>::from_channels(0, 0, 0, alpha)
+            });
+        Some(RasterizedGlyph { image, left: left as f32 })
     }
 
     pub fn handle(&self) -> FontHandle {
@@ -116,6 +305,12 @@ impl Font {
     }
 }
 
+/// Returns true for the characters that start a right-to-left run
+fn is_rtl(ch: char) -> bool {
+    use unicode_bidi::BidiClass::*;
+    matches!(unicode_bidi::bidi_class(ch), R | AL | AN | RLE | RLO | RLI)
+}
+
 #[derive(Clone)]
 pub struct FontHandle(font_kit::handle::Handle);
 
@@ -161,7 +356,29 @@ impl FontHandle {
     pub fn load(&self, pixel_size: f32) -> Result<Font, font_kit::error::FontLoadingError> {
         let font = self.0.load()?;
         let metrics = font.metrics();
-        Ok(Font { pixel_size, font, metrics, glyph_metrics_cache: Default::default() })
+        let font_data = font.copy_font_data();
+        let font_index = match &self.0 {
+            font_kit::handle::Handle::Path { font_index, .. } => *font_index,
+            font_kit::handle::Handle::Memory { font_index, .. } => *font_index,
+        };
+        let face = font_data.as_ref().and_then(|data| {
+            // SAFETY: the data is in the heap buffer of the Vec, which cannot be modified or moved
+            // while the Font holds a reference to the Arc, that is moved into the Font below.
+            // The `face` field is declared before `_font_data` so that it is dropped first, and
+            // it is private so that the face cannot outlive the Font.
+            let data: &'static [u8] =
+                unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+            rustybuzz::Face::from_slice(data, font_index)
+        });
+        Ok(Font {
+            pixel_size,
+            font,
+            face,
+            _font_data: font_data,
+            metrics,
+            glyph_metrics_cache: Default::default(),
+            shaped_text_cache: Default::default(),
+        })
     }
 
     pub fn new_from_match(family: &str) -> Self {
@@ -186,3 +403,94 @@ impl From<font_kit::handle::Handle> for FontHandle {
         Self(h)
     }
 }
+
+#[cfg(test)]
+fn test_font() -> Font {
+    let data = include_bytes!("testdata/DejaVuSans.ttf");
+    let handle = font_kit::handle::Handle::from_memory(Arc::new(data.to_vec()), 0);
+    FontHandle::from(handle).load(16.).unwrap()
+}
+
+#[test]
+fn test_shape_text_bidi() {
+    let font = test_font();
+    let clusters =
+        |text| font.shape_text(text).iter().map(|glyph| glyph.cluster).collect::<Vec<_>>();
+
+    // The Hebrew word after the latin letters is shown from right to left
+    assert_eq!(clusters("ab \u{5e9}\u{5dc}\u{5d5}\u{5dd}"), [0, 1, 2, 9, 7, 5, 3]);
+    // In a right-to-left paragraph, the latin letters and the space come first
+    assert_eq!(clusters("\u{5e9}\u{5dc}\u{5d5}\u{5dd} ab"), [9, 10, 8, 6, 4, 2, 0]);
+
+    let glyphs = font.shape_text("a\u{5d0}");
+    assert!(!glyphs[0].rtl && glyphs[1].rtl);
+    assert!(glyphs.iter().all(|glyph| glyph.glyph_id != 0 && glyph.x_advance > 0.));
+}
+
+#[test]
+fn test_shape_text_missing_glyph() {
+    let font = test_font();
+
+    // The CJK characters are not in the font
+    let glyphs = font.shape_text("a\u{4e00}b");
+    assert_eq!(glyphs.len(), 3);
+    assert_eq!(glyphs[1].glyph_id, font.glyph_for_char('\u{FFFD}'));
+    assert!(glyphs[1].glyph_id != 0 && glyphs[1].x_advance > 0.);
+}
+
+#[test]
+fn test_shape_text_cache() {
+    let font = test_font();
+
+    let glyphs = font.shape_text("hello");
+    assert!(Rc::ptr_eq(&glyphs, &font.shape_text("hello")));
+    assert_eq!(font.shape_text("world").len(), 5);
+
+    // The least recently used texts are shaped again
+    for i in 0..SHAPED_TEXT_CACHE_SIZE {
+        font.shape_text(&i.to_string());
+    }
+    let reshaped = font.shape_text("hello");
+    assert!(!Rc::ptr_eq(&glyphs, &reshaped));
+    assert_eq!(
+        glyphs.iter().map(|glyph| glyph.glyph_id).collect::<Vec<_>>(),
+        reshaped.iter().map(|glyph| glyph.glyph_id).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_shape_text_clusters() {
+    let font = test_font();
+
+    // The combining acute accent is in the cluster of its base letter
+    let text = "ae\u{301}b";
+    let clusters = font.cluster_advances(text);
+    assert_eq!(clusters.iter().map(|(offset, _)| *offset).collect::<Vec<_>>(), [0, 1, 4]);
+    let width: f32 = clusters.iter().map(|(_, advance)| advance).sum();
+    assert_eq!(width, font.text_width(text));
+
+    let visual_clusters = font.visual_clusters("\u{5d0}\u{5d1}");
+    assert_eq!(
+        visual_clusters.iter().map(|c| (c.start, c.end)).collect::<Vec<_>>(),
+        [(2, 4), (0, 2)]
+    );
+    assert!(visual_clusters.iter().all(|cluster| cluster.rtl));
+    assert_eq!(visual_clusters[0].x, 0.);
+    assert_eq!(visual_clusters[1].x, visual_clusters[0].width);
+}
+
+#[test]
+fn test_shape_text_ligature() {
+    let font = test_font();
+
+    // "ffi" is a single glyph
+    let glyphs = font.shape_text("office");
+    assert_eq!(glyphs.iter().map(|glyph| glyph.cluster).collect::<Vec<_>>(), [0, 1, 4, 5]);
+    assert_ne!(glyphs[1].glyph_id, font.glyph_for_char('f'));
+
+    let clusters = font.visual_clusters("office");
+    assert_eq!((clusters[1].start, clusters[1].end), (1, 4));
+    // A caret in the ligature is placed within its glyph
+    let x = super::x_position_for_offset(&clusters, 2);
+    assert!(x > clusters[1].x && x < clusters[2].x);
+}
@@ -7,6 +7,7 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
+use crate::items::{TextOverflow, TextWrap};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -21,6 +22,235 @@ mod canvasfont;
 #[cfg(target_arch = "wasm32")]
 pub use canvasfont::*;
 
+impl Font {
+    /// Returns the byte offset of the cluster boundary in `text` that is the closest to the
+    /// horizontal position `x`, relative to the start of the text.
+    pub fn text_offset_for_x_position(&self, text: &str, x: f32) -> usize {
+        offset_for_x_position(&self.visual_clusters(text), x)
+    }
+
+    /// Returns the byte offset of the clusters of `text` in logical order, with the advance of
+    /// their glyphs.
+    pub fn cluster_advances(&self, text: &str) -> Vec<(usize, f32)> {
+        let mut clusters: Vec<_> = self
+            .visual_clusters(text)
+            .iter()
+            .map(|cluster| (cluster.start, cluster.width))
+            .collect();
+        clusters.sort_unstable_by_key(|(start, _)| *start);
+        clusters
+    }
+
+    /// Splits `text` into the lines that are shown when it is laid out in an element of the
+    /// given `width`. See [`text_lines`].
+    pub fn text_lines(
+        &self,
+        text: &str,
+        width: f32,
+        wrap: TextWrap,
+        overflow: TextOverflow,
+    ) -> Vec<TextLine> {
+        text_lines(text, width, wrap, overflow, |text| self.cluster_advances(text))
+    }
+}
+
+/// The character that replaces the end of the lines that are elided
+pub const ELLIPSIS: &str = "\u{2026}";
+
+/// A line of text, as returned by [`text_lines`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// The text of the line, without the line break
+    pub text: String,
+    /// The width of the text of the line
+    pub width: f32,
+}
+
+/// A group of characters that are shaped together and that cannot be broken, such as a ligature
+/// or a letter with its combining marks, as returned by [`Font::visual_clusters`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisualCluster {
+    /// The byte range of the characters of the cluster in the text
+    pub start: usize,
+    pub end: usize,
+    /// The horizontal position of the left of the cluster, relative to the start of the text
+    pub x: f32,
+    pub width: f32,
+    /// True when the cluster is part of a right-to-left run, so its characters start on the right
+    pub rtl: bool,
+}
+
+/// Returns the byte offset of the cluster boundary that is the closest to the horizontal
+/// position `x`. `clusters` are the clusters of a text in visual order.
+pub fn offset_for_x_position(clusters: &[VisualCluster], x: f32) -> usize {
+    let cluster = match clusters.iter().find(|cluster| x < cluster.x + cluster.width) {
+        Some(cluster) => cluster,
+        None => match clusters.last() {
+            Some(cluster) => cluster,
+            None => return 0,
+        },
+    };
+    // The left edge of a right-to-left cluster is its end
+    if (x < cluster.x + cluster.width / 2.) != cluster.rtl {
+        cluster.start
+    } else {
+        cluster.end
+    }
+}
+
+/// Returns the horizontal position of the caret placed at the byte `offset` of a text.
+/// `clusters` are the clusters of that text in visual order.
+pub fn x_position_for_offset(clusters: &[VisualCluster], offset: usize) -> f32 {
+    match clusters.iter().find(|cluster| cluster.start <= offset && offset < cluster.end) {
+        Some(cluster) => {
+            // Within a ligature, the characters share the width of the cluster
+            let fraction = (offset - cluster.start) as f32 / (cluster.end - cluster.start) as f32;
+            if cluster.rtl {
+                cluster.x + cluster.width * (1. - fraction)
+            } else {
+                cluster.x + cluster.width * fraction
+            }
+        }
+        // At the end of the text, the caret follows the last cluster in logical order
+        None => clusters.iter().max_by_key(|cluster| cluster.end).map_or(0., |cluster| {
+            if cluster.rtl {
+                cluster.x
+            } else {
+                cluster.x + cluster.width
+            }
+        }),
+    }
+}
+
+/// Splits `text` into lines, using `cluster_advances` to measure them.
+///
+/// `cluster_advances` returns the byte offset of the clusters of a text in logical order, with
+/// their advance, like [`Font::cluster_advances`]. Each paragraph is measured once, and the lines
+/// are only cut at the boundaries of these clusters.
+///
+/// The text is always broken at the `\n` characters. With [`TextWrap::word_wrap`], the lines
+/// are also broken at the last whitespace before they exceed `width`. The lines that are still
+/// wider than `width` are then cut according to `overflow`.
+/// A `width` of zero or less means that the text is not constrained, which is the case of
+/// elements that have no width.
+pub fn text_lines(
+    text: &str,
+    width: f32,
+    wrap: TextWrap,
+    overflow: TextOverflow,
+    cluster_advances: impl Fn(&str) -> Vec<(usize, f32)>,
+) -> Vec<TextLine> {
+    let constrained = width > 0.;
+    let ellipsis_width =
+        || cluster_advances(ELLIPSIS).iter().map(|(_, advance)| advance).sum::<f32>();
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let measured = MeasuredText::new(paragraph, cluster_advances(paragraph));
+        let mut paragraph_lines = Vec::new();
+        if constrained && wrap == TextWrap::word_wrap {
+            measured.word_wrap(width, &mut paragraph_lines);
+        } else {
+            paragraph_lines.push(0..paragraph.len());
+        }
+
+        lines.extend(paragraph_lines.into_iter().map(|line| {
+            let line_width = measured.width(line.clone());
+            if !constrained || line_width <= width {
+                return TextLine { text: paragraph[line].into(), width: line_width };
+            }
+            match overflow {
+                TextOverflow::clip => {
+                    let end = measured.fitting_end(line.start, width);
+                    TextLine {
+                        text: paragraph[line.start..end].into(),
+                        width: measured.width(line.start..end),
+                    }
+                }
+                TextOverflow::elide => {
+                    let ellipsis_width = ellipsis_width();
+                    let end = measured.fitting_end(line.start, width - ellipsis_width);
+                    let kept = paragraph[line.start..end].trim_end();
+                    TextLine {
+                        text: format!("{}{}", kept, ELLIPSIS),
+                        width: measured.width(line.start..line.start + kept.len()) + ellipsis_width,
+                    }
+                }
+            }
+        }));
+    }
+    lines
+}
+
+/// A paragraph with the horizontal position of each of its clusters
+struct MeasuredText<'a> {
+    text: &'a str,
+    /// The byte offset of each cluster, with its position from the start of the text, followed
+    /// by the end of the text and its width
+    clusters: Vec<(usize, f32)>,
+}
+
+impl<'a> MeasuredText<'a> {
+    fn new(text: &'a str, cluster_advances: Vec<(usize, f32)>) -> Self {
+        let mut x = 0.;
+        let mut clusters = Vec::with_capacity(cluster_advances.len() + 1);
+        for (offset, advance) in cluster_advances {
+            clusters.push((offset, x));
+            x += advance;
+        }
+        clusters.push((text.len(), x));
+        Self { text, clusters }
+    }
+
+    /// Returns the index of the cluster that contains the byte `offset`
+    fn cluster_index(&self, offset: usize) -> usize {
+        match self.clusters.binary_search_by(|(cluster, _)| cluster.cmp(&offset)) {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        }
+    }
+
+    /// Returns the width of the clusters in the `range` of bytes
+    fn width(&self, range: std::ops::Range<usize>) -> f32 {
+        self.clusters[self.cluster_index(range.end)].1
+            - self.clusters[self.cluster_index(range.start)].1
+    }
+
+    /// Returns the end of the longest sequence of clusters from `start` that is not wider
+    /// than `width`
+    fn fitting_end(&self, start: usize, width: f32) -> usize {
+        let index = self.cluster_index(start);
+        let start_x = self.clusters[index].1;
+        self.clusters[index + 1..]
+            .iter()
+            .take_while(|(_, x)| x - start_x <= width)
+            .last()
+            .map_or(start, |(offset, _)| *offset)
+    }
+
+    /// Breaks the text at the whitespace so that the lines fit in `width` when possible, and
+    /// appends the byte range of the lines to `lines`.
+    fn word_wrap(&self, width: f32, lines: &mut Vec<std::ops::Range<usize>>) {
+        let text = self.text;
+        let mut line_start = 0;
+        // The position of the last break opportunity of the current line
+        let mut line_end = 0;
+        let breaks = text
+            .char_indices()
+            .filter(|(_, ch)| ch.is_whitespace())
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()));
+        for offset in breaks {
+            if line_end > line_start && self.width(line_start..offset) > width {
+                lines.push(line_start..line_end);
+                // The whitespace at which the line is broken is not part of any line
+                line_start = line_end + text[line_end..].chars().next().map_or(0, char::len_utf8);
+            }
+            line_end = offset;
+        }
+        lines.push(line_start..text.len());
+    }
+}
+
 struct FontMatch {
     handle: FontHandle,
     fonts_per_pixel_size: Vec<Rc<Font>>,
@@ -57,3 +287,90 @@ impl FontCache {
 thread_local! {
     pub static FONT_CACHE: FontCache = Default::default();
 }
+
+#[test]
+fn test_text_lines() {
+    // Every character is 10 pixels wide
+    let text_width =
+        |text: &str| -> Vec<(usize, f32)> { text.char_indices().map(|c| (c.0, 10.)).collect() };
+    let lines = |text, width, wrap, overflow| {
+        text_lines(text, width, wrap, overflow, text_width)
+            .into_iter()
+            .map(|line| line.text)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        lines("hello world\nfoo", 0., TextWrap::word_wrap, TextOverflow::elide),
+        ["hello world", "foo"]
+    );
+    assert_eq!(
+        lines("hello world\nfoo", 80., TextWrap::no_wrap, TextOverflow::clip),
+        ["hello wo", "foo"]
+    );
+    assert_eq!(
+        lines("hello world", 80., TextWrap::no_wrap, TextOverflow::elide),
+        ["hello w\u{2026}"]
+    );
+    assert_eq!(
+        lines("the quick brown fox jumps", 110., TextWrap::word_wrap, TextOverflow::clip),
+        ["the quick", "brown fox", "jumps"]
+    );
+    assert_eq!(
+        lines("a verylongword b", 50., TextWrap::word_wrap, TextOverflow::elide),
+        ["a", "very\u{2026}", "b"]
+    );
+
+    let lines = text_lines("ab\ncde", 0., TextWrap::no_wrap, TextOverflow::clip, text_width);
+    assert_eq!(lines.iter().map(|l| l.width).collect::<Vec<_>>(), [20., 30.]);
+    let lines = text_lines("hello world", 80., TextWrap::no_wrap, TextOverflow::elide, text_width);
+    assert_eq!(lines.iter().map(|l| l.width).collect::<Vec<_>>(), [80.]);
+
+    // "ffi" is a ligature: a single cluster of 15 pixels that is never cut
+    let ligature = |text: &str| {
+        let mut clusters = Vec::new();
+        let mut offset = 0;
+        while offset < text.len() {
+            let len = if text[offset..].starts_with("ffi") { 3 } else { 1 };
+            clusters.push((offset, if len == 3 { 15. } else { 10. }));
+            offset += len;
+        }
+        clusters
+    };
+    assert_eq!(
+        text_lines("office", 20., TextWrap::no_wrap, TextOverflow::clip, ligature),
+        [TextLine { text: "o".into(), width: 10. }]
+    );
+    assert_eq!(
+        text_lines("office", 30., TextWrap::no_wrap, TextOverflow::clip, ligature),
+        [TextLine { text: "offi".into(), width: 25. }]
+    );
+}
+
+#[test]
+fn test_offset_and_x_position() {
+    // "ab" followed by the right-to-left "CDE", where "DE" is a ligature
+    let cluster = |start, end, x, width, rtl| VisualCluster { start, end, x, width, rtl };
+    let clusters = [
+        cluster(0, 1, 0., 10., false),
+        cluster(1, 2, 10., 10., false),
+        cluster(3, 5, 20., 20., true),
+        cluster(2, 3, 40., 10., true),
+    ];
+    assert_eq!(offset_for_x_position(&clusters, -5.), 0);
+    assert_eq!(offset_for_x_position(&clusters, 6.), 1);
+    assert_eq!(offset_for_x_position(&clusters, 22.), 5);
+    assert_eq!(offset_for_x_position(&clusters, 38.), 3);
+    assert_eq!(offset_for_x_position(&clusters, 42.), 3);
+    assert_eq!(offset_for_x_position(&clusters, 48.), 2);
+    assert_eq!(offset_for_x_position(&clusters, 100.), 2);
+    assert_eq!(offset_for_x_position(&[], 10.), 0);
+
+    assert_eq!(x_position_for_offset(&clusters, 0), 0.);
+    assert_eq!(x_position_for_offset(&clusters, 1), 10.);
+    assert_eq!(x_position_for_offset(&clusters, 2), 50.);
+    assert_eq!(x_position_for_offset(&clusters, 3), 40.);
+    assert_eq!(x_position_for_offset(&clusters, 4), 30.);
+    assert_eq!(x_position_for_offset(&clusters, 5), 20.);
+    assert_eq!(x_position_for_offset(&[], 0), 0.);
+}
@@ -8,8 +8,11 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 extern crate alloc;
-use crate::input::{MouseEvent, MouseEventType};
-use crate::items::ItemRef;
+use crate::input::{
+    FocusEvent, FocusItemAddress, KeyCode, KeyEvent, KeyEventResult, KeyEventType,
+    KeyboardModifiers, MouseEvent, MouseEventType,
+};
+use crate::items::{ItemRef, MouseCursor, PointerButton};
 use crate::properties::{InterpolatedPropertyValue, Property};
 #[cfg(feature = "rtti")]
 use crate::rtti::{BuiltinItem, FieldInfo, PropertyInfo, ValueType};
@@ -19,7 +22,7 @@ use cgmath::Matrix4;
 use const_field_offset::FieldOffsets;
 use core::pin::Pin;
 use sixtyfps_corelib_macros::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 /// 2D Rectangle
@@ -161,11 +164,27 @@ pub enum HighLevelRenderingPrimitive {
         source: crate::Resource,
     },
     // TODO: turn color into a rendering variable. Needs fixing of the wasm canvas code path though.
+    // The lines are aligned within `width`, and wrapped or cut when they don't fit in it.
     Text {
         text: crate::SharedString,
         font_family: crate::SharedString,
         font_size: f32,
         color: Color,
+        width: f32,
+        horizontal_alignment: crate::items::TextHorizontalAlignment,
+        wrap: crate::items::TextWrap,
+        overflow: crate::items::TextOverflow,
+    },
+    // Expected rendering variables in order: Color (selection background color),
+    // Color (text cursor color)
+    TextInput {
+        text: crate::SharedString,
+        font_family: crate::SharedString,
+        font_size: f32,
+        color: Color,
+        cursor_position: i32,
+        anchor_position: i32,
+        text_cursor_width: f32,
     },
     // Expected rendering variables in order: Color (fill color), Color (stroke color)
     Path {
@@ -391,6 +410,8 @@ pub struct GraphicsWindow<Backend: GraphicsBackend + 'static> {
     window_factory: Box<WindowFactoryFn<Backend>>,
     map_state: RefCell<GraphicsWindowBackendState<Backend>>,
     properties: Pin<Box<WindowProperties>>,
+    focus_item: Cell<Option<FocusItemAddress>>,
+    mouse_cursor: Cell<MouseCursor>,
 }
 
 impl<Backend: GraphicsBackend + 'static> GraphicsWindow<Backend> {
@@ -402,6 +423,8 @@ impl<Backend: GraphicsBackend + 'static> GraphicsWindow<Backend> {
             window_factory: Box::new(graphics_backend_factory),
             map_state: RefCell::new(GraphicsWindowBackendState::Unmapped),
             properties: Box::pin(WindowProperties::default()),
+            focus_item: Cell::new(None),
+            mouse_cursor: Cell::new(MouseCursor::default()),
         })
     }
 
@@ -463,11 +486,85 @@ impl<Backend: GraphicsBackend> crate::eventloop::GenericWindow for GraphicsWindo
         &self,
         pos: winit::dpi::PhysicalPosition<f64>,
         what: MouseEventType,
+        button: PointerButton,
+        modifiers: KeyboardModifiers,
         component: crate::component::ComponentRefPin,
     ) {
-        component
-            .as_ref()
-            .input_event(MouseEvent { pos: euclid::point2(pos.x as _, pos.y as _), what });
+        crate::input::take_requested_mouse_cursor();
+        let mut event = MouseEvent::new(euclid::point2(pos.x as _, pos.y as _), what);
+        event.button = button;
+        event.modifiers = modifiers;
+        component.as_ref().input_event(event);
+        let cursor = crate::input::take_requested_mouse_cursor().unwrap_or_default();
+        if self.mouse_cursor.replace(cursor) != cursor {
+            let icon = match cursor {
+                MouseCursor::cursor_default => winit::window::CursorIcon::Default,
+                MouseCursor::cursor_pointer => winit::window::CursorIcon::Hand,
+                MouseCursor::cursor_text => winit::window::CursorIcon::Text,
+                MouseCursor::cursor_move => winit::window::CursorIcon::Move,
+                MouseCursor::cursor_ew_resize => winit::window::CursorIcon::EwResize,
+                MouseCursor::cursor_ns_resize => winit::window::CursorIcon::NsResize,
+                MouseCursor::cursor_nesw_resize => winit::window::CursorIcon::NeswResize,
+                MouseCursor::cursor_nwse_resize => winit::window::CursorIcon::NwseResize,
+            };
+            self.with_platform_window(&|window| window.set_cursor_icon(icon));
+        }
+    }
+
+    fn process_mouse_wheel(
+        &self,
+        pos: winit::dpi::PhysicalPosition<f64>,
+        delta: winit::event::MouseScrollDelta,
+        modifiers: KeyboardModifiers,
+        component: crate::component::ComponentRefPin,
+    ) {
+        let mut event =
+            MouseEvent::new(euclid::point2(pos.x as _, pos.y as _), MouseEventType::MouseWheel);
+        event.modifiers = modifiers;
+        match delta {
+            winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                event.line_delta = euclid::point2(x, y);
+            }
+            winit::event::MouseScrollDelta::PixelDelta(delta) => {
+                let delta = delta.to_physical::<f32>(self.scale_factor() as f64);
+                event.pixel_delta = euclid::point2(delta.x, delta.y);
+            }
+        }
+        component.as_ref().input_event(event);
+    }
+
+    fn process_key_input(&self, event: &KeyEvent, component: crate::component::ComponentRefPin) {
+        component.as_ref().key_event(event);
+    }
+
+    fn dispatch_key_event(
+        &self,
+        event: &KeyEvent,
+        component: crate::component::ComponentRefPin,
+    ) -> KeyEventResult {
+        if event.key == KeyCode::Tab {
+            if event.event_type == KeyEventType::KeyPressed {
+                self.focus_item.set(crate::input::focus_next_item(
+                    component,
+                    self.focus_item.get(),
+                    event.modifiers.shift,
+                ));
+            }
+            return KeyEventResult::EventAccepted;
+        }
+        crate::input::process_key_event(component, self.focus_item.get(), event)
+    }
+
+    fn set_focus_item(&self, component: crate::component::ComponentRefPin, item: Pin<ItemRef>) {
+        self.focus_item.set(crate::input::move_focus(
+            component,
+            self.focus_item.get(),
+            Some(item.as_ptr()),
+        ));
+    }
+
+    fn set_focus_item_at(&self, component: crate::component::ComponentRefPin, pos: Point) {
+        self.focus_item.set(crate::input::focus_item_at(component, self.focus_item.get(), pos));
     }
 
     fn with_platform_window(&self, callback: &dyn Fn(&winit::window::Window)) {
@@ -594,6 +691,18 @@ impl<Backend: GraphicsBackend> crate::eventloop::GenericWindow for GraphicsWindo
         self: Rc<Self>,
         component: core::pin::Pin<crate::component::ComponentRef>,
     ) {
+        // The items of the component are going away. If the focus item is one of them, it loses
+        // the focus now, so that its address is not kept after the item is freed, where another
+        // item could be allocated and get the key events without having received FocusIn.
+        if let Some(focus_item) = self.focus_item.get() {
+            if crate::input::with_item_at_address(component, focus_item, |item| {
+                item.as_ref().focus_event(&FocusEvent::FocusOut)
+            })
+            .is_some()
+            {
+                self.focus_item.set(None);
+            }
+        }
         match &*self.map_state.borrow() {
             GraphicsWindowBackendState::Unmapped => {}
             GraphicsWindowBackendState::Mapped(window) => {
@@ -7,14 +7,18 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
-/*! Module handling mouse events
-
-TODO: Keyboard events
+/*! Module handling mouse and keyboard events
 */
 
 use crate::component::ComponentRefPin;
 use crate::graphics::Point;
-use crate::item_tree::{ItemVisitorResult, VisitChildrenResult};
+use crate::item_tree::{
+    ItemVisitor, ItemVisitorResult, ItemVisitorVTable, TraversalOrder, VisitChildrenResult,
+};
+use crate::items::{ItemRef, MouseCursor, PointerButton};
+use crate::SharedString;
+use core::cell::Cell;
+use core::pin::Pin;
 use euclid::default::Vector2D;
 
 /// The type of a MouseEvent
@@ -29,6 +33,9 @@ pub enum MouseEventType {
     MouseMoved,
     /// The mouse exited the item or component
     MouseExit,
+    /// The mouse wheel was turned, or the user scrolled on a trackpad. The amount is in the
+    /// `pixel_delta` and `line_delta` fields of the MouseEvent.
+    MouseWheel,
 }
 
 /// Structur representing a mouse event
@@ -39,6 +46,81 @@ pub struct MouseEvent {
     pub pos: Point,
     /// The action performed (pressed/released/moced)
     pub what: MouseEventType,
+    /// For MouseWheel events, the amount to scroll in pixels, as reported by trackpads
+    /// and high resolution wheels. Zero otherwise.
+    pub pixel_delta: Point,
+    /// For MouseWheel events, the amount to scroll in lines, as reported by classic mouse
+    /// wheels. Zero otherwise.
+    pub line_delta: Point,
+    /// The button that was pressed or released. For other events, this is `PointerButton::none`
+    pub button: PointerButton,
+    /// The state of the keyboard modifiers when the event happened
+    pub modifiers: KeyboardModifiers,
+}
+
+impl MouseEvent {
+    /// Creates an event that is not a MouseWheel event, without any button or modifiers
+    pub fn new(pos: Point, what: MouseEventType) -> Self {
+        Self {
+            pos,
+            what,
+            pixel_delta: Point::new(0., 0.),
+            line_delta: Point::new(0., 0.),
+            button: PointerButton::none,
+            modifiers: KeyboardModifiers::default(),
+        }
+    }
+}
+
+thread_local!(static REQUESTED_MOUSE_CURSOR: Cell<Option<MouseCursor>> = Cell::new(None));
+
+/// Items call this while they handle a mouse event to choose the shape of the mouse cursor.
+/// The window shows the default cursor if no item requested one.
+pub fn request_mouse_cursor(cursor: MouseCursor) {
+    REQUESTED_MOUSE_CURSOR.with(|requested| requested.set(Some(cursor)))
+}
+
+/// Returns the cursor requested since the last call, if any.
+pub(crate) fn take_requested_mouse_cursor() -> Option<MouseCursor> {
+    REQUESTED_MOUSE_CURSOR.with(|requested| requested.take())
+}
+
+thread_local!(static REQUESTED_MOUSE_MOVE_TIME: Cell<Option<u64>> = Cell::new(None));
+thread_local!(static REQUESTED_MOUSE_MOVE_TIMER: crate::eventloop::Timer = Default::default());
+
+/// Items that need to react to time passing while the mouse stays still, such as a long press,
+/// call this. The event loop then sends a MouseMoved event at the cursor position once the time,
+/// in milliseconds since the start of the animation driver, is reached.
+pub fn request_mouse_move_at(time_in_ms: u64) {
+    let time_in_ms = REQUESTED_MOUSE_MOVE_TIME.with(|requested| {
+        let time = requested.get().map_or(time_in_ms, |t| t.min(time_in_ms));
+        requested.set(Some(time));
+        time
+    });
+    let now = crate::animations::CURRENT_ANIMATION_DRIVER
+        .with(|driver| driver.elapsed().as_millis() as u64);
+    // Triggering the timer wakes up the event loop and redraws the windows, which is when the
+    // mouse move is sent.
+    REQUESTED_MOUSE_MOVE_TIMER.with(|timer| {
+        timer.start(
+            crate::eventloop::TimerMode::SingleShot,
+            core::time::Duration::from_millis(time_in_ms.saturating_sub(now)),
+            || {},
+        )
+    });
+}
+
+/// Returns true, and forgets the request, if the time of the requested mouse move is reached
+pub(crate) fn take_due_mouse_move() -> bool {
+    let now = crate::animations::CURRENT_ANIMATION_DRIVER
+        .with(|driver| driver.elapsed().as_millis() as u64);
+    REQUESTED_MOUSE_MOVE_TIME.with(|requested| match requested.get() {
+        Some(time) if time <= now => {
+            requested.set(None);
+            true
+        }
+        _ => false,
+    })
 }
 
 /// This value is returned by the input handler of a component
@@ -66,50 +148,406 @@ pub enum InputEventResult {
     GrabMouse,
 }
 
-pub fn process_ungrabbed_mouse_event(
+/// The code of a key. Keys that produce text also report that text in `KeyEvent::text`,
+/// so this enum only contains the keys that are useful on their own (navigation, editing,
+/// function keys, and letters and digits for shortcuts)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+pub enum KeyCode {
+    Unknown,
+    Escape,
+    Tab,
+    Backspace,
+    Return,
+    Space,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Left,
+    Up,
+    Right,
+    Down,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Default for KeyCode {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl From<winit::event::VirtualKeyCode> for KeyCode {
+    fn from(code: winit::event::VirtualKeyCode) -> Self {
+        use winit::event::VirtualKeyCode as VK;
+        match code {
+            VK::Escape => Self::Escape,
+            VK::Tab => Self::Tab,
+            VK::Back => Self::Backspace,
+            VK::Return => Self::Return,
+            VK::Space => Self::Space,
+            VK::Insert => Self::Insert,
+            VK::Delete => Self::Delete,
+            VK::Home => Self::Home,
+            VK::End => Self::End,
+            VK::PageUp => Self::PageUp,
+            VK::PageDown => Self::PageDown,
+            VK::Left => Self::Left,
+            VK::Up => Self::Up,
+            VK::Right => Self::Right,
+            VK::Down => Self::Down,
+            VK::F1 => Self::F1,
+            VK::F2 => Self::F2,
+            VK::F3 => Self::F3,
+            VK::F4 => Self::F4,
+            VK::F5 => Self::F5,
+            VK::F6 => Self::F6,
+            VK::F7 => Self::F7,
+            VK::F8 => Self::F8,
+            VK::F9 => Self::F9,
+            VK::F10 => Self::F10,
+            VK::F11 => Self::F11,
+            VK::F12 => Self::F12,
+            VK::Key0 | VK::Numpad0 => Self::Key0,
+            VK::Key1 | VK::Numpad1 => Self::Key1,
+            VK::Key2 | VK::Numpad2 => Self::Key2,
+            VK::Key3 | VK::Numpad3 => Self::Key3,
+            VK::Key4 | VK::Numpad4 => Self::Key4,
+            VK::Key5 | VK::Numpad5 => Self::Key5,
+            VK::Key6 | VK::Numpad6 => Self::Key6,
+            VK::Key7 | VK::Numpad7 => Self::Key7,
+            VK::Key8 | VK::Numpad8 => Self::Key8,
+            VK::Key9 | VK::Numpad9 => Self::Key9,
+            VK::A => Self::A,
+            VK::B => Self::B,
+            VK::C => Self::C,
+            VK::D => Self::D,
+            VK::E => Self::E,
+            VK::F => Self::F,
+            VK::G => Self::G,
+            VK::H => Self::H,
+            VK::I => Self::I,
+            VK::J => Self::J,
+            VK::K => Self::K,
+            VK::L => Self::L,
+            VK::M => Self::M,
+            VK::N => Self::N,
+            VK::O => Self::O,
+            VK::P => Self::P,
+            VK::Q => Self::Q,
+            VK::R => Self::R,
+            VK::S => Self::S,
+            VK::T => Self::T,
+            VK::U => Self::U,
+            VK::V => Self::V,
+            VK::W => Self::W,
+            VK::X => Self::X,
+            VK::Y => Self::Y,
+            VK::Z => Self::Z,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The state of the keyboard modifiers at the time of an event
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct KeyboardModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The "Windows" key on PC keyboards, or the command key on mac
+    pub meta: bool,
+}
+
+impl From<winit::event::ModifiersState> for KeyboardModifiers {
+    fn from(state: winit::event::ModifiersState) -> Self {
+        Self { shift: state.shift(), control: state.ctrl(), alt: state.alt(), meta: state.logo() }
+    }
+}
+
+/// The type of a KeyEvent
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeyEventType {
+    /// A key was pressed (or auto-repeated)
+    KeyPressed,
+    /// A key was released
+    KeyReleased,
+}
+
+/// Structure representing a key event
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    /// Whether the key was pressed or released
+    pub event_type: KeyEventType,
+    /// The key. This is `KeyCode::Unknown` for keys that are only known by the text they produce
+    pub key: KeyCode,
+    /// The text produced by the key press, empty if the key does not produce text
+    pub text: SharedString,
+    /// The modifiers that were held at the time of the event
+    pub modifiers: KeyboardModifiers,
+}
+
+/// This value is returned by the key event handler of an item or a component
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeyEventResult {
+    /// The event was handled and must not be delivered to other items
+    EventAccepted,
+    EventIgnored,
+}
+
+/// An event sent to an item when it gains or loses the keyboard focus
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FocusEvent {
+    /// The item is given the focus
+    FocusIn,
+    /// The item loses the focus
+    FocusOut,
+}
+
+/// This value is returned by the focus event handler of an item
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FocusEventResult {
+    /// The item can have the focus and has updated its state accordingly
+    FocusAccepted,
+    /// The item cannot have the focus
+    FocusIgnored,
+}
+
+/// The focus item of a window is tracked by its address. The address is only ever compared
+/// to the address of the items in the tree and never dereferenced. The window forgets it when
+/// the component that contains the item is dropped, see `free_graphics_resources`.
+pub type FocusItemAddress = *const u8;
+
+/// Call the callback with the item of the component tree located at the given address.
+/// Returns None if no such item exists.
+pub(crate) fn with_item_at_address<R>(
     component: ComponentRefPin,
-    event: MouseEvent,
-) -> (InputEventResult, VisitChildrenResult) {
-    let offset = Vector2D::new(0., 0.);
+    address: FocusItemAddress,
+    callback: impl FnOnce(Pin<ItemRef>) -> R,
+) -> Option<R> {
+    let mut callback = Some(callback);
+    let mut result = None;
+    crate::item_tree::visit_items(
+        component,
+        crate::item_tree::TraversalOrder::BackToFront,
+        |_, item, _| -> ItemVisitorResult<()> {
+            if item.as_ptr() == address {
+                result = callback.take().map(|callback| callback(item));
+                ItemVisitorResult::Abort
+            } else {
+                ItemVisitorResult::Continue(())
+            }
+        },
+        (),
+    );
+    result
+}
 
-    let mut result = InputEventResult::EventIgnored;
-    let item_index = crate::item_tree::visit_items(
+/// Deliver the key event to the item that has the focus
+pub fn process_key_event(
+    component: ComponentRefPin,
+    focus_item: Option<FocusItemAddress>,
+    event: &KeyEvent,
+) -> KeyEventResult {
+    focus_item
+        .and_then(|focus_item| {
+            with_item_at_address(component, focus_item, |item| item.as_ref().key_event(event))
+        })
+        .unwrap_or(KeyEventResult::EventIgnored)
+}
+
+/// Give the focus to the first of the candidates that accepts it. Once an item has accepted the
+/// focus, the previous focus item receives a FocusOut event.
+///
+/// Returns the new focus item, which is `old_focus` if none of the candidates accepted the focus
+/// or if `old_focus` comes first among the candidates.
+pub fn move_focus(
+    component: ComponentRefPin,
+    old_focus: Option<FocusItemAddress>,
+    candidates: impl IntoIterator<Item = FocusItemAddress>,
+) -> Option<FocusItemAddress> {
+    for candidate in candidates {
+        if Some(candidate) == old_focus {
+            return old_focus;
+        }
+        let result = with_item_at_address(component, candidate, |item| {
+            item.as_ref().focus_event(&FocusEvent::FocusIn)
+        });
+        if result == Some(FocusEventResult::FocusAccepted) {
+            if let Some(old_focus) = old_focus {
+                with_item_at_address(component, old_focus, |item| {
+                    item.as_ref().focus_event(&FocusEvent::FocusOut)
+                });
+            }
+            return Some(candidate);
+        }
+    }
+    old_focus
+}
+
+/// Move the focus to the next item of the tree that accepts it, or to the previous one if
+/// `backwards` is true. The focus chain wraps around.
+pub fn focus_next_item(
+    component: ComponentRefPin,
+    old_focus: Option<FocusItemAddress>,
+    backwards: bool,
+) -> Option<FocusItemAddress> {
+    let mut focus_chain = Vec::new();
+    crate::item_tree::visit_items(
+        component,
+        crate::item_tree::TraversalOrder::BackToFront,
+        |_, item, _| -> ItemVisitorResult<()> {
+            focus_chain.push(item.as_ptr());
+            ItemVisitorResult::Continue(())
+        },
+        (),
+    );
+    if backwards {
+        focus_chain.reverse();
+    }
+    let start = old_focus
+        .and_then(|old_focus| focus_chain.iter().position(|item| *item == old_focus))
+        .map_or(0, |pos| pos + 1);
+    let (before, after) = focus_chain.split_at(start);
+    move_focus(component, old_focus, after.iter().chain(before.iter()).copied())
+}
+
+/// Give the focus to the topmost item at the given position that accepts it.
+pub fn focus_item_at(
+    component: ComponentRefPin,
+    old_focus: Option<FocusItemAddress>,
+    pos: Point,
+) -> Option<FocusItemAddress> {
+    let mut candidates = Vec::new();
+    crate::item_tree::visit_items(
         component,
-        crate::item_tree::TraversalOrder::FrontToBack,
+        crate::item_tree::TraversalOrder::BackToFront,
         |_, item, offset| -> ItemVisitorResult<Vector2D<f32>> {
             let geom = item.as_ref().geometry();
             let geom = geom.translate(*offset);
-
-            if geom.contains(event.pos) {
-                let mut event2 = event.clone();
-                event2.pos -= geom.origin.to_vector();
-                match item.as_ref().input_event(event2) {
-                    InputEventResult::EventAccepted => {
-                        result = InputEventResult::EventAccepted;
-                        return ItemVisitorResult::Abort;
-                    }
-                    InputEventResult::EventIgnored => (),
-                    InputEventResult::GrabMouse => {
-                        result = InputEventResult::GrabMouse;
-                        return ItemVisitorResult::Abort;
-                    }
-                };
+            if geom.contains(pos) {
+                candidates.push(item.as_ptr());
             }
-
             ItemVisitorResult::Continue(geom.origin.to_vector())
         },
-        offset,
+        Vector2D::new(0., 0.),
     );
+    move_focus(component, old_focus, candidates.into_iter().rev())
+}
 
+/// Send the event to the topmost item under the mouse that takes it.
+///
+/// Returns the result and the item that took the event, or `VisitChildrenResult::CONTINUE` if no
+/// item took it. The items of repeated components get the event through the `input_event` of their
+/// component, so that this component keeps track of its own mouse grab and hovered item.
+pub fn process_ungrabbed_mouse_event(
+    component: ComponentRefPin,
+    event: MouseEvent,
+) -> (InputEventResult, VisitChildrenResult) {
+    let mut result = InputEventResult::EventIgnored;
+    let item_index =
+        send_to_item_under_mouse(component, -1, event, Vector2D::new(0., 0.), &mut result);
     (
         result,
-        if result == InputEventResult::GrabMouse {
-            item_index
-        } else {
+        if result == InputEventResult::EventIgnored {
             VisitChildrenResult::CONTINUE
+        } else {
+            item_index
         },
     )
 }
+
+fn send_to_item_under_mouse(
+    component: ComponentRefPin,
+    parent_index: isize,
+    event: MouseEvent,
+    offset: Vector2D<f32>,
+    result: &mut InputEventResult,
+) -> VisitChildrenResult {
+    let mut visitor = |c: ComponentRefPin, index: isize, item: Pin<ItemRef>| {
+        let mut event2 = event.clone();
+        event2.pos -= offset;
+        if index == 0 && parent_index != -1 {
+            // The root item of a repeated component
+            *result = c.as_ref().input_event(event2);
+            return if *result == InputEventResult::EventIgnored {
+                VisitChildrenResult::CONTINUE
+            } else {
+                VisitChildrenResult::abort(0, 0)
+            };
+        }
+        let geom = item.as_ref().geometry();
+        if geom.contains(event2.pos) {
+            event2.pos -= geom.origin.to_vector();
+            match item.as_ref().input_event(event2) {
+                InputEventResult::EventIgnored => (),
+                res => {
+                    *result = res;
+                    return VisitChildrenResult::abort(index as usize, 0);
+                }
+            }
+        }
+        send_to_item_under_mouse(c, index, event, offset + geom.origin.to_vector(), &mut *result)
+    };
+    vtable::new_vref!(let mut visitor : VRefMut<ItemVisitorVTable> for ItemVisitor = &mut visitor);
+    component.as_ref().visit_children_item(parent_index, TraversalOrder::FrontToBack, visitor)
+}
+
 /*
 /// The event must be in the component coordinate
 /// Returns the new grabber.
@@ -141,10 +579,10 @@ pub(crate) mod ffi {
     pub extern "C" fn sixtyfps_process_ungrabbed_mouse_event(
         component: core::pin::Pin<crate::component::ComponentRef>,
         event: MouseEvent,
-        new_mouse_grabber: &mut crate::item_tree::VisitChildrenResult,
+        item_under_mouse: &mut crate::item_tree::VisitChildrenResult,
     ) -> InputEventResult {
-        let (res, grab) = process_ungrabbed_mouse_event(component, event);
-        *new_mouse_grabber = grab;
+        let (res, item) = process_ungrabbed_mouse_event(component, event);
+        *item_under_mouse = item;
         res
     }
     /*
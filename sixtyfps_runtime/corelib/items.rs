@@ -26,7 +26,10 @@ When adding an item or a property, it needs to be kept in sync with different pl
 #![allow(missing_docs)] // because documenting each property of items is redundent
 
 use super::graphics::{Color, HighLevelRenderingPrimitive, PathData, Rect, Resource};
-use super::input::{InputEventResult, MouseEvent, MouseEventType};
+use super::input::{
+    FocusEvent, FocusEventResult, InputEventResult, KeyCode, KeyEvent, KeyEventResult,
+    KeyEventType, MouseEvent, MouseEventType,
+};
 use super::item_rendering::CachedRenderingData;
 use super::layout::LayoutInfo;
 #[cfg(feature = "rtti")]
@@ -67,6 +70,13 @@ pub struct ItemVTable {
     /// input event
     pub input_event:
         extern "C" fn(core::pin::Pin<VRef<ItemVTable>>, MouseEvent) -> InputEventResult,
+
+    /// key event
+    pub key_event: extern "C" fn(core::pin::Pin<VRef<ItemVTable>>, &KeyEvent) -> KeyEventResult,
+
+    /// focus event: the item gains or loses the keyboard focus
+    pub focus_event:
+        extern "C" fn(core::pin::Pin<VRef<ItemVTable>>, &FocusEvent) -> FocusEventResult,
 }
 
 /// Alias for `vtable::VRef<ItemVTable>` which represent a pointer to a `dyn Item` with
@@ -118,6 +128,14 @@ impl Item for Rectangle {
     fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for Rectangle {
@@ -187,6 +205,14 @@ impl Item for BorderRectangle {
     fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for BorderRectangle {
@@ -254,6 +280,14 @@ impl Item for Image {
     fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for Image {
@@ -299,6 +333,40 @@ impl Default for TextVerticalAlignment {
     }
 }
 
+/// How the text of a `Text` element is broken into lines
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum TextWrap {
+    /// The text is only broken at the `\n` characters
+    no_wrap,
+    /// The text is also broken between words when it does not fit in the width of the element
+    word_wrap,
+}
+
+impl Default for TextWrap {
+    fn default() -> Self {
+        Self::no_wrap
+    }
+}
+
+/// What happens to the lines of a `Text` element that are wider than the element
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum TextOverflow {
+    /// The characters that do not fit are not shown
+    clip,
+    /// The end of the line is replaced by an ellipsis
+    elide,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        Self::clip
+    }
+}
+
 /// The implementation of the `Text` element
 #[repr(C)]
 #[derive(FieldOffsets, Default, BuiltinItem)]
@@ -310,6 +378,8 @@ pub struct Text {
     pub color: Property<Color>,
     pub horizontal_alignment: Property<TextHorizontalAlignment>,
     pub vertical_alignment: Property<TextVerticalAlignment>,
+    pub wrap: Property<TextWrap>,
+    pub overflow: Property<TextOverflow>,
     pub x: Property<f32>,
     pub y: Property<f32>,
     pub width: Property<f32>,
@@ -333,25 +403,25 @@ impl Item for Text {
             font_family: Self::FIELD_OFFSETS.font_family.apply_pin(self).get(),
             font_size: Self::FIELD_OFFSETS.font_size.apply_pin(self).get(),
             color: Self::FIELD_OFFSETS.color.apply_pin(self).get(),
+            width: Self::FIELD_OFFSETS.width.apply_pin(self).get(),
+            horizontal_alignment: Self::FIELD_OFFSETS.horizontal_alignment.apply_pin(self).get(),
+            wrap: Self::FIELD_OFFSETS.wrap.apply_pin(self).get(),
+            overflow: Self::FIELD_OFFSETS.overflow.apply_pin(self).get(),
         }
     }
 
     fn rendering_variables(self: Pin<&Self>) -> SharedArray<RenderingVariable> {
-        let layout_info = self.layouting_info();
         let rect = self.geometry();
+        let text_height = self.height_for_width(rect.width());
 
-        let hor_alignment = Self::FIELD_OFFSETS.horizontal_alignment.apply_pin(self).get();
-        let translate_x = match hor_alignment {
-            TextHorizontalAlignment::align_left => 0.,
-            TextHorizontalAlignment::align_center => rect.width() / 2. - layout_info.min_width / 2.,
-            TextHorizontalAlignment::align_right => rect.width() - layout_info.min_width,
-        };
+        // Each line is aligned horizontally by the rendering primitive
+        let translate_x = 0.;
 
         let ver_alignment = Self::FIELD_OFFSETS.vertical_alignment.apply_pin(self).get();
         let translate_y = match ver_alignment {
             TextVerticalAlignment::align_top => 0.,
-            TextVerticalAlignment::align_center => rect.height() / 2. - layout_info.min_height / 2.,
-            TextVerticalAlignment::align_bottom => rect.height() - layout_info.min_height,
+            TextVerticalAlignment::align_center => rect.height() / 2. - text_height / 2.,
+            TextVerticalAlignment::align_bottom => rect.height() - text_height,
         };
 
         SharedArray::from(&[RenderingVariable::Translate(translate_x, translate_y)])
@@ -361,16 +431,32 @@ impl Item for Text {
         let font_family = Self::FIELD_OFFSETS.font_family.apply_pin(self).get();
         let font_size = Self::FIELD_OFFSETS.font_size.apply_pin(self).get();
         let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+        let wrap = Self::FIELD_OFFSETS.wrap.apply_pin(self).get();
+        let overflow = Self::FIELD_OFFSETS.overflow.apply_pin(self).get();
 
         crate::font::FONT_CACHE.with(|fc| {
             let font = fc.find_font(&font_family, font_size);
-            let width = font.text_width(&text);
-            let height = font.font_height();
+            // The minimum width must not depend on the current width, otherwise the text could
+            // only ever shrink. So it is the width of what cannot be broken or elided.
+            let min_width = match (overflow, wrap) {
+                (TextOverflow::elide, _) => font.text_width(crate::font::ELLIPSIS),
+                (TextOverflow::clip, TextWrap::no_wrap) => {
+                    text.split('\n').map(|line| font.text_width(line)).fold(0., f32::max)
+                }
+                (TextOverflow::clip, TextWrap::word_wrap) => {
+                    text.split_whitespace().map(|word| font.text_width(word)).fold(0., f32::max)
+                }
+            };
+            // The height of a wrapped text depends on its width, which the layouts query with
+            // `height_for_width`. Without a width, this is the height at the minimum width.
+            let line_count = font.text_lines(&text, min_width, wrap, overflow).len();
+            let height = font.font_height() * line_count as f32;
             LayoutInfo {
-                min_width: width,
+                min_width,
                 max_width: f32::MAX,
                 min_height: height,
                 max_height: height,
+                ..LayoutInfo::default()
             }
         })
     }
@@ -378,6 +464,35 @@ impl Item for Text {
     fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
+}
+
+impl Text {
+    /// Returns the height that the text needs when it is `width` wide
+    pub fn height_for_width(self: Pin<&Self>, width: f32) -> f32 {
+        let font_family = Self::FIELD_OFFSETS.font_family.apply_pin(self).get();
+        let font_size = Self::FIELD_OFFSETS.font_size.apply_pin(self).get();
+        let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+        let wrap = Self::FIELD_OFFSETS.wrap.apply_pin(self).get();
+        let overflow = Self::FIELD_OFFSETS.overflow.apply_pin(self).get();
+
+        crate::font::FONT_CACHE.with(|fc| {
+            let font = fc.find_font(&font_family, font_size);
+            font.font_height() * font.text_lines(&text, width, wrap, overflow).len() as f32
+        })
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_text_height_for_width(text: *const Text, width: f32) -> f32 {
+    Pin::new_unchecked(&*text).height_for_width(width)
 }
 
 impl ItemConsts for Text {
@@ -391,6 +506,417 @@ ItemVTable_static! {
     pub static TextVTable for Text
 }
 
+/// Internal state of the `TextInput` element
+#[repr(C)]
+#[derive(Default, Copy, Clone, Debug)]
+struct TextInputData {
+    /// Set while the mouse button is held down, the selection then follows the mouse
+    pressed: bool,
+}
+
+/// The interval at which the text cursor of a focused `TextInput` blinks
+const TEXT_CURSOR_BLINK_INTERVAL: core::time::Duration = core::time::Duration::from_millis(500);
+
+/// Makes the text cursor of the focused `TextInput` blink. The timer toggles the visibility of
+/// the cursor, and triggering it requests a redraw, so there is one redraw per blink phase.
+struct TextCursorBlinker {
+    cursor_visible: Pin<Box<Property<bool>>>,
+    timer: crate::eventloop::Timer,
+    /// The `TextInput` for which the cursor blinks
+    owner: core::cell::Cell<*const TextInput>,
+}
+
+thread_local!(static TEXT_CURSOR_BLINKER: TextCursorBlinker = TextCursorBlinker {
+    cursor_visible: Box::pin(Property::new(true)),
+    timer: Default::default(),
+    owner: core::cell::Cell::new(core::ptr::null()),
+});
+
+impl TextCursorBlinker {
+    /// Shows the cursor of the given `TextInput` and starts the blinking over. The cursor stays
+    /// visible while the user is typing.
+    fn restart(owner: Pin<&TextInput>) {
+        TEXT_CURSOR_BLINKER.with(|blinker| {
+            blinker.owner.set(owner.get_ref());
+            blinker.cursor_visible.as_ref().set(true);
+            blinker.timer.start(
+                crate::eventloop::TimerMode::Repeated,
+                TEXT_CURSOR_BLINK_INTERVAL,
+                || {
+                    TEXT_CURSOR_BLINKER.with(|blinker| {
+                        let cursor_visible = blinker.cursor_visible.as_ref();
+                        cursor_visible.set(!cursor_visible.get());
+                    })
+                },
+            );
+        })
+    }
+
+    /// Stops the blinking, unless another `TextInput` took it over already
+    fn stop(owner: Pin<&TextInput>) {
+        // The items may be dropped while the thread local storage is destroyed
+        let _ = TEXT_CURSOR_BLINKER.try_with(|blinker| {
+            if core::ptr::eq(blinker.owner.get(), owner.get_ref()) {
+                blinker.owner.set(core::ptr::null());
+                blinker.timer.stop();
+            }
+        });
+    }
+
+    fn cursor_visible() -> bool {
+        TEXT_CURSOR_BLINKER.with(|blinker| blinker.cursor_visible.as_ref().get())
+    }
+}
+
+/// The time, in milliseconds since the start of the animation driver. It follows the mocked time
+/// in tests.
+fn current_time_in_ms() -> u64 {
+    crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.elapsed().as_millis() as u64)
+}
+
+/// The implementation of the `TextInput` element
+#[repr(C)]
+#[derive(FieldOffsets, Default, BuiltinItem)]
+#[pin_drop]
+pub struct TextInput {
+    pub text: Property<SharedString>,
+    pub font_family: Property<SharedString>,
+    pub font_size: Property<f32>,
+    pub color: Property<Color>,
+    pub selection_background_color: Property<Color>,
+    pub x: Property<f32>,
+    pub y: Property<f32>,
+    pub width: Property<f32>,
+    pub height: Property<f32>,
+    /// The position of the text cursor, as a byte offset in `text`
+    pub cursor_position: Property<i32>,
+    /// The other end of the selection, as a byte offset in `text`. It is equal to
+    /// `cursor_position` when there is no selection
+    pub anchor_position: Property<i32>,
+    pub text_cursor_width: Property<f32>,
+    pub has_focus: Property<bool>,
+    pub accepted: Signal<()>,
+    pub edited: Signal<()>,
+    pub cached_rendering_data: CachedRenderingData,
+    data: Property<TextInputData>,
+}
+
+impl Item for TextInput {
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(
+            Self::FIELD_OFFSETS.x.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.y.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.width.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.height.apply_pin(self).get(),
+        )
+    }
+    fn rendering_primitive(self: Pin<&Self>) -> HighLevelRenderingPrimitive {
+        let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+        let cursor_position = self.cursor_position(&text);
+        let anchor_position = self.anchor_position(&text);
+        HighLevelRenderingPrimitive::TextInput {
+            text,
+            font_family: Self::FIELD_OFFSETS.font_family.apply_pin(self).get(),
+            font_size: Self::FIELD_OFFSETS.font_size.apply_pin(self).get(),
+            color: Self::FIELD_OFFSETS.color.apply_pin(self).get(),
+            cursor_position: cursor_position as i32,
+            anchor_position: anchor_position as i32,
+            text_cursor_width: Self::FIELD_OFFSETS.text_cursor_width.apply_pin(self).get(),
+        }
+    }
+
+    fn rendering_variables(self: Pin<&Self>) -> SharedArray<RenderingVariable> {
+        let cursor_visible = Self::FIELD_OFFSETS.has_focus.apply_pin(self).get()
+            && TextCursorBlinker::cursor_visible();
+        let cursor_color = if cursor_visible {
+            Self::FIELD_OFFSETS.color.apply_pin(self).get()
+        } else {
+            Color::TRANSPARENT
+        };
+        SharedArray::from(&[
+            RenderingVariable::Color(
+                Self::FIELD_OFFSETS.selection_background_color.apply_pin(self).get(),
+            ),
+            RenderingVariable::Color(cursor_color),
+        ])
+    }
+
+    fn layouting_info(self: Pin<&Self>) -> LayoutInfo {
+        let font_family = Self::FIELD_OFFSETS.font_family.apply_pin(self).get();
+        let font_size = Self::FIELD_OFFSETS.font_size.apply_pin(self).get();
+        let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+
+        crate::font::FONT_CACHE.with(|fc| {
+            let font = fc.find_font(&font_family, font_size);
+            let width = font.text_width(&text);
+            let height = font.font_height();
+            LayoutInfo {
+                min_width: width,
+                max_width: f32::MAX,
+                min_height: height,
+                max_height: height,
+                ..LayoutInfo::default()
+            }
+        })
+    }
+
+    fn input_event(self: Pin<&Self>, event: MouseEvent) -> InputEventResult {
+        let mut data = Self::FIELD_OFFSETS.data.apply_pin(self).get();
+        let result = match event.what {
+            MouseEventType::MousePressed => {
+                let position = self.text_offset_for_x_position(event.pos.x);
+                self.set_cursor_position(position, false);
+                data.pressed = true;
+                InputEventResult::GrabMouse
+            }
+            MouseEventType::MouseMoved if data.pressed => {
+                let position = self.text_offset_for_x_position(event.pos.x);
+                self.set_cursor_position(position, true);
+                InputEventResult::GrabMouse
+            }
+            MouseEventType::MouseMoved => InputEventResult::EventIgnored,
+            MouseEventType::MouseWheel => return InputEventResult::EventIgnored,
+            MouseEventType::MouseExit | MouseEventType::MouseReleased => {
+                data.pressed = false;
+                InputEventResult::EventAccepted
+            }
+        };
+        Self::FIELD_OFFSETS.data.apply_pin(self).set(data);
+        if Self::FIELD_OFFSETS.has_focus.apply_pin(self).get() {
+            TextCursorBlinker::restart(self);
+        }
+        result
+    }
+
+    fn key_event(self: Pin<&Self>, event: &KeyEvent) -> KeyEventResult {
+        if event.event_type != KeyEventType::KeyPressed {
+            return KeyEventResult::EventIgnored;
+        }
+        let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+        let cursor = self.cursor_position(&text);
+        let anchor = self.anchor_position(&text);
+        let extend_selection = event.modifiers.shift;
+        match event.key {
+            KeyCode::Left => {
+                let position = if anchor != cursor && !extend_selection {
+                    cursor.min(anchor)
+                } else {
+                    text[..cursor].char_indices().next_back().map_or(0, |(offset, _)| offset)
+                };
+                self.set_cursor_position(position, extend_selection);
+            }
+            KeyCode::Right => {
+                let position = if anchor != cursor && !extend_selection {
+                    cursor.max(anchor)
+                } else {
+                    text[cursor..].chars().next().map_or(cursor, |ch| cursor + ch.len_utf8())
+                };
+                self.set_cursor_position(position, extend_selection);
+            }
+            KeyCode::Home => self.set_cursor_position(0, extend_selection),
+            KeyCode::End => self.set_cursor_position(text.len(), extend_selection),
+            KeyCode::A if event.modifiers.control => {
+                Self::FIELD_OFFSETS.anchor_position.apply_pin(self).set(0);
+                Self::FIELD_OFFSETS.cursor_position.apply_pin(self).set(text.len() as i32);
+            }
+            KeyCode::Backspace => {
+                let start = if anchor != cursor {
+                    cursor.min(anchor)
+                } else {
+                    text[..cursor].char_indices().next_back().map_or(0, |(offset, _)| offset)
+                };
+                self.replace_text(&text, start..cursor.max(anchor), "");
+            }
+            KeyCode::Delete => {
+                let end = if anchor != cursor {
+                    cursor.max(anchor)
+                } else {
+                    text[cursor..].chars().next().map_or(cursor, |ch| cursor + ch.len_utf8())
+                };
+                self.replace_text(&text, cursor.min(anchor)..end, "");
+            }
+            KeyCode::Return => Self::FIELD_OFFSETS.accepted.apply_pin(self).emit(&()),
+            _ if !event.text.is_empty() && !event.modifiers.control && !event.modifiers.meta => {
+                self.replace_text(&text, cursor.min(anchor)..cursor.max(anchor), &event.text);
+            }
+            _ => return KeyEventResult::EventIgnored,
+        }
+        TextCursorBlinker::restart(self);
+        KeyEventResult::EventAccepted
+    }
+
+    fn focus_event(self: Pin<&Self>, event: &FocusEvent) -> FocusEventResult {
+        let has_focus = *event == FocusEvent::FocusIn;
+        Self::FIELD_OFFSETS.has_focus.apply_pin(self).set(has_focus);
+        if has_focus {
+            TextCursorBlinker::restart(self);
+        } else {
+            TextCursorBlinker::stop(self);
+        }
+        FocusEventResult::FocusAccepted
+    }
+}
+
+impl const_field_offset::PinnedDrop for TextInput {
+    fn drop(self: Pin<&mut Self>) {
+        // The blinker must not keep the address of a TextInput that is freed while it has the focus
+        TextCursorBlinker::stop(self.as_ref());
+    }
+}
+
+#[test]
+fn test_text_cursor_blinker_stops() {
+    let stopped = || {
+        TEXT_CURSOR_BLINKER
+            .with(|blinker| !blinker.timer.running() && blinker.owner.get().is_null())
+    };
+
+    let input = Box::pin(TextInput::default());
+    input.as_ref().focus_event(&FocusEvent::FocusIn);
+    assert!(!stopped());
+    input.as_ref().focus_event(&FocusEvent::FocusOut);
+    assert!(stopped());
+
+    // An input that is dropped while it has the focus stops the blinking too
+    input.as_ref().focus_event(&FocusEvent::FocusIn);
+    drop(input);
+    assert!(stopped());
+}
+
+impl TextInput {
+    /// The cursor position, clamped to a character boundary of the text
+    fn cursor_position(self: Pin<&Self>, text: &str) -> usize {
+        Self::clamp_to_char_boundary(
+            text,
+            Self::FIELD_OFFSETS.cursor_position.apply_pin(self).get(),
+        )
+    }
+
+    /// The anchor position, clamped to a character boundary of the text
+    fn anchor_position(self: Pin<&Self>, text: &str) -> usize {
+        Self::clamp_to_char_boundary(
+            text,
+            Self::FIELD_OFFSETS.anchor_position.apply_pin(self).get(),
+        )
+    }
+
+    fn clamp_to_char_boundary(text: &str, position: i32) -> usize {
+        let mut position = (position.max(0) as usize).min(text.len());
+        while !text.is_char_boundary(position) {
+            position -= 1;
+        }
+        position
+    }
+
+    /// Move the cursor to the given byte offset. The selection is cleared, unless
+    /// `extend_selection` is true.
+    fn set_cursor_position(self: Pin<&Self>, position: usize, extend_selection: bool) {
+        Self::FIELD_OFFSETS.cursor_position.apply_pin(self).set(position as i32);
+        if !extend_selection {
+            Self::FIELD_OFFSETS.anchor_position.apply_pin(self).set(position as i32);
+        }
+    }
+
+    /// Replace the given range of the text and place the cursor after the inserted text.
+    fn replace_text(self: Pin<&Self>, text: &str, range: core::ops::Range<usize>, new_text: &str) {
+        if range.start == range.end && new_text.is_empty() {
+            return;
+        }
+        let mut result = String::with_capacity(text.len() - range.len() + new_text.len());
+        result.push_str(&text[..range.start]);
+        result.push_str(new_text);
+        result.push_str(&text[range.end..]);
+        Self::FIELD_OFFSETS.text.apply_pin(self).set(result.as_str().into());
+        self.set_cursor_position(range.start + new_text.len(), false);
+        Self::FIELD_OFFSETS.edited.apply_pin(self).emit(&());
+    }
+
+    fn text_offset_for_x_position(self: Pin<&Self>, x: f32) -> usize {
+        let font_family = Self::FIELD_OFFSETS.font_family.apply_pin(self).get();
+        let font_size = Self::FIELD_OFFSETS.font_size.apply_pin(self).get();
+        let text = Self::FIELD_OFFSETS.text.apply_pin(self).get();
+        crate::font::FONT_CACHE
+            .with(|fc| fc.find_font(&font_family, font_size).text_offset_for_x_position(&text, x))
+    }
+}
+
+impl ItemConsts for TextInput {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
+        Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+ItemVTable_static! {
+    /// The VTable for `TextInput`
+    #[no_mangle]
+    pub static TextInputVTable for TextInput
+}
+
+/// Called by the destructor of the C++ `TextInput`, which does not run the Rust `Drop`
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_text_input_drop(item: *const TextInput) {
+    TextCursorBlinker::stop(Pin::new_unchecked(&*item));
+}
+
+/// The shape of the mouse cursor while it is over a `TouchArea`
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum MouseCursor {
+    cursor_default,
+    cursor_pointer,
+    cursor_text,
+    cursor_move,
+    cursor_ew_resize,
+    cursor_ns_resize,
+    cursor_nesw_resize,
+    cursor_nwse_resize,
+}
+
+impl Default for MouseCursor {
+    fn default() -> Self {
+        Self::cursor_default
+    }
+}
+
+/// The mouse button of a mouse event
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum PointerButton {
+    none,
+    left,
+    right,
+    middle,
+}
+
+impl Default for PointerButton {
+    fn default() -> Self {
+        Self::none
+    }
+}
+
+/// Internal state of the `TouchArea` element
+#[repr(C)]
+#[derive(Default, Copy, Clone, Debug)]
+struct TouchAreaData {
+    /// The time of the current or last press, in milliseconds since the start of the animations
+    pressed_time: u64,
+    /// Set once the current press turned into a long press. No click is emitted for it.
+    long_pressed: bool,
+    /// Set when the last click can still become a double click
+    has_last_click: bool,
+    last_click_time: u64,
+    last_click_x: f32,
+    last_click_y: f32,
+}
+
+/// The maximum time between the release of two clicks that make a double click
+const DOUBLE_CLICK_INTERVAL: u64 = 500;
+/// The maximum distance, in pixels, between two clicks that make a double click
+const DOUBLE_CLICK_DISTANCE: f32 = 5.;
+/// The time the mouse needs to stay pressed for a long press
+const LONG_PRESS_DURATION: u64 = 500;
+
 /// The implementation of the `TouchArea` element
 #[repr(C)]
 #[derive(FieldOffsets, Default, BuiltinItem)]
@@ -407,12 +933,24 @@ pub struct TouchArea {
     /// implementation item only, for the compiler it would stay separate properties)
     pub pressed_x: Property<f32>,
     pub pressed_y: Property<f32>,
+    /// The button of the last mouse press
+    pub pressed_button: Property<PointerButton>,
     /// FIXME: should maybe be as parameter to the mouse event instead. Or at least just one property
     pub mouse_x: Property<f32>,
     pub mouse_y: Property<f32>,
+    /// The state of the keyboard modifiers at the time of the last mouse event
+    pub shift_modifier: Property<bool>,
+    pub control_modifier: Property<bool>,
+    pub alt_modifier: Property<bool>,
+    pub meta_modifier: Property<bool>,
+    pub has_hover: Property<bool>,
+    pub mouse_cursor: Property<MouseCursor>,
     pub clicked: Signal<()>,
+    pub double_clicked: Signal<()>,
+    pub long_pressed: Signal<()>,
     /// FIXME: remove this
     pub cached_rendering_data: CachedRenderingData,
+    data: Property<TouchAreaData>,
 }
 
 impl Item for TouchArea {
@@ -437,26 +975,89 @@ impl Item for TouchArea {
     }
 
     fn input_event(self: Pin<&Self>, event: MouseEvent) -> InputEventResult {
+        if matches!(event.what, MouseEventType::MouseWheel) {
+            return InputEventResult::EventIgnored;
+        }
         Self::FIELD_OFFSETS.mouse_x.apply_pin(self).set(event.pos.x);
         Self::FIELD_OFFSETS.mouse_y.apply_pin(self).set(event.pos.y);
+        Self::FIELD_OFFSETS.shift_modifier.apply_pin(self).set(event.modifiers.shift);
+        Self::FIELD_OFFSETS.control_modifier.apply_pin(self).set(event.modifiers.control);
+        Self::FIELD_OFFSETS.alt_modifier.apply_pin(self).set(event.modifiers.alt);
+        Self::FIELD_OFFSETS.meta_modifier.apply_pin(self).set(event.modifiers.meta);
+
+        let pressed = Self::FIELD_OFFSETS.pressed.apply_pin(self).get();
+        let hover = !matches!(event.what, MouseEventType::MouseExit)
+            && event.pos.x >= 0.
+            && event.pos.y >= 0.
+            && event.pos.x < Self::FIELD_OFFSETS.width.apply_pin(self).get()
+            && event.pos.y < Self::FIELD_OFFSETS.height.apply_pin(self).get();
+        Self::FIELD_OFFSETS.has_hover.apply_pin(self).set(hover);
+        if hover || pressed {
+            crate::input::request_mouse_cursor(
+                Self::FIELD_OFFSETS.mouse_cursor.apply_pin(self).get(),
+            );
+        }
 
-        let result = if matches!(event.what, MouseEventType::MouseReleased) {
-            Self::FIELD_OFFSETS.clicked.apply_pin(self).emit(());
-            InputEventResult::EventAccepted
-        } else {
-            InputEventResult::GrabMouse
+        let now = current_time_in_ms();
+        let mut data = Self::FIELD_OFFSETS.data.apply_pin(self).get();
+        match event.what {
+            MouseEventType::MousePressed => {
+                data.pressed_time = now;
+                data.long_pressed = false;
+                // Get an event when the press becomes a long press, even if the mouse stays still
+                crate::input::request_mouse_move_at(now + LONG_PRESS_DURATION);
+            }
+            MouseEventType::MouseExit => {}
+            _ if pressed
+                && !data.long_pressed
+                && now.saturating_sub(data.pressed_time) >= LONG_PRESS_DURATION =>
+            {
+                data.long_pressed = true;
+                Self::FIELD_OFFSETS.long_pressed.apply_pin(self).emit(&());
+            }
+            _ => {}
+        }
+        if matches!(event.what, MouseEventType::MouseReleased) && !data.long_pressed {
+            Self::FIELD_OFFSETS.clicked.apply_pin(self).emit(&());
+            let is_double_click = data.has_last_click
+                && now.saturating_sub(data.last_click_time) <= DOUBLE_CLICK_INTERVAL
+                && (event.pos.x - data.last_click_x).abs() <= DOUBLE_CLICK_DISTANCE
+                && (event.pos.y - data.last_click_y).abs() <= DOUBLE_CLICK_DISTANCE;
+            if is_double_click {
+                // A third click starts over instead of making another double click
+                data.has_last_click = false;
+                Self::FIELD_OFFSETS.double_clicked.apply_pin(self).emit(&());
+            } else {
+                data.has_last_click = true;
+                data.last_click_time = now;
+                data.last_click_x = event.pos.x;
+                data.last_click_y = event.pos.y;
+            }
+        }
+        Self::FIELD_OFFSETS.data.apply_pin(self).set(data);
+
+        // Accepting the mouse move while hovered means that the TouchArea gets a MouseExit when
+        // the mouse leaves it.
+        let result = match event.what {
+            MouseEventType::MouseReleased | MouseEventType::MouseExit => {
+                InputEventResult::EventAccepted
+            }
+            _ => InputEventResult::GrabMouse,
         };
 
         Self::FIELD_OFFSETS.pressed.apply_pin(self).set(match event.what {
             MouseEventType::MousePressed => {
                 Self::FIELD_OFFSETS.pressed_x.apply_pin(self).set(event.pos.x);
                 Self::FIELD_OFFSETS.pressed_y.apply_pin(self).set(event.pos.y);
+                Self::FIELD_OFFSETS.pressed_button.apply_pin(self).set(event.button);
                 true
             }
             MouseEventType::MouseExit | MouseEventType::MouseReleased => false,
-            MouseEventType::MouseMoved => {
-                return if Self::FIELD_OFFSETS.pressed.apply_pin(self).get() {
+            MouseEventType::MouseMoved | MouseEventType::MouseWheel => {
+                return if pressed {
                     InputEventResult::GrabMouse
+                } else if hover {
+                    InputEventResult::EventAccepted
                 } else {
                     InputEventResult::EventIgnored
                 }
@@ -464,6 +1065,14 @@ impl Item for TouchArea {
         });
         result
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for TouchArea {
@@ -527,6 +1136,14 @@ impl Item for Path {
     fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for Path {
@@ -582,6 +1199,14 @@ impl Item for Flickable {
         // FIXME
         InputEventResult::EventAccepted
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for Flickable {
@@ -642,6 +1267,8 @@ pub struct PropertyAnimation {
     pub loop_count: i32,
     #[rtti_field]
     pub easing: crate::animations::EasingCurve,
+    #[rtti_field]
+    pub delay: i32,
 }
 
 /// The implementation of the `Window` element
@@ -678,6 +1305,14 @@ impl Item for Window {
     fn input_event(self: Pin<&Self>, _event: MouseEvent) -> InputEventResult {
         InputEventResult::EventIgnored
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for Window {
@@ -690,3 +1325,238 @@ ItemVTable_static! {
     #[no_mangle]
     pub static WindowVTable for Window
 }
+
+/// The implementation of the `Timer` element
+#[repr(C)]
+#[derive(FieldOffsets, Default, BuiltinItem)]
+#[pin]
+pub struct TimerItem {
+    /// The interval in milliseconds
+    pub interval: Property<i32>,
+    pub running: Property<bool>,
+    pub repeat: Property<bool>,
+    pub triggered: Signal<()>,
+    data: TimerItemDataBox,
+    /// FIXME: remove this
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for TimerItem {
+    fn geometry(self: Pin<&Self>) -> Rect {
+        Rect::default()
+    }
+    fn rendering_primitive(self: Pin<&Self>) -> HighLevelRenderingPrimitive {
+        HighLevelRenderingPrimitive::NoContents
+    }
+
+    fn rendering_variables(self: Pin<&Self>) -> SharedArray<RenderingVariable> {
+        SharedArray::from(&[])
+    }
+
+    fn layouting_info(self: Pin<&Self>) -> LayoutInfo {
+        LayoutInfo::default()
+    }
+
+    fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
+}
+
+impl ItemConsts for TimerItem {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
+        Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+ItemVTable_static! {
+    /// The VTable for `TimerItem`
+    #[no_mangle]
+    pub static TimerItemVTable for TimerItem
+}
+
+impl TimerItem {
+    /// Registers the item so that the timer follows the `running`, `interval` and `repeat`
+    /// properties. The properties are first evaluated the next time the changes are processed,
+    /// so this must be called once the item is at its final address.
+    pub fn init(self: Pin<&Self>) {
+        self.data.item.set(self.get_ref() as *const Self);
+        crate::eventloop::register_change_watcher(self.data.downgrade());
+    }
+}
+
+/// The runtime data of the `Timer` element
+pub struct TimerItemData {
+    /// Set by `TimerItem::init`. The item owns this data, so it outlives it.
+    item: core::cell::Cell<*const TimerItem>,
+    timer: crate::eventloop::Timer,
+    tracker: Pin<Box<crate::properties::PropertyTracker>>,
+}
+
+impl crate::eventloop::ChangeWatcher for TimerItemData {
+    fn process_changes(&self) -> bool {
+        if !self.tracker.is_dirty() {
+            return false;
+        }
+        let item_ptr = self.item.get();
+        // Safety: the item owns this data and the pointer was set when the item was pinned
+        let item = unsafe { Pin::new_unchecked(&*item_ptr) };
+        let (running, interval, repeat) = self.tracker.as_ref().evaluate(|| {
+            (
+                TimerItem::FIELD_OFFSETS.running.apply_pin(item).get(),
+                TimerItem::FIELD_OFFSETS.interval.apply_pin(item).get(),
+                TimerItem::FIELD_OFFSETS.repeat.apply_pin(item).get(),
+            )
+        });
+        if !running {
+            self.timer.stop();
+            return false;
+        }
+        let mode = if repeat {
+            crate::eventloop::TimerMode::Repeated
+        } else {
+            crate::eventloop::TimerMode::SingleShot
+        };
+        let interval = core::time::Duration::from_millis(interval.max(0) as u64);
+        self.timer.start(mode, interval, move || {
+            // Safety: the timer is owned by the item, so the item is alive when it is triggered
+            let item = unsafe { Pin::new_unchecked(&*item_ptr) };
+            if !repeat {
+                TimerItem::FIELD_OFFSETS.running.apply_pin(item).set(false);
+            }
+            TimerItem::FIELD_OFFSETS.triggered.apply_pin(item).emit(&());
+        });
+        false
+    }
+}
+
+#[repr(C)]
+/// Wraps the internal datastructure for the Timer
+pub struct TimerItemDataBox(core::ptr::NonNull<TimerItemData>);
+
+impl TimerItemDataBox {
+    fn downgrade(&self) -> std::rc::Weak<dyn crate::eventloop::ChangeWatcher> {
+        // Safety: the self.0 was constructed from a Rc::into_raw in TimerItemDataBox::default
+        let rc = unsafe { std::rc::Rc::from_raw(self.0.as_ptr()) };
+        let weak = std::rc::Rc::downgrade(&rc);
+        core::mem::forget(rc);
+        weak
+    }
+}
+
+impl Default for TimerItemDataBox {
+    fn default() -> Self {
+        let data = std::rc::Rc::new(TimerItemData {
+            item: core::cell::Cell::new(core::ptr::null()),
+            timer: Default::default(),
+            tracker: Box::pin(Default::default()),
+        });
+        TimerItemDataBox(core::ptr::NonNull::new(std::rc::Rc::into_raw(data) as *mut _).unwrap())
+    }
+}
+impl Drop for TimerItemDataBox {
+    fn drop(&mut self) {
+        // Safety: the self.0 was constructed from a Rc::into_raw in TimerItemDataBox::default
+        unsafe {
+            std::rc::Rc::from_raw(self.0.as_ptr());
+        }
+    }
+}
+impl core::ops::Deref for TimerItemDataBox {
+    type Target = TimerItemData;
+    fn deref(&self) -> &Self::Target {
+        // Safety: initialized in TimerItemDataBox::default
+        unsafe { self.0.as_ref() }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_timer_item_data_init(data: *mut TimerItemDataBox) {
+    std::ptr::write(data, TimerItemDataBox::default());
+}
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_timer_item_data_free(data: *mut TimerItemDataBox) {
+    std::ptr::read(data);
+}
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_timer_item_init(item: *const TimerItem) {
+    Pin::new_unchecked(&*item).init();
+}
+
+/// The implementation of the `FocusScope` element
+#[repr(C)]
+#[derive(FieldOffsets, Default, BuiltinItem)]
+#[pin]
+pub struct FocusScope {
+    pub x: Property<f32>,
+    pub y: Property<f32>,
+    pub width: Property<f32>,
+    pub height: Property<f32>,
+    /// The name of the `KeyCode` of the last key event
+    pub key: Property<SharedString>,
+    /// The text of the last key event
+    pub text: Property<SharedString>,
+    pub has_focus: Property<bool>,
+    pub key_pressed: Signal<()>,
+    pub key_released: Signal<()>,
+    /// FIXME: remove this
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for FocusScope {
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(
+            Self::FIELD_OFFSETS.x.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.y.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.width.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.height.apply_pin(self).get(),
+        )
+    }
+    fn rendering_primitive(self: Pin<&Self>) -> HighLevelRenderingPrimitive {
+        HighLevelRenderingPrimitive::NoContents
+    }
+
+    fn rendering_variables(self: Pin<&Self>) -> SharedArray<RenderingVariable> {
+        SharedArray::from(&[])
+    }
+
+    fn layouting_info(self: Pin<&Self>) -> LayoutInfo {
+        LayoutInfo::default()
+    }
+
+    fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, event: &KeyEvent) -> KeyEventResult {
+        Self::FIELD_OFFSETS.key.apply_pin(self).set(event.key.to_string().as_str().into());
+        Self::FIELD_OFFSETS.text.apply_pin(self).set(event.text.clone());
+        match event.event_type {
+            KeyEventType::KeyPressed => Self::FIELD_OFFSETS.key_pressed.apply_pin(self).emit(&()),
+            KeyEventType::KeyReleased => Self::FIELD_OFFSETS.key_released.apply_pin(self).emit(&()),
+        }
+        KeyEventResult::EventAccepted
+    }
+
+    fn focus_event(self: Pin<&Self>, event: &FocusEvent) -> FocusEventResult {
+        Self::FIELD_OFFSETS.has_focus.apply_pin(self).set(*event == FocusEvent::FocusIn);
+        FocusEventResult::FocusAccepted
+    }
+}
+
+impl ItemConsts for FocusScope {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
+        Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+ItemVTable_static! {
+    /// The VTable for `FocusScope`
+    #[no_mangle]
+    pub static FocusScopeVTable for FocusScope
+}
@@ -12,6 +12,7 @@ LICENSE END */
 //! Currently this is a very basic implementation
 
 use crate::{slice::Slice, Property};
+use core::ffi::c_void;
 
 type Coord = f32;
 
@@ -27,15 +28,30 @@ pub struct LayoutInfo {
     pub min_height: f32,
     /// The maximum height for the item.
     pub max_height: f32,
+    /// The horizontal stretch factor, relative to the other items of the layout.
+    pub horizontal_stretch: f32,
+    /// The vertical stretch factor, relative to the other items of the layout.
+    pub vertical_stretch: f32,
 }
 
 impl Default for LayoutInfo {
     fn default() -> Self {
-        LayoutInfo { min_width: 0., max_width: f32::MAX, min_height: 0., max_height: f32::MAX }
+        LayoutInfo {
+            min_width: 0.,
+            max_width: f32::MAX,
+            min_height: 0.,
+            max_height: f32::MAX,
+            horizontal_stretch: 1.,
+            vertical_stretch: 1.,
+        }
     }
 }
 
 impl LayoutInfo {
+    /// Merges the layout info of the layout within an element (`self`) with the layout info of
+    /// the element itself (`other`). The size constraints are combined, but the stretch factors
+    /// are those of the element: the stretch of the elements inside the layout does not override
+    /// the stretch of the element that contains it.
     // Note: This "logic" is duplicated in the cpp generator's generated code for merging layout infos.
     pub fn merge(&self, other: &LayoutInfo) -> Self {
         Self {
@@ -43,6 +59,8 @@ impl LayoutInfo {
             max_width: self.max_width.min(other.max_width),
             min_height: self.min_height.max(other.min_height),
             max_height: self.max_height.min(other.max_height),
+            horizontal_stretch: other.horizontal_stretch,
+            vertical_stretch: other.vertical_stretch,
         }
     }
 }
@@ -163,7 +181,7 @@ impl Default for Constraint {
 }
 
 #[repr(C)]
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Padding {
     pub left: Coord,
     pub right: Coord,
@@ -183,65 +201,273 @@ pub struct GridLayoutData<'a> {
     pub cells: Slice<'a, GridLayoutCellData<'a>>,
 }
 
+/// The alignment of an item within its cell of a GridLayout
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum CellAlignment {
+    stretch,
+    start,
+    center,
+    end,
+}
+
+impl Default for CellAlignment {
+    fn default() -> Self {
+        Self::stretch
+    }
+}
+
+/// A callback that returns the minimum height that an item needs for a given width.
+///
+/// This is used for items whose height depend on their width, such as a word wrapped Text,
+/// a FlowLayout, or a layout that contains such items.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct HeightForWidth<'a> {
+    /// Called with `data` as first argument and the width. If None, the height of the item does
+    /// not depend on its width.
+    pub function: Option<extern "C" fn(*const c_void, Coord) -> Coord>,
+    pub data: *const c_void,
+    pub phantom: core::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> Default for HeightForWidth<'a> {
+    fn default() -> Self {
+        Self { function: None, data: core::ptr::null(), phantom: Default::default() }
+    }
+}
+
+impl<'a> HeightForWidth<'a> {
+    /// Create a HeightForWidth that calls the given closure
+    pub fn new<F: Fn(Coord) -> Coord>(f: &'a F) -> Self {
+        extern "C" fn call<F: Fn(Coord) -> Coord>(data: *const c_void, width: Coord) -> Coord {
+            // Safety: data was created from a `&F` in `new`
+            unsafe { (*(data as *const F))(width) }
+        }
+        Self {
+            function: Some(call::<F>),
+            data: f as *const F as *const c_void,
+            phantom: Default::default(),
+        }
+    }
+
+    /// Returns the minimum height for the given width, or None if the height does not depend on
+    /// the width
+    pub fn height_for_width(&self, width: Coord) -> Option<Coord> {
+        self.function.map(|f| f(self.data, width))
+    }
+}
+
 #[repr(C)]
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct GridLayoutCellData<'a> {
     pub col: u16,
     pub row: u16,
     pub colspan: u16,
     pub rowspan: u16,
     pub constraint: LayoutInfo,
+    pub horizontal_alignment: CellAlignment,
+    pub vertical_alignment: CellAlignment,
     pub x: Option<&'a Property<Coord>>,
     pub y: Option<&'a Property<Coord>>,
     pub width: Option<&'a Property<Coord>>,
     pub height: Option<&'a Property<Coord>>,
+    /// Set for the items whose minimum height depends on their width. Once the width of the
+    /// columns is known, it is used instead of `constraint.min_height`.
+    pub height_for_width: HeightForWidth<'a>,
 }
 
-/// FIXME: rename with sixstyfps prefix
-#[no_mangle]
-pub extern "C" fn solve_grid_layout(data: &GridLayoutData) {
+/// Returns the position and the size of the slot of the cells at `pos` spanning `span`
+/// rows or columns
+fn cell_slot(layout_data: &[internal::LayoutData], pos: u16, span: u16) -> (Coord, Coord) {
+    let first = &layout_data[pos as usize];
+    let last = &layout_data[pos as usize + span.max(1) as usize - 1];
+    (first.pos, last.pos + last.size - first.pos)
+}
+
+/// Returns the width of the cell once the columns are solved
+fn cell_width(cell: &GridLayoutCellData, col_layout_data: &[internal::LayoutData]) -> Coord {
+    let (x, width) = cell_slot(col_layout_data, cell.col, cell.colspan);
+    let c = &cell.constraint;
+    align_in_cell(cell.horizontal_alignment, x, width, c.min_width, c.max_width).1
+}
+
+/// Returns the minimum height of the cell. When the columns are already solved, this is the
+/// height that the cell needs for its width, if it depends on the width.
+fn cell_min_height(
+    cell: &GridLayoutCellData,
+    col_layout_data: Option<&[internal::LayoutData]>,
+) -> Coord {
+    col_layout_data
+        .and_then(|cols| cell.height_for_width.height_for_width(cell_width(cell, cols)))
+        .unwrap_or(cell.constraint.min_height)
+}
+
+/// Returns the sum of the minimum sizes, plus the spacing between them
+fn min_size(layout_data: &[internal::LayoutData], spacing: Coord) -> Coord {
+    layout_data.iter().map(|data| data.min).sum::<Coord>()
+        + spacing * (layout_data.len().max(1) - 1) as Coord
+}
+
+/// Compute the layout data of every column (when `horizontal` is true) or every row of the grid.
+/// When the columns are already solved, `col_layout_data` is set, so that the height of the
+/// rows takes into account the height that the cells need for their width.
+fn grid_layout_data(
+    cells: &Slice<GridLayoutCellData>,
+    count: usize,
+    spacing: Coord,
+    horizontal: bool,
+    col_layout_data: Option<&[internal::LayoutData]>,
+) -> Vec<internal::LayoutData> {
+    let cell_data = |cell: &GridLayoutCellData| {
+        let c = &cell.constraint;
+        if horizontal {
+            (
+                cell.col as usize,
+                cell.colspan.max(1) as usize,
+                c.min_width,
+                c.max_width,
+                c.horizontal_stretch,
+            )
+        } else {
+            (
+                cell.row as usize,
+                cell.rowspan.max(1) as usize,
+                cell_min_height(cell, col_layout_data),
+                c.max_height,
+                c.vertical_stretch,
+            )
+        }
+    };
+
+    let mut layout_data = vec![internal::LayoutData::default(); count];
+    let mut has_stretch = vec![false; count];
+    for cell in cells.iter() {
+        let (pos, span, min, max, stretch) = cell_data(cell);
+        if span != 1 {
+            continue;
+        }
+        let data = &mut layout_data[pos];
+        data.max = data.max.min(max);
+        data.min = data.min.max(min);
+        data.pref = data.pref.max(min);
+        data.stretch = if has_stretch[pos] { data.stretch.max(stretch) } else { stretch };
+        has_stretch[pos] = true;
+    }
+
+    // Cells spanning several columns or rows enlarge the ones they span if they are too small
+    for cell in cells.iter() {
+        let (pos, span, min, _, _) = cell_data(cell);
+        if span == 1 {
+            continue;
+        }
+        let spanned = &mut layout_data[pos..pos + span];
+        let current_min =
+            spanned.iter().map(|data| data.min).sum::<Coord>() + spacing * (span - 1) as Coord;
+        if current_min < min {
+            let missing = (min - current_min) / span as Coord;
+            for data in spanned {
+                data.min += missing;
+                data.pref += missing;
+            }
+        }
+    }
+
+    for data in layout_data.iter_mut() {
+        data.max = data.max.max(data.min);
+    }
+    layout_data
+}
+
+/// Returns the position and size of an item which has a given minimum and maximum size,
+/// within a slot starting at `pos` of size `size`.
+fn align_in_cell(
+    alignment: CellAlignment,
+    pos: Coord,
+    size: Coord,
+    min: Coord,
+    max: Coord,
+) -> (Coord, Coord) {
+    if alignment == CellAlignment::stretch {
+        return (pos, size.min(max));
+    }
+    let item_size = min.min(size);
+    match alignment {
+        CellAlignment::stretch | CellAlignment::start => (pos, item_size),
+        CellAlignment::center => (pos + (size - item_size) / 2., item_size),
+        CellAlignment::end => (pos + size - item_size, item_size),
+    }
+}
+
+fn grid_dimensions(cells: &Slice<GridLayoutCellData>) -> (usize, usize) {
     let (mut num_col, mut num_row) = (0, 0);
-    for cell in data.cells.iter() {
-        num_row = num_row.max(cell.row + cell.rowspan);
-        num_col = num_col.max(cell.col + cell.colspan);
+    for cell in cells.iter() {
+        num_row = num_row.max(cell.row as usize + cell.rowspan.max(1) as usize);
+        num_col = num_col.max(cell.col as usize + cell.colspan.max(1) as usize);
     }
+    (num_col, num_row)
+}
 
+/// Solve the columns of the grid within `width`, and compute the layout data of the rows for the
+/// width that the cells got. The columns are solved first, so that the rows can use the height
+/// that the cells need for their width.
+fn grid_layout_data_for_width(
+    cells: &Slice<GridLayoutCellData>,
+    (num_col, num_row): (usize, usize),
+    spacing: Coord,
+    x: Coord,
+    width: Coord,
+) -> (Vec<internal::LayoutData>, Vec<internal::LayoutData>) {
+    let mut col_layout_data = grid_layout_data(cells, num_col, spacing, true, None);
+    internal::layout_items(&mut col_layout_data, x, width, spacing);
+    let row_layout_data = grid_layout_data(cells, num_row, spacing, false, Some(&col_layout_data));
+    (col_layout_data, row_layout_data)
+}
+
+/// FIXME: rename with sixstyfps prefix
+#[no_mangle]
+pub extern "C" fn solve_grid_layout(data: &GridLayoutData) {
+    let (num_col, num_row) = grid_dimensions(&data.cells);
     if num_col < 1 || num_row < 1 {
         return;
     }
 
-    let mut row_layout_data = vec![internal::LayoutData::default(); num_row as usize];
-    let mut col_layout_data = vec![internal::LayoutData::default(); num_col as usize];
-    for cell in data.cells.iter() {
-        let rdata = &mut row_layout_data[cell.row as usize];
-        let cdata = &mut col_layout_data[cell.col as usize];
-        rdata.max = rdata.max.min(cell.constraint.max_height);
-        cdata.max = cdata.max.min(cell.constraint.max_width);
-        rdata.min = rdata.min.max(cell.constraint.min_height);
-        cdata.min = cdata.min.max(cell.constraint.min_width);
-        rdata.pref = rdata.pref.max(cell.constraint.min_height);
-        cdata.pref = cdata.pref.max(cell.constraint.min_width);
-    }
-
+    let (col_layout_data, mut row_layout_data) = grid_layout_data_for_width(
+        &data.cells,
+        (num_col, num_row),
+        data.spacing,
+        data.x + data.padding.left,
+        data.width - (data.padding.left + data.padding.right),
+    );
     internal::layout_items(
         &mut row_layout_data,
         data.y + data.padding.top,
         data.height - (data.padding.top + data.padding.bottom),
         data.spacing,
     );
-    internal::layout_items(
-        &mut col_layout_data,
-        data.x + data.padding.left,
-        data.width - (data.padding.left + data.padding.right),
-        data.spacing,
-    );
+
     for cell in data.cells.iter() {
-        let rdata = &row_layout_data[cell.row as usize];
-        let cdata = &col_layout_data[cell.col as usize];
-        cell.x.map(|p| p.set(cdata.pos));
-        cell.width.map(|p| p.set(cdata.size));
-        cell.y.map(|p| p.set(rdata.pos));
-        cell.height.map(|p| p.set(rdata.size));
+        let (x, width) = cell_slot(&col_layout_data, cell.col, cell.colspan);
+        let (x, width) = align_in_cell(
+            cell.horizontal_alignment,
+            x,
+            width,
+            cell.constraint.min_width,
+            cell.constraint.max_width,
+        );
+        let (y, height) = cell_slot(&row_layout_data, cell.row, cell.rowspan);
+        let (y, height) = align_in_cell(
+            cell.vertical_alignment,
+            y,
+            height,
+            cell_min_height(cell, Some(&col_layout_data)),
+            cell.constraint.max_height,
+        );
+        cell.x.map(|p| p.set(x));
+        cell.width.map(|p| p.set(width));
+        cell.y.map(|p| p.set(y));
+        cell.height.map(|p| p.set(height));
     }
 }
 
@@ -251,50 +477,574 @@ pub extern "C" fn grid_layout_info<'a>(
     spacing: Coord,
     padding: &Padding,
 ) -> LayoutInfo {
-    let (mut num_col, mut num_row) = (0, 0);
-    for cell in cells.iter() {
-        num_row = num_row.max(cell.row + cell.rowspan);
-        num_col = num_col.max(cell.col + cell.colspan);
-    }
-
+    let (num_col, num_row) = grid_dimensions(cells);
     if num_col < 1 || num_row < 1 {
-        return LayoutInfo { min_width: 0., max_width: 0., min_height: 0., max_height: 0. };
+        return LayoutInfo {
+            min_width: 0.,
+            max_width: 0.,
+            min_height: 0.,
+            max_height: 0.,
+            ..LayoutInfo::default()
+        };
     };
 
-    let mut row_layout_data = vec![internal::LayoutData::default(); num_row as usize];
-    let mut col_layout_data = vec![internal::LayoutData::default(); num_col as usize];
-    for cell in cells.iter() {
-        let rdata = &mut row_layout_data[cell.row as usize];
-        let cdata = &mut col_layout_data[cell.col as usize];
-        rdata.max = rdata.max.min(cell.constraint.max_height);
-        cdata.max = cdata.max.min(cell.constraint.max_width);
-        rdata.min = rdata.min.max(cell.constraint.min_height);
-        cdata.min = cdata.min.max(cell.constraint.min_width);
-        rdata.pref = rdata.pref.max(cell.constraint.min_height);
-        cdata.pref = cdata.pref.max(cell.constraint.min_width);
-    }
+    let col_layout_data = grid_layout_data(cells, num_col, spacing, true, None);
+    let inner_min_width = min_size(&col_layout_data, spacing);
+    // The minimum height is the height that the cells need when the grid has its minimum width
+    let (_, row_layout_data) =
+        grid_layout_data_for_width(cells, (num_col, num_row), spacing, 0., inner_min_width);
 
     let spacing_h = spacing * (num_row - 1) as Coord;
     let spacing_w = spacing * (num_col - 1) as Coord;
 
-    let min_height = row_layout_data.iter().map(|data| data.min).sum::<Coord>()
-        + spacing_h
-        + padding.left
-        + padding.right;
+    let min_height = min_size(&row_layout_data, spacing) + padding.top + padding.bottom;
     let max_height = row_layout_data.iter().map(|data| data.max).sum::<Coord>()
         + spacing_h
         + padding.top
         + padding.bottom;
-    let min_width = col_layout_data.iter().map(|data| data.min).sum::<Coord>()
+    let min_width = inner_min_width + padding.left + padding.right;
+    let max_width = col_layout_data.iter().map(|data| data.max).sum::<Coord>()
         + spacing_w
         + padding.left
         + padding.right;
-    let max_width = col_layout_data.iter().map(|data| data.max).sum::<Coord>()
-        + spacing_w
+
+    LayoutInfo { min_width, max_width, min_height, max_height, ..LayoutInfo::default() }
+}
+
+/// Returns the minimum height of the grid when it is `width` wide
+#[no_mangle]
+pub extern "C" fn grid_layout_height_for_width<'a>(
+    cells: &Slice<'a, GridLayoutCellData<'a>>,
+    spacing: Coord,
+    padding: &Padding,
+    width: Coord,
+) -> Coord {
+    let (num_col, num_row) = grid_dimensions(cells);
+    if num_col < 1 || num_row < 1 {
+        return 0.;
+    }
+    let (_, row_layout_data) = grid_layout_data_for_width(
+        cells,
+        (num_col, num_row),
+        spacing,
+        0.,
+        width - (padding.left + padding.right),
+    );
+    min_size(&row_layout_data, spacing) + padding.top + padding.bottom
+}
+
+#[test]
+fn test_solve_grid_layout() {
+    let props: Vec<_> = (0..6).map(|_| Box::pin(Property::<Coord>::default())).collect();
+    let cell = |col, row, colspan, constraint, x: usize, width: usize| GridLayoutCellData {
+        col,
+        row,
+        colspan,
+        rowspan: 1,
+        constraint,
+        x: Some(&*props[x]),
+        width: Some(&*props[width]),
+        ..Default::default()
+    };
+    let cells = [
+        cell(0, 0, 2, LayoutInfo::default(), 0, 1),
+        cell(
+            0,
+            1,
+            1,
+            LayoutInfo { min_width: 50., horizontal_stretch: 0., ..Default::default() },
+            2,
+            3,
+        ),
+        cell(1, 1, 1, LayoutInfo::default(), 4, 5),
+    ];
+    let padding = Padding::default();
+    let data = GridLayoutData {
+        width: 300.,
+        height: 100.,
+        x: 0.,
+        y: 0.,
+        spacing: 0.,
+        padding: &padding,
+        cells: Slice::from_slice(&cells),
+    };
+
+    solve_grid_layout(&data);
+    let values: Vec<Coord> = props.iter().map(|p| p.as_ref().get()).collect();
+    assert_eq!(values, [0., 300., 0., 50., 50., 250.]);
+
+    let mut cells = cells;
+    cells[0].constraint.min_width = 400.;
+    let info = grid_layout_info(&Slice::from_slice(&cells), 0., &padding);
+    assert_eq!(info.min_width, 400.);
+}
+
+/// The alignment of the items within a box layout, along the direction of the layout
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum LayoutAlignment {
+    stretch,
+    center,
+    start,
+    end,
+    space_between,
+    space_around,
+}
+
+impl Default for LayoutAlignment {
+    fn default() -> Self {
+        Self::stretch
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct BoxLayoutData<'a> {
+    pub width: Coord,
+    pub height: Coord,
+    pub x: Coord,
+    pub y: Coord,
+    pub spacing: Coord,
+    pub padding: &'a Padding,
+    pub alignment: LayoutAlignment,
+    pub cells: Slice<'a, BoxLayoutCellData<'a>>,
+}
+
+#[repr(C)]
+#[derive(Default, Debug, Clone)]
+pub struct BoxLayoutCellData<'a> {
+    pub constraint: LayoutInfo,
+    pub x: Option<&'a Property<Coord>>,
+    pub y: Option<&'a Property<Coord>>,
+    pub width: Option<&'a Property<Coord>>,
+    pub height: Option<&'a Property<Coord>>,
+    /// Set for the items whose minimum height depends on their width. Once the width of the
+    /// item is known, it is used instead of `constraint.min_height`.
+    pub height_for_width: HeightForWidth<'a>,
+}
+
+impl<'a> BoxLayoutCellData<'a> {
+    /// Returns the minimum height of the cell when it is `width` wide
+    fn min_height_for_width(&self, width: Coord) -> Coord {
+        self.height_for_width.height_for_width(width).unwrap_or(self.constraint.min_height)
+    }
+
+    /// Returns the width of the cell in a VerticalLayout whose cells are `width` wide
+    fn cross_width(&self, width: Coord) -> Coord {
+        width.min(self.constraint.max_width).max(self.constraint.min_width)
+    }
+}
+
+/// Place the items one after the other within `size`, according to the alignment
+fn align_items(
+    layout_data: &mut [internal::LayoutData],
+    main_pos: Coord,
+    main_size: Coord,
+    spacing: Coord,
+    alignment: LayoutAlignment,
+) {
+    let num_spacings = (layout_data.len() - 1) as Coord;
+    let pref_size = layout_data.iter().map(|it| it.pref).sum::<Coord>();
+    let extra_space = main_size - pref_size - spacing * num_spacings;
+
+    if alignment == LayoutAlignment::stretch || extra_space <= 0. {
+        internal::layout_items(layout_data, main_pos, main_size, spacing);
+    } else {
+        let (start, spacing) = match alignment {
+            LayoutAlignment::stretch | LayoutAlignment::start => (main_pos, spacing),
+            LayoutAlignment::end => (main_pos + extra_space, spacing),
+            LayoutAlignment::center => (main_pos + extra_space / 2., spacing),
+            LayoutAlignment::space_between if num_spacings > 0. => {
+                (main_pos, spacing + extra_space / num_spacings)
+            }
+            LayoutAlignment::space_between => (main_pos, spacing),
+            LayoutAlignment::space_around => {
+                let around = extra_space / layout_data.len() as Coord;
+                (main_pos + around / 2., spacing + around)
+            }
+        };
+        let mut pos = start;
+        for it in layout_data.iter_mut() {
+            it.size = it.pref;
+            it.pos = pos;
+            pos += it.size + spacing;
+        }
+    }
+}
+
+/// Compute the layout data of the cells of a box layout along its direction. For a
+/// VerticalLayout, `width` is the width of the cells, which their minimum height may depend on.
+fn box_layout_data(
+    cells: &Slice<BoxLayoutCellData>,
+    is_horizontal: bool,
+    width: Coord,
+) -> Vec<internal::LayoutData> {
+    cells
+        .iter()
+        .map(|cell| {
+            let c = &cell.constraint;
+            let (min, max, stretch) = if is_horizontal {
+                (c.min_width, c.max_width, c.horizontal_stretch)
+            } else {
+                let min = cell.min_height_for_width(cell.cross_width(width));
+                (min, c.max_height.max(min), c.vertical_stretch)
+            };
+            internal::LayoutData { min, max, pref: min, stretch, ..Default::default() }
+        })
+        .collect()
+}
+
+/// Returns the minimum height of the cells of a box layout, without the padding, when they are
+/// laid out in `width`
+fn box_layout_min_height(
+    cells: &Slice<BoxLayoutCellData>,
+    spacing: Coord,
+    alignment: LayoutAlignment,
+    is_horizontal: bool,
+    width: Coord,
+) -> Coord {
+    if is_horizontal {
+        let mut layout_data = box_layout_data(cells, true, width);
+        align_items(&mut layout_data, 0., width, spacing, alignment);
+        cells
+            .iter()
+            .zip(layout_data.iter())
+            .map(|(cell, ld)| cell.min_height_for_width(ld.size))
+            .fold(0., Coord::max)
+    } else {
+        min_size(&box_layout_data(cells, false, width), spacing)
+    }
+}
+
+/// Solve a BoxLayout. A `HorizontalLayout` when is_horizontal is true, a `VerticalLayout` otherwise
+#[no_mangle]
+pub extern "C" fn solve_box_layout(data: &BoxLayoutData, is_horizontal: bool) {
+    if data.cells.is_empty() {
+        return;
+    }
+
+    let (main_pos, main_size, cross_pos, cross_size) = if is_horizontal {
+        (
+            data.x + data.padding.left,
+            data.width - (data.padding.left + data.padding.right),
+            data.y + data.padding.top,
+            data.height - (data.padding.top + data.padding.bottom),
+        )
+    } else {
+        (
+            data.y + data.padding.top,
+            data.height - (data.padding.top + data.padding.bottom),
+            data.x + data.padding.left,
+            data.width - (data.padding.left + data.padding.right),
+        )
+    };
+
+    let mut layout_data =
+        box_layout_data(&data.cells, is_horizontal, if is_horizontal { 0. } else { cross_size });
+
+    align_items(&mut layout_data, main_pos, main_size, data.spacing, data.alignment);
+
+    for (cell, ld) in data.cells.iter().zip(layout_data.iter()) {
+        let (x, y, width, height) = if is_horizontal {
+            let min_height = cell.min_height_for_width(ld.size);
+            let height = cross_size.min(cell.constraint.max_height).max(min_height);
+            (ld.pos, cross_pos, ld.size, height)
+        } else {
+            (cross_pos, ld.pos, cell.cross_width(cross_size), ld.size)
+        };
+        cell.x.map(|p| p.set(x));
+        cell.y.map(|p| p.set(y));
+        cell.width.map(|p| p.set(width));
+        cell.height.map(|p| p.set(height));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn box_layout_info<'a>(
+    cells: &Slice<'a, BoxLayoutCellData<'a>>,
+    spacing: Coord,
+    padding: &Padding,
+    alignment: LayoutAlignment,
+    is_horizontal: bool,
+) -> LayoutInfo {
+    let padding_h = padding.left + padding.right;
+    let padding_v = padding.top + padding.bottom;
+    if cells.is_empty() {
+        return LayoutInfo {
+            min_width: padding_h,
+            max_width: padding_h,
+            min_height: padding_v,
+            max_height: padding_v,
+            ..LayoutInfo::default()
+        };
+    }
+
+    let extra_spacing = spacing * (cells.len() - 1) as Coord;
+    let sum_or_unbounded = |values: &mut dyn Iterator<Item = Coord>| {
+        if alignment == LayoutAlignment::stretch {
+            values.sum::<Coord>() + extra_spacing
+        } else {
+            Coord::MAX
+        }
+    };
+
+    // The minimum height is the height that the cells need when the layout has its minimum width
+    if is_horizontal {
+        let min_width = cells.iter().map(|c| c.constraint.min_width).sum::<Coord>() + extra_spacing;
+        let max_width = sum_or_unbounded(&mut cells.iter().map(|c| c.constraint.max_width));
+        let min_height = box_layout_min_height(cells, spacing, alignment, true, min_width);
+        let max_height =
+            cells.iter().map(|c| c.constraint.max_height).fold(0., Coord::max).max(min_height);
+        LayoutInfo {
+            min_width: min_width + padding_h,
+            max_width: max_width.max(min_width) + padding_h,
+            min_height: min_height + padding_v,
+            max_height: max_height + padding_v,
+            ..LayoutInfo::default()
+        }
+    } else {
+        let min_width = cells.iter().map(|c| c.constraint.min_width).fold(0., Coord::max);
+        let min_height = box_layout_min_height(cells, spacing, alignment, false, min_width);
+        let max_height = sum_or_unbounded(&mut cells.iter().map(|c| c.constraint.max_height));
+        let max_width =
+            cells.iter().map(|c| c.constraint.max_width).fold(0., Coord::max).max(min_width);
+        LayoutInfo {
+            min_width: min_width + padding_h,
+            max_width: max_width + padding_h,
+            min_height: min_height + padding_v,
+            max_height: max_height.max(min_height) + padding_v,
+            ..LayoutInfo::default()
+        }
+    }
+}
+
+/// Returns the minimum height of a HorizontalLayout (when `is_horizontal` is true) or a
+/// VerticalLayout when it is `width` wide
+#[no_mangle]
+pub extern "C" fn box_layout_height_for_width<'a>(
+    cells: &Slice<'a, BoxLayoutCellData<'a>>,
+    spacing: Coord,
+    padding: &Padding,
+    alignment: LayoutAlignment,
+    is_horizontal: bool,
+    width: Coord,
+) -> Coord {
+    let inner_width = width - (padding.left + padding.right);
+    box_layout_min_height(cells, spacing, alignment, is_horizontal, inner_width)
         + padding.top
-        + padding.bottom;
+        + padding.bottom
+}
+
+/// Split the cells of a FlowLayout in lines that fit in `width`.
+/// Returns the ranges of the cells of each line, and the height of each line.
+fn flow_layout_lines(
+    cells: &Slice<BoxLayoutCellData>,
+    spacing: Coord,
+    width: Coord,
+) -> Vec<(core::ops::Range<usize>, Coord)> {
+    let mut lines = vec![];
+    let mut line_start = 0;
+    let (mut line_width, mut line_height) = (0., 0.);
+    for (i, cell) in cells.iter().enumerate() {
+        let item_width = cell.constraint.min_width;
+        if i > line_start && line_width + spacing + item_width > width {
+            lines.push((line_start..i, line_height));
+            line_start = i;
+            line_width = 0.;
+            line_height = 0.;
+        }
+        line_width += if i > line_start { spacing + item_width } else { item_width };
+        line_height = cell.min_height_for_width(item_width).max(line_height);
+    }
+    if line_start < cells.len() {
+        lines.push((line_start..cells.len(), line_height));
+    }
+    lines
+}
 
-    LayoutInfo { min_width, max_width, min_height, max_height }
+/// Solve a FlowLayout: the items are placed from left to right, and wrap to the next line when
+/// there is not enough space.
+#[no_mangle]
+pub extern "C" fn solve_flow_layout(data: &BoxLayoutData) {
+    let width = data.width - (data.padding.left + data.padding.right);
+    let mut y = data.y + data.padding.top;
+    for (range, line_height) in flow_layout_lines(&data.cells, data.spacing, width) {
+        let cells = &data.cells[range];
+        let mut layout_data: Vec<_> = cells
+            .iter()
+            .map(|cell| internal::LayoutData {
+                min: cell.constraint.min_width,
+                max: cell.constraint.max_width,
+                pref: cell.constraint.min_width,
+                stretch: cell.constraint.horizontal_stretch,
+                ..Default::default()
+            })
+            .collect();
+        align_items(
+            &mut layout_data,
+            data.x + data.padding.left,
+            width,
+            data.spacing,
+            data.alignment,
+        );
+        for (cell, ld) in cells.iter().zip(layout_data.iter()) {
+            cell.x.map(|p| p.set(ld.pos));
+            cell.y.map(|p| p.set(y));
+            cell.width.map(|p| p.set(ld.size));
+            cell.height.map(|p| {
+                p.set(line_height.min(cell.constraint.max_height).max(cell.constraint.min_height))
+            });
+        }
+        y += line_height + data.spacing;
+    }
+}
+
+/// Returns the constraints of a FlowLayout. The minimum height is the height that the layout
+/// needs when it is `width` wide.
+#[no_mangle]
+pub extern "C" fn flow_layout_info<'a>(
+    cells: &Slice<'a, BoxLayoutCellData<'a>>,
+    spacing: Coord,
+    padding: &Padding,
+    width: Coord,
+) -> LayoutInfo {
+    let padding_h = padding.left + padding.right;
+    let padding_v = padding.top + padding.bottom;
+    let lines = flow_layout_lines(cells, spacing, width - padding_h);
+    let lines_height = lines.iter().map(|(_, height)| height).sum::<Coord>()
+        + spacing * (lines.len().max(1) - 1) as Coord;
+    LayoutInfo {
+        min_width: cells.iter().map(|c| c.constraint.min_width).fold(0., Coord::max) + padding_h,
+        min_height: lines_height + padding_v,
+        ..LayoutInfo::default()
+    }
+}
+
+#[test]
+fn test_grid_layout_height_for_width() {
+    let props: Vec<_> = (0..2).map(|_| Box::pin(Property::<Coord>::default())).collect();
+    let height_for_width = |width: Coord| 1000. / width;
+    let cells = [
+        GridLayoutCellData {
+            vertical_alignment: CellAlignment::start,
+            width: Some(&*props[0]),
+            height: Some(&*props[1]),
+            constraint: LayoutInfo { min_width: 20., ..Default::default() },
+            height_for_width: HeightForWidth::new(&height_for_width),
+            ..Default::default()
+        },
+        GridLayoutCellData { row: 1, ..Default::default() },
+    ];
+    let padding = Padding::default();
+    solve_grid_layout(&GridLayoutData {
+        width: 100.,
+        height: 100.,
+        x: 0.,
+        y: 0.,
+        spacing: 0.,
+        padding: &padding,
+        cells: Slice::from_slice(&cells),
+    });
+    assert_eq!(props[0].as_ref().get(), 100.);
+    assert_eq!(props[1].as_ref().get(), 10.);
+
+    let cells = Slice::from_slice(&cells);
+    assert_eq!(grid_layout_height_for_width(&cells, 0., &padding, 50.), 20.);
+    // At its minimum width, the cell needs to be 1000 / 20 high
+    assert_eq!(grid_layout_info(&cells, 0., &padding).min_height, 50.);
+}
+
+#[test]
+fn test_box_layout_height_for_width() {
+    let props: Vec<_> = (0..2).map(|_| Box::pin(Property::<Coord>::default())).collect();
+    let height_for_width = |width: Coord| 1000. / width;
+    let cells = [
+        BoxLayoutCellData {
+            constraint: LayoutInfo { min_width: 20., ..Default::default() },
+            width: Some(&*props[0]),
+            height: Some(&*props[1]),
+            height_for_width: HeightForWidth::new(&height_for_width),
+            ..Default::default()
+        },
+        BoxLayoutCellData {
+            constraint: LayoutInfo { min_height: 5., max_height: 5., ..Default::default() },
+            ..Default::default()
+        },
+    ];
+    let padding = Padding { left: 0., right: 0., top: 1., bottom: 1. };
+    solve_box_layout(
+        &BoxLayoutData {
+            width: 100.,
+            height: 100.,
+            x: 0.,
+            y: 0.,
+            spacing: 0.,
+            padding: &padding,
+            alignment: LayoutAlignment::start,
+            cells: Slice::from_slice(&cells),
+        },
+        false,
+    );
+    assert_eq!(props[0].as_ref().get(), 100.);
+    assert_eq!(props[1].as_ref().get(), 10.);
+
+    let cells = Slice::from_slice(&cells);
+    let alignment = LayoutAlignment::stretch;
+    assert_eq!(box_layout_height_for_width(&cells, 0., &padding, alignment, false, 50.), 27.);
+    assert_eq!(box_layout_info(&cells, 0., &padding, alignment, false).min_height, 57.);
+    // Horizontally, the cells get their minimum width and stretch in the rest
+    assert_eq!(box_layout_height_for_width(&cells, 0., &padding, alignment, true, 60.), 27.);
+}
+
+#[test]
+fn test_solve_box_layout() {
+    let props: Vec<_> = (0..6).map(|_| Box::pin(Property::<Coord>::default())).collect();
+    let cell = |min_width: Coord, max_width: Coord, x: usize, width: usize| BoxLayoutCellData {
+        constraint: LayoutInfo { min_width, max_width, ..Default::default() },
+        x: Some(&*props[x]),
+        width: Some(&*props[width]),
+        ..Default::default()
+    };
+    let cells = [cell(10., 20., 0, 1), cell(10., 1000., 2, 3), cell(30., 30., 4, 5)];
+    let padding = Padding { left: 5., right: 5., top: 0., bottom: 0. };
+    let mut data = BoxLayoutData {
+        width: 100.,
+        height: 50.,
+        x: 0.,
+        y: 0.,
+        spacing: 5.,
+        padding: &padding,
+        alignment: LayoutAlignment::stretch,
+        cells: Slice::from_slice(&cells),
+    };
+
+    solve_box_layout(&data, true);
+    let values: Vec<Coord> = props.iter().map(|p| p.as_ref().get()).collect();
+    assert_eq!(values, [5., 20., 30., 30., 65., 30.]);
+
+    data.alignment = LayoutAlignment::center;
+    solve_box_layout(&data, true);
+    let values: Vec<Coord> = props.iter().map(|p| p.as_ref().get()).collect();
+    assert_eq!(values, [20., 10., 35., 10., 50., 30.]);
+
+    data.alignment = LayoutAlignment::space_between;
+    solve_box_layout(&data, true);
+    let values: Vec<Coord> = props.iter().map(|p| p.as_ref().get()).collect();
+    assert_eq!(values, [5., 10., 35., 10., 65., 30.]);
+
+    let info = box_layout_info(&data.cells, 5., &padding, LayoutAlignment::stretch, true);
+    assert_eq!(info.min_width, 70.);
+    assert_eq!(info.max_width, 1070.);
+}
+
+#[test]
+fn test_layout_info_merge() {
+    let layout_info = LayoutInfo { min_width: 20., max_width: 100., ..Default::default() };
+    let element_info = LayoutInfo { max_width: 50., horizontal_stretch: 0., ..Default::default() };
+    let merged = layout_info.merge(&element_info);
+    assert_eq!((merged.min_width, merged.max_width), (20., 50.));
+    assert_eq!((merged.horizontal_stretch, merged.vertical_stretch), (0., 1.));
 }
 
 #[repr(C)]
@@ -385,3 +1135,34 @@ pub extern "C" fn solve_path_layout(data: &PathLayoutData) {
         }
     }
 }
+
+#[test]
+fn test_solve_flow_layout() {
+    let props: Vec<_> = (0..6).map(|_| Box::pin(Property::<Coord>::default())).collect();
+    let cell = |min_height: Coord, x: usize, y: usize| BoxLayoutCellData {
+        constraint: LayoutInfo { min_width: 40., max_width: 40., min_height, ..Default::default() },
+        x: Some(&*props[x]),
+        y: Some(&*props[y]),
+        ..Default::default()
+    };
+    let cells = [cell(10., 0, 1), cell(20., 2, 3), cell(10., 4, 5)];
+    let padding = Padding::default();
+    let data = BoxLayoutData {
+        width: 100.,
+        height: 100.,
+        x: 0.,
+        y: 0.,
+        spacing: 10.,
+        padding: &padding,
+        alignment: LayoutAlignment::start,
+        cells: Slice::from_slice(&cells),
+    };
+
+    solve_flow_layout(&data);
+    let values: Vec<Coord> = props.iter().map(|p| p.as_ref().get()).collect();
+    assert_eq!(values, [0., 0., 50., 0., 0., 30.]);
+
+    let info = flow_layout_info(&data.cells, 10., &padding, 100.);
+    assert_eq!(info.min_width, 40.);
+    assert_eq!(info.min_height, 40.);
+}
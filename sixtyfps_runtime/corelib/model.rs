@@ -9,29 +9,252 @@
 LICENSE END */
 //! Models
 
-/*
-use super::datastructures::ComponentVTable;
+use core::cell::RefCell;
+use std::rc::{Rc, Weak};
 
-/// Virtual table for a model.
+/// Trait implemented by the views (for example the repeaters) that display the content of a model.
 ///
-/// TODO: how to represent the data
+/// The model calls these functions to notify its views about changes of its content.
+pub trait ViewAbstraction {
+    /// The data of the row `row` has changed
+    fn row_changed(&self, row: usize);
+    /// `count` rows were inserted at `index`
+    fn row_added(&self, index: usize, count: usize);
+    /// `count` rows were removed at `index`
+    fn row_removed(&self, index: usize, count: usize);
+}
+
+/// A weak reference to a view, as passed to [`Model::attach_peer`]
+pub type ModelPeer = Weak<dyn ViewAbstraction>;
+
+/// Dispatch the notifications of a model to all the attached views.
 ///
-/// TODO: how to get notification when it changes
-#[repr(C)]
-#[vtable]
-pub struct ModelVTable {
-    /// Number of items
-    count: unsafe fn(VRef<ModelVTable>) -> u32,
-
-    /// Returns the data. (FIXME: find out what this returns exactly)
-    data: unsafe fn(VRef<ModelVTable>, n: u32) -> *const (),
-}*/
-/*
-/// This structure will hold a vector of the component instaces
-#[repr(C)]
-pub struct ComponentVecHolder {
-    mode: vtable::VBox<ModelType>
-    // Possible optimization: all the VBox should have the same VTable kown to the parent component
-    _todo: Vec<vtable::VBox<super::datastructures::ComponentVTable>>,
-}
-*/
+/// Models usually contain a `ModelNotify` and forward [`Model::attach_peer`] to [`ModelNotify::attach`]
+#[derive(Default)]
+pub struct ModelNotify {
+    peers: RefCell<Vec<ModelPeer>>,
+}
+
+impl ModelNotify {
+    /// Notify the peers that a specific row was changed
+    pub fn row_changed(&self, row: usize) {
+        for peer in self.live_peers() {
+            peer.row_changed(row)
+        }
+    }
+    /// Notify the peers that `count` rows were inserted at `index`
+    pub fn row_added(&self, index: usize, count: usize) {
+        for peer in self.live_peers() {
+            peer.row_added(index, count)
+        }
+    }
+    /// Notify the peers that `count` rows were removed at `index`
+    pub fn row_removed(&self, index: usize, count: usize) {
+        for peer in self.live_peers() {
+            peer.row_removed(index, count)
+        }
+    }
+
+    /// Register a view that should be notified of the changes
+    pub fn attach(&self, peer: ModelPeer) {
+        let mut peers = self.peers.borrow_mut();
+        peers.retain(|p| p.strong_count() > 0);
+        peers.push(peer);
+    }
+
+    /// Upgrade the peers that are still alive. The vector is copied so that the peers
+    /// can attach themselves again while being notified.
+    fn live_peers(&self) -> Vec<Rc<dyn ViewAbstraction>> {
+        self.peers.borrow().iter().filter_map(|p| p.upgrade()).collect()
+    }
+}
+
+/// A Model is providing Data for the Repeater or ListView elements of the `.60` language
+pub trait Model {
+    /// The model data: A model is a set of row and each row has this data
+    type Data;
+    /// The amount of row in the model
+    fn row_count(&self) -> usize;
+    /// Returns the data for a particular row. This function should be called with `row < row_count()`.
+    fn row_data(&self, row: usize) -> Self::Data;
+    /// Sets the data for a particular row.
+    ///
+    /// This function should be called with `row < row_count()`.
+    /// The default implementation ignores the call, which is what read-only models want.
+    fn set_row_data(&self, _row: usize, _data: Self::Data) {}
+    /// Should forward to the internal [`ModelNotify::attach`]
+    /// (which is usually the only thing to do)
+    fn attach_peer(&self, peer: ModelPeer);
+}
+
+/// A model backed by a `Vec<T>`
+pub struct VecModel<T> {
+    array: RefCell<Vec<T>>,
+    notify: ModelNotify,
+}
+
+impl<T> Default for VecModel<T> {
+    fn default() -> Self {
+        Self { array: Default::default(), notify: Default::default() }
+    }
+}
+
+impl<T> From<Vec<T>> for VecModel<T> {
+    fn from(array: Vec<T>) -> Self {
+        Self { array: RefCell::new(array), notify: Default::default() }
+    }
+}
+
+impl<T> VecModel<T> {
+    /// Add a row at the end of the model
+    pub fn push(&self, value: T) {
+        self.array.borrow_mut().push(value);
+        self.notify.row_added(self.array.borrow().len() - 1, 1)
+    }
+
+    /// Inserts a row at position index. All rows after that are shifted.
+    /// This function panics if index is > row_count().
+    pub fn insert(&self, index: usize, value: T) {
+        self.array.borrow_mut().insert(index, value);
+        self.notify.row_added(index, 1)
+    }
+
+    /// Remove the row at the given index from the model and return it.
+    /// This function panics if index is >= row_count().
+    pub fn remove(&self, index: usize) -> T {
+        let r = self.array.borrow_mut().remove(index);
+        self.notify.row_removed(index, 1);
+        r
+    }
+}
+
+impl<T: Clone> Model for VecModel<T> {
+    type Data = T;
+
+    fn row_count(&self) -> usize {
+        self.array.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Self::Data {
+        self.array.borrow()[row].clone()
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        self.array.borrow_mut()[row] = data;
+        self.notify.row_changed(row);
+    }
+
+    fn attach_peer(&self, peer: ModelPeer) {
+        self.notify.attach(peer);
+    }
+}
+
+/// Properties of type array in the .60 language are represented as
+/// an [Option] of an [Rc] of something implementing the [Model] trait
+pub struct ModelHandle<T>(Option<Rc<dyn Model<Data = T>>>);
+
+impl<T> ModelHandle<T> {
+    /// Create a new handle wrapping the given model
+    pub fn new(model: Rc<dyn Model<Data = T>>) -> Self {
+        Self(Some(model))
+    }
+}
+
+impl<T> Default for ModelHandle<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Clone for ModelHandle<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> PartialEq for ModelHandle<T> {
+    /// Two handles are equal if they point to the same model
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Rc::as_ptr(a) as *const u8 == Rc::as_ptr(b) as *const u8,
+            _ => false,
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for ModelHandle<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ModelHandle({} rows)", self.row_count())
+    }
+}
+
+impl<T> From<Rc<dyn Model<Data = T>>> for ModelHandle<T> {
+    fn from(model: Rc<dyn Model<Data = T>>) -> Self {
+        Self(Some(model))
+    }
+}
+
+impl<T> Model for ModelHandle<T> {
+    type Data = T;
+
+    fn row_count(&self) -> usize {
+        self.0.as_ref().map_or(0, |model| model.row_count())
+    }
+
+    fn row_data(&self, row: usize) -> Self::Data {
+        self.0.as_ref().unwrap().row_data(row)
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        if let Some(model) = self.0.as_ref() {
+            model.set_row_data(row, data)
+        }
+    }
+
+    fn attach_peer(&self, peer: ModelPeer) {
+        if let Some(model) = self.0.as_ref() {
+            model.attach_peer(peer);
+        }
+    }
+}
+
+#[test]
+fn test_vec_model_notify() {
+    #[derive(Default)]
+    struct TestView {
+        changes: RefCell<Vec<(&'static str, usize, usize)>>,
+    }
+    impl ViewAbstraction for TestView {
+        fn row_changed(&self, row: usize) {
+            self.changes.borrow_mut().push(("changed", row, 1));
+        }
+        fn row_added(&self, index: usize, count: usize) {
+            self.changes.borrow_mut().push(("added", index, count));
+        }
+        fn row_removed(&self, index: usize, count: usize) {
+            self.changes.borrow_mut().push(("removed", index, count));
+        }
+    }
+
+    let model = VecModel::from(vec![1, 2, 3]);
+    let view = Rc::new(TestView::default());
+    let peer: Rc<dyn ViewAbstraction> = view.clone();
+    model.attach_peer(Rc::downgrade(&peer));
+
+    model.push(4);
+    model.insert(0, 0);
+    model.set_row_data(2, 20);
+    assert_eq!(model.remove(1), 1);
+    assert_eq!(model.row_count(), 4);
+    assert_eq!((0..4).map(|i| model.row_data(i)).collect::<Vec<_>>(), vec![0, 20, 3, 4]);
+    assert_eq!(
+        *view.changes.borrow(),
+        vec![("added", 3, 1), ("added", 0, 1), ("changed", 2, 1), ("removed", 1, 1)]
+    );
+
+    // Once the view is gone, it is no longer notified
+    drop(peer);
+    drop(view);
+    model.push(5);
+}
@@ -77,6 +77,7 @@ mod single_linked_list_pin {
 
 use core::cell::{Cell, RefCell, UnsafeCell};
 use core::{marker::PhantomPinned, pin::Pin};
+use std::rc::Rc;
 
 use crate::graphics::Color;
 use crate::items::PropertyAnimation;
@@ -95,6 +96,9 @@ struct BindingVTable {
     drop: unsafe fn(_self: *mut BindingHolder),
     evaluate: unsafe fn(_self: *mut BindingHolder, value: *mut ()) -> BindingResult,
     mark_dirty: unsafe fn(_self: *const BindingHolder),
+    intercept_set: unsafe fn(_self: *const BindingHolder, value: *const ()) -> bool,
+    intercept_set_binding:
+        unsafe fn(_self: *const BindingHolder, new_binding: *mut BindingHolder) -> bool,
 }
 
 /// A binding trait object can be used to dynamically produces values for a property.
@@ -106,6 +110,26 @@ trait BindingCallable {
     /// This function is used to notify the binding that one of the dependencies was changed
     /// and therefore this binding may evaluate to a different value, too.
     fn mark_dirty(self: Pin<&Self>) {}
+
+    /// Allow the binding to intercept what happens when the value is set.
+    /// The default implementation returns false, meaning the binding will simply be removed and
+    /// the property will get the new value.
+    /// When returning true, the call was intercepted and the binding will not be removed,
+    /// but the property will still have that value
+    unsafe fn intercept_set(self: Pin<&Self>, _value: *const ()) -> bool {
+        false
+    }
+
+    /// Allow the binding to intercept what happens when a new binding is set.
+    /// The default implementation returns false, meaning the binding will simply be removed.
+    /// When returning true, the call was intercepted and the binding will not be removed,
+    /// and the new binding is owned by this binding.
+    unsafe fn intercept_set_binding(self: Pin<&Self>, _new_binding: *mut BindingHolder) -> bool {
+        false
+    }
+
+    /// Whether this binding is a TwoWayBinding
+    const IS_TWO_WAY_BINDING: bool = false;
 }
 
 impl<F: Fn(*mut ()) -> BindingResult> BindingCallable for F {
@@ -126,6 +150,8 @@ struct BindingHolder<B = ()> {
     vtable: &'static BindingVTable,
     /// The binding is dirty and need to be re_evaluated
     dirty: Cell<bool>,
+    /// Specify that B is a `TwoWayBinding<T>`
+    is_two_way_binding: bool,
     pinned: PhantomPinned,
     binding: B,
 }
@@ -153,6 +179,23 @@ fn alloc_binding_holder<B: BindingCallable + 'static>(binding: B) -> *mut Bindin
         Pin::new_unchecked(&((*(_self as *const BindingHolder<B>)).binding)).mark_dirty()
     }
 
+    /// Safety: _self must be a pointer to a `BindingHolder<B>`
+    unsafe fn intercept_set<B: BindingCallable>(
+        _self: *const BindingHolder,
+        value: *const (),
+    ) -> bool {
+        Pin::new_unchecked(&((*(_self as *const BindingHolder<B>)).binding)).intercept_set(value)
+    }
+
+    /// Safety: _self must be a pointer to a `BindingHolder<B>`
+    unsafe fn intercept_set_binding<B: BindingCallable>(
+        _self: *const BindingHolder,
+        new_binding: *mut BindingHolder,
+    ) -> bool {
+        Pin::new_unchecked(&((*(_self as *const BindingHolder<B>)).binding))
+            .intercept_set_binding(new_binding)
+    }
+
     trait HasBindingVTable {
         const VT: &'static BindingVTable;
     }
@@ -161,6 +204,8 @@ fn alloc_binding_holder<B: BindingCallable + 'static>(binding: B) -> *mut Bindin
             drop: binding_drop::<B>,
             evaluate: evaluate::<B>,
             mark_dirty: mark_dirty::<B>,
+            intercept_set: intercept_set::<B>,
+            intercept_set_binding: intercept_set_binding::<B>,
         };
     }
 
@@ -169,6 +214,7 @@ fn alloc_binding_holder<B: BindingCallable + 'static>(binding: B) -> *mut Bindin
         dep_nodes: Default::default(),
         vtable: <B as HasBindingVTable>::VT,
         dirty: Cell::new(true), // starts dirty so it evaluates the property when used
+        is_two_way_binding: B::IS_TWO_WAY_BINDING,
         pinned: PhantomPinned,
         binding,
     };
@@ -304,6 +350,15 @@ impl PropertyHandle {
     }
 
     fn remove_binding(&self) {
+        if let Some(binding) = self.take_binding() {
+            unsafe { ((*binding).vtable.drop)(binding) };
+        }
+        debug_assert!(self.handle.get() & 0b11 == 0);
+    }
+
+    /// Detach the binding from this property without destroying it, and return it.
+    /// The properties which depend on this property stay registered to this property.
+    fn take_binding(&self) -> Option<*mut BindingHolder> {
         assert!(!self.lock_flag(), "Recursion detected");
         let val = self.handle.get();
         if val & 0b10 == 0b10 {
@@ -314,15 +369,32 @@ impl PropertyHandle {
                     (&mut (*binding).dependencies) as *mut _ as *mut _,
                     self.handle.as_ptr() as *mut _,
                 );
-                ((*binding).vtable.drop)(binding);
+                (*binding).dependencies.set(0);
+                debug_assert!(self.handle.get() & 0b11 == 0);
+                Some(binding)
             }
-            debug_assert!(self.handle.get() & 0b11 == 0);
+        } else {
+            None
         }
     }
 
     fn set_binding<B: BindingCallable + 'static>(&self, binding: B) {
-        self.remove_binding();
         let binding = alloc_binding_holder::<B>(binding);
+        self.set_binding_impl(binding);
+    }
+
+    /// Implementation of Self::set_binding.
+    /// Takes the ownership of the binding.
+    fn set_binding_impl(&self, binding: *mut BindingHolder) {
+        let previous_binding_intercepted = self.access(|b| {
+            b.map_or(false, |b| unsafe {
+                (b.vtable.intercept_set_binding)(&*b as *const BindingHolder, binding)
+            })
+        });
+        if previous_binding_intercepted {
+            return;
+        }
+        self.remove_binding();
         debug_assert!((binding as usize) & 0b11 == 0);
         debug_assert!(self.handle.get() & 0b11 == 0);
         unsafe {
@@ -507,7 +579,14 @@ impl<T: Clone> Property<T> {
     /// be marked as dirty.
     // FIXME  pub fn set(self: Pin<&Self>, t: T) {
     pub fn set(&self, t: T) {
-        self.handle.remove_binding();
+        let previous_binding_intercepted = self.handle.access(|b| {
+            b.map_or(false, |b| unsafe {
+                (b.vtable.intercept_set)(&*b as *const BindingHolder, &t as *const T as *const ())
+            })
+        });
+        if !previous_binding_intercepted {
+            self.handle.remove_binding();
+        }
         // Safety: PropertyHandle::access ensure that the value is locked
         self.handle.access(|_| unsafe { *self.value.get() = t });
         self.handle.mark_dirty();
@@ -549,6 +628,101 @@ impl<T: Clone> Property<T> {
     }
 }
 
+impl<T: Clone + 'static> Property<T> {
+    /// Link two properties such that any change to one property is affecting the other
+    /// property as if they were, in fact, a single property.
+    ///
+    /// The value or the binding of prop2 is kept.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use sixtyfps_corelib::Property;
+    /// let prop1 = Rc::pin(Property::new(100));
+    /// let prop2 = Rc::pin(Property::new(200));
+    /// Property::link_two_way(prop1.as_ref(), prop2.as_ref());
+    /// assert_eq!(prop1.as_ref().get(), 200);
+    /// prop1.set(300);
+    /// assert_eq!(prop2.as_ref().get(), 300);
+    /// ```
+    pub fn link_two_way(prop1: Pin<&Self>, prop2: Pin<&Self>) {
+        match (prop1.two_way_common_property(), prop2.two_way_common_property()) {
+            (Some(common1), Some(common2)) if Rc::ptr_eq(&common1, &common2) => {}
+            (_, Some(common2)) => {
+                // If prop1 was already linked, the binding is intercepted and its common
+                // property follows the common property of prop2
+                prop1.handle.set_binding(TwoWayBinding { common_property: common2 });
+                prop1.handle.mark_dirty();
+            }
+            (common1, None) => {
+                let is_new = common1.is_none();
+                let common_property =
+                    common1.unwrap_or_else(|| Rc::pin(Property::new(prop2.get_internal())));
+                if let Some(binding) = prop2.handle.take_binding() {
+                    // Move the binding of prop2 to the common property.
+                    // Safety: the binding was just taken from a valid property
+                    unsafe { (*binding).dirty.set(true) };
+                    common_property.handle.set_binding_impl(binding);
+                    common_property.handle.mark_dirty();
+                } else if !is_new {
+                    common_property.set(prop2.get_internal());
+                }
+                if is_new {
+                    prop1
+                        .handle
+                        .set_binding(TwoWayBinding { common_property: common_property.clone() });
+                    prop1.handle.mark_dirty();
+                }
+                prop2.handle.set_binding(TwoWayBinding { common_property });
+                prop2.handle.mark_dirty();
+            }
+        }
+    }
+
+    /// If this property is linked with `link_two_way`, returns the property that is shared
+    /// between all the linked properties.
+    fn two_way_common_property(self: Pin<&Self>) -> Option<Pin<Rc<Property<T>>>> {
+        self.handle.access(|b| {
+            let b = b?;
+            if !b.is_two_way_binding {
+                return None;
+            }
+            // Safety: a two way binding on a Property<T> is always a TwoWayBinding<T>
+            let holder = unsafe {
+                &*(b.get_unchecked_mut() as *mut BindingHolder
+                    as *const BindingHolder<TwoWayBinding<T>>)
+            };
+            Some(holder.binding.common_property.clone())
+        })
+    }
+}
+
+/// The binding set by `Property::link_two_way` on each of the linked properties.
+/// All the linked properties forward their value and their bindings to the common property.
+struct TwoWayBinding<T> {
+    common_property: Pin<Rc<Property<T>>>,
+}
+
+impl<T: Clone + 'static> BindingCallable for TwoWayBinding<T> {
+    unsafe fn evaluate(self: Pin<&Self>, value: *mut ()) -> BindingResult {
+        *(value as *mut T) = self.common_property.as_ref().get();
+        BindingResult::KeepBinding
+    }
+
+    unsafe fn intercept_set(self: Pin<&Self>, value: *const ()) -> bool {
+        self.common_property.set((*(value as *const T)).clone());
+        true
+    }
+
+    unsafe fn intercept_set_binding(self: Pin<&Self>, new_binding: *mut BindingHolder) -> bool {
+        self.common_property.handle.set_binding_impl(new_binding);
+        self.common_property.handle.mark_dirty();
+        true
+    }
+
+    const IS_TWO_WAY_BINDING: bool = true;
+}
+
 impl<T: Clone + InterpolatedPropertyValue + 'static> Property<T> {
     /// Change the value of this property, by animating (interpolating) from the current property's value
     /// to the specified parameter value. The animation is done according to the parameters described by
@@ -582,6 +756,33 @@ impl<T: Clone + InterpolatedPropertyValue + 'static> Property<T> {
         &self,
         binding: impl Binding<T> + 'static,
         animation_data: &PropertyAnimation,
+    ) {
+        self.set_animated_binding_impl(binding, animation_data.clone(), None);
+    }
+
+    /// Set a binding to this property, animated with the animation returned by
+    /// `compute_animation_details`.
+    ///
+    /// `compute_animation_details` is called each time an animation starts, so the animation can
+    /// depend on other properties, or on the state that is being entered or left. (This is used
+    /// to implement transitions and animations whose parameters are not constant)
+    pub fn set_dynamic_animated_binding(
+        &self,
+        binding: impl Binding<T> + 'static,
+        compute_animation_details: impl Fn() -> PropertyAnimation + 'static,
+    ) {
+        self.set_animated_binding_impl(
+            binding,
+            PropertyAnimation::default(),
+            Some(Box::new(compute_animation_details)),
+        );
+    }
+
+    fn set_animated_binding_impl(
+        &self,
+        binding: impl Binding<T> + 'static,
+        animation_data: PropertyAnimation,
+        compute_animation_details: Option<Box<dyn Fn() -> PropertyAnimation>>,
     ) {
         self.handle.set_binding(AnimatedBindingCallable::<T> {
             original_binding: PropertyHandle {
@@ -598,8 +799,9 @@ impl<T: Clone + InterpolatedPropertyValue + 'static> Property<T> {
             animation_data: RefCell::new(PropertyValueAnimationData::new(
                 T::default(),
                 T::default(),
-                animation_data.clone(),
+                animation_data,
             )),
+            compute_animation_details,
         });
         self.handle.mark_dirty();
     }
@@ -622,10 +824,23 @@ impl<T: InterpolatedPropertyValue> PropertyValueAnimationData<T> {
     }
 
     fn compute_interpolated_value(&mut self) -> (T, bool) {
-        let duration = self.details.duration as u128;
+        // The duration and delay are evaluated from expressions, they may be negative
+        let duration = self.details.duration.max(0) as u128;
         let new_tick =
             crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.current_tick());
         let mut time_progress = new_tick.duration_since(self.start_time).as_millis();
+        if self.loop_iteration == 0 {
+            // The delay is only applied before the first iteration
+            let delay = self.details.delay.max(0) as u128;
+            if time_progress < delay {
+                return (self.from_value.clone(), false);
+            }
+            time_progress -= delay;
+        }
+        if duration == 0 {
+            // There is nothing to loop over in an animation without duration
+            return (self.to_value.clone(), true);
+        }
         if time_progress >= duration {
             if self.loop_iteration < self.details.loop_count || self.details.loop_count < 0 {
                 self.loop_iteration += (time_progress / duration) as i32;
@@ -635,7 +850,7 @@ impl<T: InterpolatedPropertyValue> PropertyValueAnimationData<T> {
                 return (self.to_value.clone(), true);
             }
         }
-        let progress = time_progress as f32 / self.details.duration as f32;
+        let progress = time_progress as f32 / duration as f32;
         assert!(progress <= 1.);
         let t = crate::animations::easing_curve(&self.details.easing, progress);
         let val = self.from_value.interpolate(self.to_value, t);
@@ -654,6 +869,8 @@ struct AnimatedBindingCallable<T> {
     original_binding: PropertyHandle,
     state: Cell<AnimatedBindingState>,
     animation_data: RefCell<PropertyValueAnimationData<T>>,
+    /// When set, the details of the animation are re-computed when the animation starts
+    compute_animation_details: Option<Box<dyn Fn() -> PropertyAnimation>>,
 }
 
 impl<T: InterpolatedPropertyValue> BindingCallable for AnimatedBindingCallable<T> {
@@ -677,6 +894,9 @@ impl<T: InterpolatedPropertyValue> BindingCallable for AnimatedBindingCallable<T
                 let value = &mut *(value as *mut T);
                 self.state.set(AnimatedBindingState::Animating);
                 let mut animation_data = self.animation_data.borrow_mut();
+                if let Some(compute_animation_details) = &self.compute_animation_details {
+                    animation_data.details = compute_animation_details();
+                }
                 animation_data.from_value = value.clone();
                 self.original_binding.update((&mut animation_data.to_value) as *mut T as *mut ());
                 let (val, finished) = animation_data.compute_interpolated_value();
@@ -704,6 +924,34 @@ impl<T: InterpolatedPropertyValue> BindingCallable for AnimatedBindingCallable<T
     }
 }
 
+/// The value of the property holding the state of an element that declares `states`.
+///
+/// The previous state is kept so that the transitions declared with `out` can be selected.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StateInfo {
+    /// The index of the current state
+    pub current_state: i32,
+    /// The index of the state that was current before the last change
+    pub previous_state: i32,
+}
+
+/// Set on the `property` a binding that computes the current state with `binding`, and that
+/// remembers the previous state when it changes.
+pub fn set_state_binding(property: Pin<&Property<StateInfo>>, binding: impl Fn() -> i32 + 'static) {
+    let info = Cell::new(StateInfo::default());
+    property.set_binding(move || {
+        let new_state = binding();
+        let mut state_info = info.get();
+        if new_state != state_info.current_state {
+            state_info.previous_state = state_info.current_state;
+            state_info.current_state = new_state;
+            info.set(state_info);
+        }
+        state_info
+    });
+}
+
 #[test]
 fn properties_simple_test() {
     use pin_weak::rc::PinWeak;
@@ -741,6 +989,74 @@ fn properties_simple_test() {
     assert_eq!(g(&compo.area), 8 * 8 * 2);
 }
 
+#[test]
+fn property_two_ways_test() {
+    let p1 = Rc::pin(Property::new(42));
+    let p2 = Rc::pin(Property::new(88));
+
+    let depends = Box::pin(Property::new(0));
+    depends.as_ref().set_binding({
+        let p1 = p1.clone();
+        move || p1.as_ref().get() + 8
+    });
+    assert_eq!(depends.as_ref().get(), 42 + 8);
+    Property::link_two_way(p1.as_ref(), p2.as_ref());
+    assert_eq!(p1.as_ref().get(), 88);
+    assert_eq!(p2.as_ref().get(), 88);
+    assert_eq!(depends.as_ref().get(), 88 + 8);
+    p2.as_ref().set(5);
+    assert_eq!(p1.as_ref().get(), 5);
+    assert_eq!(p2.as_ref().get(), 5);
+    assert_eq!(depends.as_ref().get(), 5 + 8);
+    p1.as_ref().set(22);
+    assert_eq!(p1.as_ref().get(), 22);
+    assert_eq!(p2.as_ref().get(), 22);
+    assert_eq!(depends.as_ref().get(), 22 + 8);
+
+    let p3 = Rc::pin(Property::new(11));
+    p2.as_ref().set_binding({
+        let p3 = p3.clone();
+        move || p3.as_ref().get() * 2
+    });
+    assert_eq!(p1.as_ref().get(), 22);
+    assert_eq!(p2.as_ref().get(), 22);
+    assert_eq!(depends.as_ref().get(), 22 + 8);
+    p3.as_ref().set(6);
+    assert_eq!(p1.as_ref().get(), 12);
+    assert_eq!(p2.as_ref().get(), 12);
+    assert_eq!(depends.as_ref().get(), 12 + 8);
+}
+
+#[test]
+fn property_two_ways_test_binding() {
+    let p1 = Rc::pin(Property::new(42));
+    let p2 = Rc::pin(Property::new(88));
+    let global = Rc::pin(Property::new(23));
+    p2.as_ref().set_binding({
+        let global = global.clone();
+        move || global.as_ref().get() + 9
+    });
+
+    // the binding of p2 is kept
+    Property::link_two_way(p1.as_ref(), p2.as_ref());
+    assert_eq!(p1.as_ref().get(), 23 + 9);
+    assert_eq!(p2.as_ref().get(), 23 + 9);
+    global.as_ref().set(55);
+    assert_eq!(p1.as_ref().get(), 55 + 9);
+    assert_eq!(p2.as_ref().get(), 55 + 9);
+
+    // Linking a third property to an already linked property
+    let p3 = Rc::pin(Property::new(0));
+    Property::link_two_way(p3.as_ref(), p1.as_ref());
+    assert_eq!(p3.as_ref().get(), 55 + 9);
+    p3.as_ref().set(1);
+    assert_eq!(p1.as_ref().get(), 1);
+    assert_eq!(p2.as_ref().get(), 1);
+    assert_eq!(p3.as_ref().get(), 1);
+    global.as_ref().set(2);
+    assert_eq!(p2.as_ref().get(), 1);
+}
+
 /// InterpolatedPropertyValue is a trait used to enable properties to be used with
 /// animations that interpolate values. The basic requirement is the ability to apply
 /// a progress that's typically between 0 and 1 to a range.
@@ -890,6 +1206,64 @@ mod animation_tests {
         assert_eq!(get_prop_value(&compo.width_times_two), 400);
     }
 
+    #[test]
+    fn properties_test_dynamic_animation() {
+        #[derive(Default)]
+        struct StateComponent {
+            state: Property<StateInfo>,
+            width: Property<i32>,
+            feed_property: Property<i32>, // used by the state binding
+        }
+
+        let compo = Rc::new(StateComponent::default());
+
+        let w = Rc::downgrade(&compo);
+        set_state_binding(unsafe { Pin::new_unchecked(&compo.state) }, move || {
+            let compo = w.upgrade().unwrap();
+            get_prop_value(&compo.feed_property)
+        });
+
+        let w = Rc::downgrade(&compo);
+        let w2 = w.clone();
+        compo.width.set_dynamic_animated_binding(
+            move || {
+                let compo = w.upgrade().unwrap();
+                if get_prop_value(&compo.state).current_state == 1 {
+                    200
+                } else {
+                    100
+                }
+            },
+            move || {
+                let compo = w2.upgrade().unwrap();
+                let state = get_prop_value(&compo.state);
+                // Only animate when entering the state 1
+                let duration = if state.current_state == 1 { DURATION.as_millis() as _ } else { 0 };
+                PropertyAnimation { duration, ..PropertyAnimation::default() }
+            },
+        );
+
+        assert_eq!(get_prop_value(&compo.width), 100);
+
+        let start_time =
+            crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.current_tick());
+        compo.feed_property.set(1);
+        assert_eq!(get_prop_value(&compo.state), StateInfo { current_state: 1, previous_state: 0 });
+
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION / 2));
+        assert_eq!(get_prop_value(&compo.width), 150);
+
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION));
+        assert_eq!(get_prop_value(&compo.width), 200);
+
+        // Leaving the state is not animated
+        compo.feed_property.set(2);
+        assert_eq!(get_prop_value(&compo.state), StateInfo { current_state: 2, previous_state: 1 });
+        assert_eq!(get_prop_value(&compo.width), 100);
+    }
+
     #[test]
     fn test_loop() {
         let compo = Component::new_test_component();
@@ -969,6 +1343,63 @@ mod animation_tests {
         // the binding should be removed
         compo.width.handle.access(|binding| assert!(binding.is_none()));
     }
+
+    #[test]
+    fn test_loop_without_duration() {
+        let compo = Component::new_test_component();
+
+        // A negative duration is the same as no duration, and there is nothing to loop over
+        let animation_details = PropertyAnimation {
+            duration: -100,
+            delay: -50,
+            loop_count: 2,
+            ..PropertyAnimation::default()
+        };
+
+        compo.width.set(100);
+        compo.width.set_animated_value(200, &animation_details);
+        assert_eq!(get_prop_value(&compo.width), 200);
+
+        // the binding should be removed
+        compo.width.handle.access(|binding| assert!(binding.is_none()));
+    }
+
+    #[test]
+    fn test_delay() {
+        let compo = Component::new_test_component();
+
+        let animation_details = PropertyAnimation {
+            duration: DURATION.as_millis() as _,
+            delay: DURATION.as_millis() as _,
+            loop_count: 1,
+            ..PropertyAnimation::default()
+        };
+
+        compo.width.set(100);
+
+        let start_time =
+            crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.current_tick());
+
+        compo.width.set_animated_value(200, &animation_details);
+        assert_eq!(get_prop_value(&compo.width), 100);
+
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION / 2));
+        assert_eq!(get_prop_value(&compo.width), 100);
+
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION + DURATION / 2));
+        assert_eq!(get_prop_value(&compo.width), 150);
+
+        // The delay is not applied again for the next loop iteration
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION * 2 + DURATION / 2));
+        assert_eq!(get_prop_value(&compo.width), 150);
+
+        crate::animations::CURRENT_ANIMATION_DRIVER
+            .with(|driver| driver.update_animations(start_time + DURATION * 3));
+        assert_eq!(get_prop_value(&compo.width), 200);
+    }
 }
 
 /// This structure allow to run a closure that queries properties, and can report
@@ -983,6 +1414,8 @@ impl Default for PropertyTracker {
             drop: |_| (),
             evaluate: |_, _| BindingResult::KeepBinding,
             mark_dirty: |_| (),
+            intercept_set: |_, _| false,
+            intercept_set_binding: |_, _| false,
         };
 
         let holder = BindingHolder {
@@ -990,6 +1423,7 @@ impl Default for PropertyTracker {
             dep_nodes: Default::default(),
             vtable: VT,
             dirty: Cell::new(true), // starts dirty so it evaluates the property when used
+            is_two_way_binding: false,
             pinned: PhantomPinned,
             binding: (),
         };
@@ -1037,6 +1471,102 @@ fn test_property_listener_scope() {
     assert!(!scope.is_dirty());
 }
 
+/// Calls a handler when the value returned by a closure changes.
+///
+/// The closure is evaluated in a [`PropertyTracker`] which is checked when the event loop
+/// processes the changes. So the handler is never called from within the evaluation of a
+/// binding, and it is not called for the first value.
+#[derive(Default)]
+pub struct ChangeTracker {
+    inner: Cell<Option<Rc<dyn crate::eventloop::ChangeWatcher>>>,
+}
+
+struct ChangeTrackerInner<T, EvalFn, NotifyFn> {
+    tracker: Pin<Box<PropertyTracker>>,
+    /// None until the first evaluation
+    value: RefCell<Option<T>>,
+    eval_fn: EvalFn,
+    notify_fn: NotifyFn,
+}
+
+impl<T: PartialEq + Clone, EvalFn: Fn() -> T, NotifyFn: Fn(&T)> crate::eventloop::ChangeWatcher
+    for ChangeTrackerInner<T, EvalFn, NotifyFn>
+{
+    fn process_changes(&self) -> bool {
+        if !self.tracker.is_dirty() {
+            return false;
+        }
+        let new_value = self.tracker.as_ref().evaluate(|| (self.eval_fn)());
+        match self.value.replace(Some(new_value.clone())) {
+            Some(old_value) if old_value != new_value => {
+                (self.notify_fn)(&new_value);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ChangeTracker {
+    /// Starts tracking the value returned by `eval_fn`. `notify_fn` is called with the new value
+    /// when it changes. Calling this function again replaces the previous closures.
+    ///
+    /// The first value is evaluated the next time the changes are processed, so it is fine
+    /// if `eval_fn` depends on things that are only set after this call.
+    pub fn init<T: PartialEq + Clone + 'static>(
+        &self,
+        eval_fn: impl Fn() -> T + 'static,
+        notify_fn: impl Fn(&T) + 'static,
+    ) {
+        let inner: Rc<dyn crate::eventloop::ChangeWatcher> = Rc::new(ChangeTrackerInner {
+            tracker: Box::pin(PropertyTracker::default()),
+            value: RefCell::new(None),
+            eval_fn,
+            notify_fn,
+        });
+        crate::eventloop::register_change_watcher(Rc::downgrade(&inner));
+        // Drop the previous one outside of the Cell
+        drop(self.inner.replace(Some(inner)));
+    }
+}
+
+#[test]
+fn test_change_tracker() {
+    let prop1 = Rc::pin(Property::new(42));
+    let prop2 = Rc::pin(Property::new(0));
+    let notified = Rc::new(RefCell::new(Vec::new()));
+    let tracker = ChangeTracker::default();
+    tracker.init(
+        {
+            let prop1 = prop1.clone();
+            move || prop1.as_ref().get() * 2
+        },
+        {
+            let (notified, prop2) = (notified.clone(), prop2.clone());
+            move |v| {
+                notified.borrow_mut().push(*v);
+                // Changing a property in the handler is fine
+                prop2.as_ref().set(*v);
+            }
+        },
+    );
+    assert!(!crate::eventloop::process_change_watchers());
+    prop1.as_ref().set(43);
+    prop1.as_ref().set(44);
+    // Nothing happens until the changes are processed
+    assert!(notified.borrow().is_empty());
+    assert!(crate::eventloop::process_change_watchers());
+    assert_eq!(*notified.borrow(), vec![88]);
+    assert_eq!(prop2.as_ref().get(), 88);
+    // The value is the same, so the handler is not called
+    prop1.as_ref().set(44);
+    assert!(!crate::eventloop::process_change_watchers());
+    drop(tracker);
+    prop1.as_ref().set(45);
+    assert!(!crate::eventloop::process_change_watchers());
+    assert_eq!(*notified.borrow(), vec![88]);
+}
+
 pub(crate) mod ffi {
     use super::*;
 
@@ -1064,37 +1594,75 @@ pub(crate) mod ffi {
     }
 
     /// Mark the fact that the property was changed and that its binding need to be removed, and
-    /// The dependencies marked dirty
+    /// The dependencies marked dirty.
+    /// `value` is a pointer to the new value, which is given to the binding if it intercepts the
+    /// change (see Property::link_two_way)
     #[no_mangle]
-    pub unsafe extern "C" fn sixtyfps_property_set_changed(handle: &PropertyHandleOpaque) {
-        handle.0.remove_binding();
+    pub unsafe extern "C" fn sixtyfps_property_set_changed(
+        handle: &PropertyHandleOpaque,
+        value: *const c_void,
+    ) {
+        let previous_binding_intercepted = handle.0.access(|b| {
+            b.map_or(false, |b| (b.vtable.intercept_set)(&*b as *const BindingHolder, value))
+        });
+        if !previous_binding_intercepted {
+            handle.0.remove_binding();
+        }
         handle.0.mark_dirty();
     }
 
-    fn make_c_function_binding(
-        binding: extern "C" fn(*mut c_void, *mut c_void),
+    struct CFunctionBinding<T> {
+        binding_function: extern "C" fn(*mut c_void, *mut T),
         user_data: *mut c_void,
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
-    ) -> impl Fn(*mut ()) -> BindingResult {
-        struct CFunctionBinding<T> {
-            binding_function: extern "C" fn(*mut c_void, *mut T),
-            user_data: *mut c_void,
-            drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        intercept_set: Option<extern "C" fn(*mut c_void, *const c_void) -> bool>,
+        intercept_set_binding: Option<extern "C" fn(*mut c_void, *mut c_void) -> bool>,
+    }
+
+    impl<T> Drop for CFunctionBinding<T> {
+        fn drop(&mut self) {
+            if let Some(x) = self.drop_user_data {
+                x(self.user_data)
+            }
         }
+    }
 
-        impl<T> Drop for CFunctionBinding<T> {
-            fn drop(&mut self) {
-                if let Some(x) = self.drop_user_data {
-                    x(self.user_data)
-                }
+    impl<T> BindingCallable for CFunctionBinding<T> {
+        unsafe fn evaluate(self: Pin<&Self>, value: *mut ()) -> BindingResult {
+            (self.binding_function)(self.user_data, value as *mut T);
+            BindingResult::KeepBinding
+        }
+
+        unsafe fn intercept_set(self: Pin<&Self>, value: *const ()) -> bool {
+            match self.intercept_set {
+                Some(intercept_set) => intercept_set(self.user_data, value),
+                None => false,
             }
         }
 
-        let b = CFunctionBinding { binding_function: binding, user_data, drop_user_data };
+        unsafe fn intercept_set_binding(self: Pin<&Self>, new_binding: *mut BindingHolder) -> bool {
+            match self.intercept_set_binding {
+                Some(intercept_set_binding) => {
+                    intercept_set_binding(self.user_data, new_binding as *mut c_void)
+                }
+                None => false,
+            }
+        }
+    }
 
-        move |value_ptr| {
-            (b.binding_function)(b.user_data, value_ptr);
-            BindingResult::KeepBinding
+    fn make_c_function_binding(
+        binding: extern "C" fn(*mut c_void, *mut c_void),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        intercept_set: Option<extern "C" fn(*mut c_void, *const c_void) -> bool>,
+        intercept_set_binding: Option<extern "C" fn(*mut c_void, *mut c_void) -> bool>,
+    ) -> CFunctionBinding<c_void> {
+        CFunctionBinding {
+            binding_function: binding,
+            user_data,
+            drop_user_data,
+            intercept_set,
+            intercept_set_binding,
         }
     }
 
@@ -1105,17 +1673,44 @@ pub(crate) mod ffi {
     ///  2. the box allocation within this binding
     /// It might be possible to reduce that by passing something with a
     /// vtable, so there is the need for less memory allocation.
+    ///
+    /// The `intercept_set` and `intercept_set_binding` callbacks are optional and allow the
+    /// binding to handle the setting of a new value or binding on the property.
+    /// (This is used to implement two way bindings)
     #[no_mangle]
     pub unsafe extern "C" fn sixtyfps_property_set_binding(
         handle: &PropertyHandleOpaque,
         binding: extern "C" fn(user_data: *mut c_void, pointer_to_value: *mut c_void),
         user_data: *mut c_void,
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        intercept_set: Option<
+            extern "C" fn(user_data: *mut c_void, pointer_to_value: *const c_void) -> bool,
+        >,
+        intercept_set_binding: Option<
+            extern "C" fn(user_data: *mut c_void, new_binding: *mut c_void) -> bool,
+        >,
     ) {
-        let binding = make_c_function_binding(binding, user_data, drop_user_data);
+        let binding = make_c_function_binding(
+            binding,
+            user_data,
+            drop_user_data,
+            intercept_set,
+            intercept_set_binding,
+        );
         handle.0.set_binding(binding);
     }
 
+    /// Set a binding that was given to the `intercept_set_binding` callback of
+    /// sixtyfps_property_set_binding. This takes the ownership of the binding.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_property_set_binding_internal(
+        handle: &PropertyHandleOpaque,
+        binding: *mut c_void,
+    ) {
+        handle.0.set_binding_impl(binding as *mut BindingHolder);
+        handle.0.mark_dirty();
+    }
+
     /// Destroy handle
     #[no_mangle]
     pub unsafe extern "C" fn sixtyfps_property_drop(handle: *mut PropertyHandleOpaque) {
@@ -1183,6 +1778,7 @@ pub(crate) mod ffi {
         user_data: *mut c_void,
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
         animation_data: &PropertyAnimation,
+        compute_animation_details: Option<Box<dyn Fn() -> PropertyAnimation>>,
     ) {
         let binding = core::mem::transmute::<
             extern "C" fn(*mut c_void, *mut T),
@@ -1195,6 +1791,8 @@ pub(crate) mod ffi {
                         binding,
                         user_data,
                         drop_user_data,
+                        None,
+                        None,
                     )) as usize)
                         | 0b10,
                 ),
@@ -1205,6 +1803,7 @@ pub(crate) mod ffi {
                 T::default(),
                 animation_data.clone(),
             )),
+            compute_animation_details,
         });
         handle.0.mark_dirty();
     }
@@ -1218,7 +1817,7 @@ pub(crate) mod ffi {
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
         animation_data: &PropertyAnimation,
     ) {
-        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data);
+        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data, None);
     }
 
     /// Internal function to set up a property animation between values produced by the specified binding for a float property.
@@ -1230,7 +1829,7 @@ pub(crate) mod ffi {
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
         animation_data: &PropertyAnimation,
     ) {
-        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data);
+        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data, None);
     }
 
     /// Internal function to set up a property animation between values produced by the specified binding for a color property.
@@ -1242,7 +1841,88 @@ pub(crate) mod ffi {
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
         animation_data: &PropertyAnimation,
     ) {
-        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data);
+        c_set_animated_binding(handle, binding, user_data, drop_user_data, animation_data, None);
+    }
+
+    unsafe fn c_set_dynamic_animated_binding<T: InterpolatedPropertyValue>(
+        handle: &PropertyHandleOpaque,
+        binding: extern "C" fn(*mut c_void, *mut T),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        compute_animation_details: extern "C" fn(*mut c_void, *mut PropertyAnimation),
+    ) {
+        // The user_data is owned by the original binding, which is dropped with the
+        // animated binding, so it outlives this closure.
+        let compute_animation_details = move || {
+            let mut animation_data = PropertyAnimation::default();
+            compute_animation_details(user_data, &mut animation_data);
+            animation_data
+        };
+        c_set_animated_binding(
+            handle,
+            binding,
+            user_data,
+            drop_user_data,
+            &PropertyAnimation::default(),
+            Some(Box::new(compute_animation_details)),
+        );
+    }
+
+    /// Internal function to set up a property animation for an integer property, with an animation that is
+    /// computed by `compute_animation_details` each time the animation starts.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_property_set_dynamic_animated_binding_int(
+        handle: &PropertyHandleOpaque,
+        binding: extern "C" fn(*mut c_void, *mut i32),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        compute_animation_details: extern "C" fn(*mut c_void, *mut PropertyAnimation),
+    ) {
+        c_set_dynamic_animated_binding(
+            handle,
+            binding,
+            user_data,
+            drop_user_data,
+            compute_animation_details,
+        );
+    }
+
+    /// Internal function to set up a property animation for a float property, with an animation that is
+    /// computed by `compute_animation_details` each time the animation starts.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_property_set_dynamic_animated_binding_float(
+        handle: &PropertyHandleOpaque,
+        binding: extern "C" fn(*mut c_void, *mut f32),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        compute_animation_details: extern "C" fn(*mut c_void, *mut PropertyAnimation),
+    ) {
+        c_set_dynamic_animated_binding(
+            handle,
+            binding,
+            user_data,
+            drop_user_data,
+            compute_animation_details,
+        );
+    }
+
+    /// Internal function to set up a property animation for a color property, with an animation that is
+    /// computed by `compute_animation_details` each time the animation starts.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_property_set_dynamic_animated_binding_color(
+        handle: &PropertyHandleOpaque,
+        binding: extern "C" fn(*mut c_void, *mut Color),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        compute_animation_details: extern "C" fn(*mut c_void, *mut PropertyAnimation),
+    ) {
+        c_set_dynamic_animated_binding(
+            handle,
+            binding,
+            user_data,
+            drop_user_data,
+            compute_animation_details,
+        );
     }
 
     #[repr(C)]
@@ -1288,4 +1968,65 @@ pub(crate) mod ffi {
     pub unsafe extern "C" fn sixtyfps_property_tracker_drop(handle: *mut PropertyTrackerOpaque) {
         core::ptr::read(handle as *mut PropertyTracker);
     }
+
+    #[repr(C)]
+    /// Opaque type representing the ChangeTracker
+    pub struct ChangeTrackerOpaque {
+        inner: [usize; 2],
+    }
+
+    static_assertions::assert_eq_align!(ChangeTrackerOpaque, ChangeTracker);
+    static_assertions::assert_eq_size!(ChangeTrackerOpaque, ChangeTracker);
+
+    /// Initialize the ChangeTracker.
+    /// `out` is assumed to be uninitialized
+    /// sixtyfps_change_tracker_drop need to be called after that
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_change_tracker_init(out: *mut ChangeTrackerOpaque) {
+        core::ptr::write(out as *mut ChangeTracker, ChangeTracker::default());
+    }
+
+    /// Start tracking. `eval_fn` evaluates the value and returns true if it is different from
+    /// the previous one (the value is stored by the caller in the user_data).
+    /// `notify_fn` is called when the value has changed.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_change_tracker_start(
+        handle: *const ChangeTrackerOpaque,
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        eval_fn: extern "C" fn(user_data: *mut c_void) -> bool,
+        notify_fn: extern "C" fn(user_data: *mut c_void),
+    ) {
+        struct UserData {
+            user_data: *mut c_void,
+            drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        }
+        impl Drop for UserData {
+            fn drop(&mut self) {
+                if let Some(x) = self.drop_user_data {
+                    x(self.user_data)
+                }
+            }
+        }
+        let ud = Rc::new(UserData { user_data, drop_user_data });
+        let ud2 = ud.clone();
+        // The value itself is compared on the C++ side, so the Rust side tracks a generation
+        // counter that is increased every time the value changes
+        let generation = Cell::new(0u64);
+        (*(handle as *const ChangeTracker)).init(
+            move || {
+                if eval_fn(ud.user_data) {
+                    generation.set(generation.get() + 1);
+                }
+                generation.get()
+            },
+            move |_| notify_fn(ud2.user_data),
+        );
+    }
+
+    /// Destroy handle
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_change_tracker_drop(handle: *mut ChangeTrackerOpaque) {
+        core::ptr::read(handle as *mut ChangeTracker);
+    }
 }
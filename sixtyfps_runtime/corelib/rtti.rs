@@ -36,9 +36,24 @@ declare_ValueType![
     crate::PathData,
     crate::animations::EasingCurve,
     crate::items::TextHorizontalAlignment,
-    crate::items::TextVerticalAlignment
+    crate::items::TextVerticalAlignment,
+    crate::items::TextWrap,
+    crate::items::TextOverflow,
+    crate::items::MouseCursor,
+    crate::items::PointerButton
 ];
 
+/// How a binding set with [`PropertyInfo::set_binding`] is animated
+pub enum AnimatedBindingKind {
+    /// The binding is not animated
+    NotAnimated,
+    /// The binding is animated with this animation
+    Animation(PropertyAnimation),
+    /// The animation is computed by this function each time an animation starts.
+    /// See [`crate::Property::set_dynamic_animated_binding`]
+    DynamicAnimation(Box<dyn Fn() -> PropertyAnimation>),
+}
+
 pub trait PropertyInfo<Item, Value> {
     fn get(&self, item: Pin<&Item>) -> Result<Value, ()>;
     fn set(
@@ -51,13 +66,20 @@ pub trait PropertyInfo<Item, Value> {
         &self,
         item: Pin<&Item>,
         binding: Box<dyn Fn() -> Value>,
-        animation: Option<PropertyAnimation>,
+        animation: AnimatedBindingKind,
     ) -> Result<(), ()>;
 
     /// The offset of the property in the item.
     /// The use of this is unsafe
     fn offset(&self) -> usize;
 
+    /// Calls Property::link_two_way with the property represented here and the property pointer
+    ///
+    /// # Safety
+    /// the property2 must be a pinned pointer to a Property of the same type
+    #[allow(unsafe_code)]
+    unsafe fn link_two_ways(&self, item: Pin<&Item>, property2: *const ());
+
     /// Returns self. This is just a trick to get auto-deref specialization of
     /// MaybeAnimatedPropertyInfoWrapper working.
     fn as_property_info(&'static self) -> &'static dyn PropertyInfo<Item, Value>
@@ -68,7 +90,7 @@ pub trait PropertyInfo<Item, Value> {
     }
 }
 
-impl<Item, T: Clone, Value: 'static> PropertyInfo<Item, Value>
+impl<Item, T: Clone + 'static, Value: 'static> PropertyInfo<Item, Value>
     for FieldOffset<Item, crate::Property<T>>
 where
     Value: TryInto<T>,
@@ -94,9 +116,9 @@ where
         &self,
         item: Pin<&Item>,
         binding: Box<dyn Fn() -> Value>,
-        animation: Option<PropertyAnimation>,
+        animation: AnimatedBindingKind,
     ) -> Result<(), ()> {
-        if animation.is_some() {
+        if !matches!(animation, AnimatedBindingKind::NotAnimated) {
             Err(())
         } else {
             self.apply_pin(item).set_binding(move || {
@@ -108,6 +130,14 @@ where
     fn offset(&self) -> usize {
         self.get_byte_offset()
     }
+
+    #[allow(unsafe_code)]
+    unsafe fn link_two_ways(&self, item: Pin<&Item>, property2: *const ()) {
+        let p1 = self.apply_pin(item);
+        // Safety: that's the invariant of this function
+        let p2 = Pin::new_unchecked((property2 as *const crate::Property<T>).as_ref().unwrap());
+        crate::Property::link_two_way(p1, p2);
+    }
 }
 
 /// Wraper for a field offset that optonally implement PropertyInfo and uses
@@ -115,7 +145,7 @@ where
 #[derive(derive_more::Deref)]
 pub struct MaybeAnimatedPropertyInfoWrapper<T, U>(pub FieldOffset<T, U>);
 
-impl<Item, T: Clone, Value: 'static> PropertyInfo<Item, Value>
+impl<Item, T: Clone + 'static, Value: 'static> PropertyInfo<Item, Value>
     for MaybeAnimatedPropertyInfoWrapper<Item, crate::Property<T>>
 where
     Value: TryInto<T>,
@@ -142,23 +172,32 @@ where
         &self,
         item: Pin<&Item>,
         binding: Box<dyn Fn() -> Value>,
-        animation: Option<PropertyAnimation>,
+        animation: AnimatedBindingKind,
     ) -> Result<(), ()> {
-        if let Some(animation) = &animation {
-            self.apply_pin(item).set_animated_binding(
-                move || {
-                    binding().try_into().map_err(|_| ()).expect("binding was of the wrong type")
-                },
-                animation,
-            );
-            Ok(())
-        } else {
-            self.0.set_binding(item, binding, None)
+        let binding =
+            move || binding().try_into().map_err(|_| ()).expect("binding was of the wrong type");
+        match animation {
+            AnimatedBindingKind::NotAnimated => {
+                self.apply_pin(item).set_binding(binding);
+            }
+            AnimatedBindingKind::Animation(animation) => {
+                self.apply_pin(item).set_animated_binding(binding, &animation);
+            }
+            AnimatedBindingKind::DynamicAnimation(compute_animation_details) => {
+                self.apply_pin(item)
+                    .set_dynamic_animated_binding(binding, compute_animation_details);
+            }
         }
+        Ok(())
     }
     fn offset(&self) -> usize {
         self.get_byte_offset()
     }
+
+    #[allow(unsafe_code)]
+    unsafe fn link_two_ways(&self, item: Pin<&Item>, property2: *const ()) {
+        self.0.link_two_ways(item, property2)
+    }
 }
 
 pub trait FieldInfo<Item, Value> {
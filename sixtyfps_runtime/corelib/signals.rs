@@ -22,28 +22,35 @@ use core::cell::Cell;
 ///
 /// The Arg represents the argument. It should always be a tuple
 ///
-#[derive(Default)]
 #[repr(C)]
-pub struct Signal<Arg> {
+pub struct Signal<Arg: ?Sized, Ret = ()> {
     /// FIXME: Box<dyn> is a fat object and we probaly want to put an erased type in there
-    handler: Cell<Option<Box<dyn Fn(Arg)>>>,
+    handler: Cell<Option<Box<dyn Fn(&Arg, &mut Ret)>>>,
 }
 
-impl<Arg> Signal<Arg> {
+impl<Arg: ?Sized, Ret> Default for Signal<Arg, Ret> {
+    fn default() -> Self {
+        Self { handler: Default::default() }
+    }
+}
+
+impl<Arg: ?Sized, Ret: Default> Signal<Arg, Ret> {
     /// Emit the signal with the given argument.
-    pub fn emit(&self, a: Arg) {
+    pub fn emit(&self, a: &Arg) -> Ret {
+        let mut r = Ret::default();
         if let Some(h) = self.handler.take() {
-            h(a);
+            h(a, &mut r);
             assert!(self.handler.take().is_none(), "Signal Handler set while emitted");
             self.handler.set(Some(h))
         }
+        r
     }
 
     /// Set an handler to be called when the signal is emited
     ///
     /// There can only be one single handler per signal.
-    pub fn set_handler(&self, f: impl Fn(Arg) + 'static) {
-        self.handler.set(Some(Box::new(f)));
+    pub fn set_handler(&self, f: impl Fn(&Arg) -> Ret + 'static) {
+        self.handler.set(Some(Box::new(move |a: &Arg, r: &mut Ret| *r = f(a))));
     }
 }
 
@@ -58,10 +65,18 @@ fn signal_simple_test() {
     let c = Rc::new(Component::default());
     let weak = Rc::downgrade(&c);
     c.clicked.set_handler(move |()| weak.upgrade().unwrap().pressed.set(true));
-    c.clicked.emit(());
+    c.clicked.emit(&());
     assert_eq!(c.pressed.get(), true);
 }
 
+#[test]
+fn signal_with_arguments_test() {
+    let signal = Signal::<(i32, i32), i32>::default();
+    assert_eq!(signal.emit(&(3, 4)), 0);
+    signal.set_handler(|(a, b)| a * b);
+    assert_eq!(signal.emit(&(3, 4)), 12);
+}
+
 pub(crate) mod ffi {
     #![allow(unsafe_code)]
 
@@ -80,28 +95,40 @@ pub(crate) mod ffi {
     /// sixtyfps_signal_drop must be called.
     #[no_mangle]
     pub unsafe extern "C" fn sixtyfps_signal_init(out: *mut SignalOpaque) {
-        assert_eq!(core::mem::size_of::<SignalOpaque>(), core::mem::size_of::<Signal<()>>());
-        core::ptr::write(out as *mut Signal<()>, Default::default());
+        assert_eq!(core::mem::size_of::<SignalOpaque>(), core::mem::size_of::<Signal<c_void>>());
+        core::ptr::write(out as *mut Signal<c_void>, Default::default());
     }
 
-    /// Emit the signal
+    /// Emit the signal.
+    /// `arg` is a pointer to the tuple of the arguments, and the return value is written in `ret`.
+    /// `ret` can be null if the signal does not return anything.
     #[no_mangle]
-    pub unsafe extern "C" fn sixtyfps_signal_emit(sig: *const SignalOpaque) {
-        let sig = &*(sig as *const Signal<()>);
-        sig.emit(());
+    pub unsafe extern "C" fn sixtyfps_signal_emit(
+        sig: *const SignalOpaque,
+        arg: *const c_void,
+        ret: *mut c_void,
+    ) {
+        let sig = &*(sig as *const Signal<c_void>);
+        if let Some(h) = sig.handler.take() {
+            let mut unit = ();
+            h(&*arg, if ret.is_null() { &mut unit } else { &mut *ret });
+            assert!(sig.handler.take().is_none(), "Signal Handler set while emitted");
+            sig.handler.set(Some(h))
+        }
     }
 
     /// Set signal handler.
     ///
-    /// The binding has signature fn(user_data)
+    /// The binding has signature fn(user_data, arg, ret) where `arg` points to the tuple of
+    /// the arguments, and the return value is to be written in `ret`
     #[no_mangle]
     pub unsafe extern "C" fn sixtyfps_signal_set_handler(
-        sig: *mut SignalOpaque,
-        binding: extern "C" fn(user_data: *mut c_void),
+        sig: *const SignalOpaque,
+        binding: extern "C" fn(user_data: *mut c_void, arg: *const c_void, ret: *mut c_void),
         user_data: *mut c_void,
         drop_user_data: Option<extern "C" fn(*mut c_void)>,
     ) {
-        let sig = &mut *(sig as *mut Signal<()>);
+        let sig = &*(sig as *const Signal<c_void>);
 
         struct UserData {
             user_data: *mut c_void,
@@ -117,15 +144,15 @@ pub(crate) mod ffi {
         }
         let ud = UserData { user_data, drop_user_data };
 
-        let real_binding = move |()| {
-            binding(ud.user_data);
+        let real_binding = move |arg: &(), ret: &mut ()| {
+            binding(ud.user_data, arg as *const c_void, ret as *mut c_void);
         };
-        sig.set_handler(real_binding);
+        sig.handler.set(Some(Box::new(real_binding)));
     }
 
     /// Destroy signal
     #[no_mangle]
     pub unsafe extern "C" fn sixtyfps_signal_drop(handle: *mut SignalOpaque) {
-        core::ptr::read(handle as *mut Signal<()>);
+        core::ptr::read(handle as *mut Signal<c_void>);
     }
 }
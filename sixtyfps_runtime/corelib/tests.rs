@@ -10,19 +10,27 @@ LICENSE END */
 //! Functions usefull for testing
 #![warn(missing_docs)]
 
-use crate::input::{MouseEvent, MouseEventType};
+use crate::input::{
+    KeyCode, KeyEvent, KeyEventType, KeyboardModifiers, MouseEvent, MouseEventType,
+};
+use crate::items::PointerButton;
+use crate::SharedString;
 
 /// SixtyFPS animations do not use real time, but use a mocked time.
 /// Normally, the event loop update the time of the animation using
 /// real time, but in tests, it is more convinient to use the fake time.
-/// This function will add some milliseconds to the fake time
+/// This function will add some milliseconds to the fake time, and trigger
+/// the timers whose timeout is reached.
 #[no_mangle]
 pub extern "C" fn sixtyfps_mock_elapsed_time(time_in_ms: u64) {
+    // Start or stop the timers according to the changes that happened since the last call
+    crate::eventloop::process_change_watchers();
     crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| {
         let mut tick = driver.current_tick();
         tick += instant::Duration::from_millis(time_in_ms);
         driver.update_animations(tick)
-    })
+    });
+    crate::eventloop::process_timers();
 }
 
 /// Simulate a click on a position within the component.
@@ -32,9 +40,82 @@ pub extern "C" fn sixtyfps_send_mouse_click(
     x: f32,
     y: f32,
 ) {
-    let pos = euclid::point2(x, y);
-    component.as_ref().input_event(MouseEvent { pos, what: MouseEventType::MouseMoved });
-    component.as_ref().input_event(MouseEvent { pos, what: MouseEventType::MousePressed });
+    sixtyfps_send_mouse_press(component, x, y);
     sixtyfps_mock_elapsed_time(50);
-    component.as_ref().input_event(MouseEvent { pos, what: MouseEventType::MouseReleased });
+    sixtyfps_send_mouse_release(component, x, y);
+}
+
+/// Simulate moving the mouse to a position within the component and pressing the left button.
+#[no_mangle]
+pub extern "C" fn sixtyfps_send_mouse_press(
+    component: core::pin::Pin<crate::component::ComponentRef>,
+    x: f32,
+    y: f32,
+) {
+    let pos = euclid::point2(x, y);
+    component.as_ref().input_event(MouseEvent::new(pos, MouseEventType::MouseMoved));
+    let mut event = MouseEvent::new(pos, MouseEventType::MousePressed);
+    event.button = PointerButton::left;
+    component.as_ref().input_event(event);
+}
+
+/// Simulate releasing the left button of the mouse at a position within the component.
+#[no_mangle]
+pub extern "C" fn sixtyfps_send_mouse_release(
+    component: core::pin::Pin<crate::component::ComponentRef>,
+    x: f32,
+    y: f32,
+) {
+    let mut event = MouseEvent::new(euclid::point2(x, y), MouseEventType::MouseReleased);
+    event.button = PointerButton::left;
+    component.as_ref().input_event(event);
+}
+
+/// Simulate turning the mouse wheel, or scrolling on a trackpad, at a position within the component.
+/// `pixel_delta_x` and `pixel_delta_y` are the deltas reported by trackpads, while `line_delta_x`
+/// and `line_delta_y` are the number of lines reported by classic mouse wheels.
+#[no_mangle]
+pub extern "C" fn sixtyfps_send_mouse_wheel(
+    component: core::pin::Pin<crate::component::ComponentRef>,
+    x: f32,
+    y: f32,
+    pixel_delta_x: f32,
+    pixel_delta_y: f32,
+    line_delta_x: f32,
+    line_delta_y: f32,
+) {
+    let mut event = MouseEvent::new(euclid::point2(x, y), MouseEventType::MouseWheel);
+    event.pixel_delta = euclid::point2(pixel_delta_x, pixel_delta_y);
+    event.line_delta = euclid::point2(line_delta_x, line_delta_y);
+    component.as_ref().input_event(event);
+}
+
+/// Simulate a key press and release for each character of the string.
+#[no_mangle]
+pub extern "C" fn sixtyfps_send_keyboard_string_sequence(
+    component: core::pin::Pin<crate::component::ComponentRef>,
+    sequence: &SharedString,
+) {
+    for ch in sequence.chars() {
+        let key = match ch {
+            '\t' => KeyCode::Tab,
+            '\n' | '\r' => KeyCode::Return,
+            ' ' => KeyCode::Space,
+            '\u{8}' => KeyCode::Backspace,
+            '\u{1b}' => KeyCode::Escape,
+            '\u{7f}' => KeyCode::Delete,
+            '0'..='9' => format!("Key{}", ch).parse().unwrap_or_default(),
+            'a'..='z' | 'A'..='Z' => {
+                ch.to_ascii_uppercase().to_string().parse().unwrap_or_default()
+            }
+            _ => KeyCode::Unknown,
+        };
+        let text: SharedString =
+            if ch.is_control() { SharedString::default() } else { ch.to_string().as_str().into() };
+        let modifiers = KeyboardModifiers { shift: ch.is_uppercase(), ..Default::default() };
+        let mut event = KeyEvent { event_type: KeyEventType::KeyPressed, key, text, modifiers };
+        component.as_ref().key_event(&event);
+        event.event_type = KeyEventType::KeyReleased;
+        component.as_ref().key_event(&event);
+    }
 }
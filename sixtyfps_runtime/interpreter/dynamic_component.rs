@@ -12,8 +12,10 @@ use crate::{dynamic_type, eval};
 use core::convert::TryInto;
 use core::ptr::NonNull;
 use dynamic_type::{Instance, InstanceBox};
-use object_tree::{Element, ElementRc};
-use sixtyfps_compilerlib::layout::{GridLayout, Layout, LayoutElement, LayoutItem, PathLayout};
+use object_tree::ElementRc;
+use sixtyfps_compilerlib::layout::{
+    BoxLayout, GridLayout, Layout, LayoutElement, LayoutItem, LayoutItemConstraints, PathLayout,
+};
 use sixtyfps_compilerlib::typeregister::Type;
 use sixtyfps_compilerlib::*;
 use sixtyfps_corelib::component::{ComponentRefPin, ComponentVTable};
@@ -24,11 +26,15 @@ use sixtyfps_corelib::item_tree::{
 use sixtyfps_corelib::items::{Flickable, ItemRef, ItemVTable, PropertyAnimation, Rectangle};
 use sixtyfps_corelib::layout::{LayoutInfo, Padding};
 use sixtyfps_corelib::properties::{InterpolatedPropertyValue, PropertyTracker};
-use sixtyfps_corelib::rtti::{self, FieldOffset, PropertyInfo};
+use sixtyfps_corelib::rtti::{self, AnimatedBindingKind, FieldOffset, PropertyInfo};
 use sixtyfps_corelib::slice::Slice;
 use sixtyfps_corelib::{Color, Property, SharedString, Signal};
 use std::collections::HashMap;
-use std::{cell::RefCell, pin::Pin, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    pin::Pin,
+    rc::Rc,
+};
 
 pub struct ComponentBox<'id> {
     instance: InstanceBox<'id>,
@@ -127,7 +133,12 @@ type RepeaterVec<'id> = core::cell::RefCell<Vec<ComponentBox<'id>>>;
 
 pub(crate) struct ComponentExtraData {
     mouse_grabber: core::cell::Cell<sixtyfps_corelib::item_tree::VisitChildrenResult>,
+    mouse_hover: core::cell::Cell<sixtyfps_corelib::item_tree::VisitChildrenResult>,
     pub(crate) window: RefCell<Option<sixtyfps_corelib::eventloop::ComponentWindow>>,
+    /// The instances of the globals, indexed by the name of the global. Only set on the root component
+    pub(crate) globals: RefCell<HashMap<String, Rc<GlobalComponent>>>,
+    /// One for each `changed prop => { ... }` handler
+    change_trackers: RefCell<Vec<sixtyfps_corelib::properties::ChangeTracker>>,
 }
 
 impl Default for ComponentExtraData {
@@ -136,11 +147,24 @@ impl Default for ComponentExtraData {
             mouse_grabber: core::cell::Cell::new(
                 sixtyfps_corelib::item_tree::VisitChildrenResult::CONTINUE,
             ),
+            mouse_hover: core::cell::Cell::new(
+                sixtyfps_corelib::item_tree::VisitChildrenResult::CONTINUE,
+            ),
             window: RefCell::new(None),
+            globals: Default::default(),
+            change_trackers: Default::default(),
         }
     }
 }
 
+/// The properties and signals of a component declared with `global`.
+/// The root component has one instance of each global it uses.
+#[derive(Default)]
+pub(crate) struct GlobalComponent {
+    pub(crate) properties: HashMap<String, Pin<Rc<Property<eval::Value>>>>,
+    pub(crate) signals: HashMap<String, Rc<Signal<[eval::Value], eval::Value>>>,
+}
+
 struct ErasedRepeaterWithinComponent<'id>(RepeaterWithinComponent<'id, 'static>);
 impl<'id, 'sub_id> From<RepeaterWithinComponent<'id, 'sub_id>>
     for ErasedRepeaterWithinComponent<'id>
@@ -185,7 +209,8 @@ pub struct ComponentDescription<'id> {
     item_tree: Vec<ItemTreeNode<crate::dynamic_type::Instance<'id>>>,
     pub(crate) items: HashMap<String, ItemWithinComponent>,
     pub(crate) custom_properties: HashMap<String, PropertiesWithinComponent>,
-    pub(crate) custom_signals: HashMap<String, FieldOffset<Instance<'id>, Signal<()>>>,
+    pub(crate) custom_signals:
+        HashMap<String, FieldOffset<Instance<'id>, Signal<[eval::Value], eval::Value>>>,
     repeater: Vec<ErasedRepeaterWithinComponent<'id>>,
     /// Map the Element::id of the repeater to the index in the `repeater` vec
     pub repeater_names: HashMap<String, usize>,
@@ -217,38 +242,8 @@ extern "C" fn visit_children_item(
         |_, order, mut visitor, index| {
             generativity::make_guard!(guard);
             let rep_in_comp = component_type.repeater[index].unerase(guard);
-            let mut vec = rep_in_comp.offset.apply(&*instance).borrow_mut();
-            if let Some(listener_offset) = rep_in_comp.property_tracker {
-                let listener = listener_offset.apply_pin(instance);
-                if listener.is_dirty() {
-                    listener.evaluate(|| {
-                        match eval::eval_expression(
-                            &rep_in_comp.model,
-                            InstanceRef { instance, component_type },
-                            &mut Default::default(),
-                        ) {
-                            crate::Value::Number(count) => populate_model(
-                                &mut *vec,
-                                rep_in_comp,
-                                component,
-                                (0..count as i32)
-                                    .into_iter()
-                                    .map(|v| crate::Value::Number(v as f64)),
-                            ),
-                            crate::Value::Array(a) => {
-                                populate_model(&mut *vec, rep_in_comp, component, a.into_iter())
-                            }
-                            crate::Value::Bool(b) => populate_model(
-                                &mut *vec,
-                                rep_in_comp,
-                                component,
-                                (if b { Some(crate::Value::Void) } else { None }).into_iter(),
-                            ),
-                            _ => panic!("Unsupported model"),
-                        }
-                    });
-                }
-            }
+            ensure_repeater_updated(InstanceRef { instance, component_type }, rep_in_comp);
+            let vec = rep_in_comp.offset.apply(&*instance).borrow();
             match order {
                 TraversalOrder::FrontToBack => {
                     for (i, x) in vec.iter().enumerate() {
@@ -339,12 +334,15 @@ fn generate_component<'id>(
             [
                 rtti_for::<Image>(),
                 rtti_for::<Text>(),
+                rtti_for::<TextInput>(),
                 rtti_for::<Rectangle>(),
                 rtti_for::<BorderRectangle>(),
                 rtti_for::<TouchArea>(),
                 rtti_for::<Path>(),
                 rtti_for::<Flickable>(),
                 rtti_for::<Window>(),
+                rtti_for::<FocusScope>(),
+                rtti_for::<TimerItem>(),
             ]
             .iter()
             .cloned(),
@@ -356,6 +354,7 @@ fn generate_component<'id>(
                 rtti_for::<qt_style::QtStyleCheckBox>(),
                 rtti_for::<qt_style::QtStyleSpinBox>(),
                 rtti_for::<qt_style::QtStyleSlider>(),
+                rtti_for::<qt_style::QtStyleLineEdit>(),
             ]
             .iter()
             .cloned(),
@@ -464,8 +463,15 @@ fn generate_component<'id>(
             Type::LogicalLength => animated_property_info::<f32>(),
             Type::Resource => property_info::<Resource>(),
             Type::Bool => property_info::<bool>(),
-            Type::Signal => {
-                custom_signals.insert(name.clone(), builder.add_field_type::<Signal<()>>());
+            Type::Array(_) => property_info::<eval::Value>(),
+            Type::Object { .. } => property_info::<eval::Value>(),
+            Type::Enumeration(_) => property_info::<eval::Value>(),
+            Type::Function { .. } => continue,
+            Type::Signal { .. } => {
+                custom_signals.insert(
+                    name.clone(),
+                    builder.add_field_type::<Signal<[eval::Value], eval::Value>>(),
+                );
                 continue;
             }
             _ => panic!("bad type"),
@@ -507,7 +513,13 @@ fn generate_component<'id>(
         todo!()
     }
 
-    let t = ComponentVTable { visit_children_item, layout_info, compute_layout, input_event };
+    let t = ComponentVTable {
+        visit_children_item,
+        layout_info,
+        compute_layout,
+        input_event,
+        key_event,
+    };
     let t = ComponentDescription {
         ct: t,
         dynamic_type: builder.build(),
@@ -525,27 +537,162 @@ fn generate_component<'id>(
     Rc::new(t)
 }
 
+/// Returns the animation used when setting a value to the property
 pub fn animation_for_property(
     component: InstanceRef,
-    all_animations: &HashMap<String, ElementRc>,
+    all_animations: &HashMap<String, object_tree::PropertyAnimation>,
     property_name: &str,
 ) -> Option<PropertyAnimation> {
-    match all_animations.get(property_name) {
-        Some(anim_elem) => Some(eval::new_struct_with_bindings(
-            &anim_elem.borrow().bindings,
-            component,
-            &mut Default::default(),
-        )),
-        None => None,
-    }
+    let anim_elem = match all_animations.get(property_name) {
+        Some(object_tree::PropertyAnimation::Static(anim_elem)) => anim_elem,
+        // The transitions only animate the bindings, setting a value uses the default animation
+        Some(object_tree::PropertyAnimation::Transition { default: Some(anim_elem), .. }) => {
+            anim_elem
+        }
+        _ => return None,
+    };
+    Some(eval::new_struct_with_bindings(
+        &anim_elem.borrow().bindings,
+        component,
+        &mut Default::default(),
+    ))
 }
 
-fn animation_for_element_property(
+/// Returns how the binding of the property is animated
+pub fn animation_for_property_binding(
     component: InstanceRef,
-    element: &Element,
+    all_animations: &HashMap<String, object_tree::PropertyAnimation>,
     property_name: &str,
-) -> Option<PropertyAnimation> {
-    animation_for_property(component, &element.property_animations, property_name)
+) -> AnimatedBindingKind {
+    let c = unsafe {
+        Pin::new_unchecked(vtable::VRef::from_raw(
+            NonNull::from(&component.component_type.ct).cast(),
+            NonNull::from(component.instance.get_ref()).cast(),
+        ))
+    };
+    match all_animations.get(property_name) {
+        Some(object_tree::PropertyAnimation::Static(anim_elem))
+            if anim_elem.borrow().bindings.values().all(|b| b.is_constant()) =>
+        {
+            AnimatedBindingKind::Animation(eval::new_struct_with_bindings(
+                &anim_elem.borrow().bindings,
+                component,
+                &mut Default::default(),
+            ))
+        }
+        Some(object_tree::PropertyAnimation::Static(anim_elem)) => {
+            // The parameters of the animation are evaluated when the animation starts
+            let anim_elem = anim_elem.clone();
+            AnimatedBindingKind::DynamicAnimation(Box::new(move || {
+                generativity::make_guard!(guard);
+                let component = unsafe { InstanceRef::from_pin_ref(c, guard) };
+                eval::new_struct_with_bindings(
+                    &anim_elem.borrow().bindings,
+                    component,
+                    &mut Default::default(),
+                )
+            }))
+        }
+        Some(object_tree::PropertyAnimation::Transition { state_ref, animations, default }) => {
+            let state_ref = state_ref.clone();
+            let animations = animations.clone();
+            let default = default.clone();
+            AnimatedBindingKind::DynamicAnimation(Box::new(move || {
+                generativity::make_guard!(guard);
+                let component = unsafe { InstanceRef::from_pin_ref(c, guard) };
+                let state = eval::eval_expression(&state_ref, component, &mut Default::default());
+                let state_field = |name: &str| match &state {
+                    eval::Value::Object(state) => match state.get(name) {
+                        Some(eval::Value::Number(n)) => *n as i32,
+                        _ => panic!("The state has no field {}", name),
+                    },
+                    _ => panic!("The state property is not an object"),
+                };
+                let anim_elem = animations
+                    .iter()
+                    .find(|a| {
+                        let field = if a.is_out { "previous_state" } else { "current_state" };
+                        state_field(field) == a.state_id
+                    })
+                    .map(|a| &a.animation)
+                    .or(default.as_ref());
+                match anim_elem {
+                    Some(anim_elem) => eval::new_struct_with_bindings(
+                        &anim_elem.borrow().bindings,
+                        component,
+                        &mut Default::default(),
+                    ),
+                    None => PropertyAnimation::default(),
+                }
+            }))
+        }
+        None => AnimatedBindingKind::NotAnimated,
+    }
+}
+
+/// Wrap the binding of the state property created by the lower_states pass, which computes the
+/// index of the current state, so that it produces the `StateInfo` object that also contains
+/// the previous state.
+fn state_binding(binding: Box<dyn Fn() -> eval::Value>) -> Box<dyn Fn() -> eval::Value> {
+    // (current_state, previous_state)
+    let info = Cell::new((0, 0));
+    Box::new(move || {
+        let new_state = match binding() {
+            eval::Value::Number(n) => n as i32,
+            _ => panic!("The state binding must be a number"),
+        };
+        let (mut current_state, mut previous_state) = info.get();
+        if new_state != current_state {
+            previous_state = current_state;
+            current_state = new_state;
+            info.set((current_state, previous_state));
+        }
+        eval::Value::Object(
+            [
+                ("current_state".to_owned(), eval::Value::Number(current_state as _)),
+                ("previous_state".to_owned(), eval::Value::Number(previous_state as _)),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+    })
+}
+
+/// Instantiate or update the components of a repeater whose model changed since the last call
+fn ensure_repeater_updated<'par_id>(
+    instance_ref: InstanceRef<'_, 'par_id>,
+    rep_in_comp: &RepeaterWithinComponent<'par_id, '_>,
+) {
+    let listener_offset = match rep_in_comp.property_tracker {
+        Some(listener_offset) => listener_offset,
+        None => return,
+    };
+    let listener = listener_offset.apply_pin(instance_ref.instance);
+    if listener.is_dirty() {
+        let component = instance_ref.borrow();
+        let mut vec = rep_in_comp.offset.apply(&*instance_ref.instance).borrow_mut();
+        listener.evaluate(|| {
+            match eval::eval_expression(&rep_in_comp.model, instance_ref, &mut Default::default()) {
+                crate::Value::Number(count) => populate_model(
+                    &mut *vec,
+                    rep_in_comp,
+                    component,
+                    (0..count as i32).into_iter().map(|v| crate::Value::Number(v as f64)),
+                ),
+                crate::Value::Array(a) => {
+                    populate_model(&mut *vec, rep_in_comp, component, a.into_iter())
+                }
+                crate::Value::Bool(b) => populate_model(
+                    &mut *vec,
+                    rep_in_comp,
+                    component,
+                    (if b { Some(crate::Value::Void) } else { None }).into_iter(),
+                ),
+                _ => panic!("Unsupported model"),
+            }
+        });
+    }
 }
 
 fn populate_model<'par_id, 'sub_id>(
@@ -594,6 +741,7 @@ pub fn instantiate<'id>(
         extra_data.window.replace(Some(
             sixtyfps_rendering_backend_gl::create_gl_window_with_canvas_id(canvas_id),
         ));
+        instantiate_globals(&component_box);
     }
 
     for item_within_component in component_type.items.values() {
@@ -602,19 +750,10 @@ pub fn instantiate<'id>(
             let elem = item_within_component.elem.borrow();
             for (prop, expr) in &elem.bindings {
                 let ty = elem.lookup_property(prop.as_str());
-                if ty == Type::Signal {
-                    let signal = item_within_component
-                        .rtti
-                        .signals
-                        .get(prop.as_str())
-                        .map(|o| &*(item.as_ptr().add(*o) as *const Signal<()>))
-                        .or_else(|| {
-                            component_type
-                                .custom_signals
-                                .get(prop.as_str())
-                                .map(|o| o.apply(instance_ref.as_ref()))
-                        })
-                        .unwrap_or_else(|| panic!("unkown signal {}", prop));
+                if matches!(ty, Type::Function { .. }) {
+                    // Functions are evaluated when they are called
+                    continue;
+                } else if matches!(ty, Type::Signal { .. }) {
                     let expr = expr.clone();
                     let component_type = component_type.clone();
                     let instance = component_box.instance.as_ptr();
@@ -622,28 +761,82 @@ pub fn instantiate<'id>(
                         NonNull::from(&component_type.ct).cast(),
                         instance.cast(),
                     ));
-                    signal.set_handler(move |_| {
-                        generativity::make_guard!(guard);
-                        eval::eval_expression(
-                            &expr,
-                            InstanceRef::from_pin_ref(c, guard),
-                            &mut Default::default(),
-                        );
-                    })
+                    if let Some(signal_offset) =
+                        item_within_component.rtti.signals.get(prop.as_str())
+                    {
+                        let signal = &*(item.as_ptr().add(*signal_offset) as *const Signal<()>);
+                        signal.set_handler(move |_| {
+                            generativity::make_guard!(guard);
+                            eval::eval_expression(
+                                &expr,
+                                InstanceRef::from_pin_ref(c, guard),
+                                &mut Default::default(),
+                            );
+                        })
+                    } else if let Some(signal_offset) =
+                        component_type.custom_signals.get(prop.as_str())
+                    {
+                        let signal = signal_offset.apply(instance_ref.as_ref());
+                        signal.set_handler(move |args| {
+                            generativity::make_guard!(guard);
+                            eval::eval_expression(
+                                &expr,
+                                InstanceRef::from_pin_ref(c, guard),
+                                &mut eval::EvalLocalContext::from_function_arguments(args.to_vec()),
+                            )
+                        })
+                    } else {
+                        panic!("unkown signal {}", prop)
+                    }
                 } else {
+                    let mut expr = Some(&expr.expression);
+                    while let Some(expression_tree::Expression::TwoWayBinding(nr, next)) = expr {
+                        let prop2 = eval::property_ptr(
+                            instance_ref,
+                            &nr.element.upgrade().unwrap(),
+                            nr.name.as_str(),
+                        );
+                        if let Some(prop_rtti) =
+                            item_within_component.rtti.properties.get(prop.as_str())
+                        {
+                            prop_rtti.link_two_ways(item, prop2);
+                        } else if let Some(PropertiesWithinComponent {
+                            offset,
+                            prop: prop_info,
+                            ..
+                        }) = component_type.custom_properties.get(prop.as_str())
+                        {
+                            prop_info.link_two_ways(Pin::new_unchecked(&*mem.add(*offset)), prop2);
+                        } else {
+                            panic!("unkown property {}", prop);
+                        }
+                        expr = next.as_deref();
+                    }
+                    let expr = match expr {
+                        Some(expr) => expr,
+                        None => continue,
+                    };
+
                     if let Some(prop_rtti) =
                         item_within_component.rtti.properties.get(prop.as_str())
                     {
-                        let maybe_animation =
-                            animation_for_element_property(instance_ref, &elem, prop);
-
                         if expr.is_constant() {
+                            let maybe_animation = animation_for_property(
+                                instance_ref,
+                                &elem.property_animations,
+                                prop,
+                            );
                             prop_rtti.set(
                                 item,
                                 eval::eval_expression(expr, instance_ref, &mut Default::default()),
                                 maybe_animation,
                             );
                         } else {
+                            let maybe_animation = animation_for_property_binding(
+                                instance_ref,
+                                &elem.property_animations,
+                                prop,
+                            );
                             let expr = expr.clone();
                             let component_type = component_type.clone();
                             let instance = component_box.instance.as_ptr();
@@ -669,17 +862,17 @@ pub fn instantiate<'id>(
                         offset, prop: prop_info, ..
                     }) = component_type.custom_properties.get(prop.as_str())
                     {
-                        let maybe_animation = animation_for_property(
-                            instance_ref,
-                            &component_type.original.root_element.borrow().property_animations,
-                            prop,
-                        );
-
-                        if expr.is_constant() {
+                        let is_state_info = ty == Type::state_info();
+                        if expr.is_constant() && !is_state_info {
                             let v =
                                 eval::eval_expression(expr, instance_ref, &mut Default::default());
                             prop_info.set(Pin::new_unchecked(&*mem.add(*offset)), v, None).unwrap();
                         } else {
+                            let maybe_animation = animation_for_property_binding(
+                                instance_ref,
+                                &component_type.original.root_element.borrow().property_animations,
+                                prop,
+                            );
                             let expr = expr.clone();
                             let component_type = component_type.clone();
                             let instance = component_box.instance.as_ptr();
@@ -687,17 +880,20 @@ pub fn instantiate<'id>(
                                 NonNull::from(&component_type.ct).cast(),
                                 instance.cast(),
                             ));
+                            let binding: Box<dyn Fn() -> eval::Value> = Box::new(move || {
+                                generativity::make_guard!(guard);
+                                eval::eval_expression(
+                                    &expr,
+                                    InstanceRef::from_pin_ref(c, guard),
+                                    &mut Default::default(),
+                                )
+                            });
+                            let binding =
+                                if is_state_info { state_binding(binding) } else { binding };
                             prop_info
                                 .set_binding(
                                     Pin::new_unchecked(&*mem.add(*offset)),
-                                    Box::new(move || {
-                                        generativity::make_guard!(guard);
-                                        eval::eval_expression(
-                                            &expr,
-                                            InstanceRef::from_pin_ref(c, guard),
-                                            &mut Default::default(),
-                                        )
-                                    }),
+                                    binding,
                                     maybe_animation,
                                 )
                                 .unwrap();
@@ -710,6 +906,47 @@ pub fn instantiate<'id>(
         }
     }
 
+    let extra_data = component_type.extra_data_offset.apply(instance_ref.as_ref());
+    for (nr, handler) in &component_type.original.root_element.borrow().change_callbacks {
+        let change_tracker = sixtyfps_corelib::properties::ChangeTracker::default();
+        let c = unsafe {
+            Pin::new_unchecked(vtable::VRef::from_raw(
+                NonNull::from(&component_type.ct).cast(),
+                component_box.instance.as_ptr().cast(),
+            ))
+        };
+        let prop = expression_tree::Expression::PropertyReference(nr.clone());
+        let handler = handler.clone();
+        change_tracker.init(
+            move || {
+                generativity::make_guard!(guard);
+                eval::eval_expression(
+                    &prop,
+                    unsafe { InstanceRef::from_pin_ref(c, guard) },
+                    &mut Default::default(),
+                )
+            },
+            move |_| {
+                generativity::make_guard!(guard);
+                eval::eval_expression(
+                    &handler,
+                    unsafe { InstanceRef::from_pin_ref(c, guard) },
+                    &mut Default::default(),
+                );
+            },
+        );
+        extra_data.change_trackers.borrow_mut().push(change_tracker);
+    }
+
+    // The timers need to be initialized once all the bindings are set
+    for item_within_component in component_type.items.values() {
+        let item = unsafe { item_within_component.item_from_component(mem) };
+        if let Some(timer) = vtable::VRef::downcast_pin::<sixtyfps_corelib::items::TimerItem>(item)
+        {
+            timer.init();
+        }
+    }
+
     for rep_in_comp in &component_type.repeater {
         generativity::make_guard!(guard);
         let rep_in_comp = rep_in_comp.unerase(guard);
@@ -740,32 +977,218 @@ pub fn instantiate<'id>(
     component_box
 }
 
+/// Create the instances of the globals used by the root component, and set their bindings
+fn instantiate_globals(component_box: &ComponentBox) {
+    let instance_ref = component_box.borrow_instance();
+    let component_type = &component_box.component_type;
+    let extra_data = component_type.extra_data_offset.apply(instance_ref.as_ref());
+    let used_globals = component_type.original.used_globals.borrow();
+
+    // All the globals are created before any binding is evaluated
+    for global in used_globals.iter() {
+        let mut global_component = GlobalComponent::default();
+        for (name, decl) in &global.root_element.borrow().property_declarations {
+            if matches!(decl.property_type, Type::Signal { .. }) {
+                global_component.signals.insert(name.clone(), Default::default());
+            } else if !matches!(decl.property_type, Type::Function { .. }) {
+                let default_value = eval::eval_expression(
+                    &expression_tree::Expression::default_value_for_type(&decl.property_type),
+                    instance_ref,
+                    &mut Default::default(),
+                );
+                global_component
+                    .properties
+                    .insert(name.clone(), Rc::pin(Property::new(default_value)));
+            }
+        }
+        extra_data.globals.borrow_mut().insert(global.id.clone(), Rc::new(global_component));
+    }
+
+    for global in used_globals.iter() {
+        let global_component = extra_data.globals.borrow()[&global.id].clone();
+        for (prop, expr) in &global.root_element.borrow().bindings {
+            // The expressions are evaluated in the context of the root component, which owns the globals
+            let c = unsafe {
+                Pin::new_unchecked(vtable::VRef::from_raw(
+                    NonNull::from(&component_type.ct).cast(),
+                    component_box.instance.as_ptr().cast(),
+                ))
+            };
+            if matches!(global.root_element.borrow().lookup_property(prop), Type::Function { .. }) {
+                continue;
+            }
+            if let Some(signal) = global_component.signals.get(prop) {
+                let expr = expr.clone();
+                signal.set_handler(move |args| {
+                    generativity::make_guard!(guard);
+                    eval::eval_expression(
+                        &expr,
+                        unsafe { InstanceRef::from_pin_ref(c, guard) },
+                        &mut eval::EvalLocalContext::from_function_arguments(args.to_vec()),
+                    )
+                })
+            } else if expr.is_constant() {
+                global_component.properties[prop].set(eval::eval_expression(
+                    expr,
+                    instance_ref,
+                    &mut Default::default(),
+                ));
+            } else {
+                let expr = expr.clone();
+                global_component.properties[prop].set_binding(move || {
+                    generativity::make_guard!(guard);
+                    eval::eval_expression(
+                        &expr,
+                        unsafe { InstanceRef::from_pin_ref(c, guard) },
+                        &mut Default::default(),
+                    )
+                });
+            }
+        }
+    }
+}
+
 use sixtyfps_corelib::layout::*;
 
+/// A function that computes the minimum height of a layout item for a given width
+type HeightForWidthFn<'a> = Rc<dyn Fn(f32) -> f32 + 'a>;
+
+#[derive(Clone)]
 pub struct GridLayoutWithCells<'a> {
     grid: &'a GridLayout,
     cells: Vec<GridLayoutCellData<'a>>,
     spacing: f32,
     padding: Padding,
+    /// For each cell, the function that computes its minimum height for a width, if any
+    height_for_width: Vec<Option<HeightForWidthFn<'a>>>,
+}
+
+impl<'a> GridLayoutWithCells<'a> {
+    /// Calls `f` with the cells, whose HeightForWidth call the functions of `height_for_width`
+    fn with_cells<R>(&self, f: impl FnOnce(&[GridLayoutCellData]) -> R) -> R {
+        let functions: Vec<_> = self.height_for_width.iter().map(|f| f.as_deref()).collect();
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .zip(functions.iter())
+            .map(|(cell, function)| GridLayoutCellData {
+                height_for_width: function
+                    .as_ref()
+                    .map_or_else(Default::default, HeightForWidth::new),
+                ..cell.clone()
+            })
+            .collect();
+        f(&cells)
+    }
+}
+
+#[derive(Clone)]
+pub struct BoxLayoutWithCells<'a> {
+    layout: &'a BoxLayout,
+    cells: Vec<BoxLayoutCellData<'a>>,
+    spacing: f32,
+    padding: Padding,
+    alignment: LayoutAlignment,
+    /// The current width of the layout, used to compute the height of a FlowLayout
+    width: f32,
+    /// For each cell, the function that computes its minimum height for a width, if any
+    height_for_width: Vec<Option<HeightForWidthFn<'a>>>,
+}
+
+impl<'a> BoxLayoutWithCells<'a> {
+    /// Calls `f` with the cells, whose HeightForWidth call the functions of `height_for_width`
+    fn with_cells<R>(&self, f: impl FnOnce(&[BoxLayoutCellData]) -> R) -> R {
+        let functions: Vec<_> = self.height_for_width.iter().map(|f| f.as_deref()).collect();
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .zip(functions.iter())
+            .map(|(cell, function)| BoxLayoutCellData {
+                height_for_width: function
+                    .as_ref()
+                    .map_or_else(Default::default, HeightForWidth::new),
+                ..cell.clone()
+            })
+            .collect();
+        f(&cells)
+    }
 }
 
 #[derive(derive_more::From)]
 enum LayoutTreeItem<'a> {
     GridLayout(GridLayoutWithCells<'a>),
+    BoxLayout(BoxLayoutWithCells<'a>),
     PathLayout(&'a PathLayout),
 }
 
 impl<'a> LayoutTreeItem<'a> {
     fn layout_info(&self) -> LayoutInfo {
         match self {
-            LayoutTreeItem::GridLayout(grid_layout) => grid_layout_info(
-                &Slice::from(grid_layout.cells.as_slice()),
-                grid_layout.spacing,
-                &grid_layout.padding,
-            ),
+            LayoutTreeItem::GridLayout(grid_layout) => grid_layout.with_cells(|cells| {
+                grid_layout_info(&Slice::from(cells), grid_layout.spacing, &grid_layout.padding)
+            }),
+            LayoutTreeItem::BoxLayout(box_layout) if box_layout.layout.wrap => box_layout
+                .with_cells(|cells| {
+                    flow_layout_info(
+                        &Slice::from(cells),
+                        box_layout.spacing,
+                        &box_layout.padding,
+                        box_layout.width,
+                    )
+                }),
+            LayoutTreeItem::BoxLayout(box_layout) => box_layout.with_cells(|cells| {
+                box_layout_info(
+                    &Slice::from(cells),
+                    box_layout.spacing,
+                    &box_layout.padding,
+                    box_layout.alignment,
+                    box_layout.layout.is_horizontal,
+                )
+            }),
             LayoutTreeItem::PathLayout(_) => todo!(),
         }
     }
+
+    /// Returns a function that computes the minimum height of this layout for a given width
+    fn height_for_width(&self) -> Option<HeightForWidthFn<'a>> {
+        match self {
+            LayoutTreeItem::GridLayout(grid_layout) => {
+                let grid_layout = grid_layout.clone();
+                Some(Rc::new(move |width| {
+                    grid_layout.with_cells(|cells| {
+                        grid_layout_height_for_width(
+                            &Slice::from(cells),
+                            grid_layout.spacing,
+                            &grid_layout.padding,
+                            width,
+                        )
+                    })
+                }))
+            }
+            LayoutTreeItem::BoxLayout(box_layout) => {
+                let box_layout = box_layout.clone();
+                Some(Rc::new(move |width| {
+                    box_layout.with_cells(|cells| {
+                        let cells = Slice::from(cells);
+                        let (spacing, padding) = (box_layout.spacing, &box_layout.padding);
+                        if box_layout.layout.wrap {
+                            flow_layout_info(&cells, spacing, padding, width).min_height
+                        } else {
+                            box_layout_height_for_width(
+                                &cells,
+                                spacing,
+                                padding,
+                                box_layout.alignment,
+                                box_layout.layout.is_horizontal,
+                                width,
+                            )
+                        }
+                    })
+                }))
+            }
+            LayoutTreeItem::PathLayout(_) => None,
+        }
+    }
 }
 
 trait LayoutItemCodeGen {
@@ -859,26 +1282,178 @@ impl LayoutItemCodeGen for LayoutElement {
     }
 }
 
+/// Returns the layout info of an item in a layout, with the constraints explicitly set on it
+fn layout_info_with_constraints<'a, 'b>(
+    item: &'a LayoutItem,
+    constraints: &LayoutItemConstraints,
+    component: InstanceRef,
+    layout_tree: &'b mut Vec<LayoutTreeItem<'a>>,
+) -> LayoutInfo {
+    let expr_eval =
+        |expr| eval::eval_expression(expr, component, &mut Default::default()).try_into().unwrap();
+    let mut layout_info = item.get_layout_info(component, layout_tree);
+    constraints.minimum_width.as_ref().map(|e| layout_info.min_width = expr_eval(e));
+    constraints.maximum_width.as_ref().map(|e| layout_info.max_width = expr_eval(e));
+    constraints.minimum_height.as_ref().map(|e| layout_info.min_height = expr_eval(e));
+    constraints.maximum_height.as_ref().map(|e| layout_info.max_height = expr_eval(e));
+    constraints.horizontal_stretch.as_ref().map(|e| layout_info.horizontal_stretch = expr_eval(e));
+    constraints.vertical_stretch.as_ref().map(|e| layout_info.vertical_stretch = expr_eval(e));
+    layout_info
+}
+
+/// Push a BoxLayoutCellData in `cells` for each instance of the repeated element, and its
+/// function in `height_for_width`
+fn push_repeated_box_layout_cells<'a>(
+    elem: &ElementRc,
+    component: InstanceRef,
+    cells: &mut Vec<BoxLayoutCellData<'a>>,
+    height_for_width: &mut Vec<Option<HeightForWidthFn<'a>>>,
+) {
+    let root_element = elem.borrow().base_type.as_component().root_element.clone();
+    let is_wrapped_text = sixtyfps_compilerlib::layout::is_wrapped_text(&root_element);
+    let rep_index = component.component_type.repeater_names[elem.borrow().id.as_str()];
+    generativity::make_guard!(guard);
+    let rep_in_comp = component.component_type.repeater[rep_index].unerase(guard);
+    // The layout may be computed before the repeater was ever visited
+    ensure_repeater_updated(component, rep_in_comp);
+    let vec = rep_in_comp.offset.apply(&*component.instance).borrow();
+    for sub_comp in vec.iter() {
+        let sub_instance = sub_comp.borrow_instance();
+        let item_info = &sub_instance.component_type.items[root_element.borrow().id.as_str()];
+        let get_prop = |name| {
+            item_info.rtti.properties.get(name).map(|p| unsafe {
+                &*(sub_instance.as_ptr().add(item_info.offset).add(p.offset())
+                    as *const Property<f32>)
+            })
+        };
+        let mut layout_info = unsafe {
+            item_info.item_from_component(sub_instance.as_ptr()).as_ref().layouting_info()
+        };
+        let constraint = |name: &str, value: &mut f32| {
+            if root_element.borrow().property_declarations.contains_key(name) {
+                let nr = expression_tree::NamedReference {
+                    element: Rc::downgrade(&root_element),
+                    name: name.into(),
+                };
+                *value = eval::eval_expression(
+                    &expression_tree::Expression::PropertyReference(nr),
+                    sub_instance,
+                    &mut Default::default(),
+                )
+                .try_into()
+                .unwrap_or_default();
+            }
+        };
+        constraint("minimum_width", &mut layout_info.min_width);
+        constraint("maximum_width", &mut layout_info.max_width);
+        constraint("minimum_height", &mut layout_info.min_height);
+        constraint("maximum_height", &mut layout_info.max_height);
+        constraint("horizontal_stretch", &mut layout_info.horizontal_stretch);
+        constraint("vertical_stretch", &mut layout_info.vertical_stretch);
+        height_for_width.push(if is_wrapped_text {
+            // The Text lives as long as the instance, like the properties of the cells
+            let text = unsafe {
+                Pin::new_unchecked(
+                    &*(sub_instance.as_ptr().add(item_info.offset)
+                        as *const sixtyfps_corelib::items::Text),
+                )
+            };
+            Some(Rc::new(move |width| text.height_for_width(width)) as HeightForWidthFn)
+        } else {
+            None
+        });
+        cells.push(BoxLayoutCellData {
+            x: get_prop("x"),
+            y: get_prop("y"),
+            width: get_prop("width"),
+            height: get_prop("height"),
+            constraint: layout_info,
+            // Set from `height_for_width` by `BoxLayoutWithCells::with_cells`
+            height_for_width: Default::default(),
+        });
+    }
+}
+
+/// Returns a function that computes the minimum height of the item for a given width, or None
+/// if the height of the item does not depend on its width
+fn item_height_for_width<'a>(
+    item: &LayoutItem,
+    component: InstanceRef,
+    layout_tree: &[LayoutTreeItem<'a>],
+) -> Option<HeightForWidthFn<'a>> {
+    if !item.has_height_for_width() {
+        return None;
+    }
+    match (item, item.layout()) {
+        (LayoutItem::Element(LayoutElement { element, .. }), None) => {
+            // This is a Text element, which lives as long as the component, like the properties
+            // of the cells
+            let item_info = &component.component_type.items[element.borrow().id.as_str()];
+            let text = unsafe {
+                Pin::new_unchecked(
+                    &*(component.as_ptr().add(item_info.offset)
+                        as *const sixtyfps_corelib::items::Text),
+                )
+            };
+            Some(Rc::new(move |width| text.height_for_width(width)))
+        }
+        (_, layout) => {
+            let layout = layout?;
+            layout_tree
+                .iter()
+                .find(|tree_item| match (tree_item, layout) {
+                    (LayoutTreeItem::GridLayout(grid_layout), Layout::GridLayout(layout)) => {
+                        std::ptr::eq(grid_layout.grid, layout)
+                    }
+                    (LayoutTreeItem::BoxLayout(box_layout), Layout::BoxLayout(layout)) => {
+                        std::ptr::eq(box_layout.layout, layout)
+                    }
+                    _ => false,
+                })?
+                .height_for_width()
+        }
+    }
+}
+
 fn collect_layouts_recursively<'a, 'b>(
     layout_tree: &'b mut Vec<LayoutTreeItem<'a>>,
     layout: &'a Layout,
     component: InstanceRef,
 ) -> &'b LayoutTreeItem<'a> {
+    let expr_eval =
+        |expr| eval::eval_expression(expr, component, &mut Default::default()).try_into().unwrap();
+    let eval_padding = |padding: &sixtyfps_compilerlib::layout::Padding| Padding {
+        left: padding.left.as_ref().map_or(0., expr_eval),
+        right: padding.right.as_ref().map_or(0., expr_eval),
+        top: padding.top.as_ref().map_or(0., expr_eval),
+        bottom: padding.bottom.as_ref().map_or(0., expr_eval),
+    };
     match layout {
         Layout::GridLayout(grid_layout) => {
-            let expr_eval = |expr| {
-                eval::eval_expression(expr, component, &mut Default::default()).try_into().unwrap()
-            };
+            let mut height_for_width = Vec::with_capacity(grid_layout.elems.len());
             let cells = grid_layout
                 .elems
                 .iter()
                 .map(|cell| {
                     let get_prop = |name| cell.item.get_property_ref(component, name);
-                    let mut layout_info = cell.item.get_layout_info(component, layout_tree);
-                    cell.minimum_width.as_ref().map(|e| layout_info.min_width = expr_eval(e));
-                    cell.maximum_width.as_ref().map(|e| layout_info.max_width = expr_eval(e));
-                    cell.minimum_height.as_ref().map(|e| layout_info.min_height = expr_eval(e));
-                    cell.maximum_height.as_ref().map(|e| layout_info.max_height = expr_eval(e));
+                    let layout_info = layout_info_with_constraints(
+                        &cell.item,
+                        &cell.constraints,
+                        component,
+                        layout_tree,
+                    );
+                    let alignment = |expr: &Option<Box<expression_tree::Expression>>| {
+                        expr.as_ref().map_or(CellAlignment::stretch, |e| {
+                            eval::eval_expression(e, component, &mut Default::default())
+                                .try_into()
+                                .unwrap()
+                        })
+                    };
+                    height_for_width.push(item_height_for_width(
+                        &cell.item,
+                        component,
+                        layout_tree,
+                    ));
 
                     GridLayoutCellData {
                         x: get_prop("x"),
@@ -890,18 +1465,77 @@ fn collect_layouts_recursively<'a, 'b>(
                         colspan: cell.colspan,
                         rowspan: cell.rowspan,
                         constraint: layout_info,
+                        horizontal_alignment: alignment(&cell.horizontal_alignment),
+                        vertical_alignment: alignment(&cell.vertical_alignment),
+                        // Set from `height_for_width` by `GridLayoutWithCells::with_cells`
+                        height_for_width: Default::default(),
                     }
                 })
                 .collect();
             let spacing = grid_layout.spacing.as_ref().map_or(0., expr_eval);
-            let padding = Padding {
-                left: grid_layout.padding.left.as_ref().map_or(0., expr_eval),
-                right: grid_layout.padding.right.as_ref().map_or(0., expr_eval),
-                top: grid_layout.padding.top.as_ref().map_or(0., expr_eval),
-                bottom: grid_layout.padding.bottom.as_ref().map_or(0., expr_eval),
-            };
-            layout_tree
-                .push(GridLayoutWithCells { grid: grid_layout, cells, spacing, padding }.into());
+            let padding = eval_padding(&grid_layout.padding);
+            layout_tree.push(
+                GridLayoutWithCells {
+                    grid: grid_layout,
+                    cells,
+                    spacing,
+                    padding,
+                    height_for_width,
+                }
+                .into(),
+            );
+        }
+        Layout::BoxLayout(box_layout) => {
+            let mut cells = Vec::with_capacity(box_layout.elems.len());
+            let mut height_for_width = Vec::with_capacity(box_layout.elems.len());
+            for cell in &box_layout.elems {
+                if let Some(elem) = cell.repeated_element() {
+                    push_repeated_box_layout_cells(
+                        elem,
+                        component,
+                        &mut cells,
+                        &mut height_for_width,
+                    );
+                    continue;
+                }
+                let get_prop = |name| cell.item.get_property_ref(component, name);
+                let layout_info = layout_info_with_constraints(
+                    &cell.item,
+                    &cell.constraints,
+                    component,
+                    layout_tree,
+                );
+                height_for_width.push(item_height_for_width(&cell.item, component, layout_tree));
+                cells.push(BoxLayoutCellData {
+                    x: get_prop("x"),
+                    y: get_prop("y"),
+                    width: get_prop("width"),
+                    height: get_prop("height"),
+                    constraint: layout_info,
+                    // Set from `height_for_width` by `BoxLayoutWithCells::with_cells`
+                    height_for_width: Default::default(),
+                });
+            }
+            let spacing = box_layout.spacing.as_ref().map_or(0., expr_eval);
+            let padding = eval_padding(&box_layout.padding);
+            let alignment = box_layout.alignment.as_ref().map_or(LayoutAlignment::stretch, |e| {
+                eval::eval_expression(e, component, &mut Default::default())
+                    .try_into()
+                    .unwrap_or_default()
+            });
+            let width = expr_eval(&*box_layout.rect.width_reference);
+            layout_tree.push(
+                BoxLayoutWithCells {
+                    layout: box_layout,
+                    cells,
+                    spacing,
+                    padding,
+                    alignment,
+                    width,
+                    height_for_width,
+                }
+                .into(),
+            );
         }
         Layout::PathLayout(layout) => layout_tree.push(layout.into()),
     }
@@ -917,7 +1551,7 @@ impl<'a> LayoutTreeItem<'a> {
         };
 
         match self {
-            Self::GridLayout(grid_layout) => {
+            Self::GridLayout(grid_layout) => grid_layout.with_cells(|cells| {
                 solve_grid_layout(&GridLayoutData {
                     width: resolve_prop_ref(&grid_layout.grid.rect.width_reference),
                     height: resolve_prop_ref(&grid_layout.grid.rect.height_reference),
@@ -925,9 +1559,26 @@ impl<'a> LayoutTreeItem<'a> {
                     y: resolve_prop_ref(&grid_layout.grid.rect.y_reference),
                     spacing: grid_layout.spacing,
                     padding: &grid_layout.padding,
-                    cells: Slice::from(grid_layout.cells.as_slice()),
-                });
-            }
+                    cells: Slice::from(cells),
+                })
+            }),
+            Self::BoxLayout(box_layout) => box_layout.with_cells(|cells| {
+                let data = BoxLayoutData {
+                    width: resolve_prop_ref(&box_layout.layout.rect.width_reference),
+                    height: resolve_prop_ref(&box_layout.layout.rect.height_reference),
+                    x: resolve_prop_ref(&box_layout.layout.rect.x_reference),
+                    y: resolve_prop_ref(&box_layout.layout.rect.y_reference),
+                    spacing: box_layout.spacing,
+                    padding: &box_layout.padding,
+                    alignment: box_layout.alignment,
+                    cells: Slice::from(cells),
+                };
+                if box_layout.layout.wrap {
+                    solve_flow_layout(&data);
+                } else {
+                    solve_box_layout(&data, box_layout.layout.is_horizontal);
+                }
+            }),
             Self::PathLayout(path_layout) => {
                 use sixtyfps_corelib::layout::*;
 
@@ -1004,33 +1655,92 @@ extern "C" fn input_event(
     let instance = unsafe { Pin::new_unchecked(&*component.as_ptr().cast::<Instance>()) };
     let extra_data = component_type.extra_data_offset.apply(&*instance);
 
-    let mouse_grabber = extra_data.mouse_grabber.get();
-    let (status, new_grab) = if let Some((item_index, rep_index)) = mouse_grabber.aborted_indexes()
+    // Clicking on an item gives it the focus. Only the root component receives the mouse press,
+    // the repeated components get the events forwarded by their parent.
+    if component_type.parent_component_offset.is_none()
+        && matches!(mouse_event.what, sixtyfps_corelib::input::MouseEventType::MousePressed)
     {
+        generativity::make_guard!(guard);
+        let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
+        if let Some(window) = eval::window_ref(instance_ref) {
+            window.set_focus_item_at(component, mouse_event.pos);
+        }
+    }
+
+    if matches!(mouse_event.what, sixtyfps_corelib::input::MouseEventType::MouseWheel) {
+        // The wheel goes to the item under the cursor, regardless of the mouse grab
+        return sixtyfps_corelib::input::process_ungrabbed_mouse_event(component, mouse_event).0;
+    }
+
+    use sixtyfps_corelib::input::{InputEventResult, MouseEvent, MouseEventType};
+    let send_to_item = |item_index: usize, rep_index: usize, mouse_event: MouseEvent| {
         let tree = &component_type.item_tree;
         let offset = sixtyfps_corelib::item_tree::item_offset(instance, tree, item_index);
         let mut event = mouse_event.clone();
         event.pos -= offset.to_vector();
-        let res = match tree[item_index] {
+        match tree[item_index] {
             ItemTreeNode::Item { item, .. } => item.apply_pin(instance).as_ref().input_event(event),
             ItemTreeNode::DynamicTree { index } => {
                 generativity::make_guard!(guard);
                 let rep_in_comp = &component_type.repeater[index].unerase(guard);
                 let vec = rep_in_comp.offset.apply(&*instance).borrow();
-                vec[rep_index].borrow().as_ref().input_event(event)
+                // The model may have changed since the component grabbed the mouse
+                match vec.get(rep_index) {
+                    Some(c) => c.borrow().as_ref().input_event(event),
+                    None => InputEventResult::EventIgnored,
+                }
             }
-        };
-        match res {
-            sixtyfps_corelib::input::InputEventResult::GrabMouse => (res, mouse_grabber),
-            _ => (res, VisitChildrenResult::CONTINUE),
         }
-    } else {
-        sixtyfps_corelib::input::process_ungrabbed_mouse_event(component, mouse_event)
     };
-    extra_data.mouse_grabber.set(new_grab);
+
+    let mouse_grabber = extra_data.mouse_grabber.get();
+    if let Some((item_index, rep_index)) = mouse_grabber.aborted_indexes() {
+        let res = send_to_item(item_index, rep_index, mouse_event);
+        extra_data.mouse_hover.set(mouse_grabber);
+        if res != InputEventResult::GrabMouse {
+            extra_data.mouse_grabber.set(VisitChildrenResult::CONTINUE);
+        }
+        return res;
+    }
+    let old_hover = extra_data.mouse_hover.get();
+    if matches!(mouse_event.what, MouseEventType::MouseExit) {
+        // Only the hovered item needs to know that the mouse left the component
+        extra_data.mouse_hover.set(VisitChildrenResult::CONTINUE);
+        return match old_hover.aborted_indexes() {
+            Some((item_index, rep_index)) => send_to_item(item_index, rep_index, mouse_event),
+            None => InputEventResult::EventIgnored,
+        };
+    }
+    let (status, item) =
+        sixtyfps_corelib::input::process_ungrabbed_mouse_event(component, mouse_event);
+    if item != old_hover {
+        if let Some((item_index, rep_index)) = old_hover.aborted_indexes() {
+            send_to_item(
+                item_index,
+                rep_index,
+                MouseEvent::new(mouse_event.pos, MouseEventType::MouseExit),
+            );
+        }
+    }
+    extra_data.mouse_hover.set(item);
+    if status == InputEventResult::GrabMouse {
+        extra_data.mouse_grabber.set(item);
+    }
     status
 }
 
+extern "C" fn key_event(
+    component: ComponentRefPin,
+    key_event: &sixtyfps_corelib::input::KeyEvent,
+) -> sixtyfps_corelib::input::KeyEventResult {
+    generativity::make_guard!(guard);
+    let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
+    match eval::window_ref(instance_ref) {
+        Some(window) => window.dispatch_key_event(eval::root_component(instance_ref), key_event),
+        None => sixtyfps_corelib::input::KeyEventResult::EventIgnored,
+    }
+}
+
 extern "C" fn compute_layout(component: ComponentRefPin) {
     generativity::make_guard!(guard);
     // This is fine since we can only be called with a component that with our vtable which is a ComponentDescription
@@ -1078,6 +1788,15 @@ impl<'a, 'id> InstanceRef<'a, 'id> {
         (&*self.instance.as_ref()) as *const Instance as *const u8
     }
 
+    pub fn borrow(&self) -> ComponentRefPin<'a> {
+        unsafe {
+            Pin::new_unchecked(vtable::VRef::from_raw(
+                NonNull::from(&self.component_type.ct).cast(),
+                NonNull::from(self.instance.get_ref()).cast(),
+            ))
+        }
+    }
+
     pub fn as_ref(&self) -> &Instance<'id> {
         &*self.instance
     }
@@ -17,8 +17,8 @@ use sixtyfps_compilerlib::expression_tree::{
 use sixtyfps_compilerlib::{object_tree::ElementRc, typeregister::Type};
 use sixtyfps_corelib as corelib;
 use sixtyfps_corelib::{
-    graphics::PathElement, items::ItemRef, items::PropertyAnimation, Color, PathData, Resource,
-    SharedArray, SharedString,
+    graphics::PathElement, items::ItemRef, items::PropertyAnimation, rtti::AnimatedBindingKind,
+    Color, PathData, Resource, SharedArray, SharedString,
 };
 use std::{collections::HashMap, rc::Rc};
 
@@ -29,9 +29,13 @@ pub trait ErasedPropertyInfo {
         &self,
         item: Pin<ItemRef>,
         binding: Box<dyn Fn() -> Value>,
-        animation: Option<PropertyAnimation>,
+        animation: AnimatedBindingKind,
     );
     fn offset(&self) -> usize;
+
+    /// Safety: Property2 must be a pinned pointer to a Property of the same type
+    #[allow(unsafe_code)]
+    unsafe fn link_two_ways(&self, item: Pin<ItemRef>, property2: *const ());
 }
 
 impl<Item: vtable::HasStaticVTable<corelib::items::ItemVTable>> ErasedPropertyInfo
@@ -47,13 +51,18 @@ impl<Item: vtable::HasStaticVTable<corelib::items::ItemVTable>> ErasedPropertyIn
         &self,
         item: Pin<ItemRef>,
         binding: Box<dyn Fn() -> Value>,
-        animation: Option<PropertyAnimation>,
+        animation: AnimatedBindingKind,
     ) {
         (*self).set_binding(ItemRef::downcast_pin(item).unwrap(), binding, animation).unwrap();
     }
     fn offset(&self) -> usize {
         (*self).offset()
     }
+    #[allow(unsafe_code)]
+    unsafe fn link_two_ways(&self, item: Pin<ItemRef>, property2: *const ()) {
+        // Safety: ErasedPropertyInfo::link_two_ways and PropertyInfo::link_two_ways have the same safety requirement
+        (*self).link_two_ways(ItemRef::downcast_pin(item).unwrap(), property2)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -159,11 +168,25 @@ macro_rules! declare_value_enum_conversion {
 
 declare_value_enum_conversion!(corelib::items::TextHorizontalAlignment, TextHorizontalAlignment);
 declare_value_enum_conversion!(corelib::items::TextVerticalAlignment, TextVerticalAlignment);
+declare_value_enum_conversion!(corelib::items::TextWrap, TextWrap);
+declare_value_enum_conversion!(corelib::items::TextOverflow, TextOverflow);
+declare_value_enum_conversion!(corelib::items::MouseCursor, MouseCursor);
+declare_value_enum_conversion!(corelib::items::PointerButton, PointerButton);
+declare_value_enum_conversion!(corelib::layout::LayoutAlignment, LayoutAlignment);
+declare_value_enum_conversion!(corelib::layout::CellAlignment, CellAlignment);
 
 /// The local variable needed for binding evaluation
 #[derive(Default)]
 pub struct EvalLocalContext {
     local_variables: HashMap<String, Value>,
+    function_arguments: Vec<Value>,
+}
+
+impl EvalLocalContext {
+    /// Create a context for a function and passing the arguments
+    pub fn from_function_arguments(function_arguments: Vec<Value>) -> Self {
+        Self { function_arguments, ..Default::default() }
+    }
 }
 
 /// Evaluate an expression and return a Value as the result of this expression
@@ -175,16 +198,24 @@ pub fn eval_expression(
     match e {
         Expression::Invalid => panic!("invalid expression while evaluating"),
         Expression::Uncompiled(_) => panic!("uncompiled expression while evaluating"),
+        Expression::TwoWayBinding(..) => panic!("invalid expression while evaluating"),
         Expression::StringLiteral(s) => Value::String(s.as_str().into()),
         Expression::NumberLiteral(n, unit) => Value::Number(unit.normalize(*n)),
         Expression::BoolLiteral(b) => Value::Bool(*b),
         Expression::SignalReference { .. } => panic!("signal in expression"),
+        Expression::FunctionReference { .. } => panic!("function reference in expression"),
+        Expression::ElementReference(_) => {
+            panic!("element references are only valid as function arguments")
+        }
         Expression::BuiltinFunctionReference(_) => panic!(
             "naked builtin function reference not allowed, should be handled by function call"
         ),
         Expression::PropertyReference(NamedReference { element, name }) => {
             load_property(component, &element.upgrade().unwrap(), name.as_ref())
         }
+        Expression::FunctionParameterReference { index, .. } => {
+            local_context.function_arguments[*index].clone()
+        }
         Expression::RepeaterIndexReference { element } => load_property(
             component,
             &element.upgrade().unwrap().borrow().base_type.as_component().root_element,
@@ -220,9 +251,16 @@ pub fn eval_expression(
             }
             v
         }
-        Expression::FunctionCall { function, .. } => {
+        Expression::FunctionCall { function, arguments } => {
             if let Expression::SignalReference(NamedReference { element, name }) = &**function {
                 let element = element.upgrade().unwrap();
+                let args = arguments
+                    .iter()
+                    .map(|e| eval_expression(e, component, local_context))
+                    .collect::<Vec<_>>();
+                if let Some(global) = global_for_element(&element, component) {
+                    return global.signals[name.as_str()].emit(args.as_slice());
+                }
                 generativity::make_guard!(guard);
                 let enclosing_component =
                     enclosing_component_for_element(&element, component, guard);
@@ -230,25 +268,63 @@ pub fn eval_expression(
 
                 let item_info = &component_type.items[element.borrow().id.as_str()];
                 let item = unsafe { item_info.item_from_component(enclosing_component.as_ptr()) };
-                let signal = item_info
-                    .rtti
-                    .signals
-                    .get(name.as_str())
-                    .map(|o| unsafe { &*(item.as_ptr().add(*o) as *const corelib::Signal<()>) })
-                    .or_else(|| {
-                        component_type
-                            .custom_signals
-                            .get(name.as_str())
-                            .map(|o| o.apply(&*enclosing_component.instance))
-                    })
-                    .unwrap_or_else(|| panic!("unkown signal {}", name));
-                signal.emit(());
-                Value::Void
+                if let Some(signal_offset) = item_info.rtti.signals.get(name.as_str()) {
+                    let signal = unsafe {
+                        &*(item.as_ptr().add(*signal_offset) as *const corelib::Signal<()>)
+                    };
+                    signal.emit(&());
+                    Value::Void
+                } else if let Some(signal_offset) = component_type.custom_signals.get(name.as_str())
+                {
+                    let signal = signal_offset.apply(&*enclosing_component.instance);
+                    signal.emit(args.as_slice())
+                } else {
+                    panic!("unkown signal {}", name)
+                }
+            } else if let Expression::FunctionReference(NamedReference { element, name }) =
+                &**function
+            {
+                let element = element.upgrade().unwrap();
+                let args = arguments
+                    .iter()
+                    .map(|e| eval_expression(e, component, local_context))
+                    .collect::<Vec<_>>();
+                let mut function_context = EvalLocalContext::from_function_arguments(args);
+                let body = element.borrow().bindings[name.as_str()].expression.clone();
+                if element.borrow().enclosing_component.upgrade().unwrap().is_global {
+                    // The body only refers to the properties of the global
+                    return eval_expression(&body, component, &mut function_context);
+                }
+                generativity::make_guard!(guard);
+                let enclosing_component =
+                    enclosing_component_for_element(&element, component, guard);
+                eval_expression(&body, enclosing_component, &mut function_context)
             } else if let Expression::BuiltinFunctionReference(funcref) = &**function {
                 match funcref {
                     BuiltinFunction::GetWindowScaleFactor => {
                         Value::Number(window_ref(component).unwrap().scale_factor() as _)
                     }
+                    BuiltinFunction::SetFocusItem => {
+                        let focus_item = match arguments.as_slice() {
+                            [Expression::ElementReference(focus_item)] => {
+                                focus_item.upgrade().unwrap()
+                            }
+                            _ => {
+                                panic!("internal error: SetFocusItem takes an element as argument")
+                            }
+                        };
+                        generativity::make_guard!(guard);
+                        let enclosing_component =
+                            enclosing_component_for_element(&focus_item, component, guard);
+                        let item_info = &enclosing_component.component_type.items
+                            [focus_item.borrow().id.as_str()];
+                        let item =
+                            unsafe { item_info.item_from_component(enclosing_component.as_ptr()) };
+                        window_ref(component)
+                            .unwrap()
+                            .set_focus_item(root_component(component), item);
+                        Value::Void
+                    }
                 }
             } else {
                 panic!("call of something not a signal")
@@ -269,6 +345,11 @@ pub fn eval_expression(
                 };
 
                 let element = element.upgrade().unwrap();
+                if let Some(global) = global_for_element(&element, component) {
+                    let p = &global.properties[name.as_str()];
+                    p.set(eval(p.as_ref().get()));
+                    return Value::Void;
+                }
                 generativity::make_guard!(guard);
                 let enclosing_component =
                     enclosing_component_for_element(&element, component, guard);
@@ -360,6 +441,13 @@ pub fn eval_expression(
             EasingCurve::CubicBezier(a, b, c, d) => {
                 corelib::animations::EasingCurve::CubicBezier([*a, *b, *c, *d])
             }
+            EasingCurve::EaseInElastic => corelib::animations::EasingCurve::EaseInElastic,
+            EasingCurve::EaseOutElastic => corelib::animations::EasingCurve::EaseOutElastic,
+            EasingCurve::EaseInOutElastic => corelib::animations::EasingCurve::EaseInOutElastic,
+            EasingCurve::EaseInBounce => corelib::animations::EasingCurve::EaseInBounce,
+            EasingCurve::EaseOutBounce => corelib::animations::EasingCurve::EaseOutBounce,
+            EasingCurve::EaseInOutBounce => corelib::animations::EasingCurve::EaseInOutBounce,
+            EasingCurve::Steps(steps) => corelib::animations::EasingCurve::Steps(*steps),
         }),
         Expression::EnumerationValue(value) => {
             Value::EnumerationValue(value.enumeration.name.clone(), value.to_string())
@@ -368,6 +456,9 @@ pub fn eval_expression(
 }
 
 fn load_property(component: InstanceRef, element: &ElementRc, name: &str) -> Value {
+    if let Some(global) = global_for_element(element, component) {
+        return global.properties[name].as_ref().get();
+    }
     generativity::make_guard!(guard);
     let enclosing_component = enclosing_component_for_element(&element, component, guard);
     let element = element.borrow();
@@ -390,6 +481,27 @@ fn load_property(component: InstanceRef, element: &ElementRc, name: &str) -> Val
     item_info.rtti.properties[name].get(item)
 }
 
+/// Return a pointer to the Property that holds the given property of the element.
+/// This is used to link two way bindings.
+pub(crate) fn property_ptr(component: InstanceRef, element: &ElementRc, name: &str) -> *const () {
+    generativity::make_guard!(guard);
+    let enclosing_component = enclosing_component_for_element(&element, component, guard);
+    let element = element.borrow();
+    if element.id == element.enclosing_component.upgrade().unwrap().root_element.borrow().id {
+        if let Some(x) = enclosing_component.component_type.custom_properties.get(name) {
+            return unsafe { enclosing_component.as_ptr().add(x.offset) as *const () };
+        }
+    };
+    let item_info = enclosing_component
+        .component_type
+        .items
+        .get(element.id.as_str())
+        .unwrap_or_else(|| panic!("Unkown element for {}.{}", element.id, name));
+    core::mem::drop(element);
+    let item = unsafe { item_info.item_from_component(enclosing_component.as_ptr()) };
+    unsafe { item.as_ptr().add(item_info.rtti.properties[name].offset()) as *const () }
+}
+
 pub fn window_ref(component: InstanceRef) -> Option<sixtyfps_corelib::eventloop::ComponentWindow> {
     if let Some(parent_offset) = component.component_type.parent_component_offset {
         let parent_component =
@@ -412,6 +524,40 @@ pub fn window_ref(component: InstanceRef) -> Option<sixtyfps_corelib::eventloop:
     }
 }
 
+/// Return the root component of the tree of components `component` is part of
+pub fn root_component<'a>(
+    component: InstanceRef<'a, '_>,
+) -> corelib::component::ComponentRefPin<'a> {
+    if let Some(parent_offset) = component.component_type.parent_component_offset {
+        let parent_component = parent_offset.apply(component.as_ref()).unwrap();
+        generativity::make_guard!(guard);
+        let parent_instance = unsafe { InstanceRef::from_pin_ref(parent_component, guard) };
+        let parent_instance = unsafe {
+            core::mem::transmute::<InstanceRef, InstanceRef<'a, 'static>>(parent_instance)
+        };
+        root_component(parent_instance)
+    } else {
+        component.borrow()
+    }
+}
+
+/// If the element is the root element of a global, return the instance of that global owned by
+/// the root component
+fn global_for_element(
+    element: &ElementRc,
+    component: InstanceRef,
+) -> Option<Rc<crate::dynamic_component::GlobalComponent>> {
+    let global = element.borrow().enclosing_component.upgrade().unwrap();
+    if !global.is_global {
+        return None;
+    }
+    generativity::make_guard!(guard);
+    let root = unsafe { InstanceRef::from_pin_ref(root_component(component), guard) };
+    let extra_data = root.component_type.extra_data_offset.apply(root.as_ref());
+    let global_component = extra_data.globals.borrow().get(&global.id).cloned();
+    Some(global_component.unwrap_or_else(|| panic!("Global {} not instantiated", global.id)))
+}
+
 fn enclosing_component_for_element<'a, 'old_id, 'new_id>(
     element: &'a ElementRc,
     component: InstanceRef<'a, 'old_id>,
@@ -100,7 +100,11 @@ impl<'id> dynamic_component::ComponentDescription<'id> {
         let x = self.custom_properties.get(name).ok_or(())?;
         unsafe {
             x.prop
-                .set_binding(Pin::new_unchecked(&*component.as_ptr().add(x.offset)), binding, None)
+                .set_binding(
+                    Pin::new_unchecked(&*component.as_ptr().add(x.offset)),
+                    binding,
+                    sixtyfps_corelib::rtti::AnimatedBindingKind::NotAnimated,
+                )
                 .unwrap()
         };
         Ok(())
@@ -118,6 +122,46 @@ impl<'id> dynamic_component::ComponentDescription<'id> {
         unsafe { x.prop.get(Pin::new_unchecked(&*component.as_ptr().add(x.offset))) }
     }
 
+    /// Set a value to a property of a global component.
+    ///
+    /// Returns an error if the component is not a root instance corresponding to this
+    /// ComponentDescription, or if the global or the property does not exist
+    pub fn set_global_property(
+        &self,
+        component: ComponentRefPin,
+        global: &str,
+        name: &str,
+        value: Value,
+    ) -> Result<(), ()> {
+        if !core::ptr::eq((&self.ct) as *const _, component.get_vtable() as *const _) {
+            return Err(());
+        }
+        let instance = unsafe { &*(component.as_ptr() as *const dynamic_type::Instance) };
+        let globals = self.extra_data_offset.apply(instance).globals.borrow();
+        let prop = globals.get(global).and_then(|g| g.properties.get(name)).ok_or(())?;
+        prop.set(value);
+        Ok(())
+    }
+
+    /// Return the value of a property of a global component
+    ///
+    /// Returns an error if the component is not a root instance corresponding to this
+    /// ComponentDescription, or if the global or the property does not exist
+    pub fn get_global_property(
+        &self,
+        component: ComponentRefPin,
+        global: &str,
+        name: &str,
+    ) -> Result<Value, ()> {
+        if !core::ptr::eq((&self.ct) as *const _, component.get_vtable() as *const _) {
+            return Err(());
+        }
+        let instance = unsafe { &*(component.as_ptr() as *const dynamic_type::Instance) };
+        let globals = self.extra_data_offset.apply(instance).globals.borrow();
+        let prop = globals.get(global).and_then(|g| g.properties.get(name)).ok_or(())?;
+        Ok(prop.as_ref().get())
+    }
+
     /// Sets an handler for a signal
     ///
     /// Returns an error if the component is not an instance corresponding to this ComponentDescription,
@@ -126,7 +170,7 @@ impl<'id> dynamic_component::ComponentDescription<'id> {
         &self,
         component: Pin<ComponentRef>,
         name: &str,
-        handler: Box<dyn Fn(())>,
+        handler: Box<dyn Fn(&[Value]) -> Value>,
     ) -> Result<(), ()> {
         if !core::ptr::eq((&self.ct) as *const _, component.get_vtable() as *const _) {
             return Err(());
@@ -141,14 +185,18 @@ impl<'id> dynamic_component::ComponentDescription<'id> {
     ///
     /// Returns an error if the component is not an instance corresponding to this ComponentDescription,
     /// or if the signal with this name does not exist in this component
-    pub fn emit_signal(&self, component: ComponentRefPin, name: &str) -> Result<(), ()> {
+    pub fn emit_signal(
+        &self,
+        component: ComponentRefPin,
+        name: &str,
+        args: &[Value],
+    ) -> Result<Value, ()> {
         if !core::ptr::eq((&self.ct) as *const _, component.get_vtable() as *const _) {
             return Err(());
         }
         let x = self.custom_signals.get(name).ok_or(())?;
         let sig = x.apply(unsafe { &*(component.as_ptr() as *const dynamic_type::Instance) });
-        sig.emit(());
-        Ok(())
+        Ok(sig.emit(args))
     }
 }
 
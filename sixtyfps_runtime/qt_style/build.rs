@@ -54,7 +54,12 @@ fn run_cbindgen() {
         language: cbindgen::Language::Cxx,
         cpp_compat: true,
         documentation: true,
-        export: cbindgen::ExportConfig { ..Default::default() },
+        export: cbindgen::ExportConfig {
+            // cbindgen drops the `()` from `Signal<()>`, and the C++ Signal is a template whose
+            // default argument is `void()`
+            rename: [("Signal".into(), "Signal<>".into())].iter().cloned().collect(),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
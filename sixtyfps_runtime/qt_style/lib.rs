@@ -15,7 +15,10 @@ use core::pin::Pin;
 #[cfg(have_qt)]
 use cpp::cpp;
 use sixtyfps_corelib::graphics::{HighLevelRenderingPrimitive, Rect, RenderingVariable, Resource};
-use sixtyfps_corelib::input::{InputEventResult, MouseEvent, MouseEventType};
+use sixtyfps_corelib::input::{
+    FocusEvent, FocusEventResult, InputEventResult, KeyEvent, KeyEventResult, MouseEvent,
+    MouseEventType,
+};
 use sixtyfps_corelib::item_rendering::CachedRenderingData;
 use sixtyfps_corelib::items::{Item, ItemConsts, ItemVTable};
 use sixtyfps_corelib::layout::LayoutInfo;
@@ -147,7 +150,7 @@ impl Item for QtStyleButton {
         Self::FIELD_OFFSETS.pressed.apply_pin(self).set(match event.what {
             MouseEventType::MousePressed => true,
             MouseEventType::MouseExit | MouseEventType::MouseReleased => false,
-            MouseEventType::MouseMoved => {
+            MouseEventType::MouseMoved | MouseEventType::MouseWheel => {
                 return if Self::FIELD_OFFSETS.pressed.apply_pin(self).get() {
                     InputEventResult::GrabMouse
                 } else {
@@ -156,12 +159,20 @@ impl Item for QtStyleButton {
             }
         });
         if matches!(event.what, MouseEventType::MouseReleased) {
-            Self::FIELD_OFFSETS.clicked.apply_pin(self).emit(());
+            Self::FIELD_OFFSETS.clicked.apply_pin(self).emit(&());
             InputEventResult::EventAccepted
         } else {
             InputEventResult::GrabMouse
         }
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for QtStyleButton {
@@ -257,15 +268,26 @@ impl Item for QtStyleCheckBox {
     }
 
     fn input_event(self: Pin<&Self>, event: MouseEvent) -> InputEventResult {
+        if matches!(event.what, MouseEventType::MouseWheel) {
+            return InputEventResult::EventIgnored;
+        }
         if matches!(event.what, MouseEventType::MouseReleased) {
             Self::FIELD_OFFSETS
                 .checked
                 .apply_pin(self)
                 .set(!Self::FIELD_OFFSETS.checked.apply_pin(self).get());
-            Self::FIELD_OFFSETS.toggled.apply_pin(self).emit(())
+            Self::FIELD_OFFSETS.toggled.apply_pin(self).emit(&())
         }
         InputEventResult::EventAccepted
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for QtStyleCheckBox {
@@ -450,7 +472,7 @@ impl Item for QtStyleSpinBox {
                         }
                         true
                     }
-                    MouseEventType::MouseMoved => false,
+                    MouseEventType::MouseMoved | MouseEventType::MouseWheel => false,
                 };
             data.active_controls = new_control;
             if changed {
@@ -459,6 +481,14 @@ impl Item for QtStyleSpinBox {
         }
         InputEventResult::EventAccepted
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for QtStyleSpinBox {
@@ -639,6 +669,7 @@ impl Item for QtStyleSlider {
                     data.pressed_position = None;
                     InputEventResult::EventAccepted
                 }
+                MouseEventType::MouseWheel => InputEventResult::EventIgnored,
                 MouseEventType::MouseMoved => {
                     if let Some((pressed_x, _)) = data.pressed_position {
                         // FIXME: use QStyle::subControlRect to find out the actual size of the groove
@@ -657,6 +688,14 @@ impl Item for QtStyleSlider {
         }
         result
     }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
 }
 
 impl ItemConsts for QtStyleSlider {
@@ -665,3 +704,103 @@ impl ItemConsts for QtStyleSlider {
 }
 
 ItemVTable_static! { #[no_mangle] pub static QtStyleSliderVTable for QtStyleSlider }
+
+#[repr(C)]
+#[derive(FieldOffsets, Default, BuiltinItem)]
+#[pin]
+pub struct QtStyleLineEdit {
+    pub x: Property<f32>,
+    pub y: Property<f32>,
+    pub width: Property<f32>,
+    pub height: Property<f32>,
+    pub focused: Property<bool>,
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for QtStyleLineEdit {
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(
+            Self::FIELD_OFFSETS.x.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.y.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.width.apply_pin(self).get(),
+            Self::FIELD_OFFSETS.height.apply_pin(self).get(),
+        )
+    }
+    fn rendering_primitive(self: Pin<&Self>) -> HighLevelRenderingPrimitive {
+        #[cfg(have_qt)]
+        {
+            let focused: bool = Self::FIELD_OFFSETS.focused.apply_pin(self).get();
+            let size: qttypes::QSize = qttypes::QSize {
+                width: Self::FIELD_OFFSETS.width.apply_pin(self).get() as _,
+                height: Self::FIELD_OFFSETS.height.apply_pin(self).get() as _,
+            };
+
+            let img = cpp!(unsafe [
+                size as "QSize",
+                focused as "bool"
+            ] -> qttypes::QImage as "QImage" {
+                ensure_initialized();
+                QImage img(size, QImage::Format_ARGB32);
+                img.fill(Qt::transparent);
+                QPainter p(&img);
+                QStyleOptionFrame option;
+                option.rect = QRect(img.rect());
+                option.lineWidth = 1;
+                option.midLineWidth = 0;
+                option.state = QStyle::State_Enabled | QStyle::State_Sunken;
+                if (focused)
+                    option.state |= QStyle::State_HasFocus;
+                qApp->style()->drawPrimitive(QStyle::PE_PanelLineEdit, &option, &p, nullptr);
+                return img;
+            });
+            return HighLevelRenderingPrimitive::Image { source: to_resource(img) };
+        }
+        #[cfg(not(have_qt))]
+        HighLevelRenderingPrimitive::NoContents
+    }
+
+    fn rendering_variables(self: Pin<&Self>) -> SharedArray<RenderingVariable> {
+        SharedArray::from(&[])
+    }
+
+    fn layouting_info(self: Pin<&Self>) -> LayoutInfo {
+        #[cfg(have_qt)]
+        {
+            let size = cpp!(unsafe [] -> qttypes::QSize as "QSize" {
+                ensure_initialized();
+                QStyleOptionFrame option;
+                option.lineWidth = 1;
+                auto content = QSize(option.fontMetrics.averageCharWidth() * 17, option.fontMetrics.height());
+                return qApp->style()->sizeFromContents(QStyle::CT_LineEdit, &option, content, nullptr)
+                    .expandedTo(QApplication::globalStrut());
+            });
+            LayoutInfo {
+                min_width: size.width as f32,
+                min_height: size.height as f32,
+                max_height: size.height as f32,
+                ..LayoutInfo::default()
+            }
+        }
+        #[cfg(not(have_qt))]
+        LayoutInfo::default()
+    }
+
+    fn input_event(self: Pin<&Self>, _: MouseEvent) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
+}
+
+impl ItemConsts for QtStyleLineEdit {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
+        Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+ItemVTable_static! { #[no_mangle] pub static QtStyleLineEditVTable for QtStyleLineEdit }
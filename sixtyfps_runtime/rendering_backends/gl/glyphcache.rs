@@ -14,6 +14,8 @@ use collections::hash_map::HashMap;
 use itertools::Itertools;
 use sixtyfps_corelib::font::Font;
 use sixtyfps_corelib::font::FontHandle;
+use sixtyfps_corelib::font::ShapedGlyph;
+use sixtyfps_corelib::graphics::Point;
 use std::cell::RefCell;
 use std::{collections, rc::Rc};
 
@@ -54,7 +56,8 @@ impl GlyphCache {
 
 pub struct PreRenderedGlyph {
     pub glyph_allocation: Option<AtlasAllocation>,
-    pub advance: f32,
+    /// The horizontal position of the left of the glyph image, relative to the glyph origin
+    pub left: f32,
 }
 
 pub struct CachedFontGlyphs {
@@ -73,48 +76,40 @@ impl CachedFontGlyphs {
         gl: &'a Rc<glow::Context>,
         atlas: &'a mut TextureAtlas,
         text: &'a str,
-    ) -> impl Iterator<Item = &PreRenderedGlyph> + 'a {
-        let glyphs =
-            self.font.clone().string_to_glyphs(text).collect::<smallvec::SmallVec<[(_, _); 32]>>();
+    ) -> impl Iterator<Item = (ShapedGlyph, &PreRenderedGlyph)> + 'a {
+        let glyphs = self.font.shape_text(text);
 
-        glyphs.iter().for_each(|(ch, glyph)| {
-            if !self.glyphs.contains_key(&glyph) {
+        glyphs.iter().for_each(|glyph| {
+            if !self.glyphs.contains_key(&glyph.glyph_id) {
                 // ensure the glyph is cached
-                self.glyphs.insert(*glyph, self.render_glyph(gl, atlas, *ch, *glyph));
+                self.glyphs.insert(glyph.glyph_id, self.render_glyph(gl, atlas, glyph.glyph_id));
             }
         });
 
-        GlyphIter { gl_font: self, glyph_it: glyphs.into_iter().map(|(_, g)| g) }
+        GlyphIter { gl_font: self, glyph_it: (0..glyphs.len()).map(move |i| glyphs[i].clone()) }
     }
 
     fn render_glyph(
         &self,
         gl: &Rc<glow::Context>,
         atlas: &mut TextureAtlas,
-        ch: char,
         glyph_id: u32,
     ) -> PreRenderedGlyph {
-        let advance = self.font.glyph_metrics(glyph_id).advance;
-
-        let glyph_allocation = if !ch.is_whitespace() {
-            let glyph_image = self.font.rasterize_glyph(glyph_id);
-
-            Some(
-                atlas.allocate_image_in_atlas(
-                    gl,
-                    image::ImageBuffer::<_, &[u8]>::from_raw(
-                        glyph_image.width(),
-                        glyph_image.height(),
-                        &glyph_image,
-                    )
-                    .unwrap(),
-                ),
-            )
-        } else {
-            None
+        let glyph = match self.font.rasterize_glyph(glyph_id) {
+            Some(glyph) => glyph,
+            None => return PreRenderedGlyph { glyph_allocation: None, left: 0. },
         };
+        let glyph_allocation = atlas.allocate_image_in_atlas(
+            gl,
+            image::ImageBuffer::<_, &[u8]>::from_raw(
+                glyph.image.width(),
+                glyph.image.height(),
+                &glyph.image,
+            )
+            .unwrap(),
+        );
 
-        PreRenderedGlyph { glyph_allocation, advance }
+        PreRenderedGlyph { glyph_allocation: Some(glyph_allocation), left: glyph.left }
     }
 
     pub fn render_glyphs(
@@ -122,22 +117,25 @@ impl CachedFontGlyphs {
         context: &Rc<glow::Context>,
         texture_atlas: &mut TextureAtlas,
         text: &str,
+        origin: Point,
     ) -> Vec<GlyphRun> {
-        let mut x = 0.;
+        let mut x = origin.x;
 
         self.layout_glyphs(&context, texture_atlas, text)
-            .filter_map(|cached_glyph| {
-                let glyph_x = x;
-                x += cached_glyph.advance;
+            .filter_map(|(glyph, cached_glyph)| {
+                let glyph_x = x + glyph.x_offset + cached_glyph.left;
+                // The offsets of the shaping point up, while the y axis points down
+                let y = origin.y - glyph.y_offset;
+                x += glyph.x_advance;
 
                 if let Some(glyph_allocation) = &cached_glyph.glyph_allocation {
                     let glyph_width = glyph_allocation.texture_coordinates.width() as f32;
                     let glyph_height = glyph_allocation.texture_coordinates.height() as f32;
 
-                    let vertex1 = Vertex { _pos: [glyph_x, 0.] };
-                    let vertex2 = Vertex { _pos: [glyph_x + glyph_width, 0.] };
-                    let vertex3 = Vertex { _pos: [glyph_x + glyph_width, glyph_height] };
-                    let vertex4 = Vertex { _pos: [glyph_x, glyph_height] };
+                    let vertex1 = Vertex { _pos: [glyph_x, y] };
+                    let vertex2 = Vertex { _pos: [glyph_x + glyph_width, y] };
+                    let vertex3 = Vertex { _pos: [glyph_x + glyph_width, y + glyph_height] };
+                    let vertex4 = Vertex { _pos: [glyph_x, y + glyph_height] };
 
                     let vertices = [vertex1, vertex2, vertex3, vertex1, vertex3, vertex4];
                     let texture_vertices = glyph_allocation.normalized_texture_coordinates();
@@ -180,12 +178,13 @@ pub struct GlyphIter<'a, GlyphIterator> {
 
 impl<'a, GlyphIterator> Iterator for GlyphIter<'a, GlyphIterator>
 where
-    GlyphIterator: std::iter::Iterator<Item = u32>,
+    GlyphIterator: std::iter::Iterator<Item = ShapedGlyph>,
 {
-    type Item = &'a PreRenderedGlyph;
+    type Item = (ShapedGlyph, &'a PreRenderedGlyph);
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(glyph_id) = self.glyph_it.next() {
-            Some(&self.gl_font.glyphs[&glyph_id])
+        if let Some(glyph) = self.glyph_it.next() {
+            let cached_glyph = &self.gl_font.glyphs[&glyph.glyph_id];
+            Some((glyph, cached_glyph))
         } else {
             None
         }
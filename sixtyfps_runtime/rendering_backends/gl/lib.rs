@@ -21,6 +21,7 @@ use sixtyfps_corelib::{
         HighLevelRenderingPrimitive, Point, Rect, RenderingPrimitivesBuilder, RenderingVariable,
         Resource, Size,
     },
+    items::TextHorizontalAlignment,
     SharedArray,
 };
 use smallvec::{smallvec, SmallVec};
@@ -375,13 +376,90 @@ impl RenderingPrimitivesBuilder for GLRenderingPrimitivesBuilder {
                         Resource::None => SmallVec::new(),
                     }
                 }
-                HighLevelRenderingPrimitive::Text { text, font_family, font_size, color } => {
+                HighLevelRenderingPrimitive::Text {
+                    text,
+                    font_family,
+                    font_size,
+                    color,
+                    width,
+                    horizontal_alignment,
+                    wrap,
+                    overflow,
+                } => {
                     let pixel_size = if *font_size != 0. {
                         *font_size
                     } else {
                         16.0 * self.window_scale_factor()
                     };
-                    smallvec![self.create_glyph_runs(text, font_family, pixel_size, *color)]
+                    let font = sixtyfps_corelib::font::FONT_CACHE
+                        .with(|fc| fc.find_font(font_family, pixel_size));
+                    let line_height = font.font_height();
+                    font.text_lines(text, *width, *wrap, *overflow)
+                        .iter()
+                        .enumerate()
+                        .map(|(index, line)| {
+                            let x = match horizontal_alignment {
+                                TextHorizontalAlignment::align_left => 0.,
+                                TextHorizontalAlignment::align_center => (width - line.width) / 2.,
+                                TextHorizontalAlignment::align_right => width - line.width,
+                            };
+                            let y = index as f32 * line_height;
+                            self.create_glyph_runs(
+                                &line.text,
+                                font_family,
+                                pixel_size,
+                                *color,
+                                Point::new(x, y),
+                            )
+                        })
+                        .collect()
+                }
+                HighLevelRenderingPrimitive::TextInput {
+                    text,
+                    font_family,
+                    font_size,
+                    color,
+                    cursor_position,
+                    anchor_position,
+                    text_cursor_width,
+                } => {
+                    let pixel_size = if *font_size != 0. {
+                        *font_size
+                    } else {
+                        16.0 * self.window_scale_factor()
+                    };
+                    let font = sixtyfps_corelib::font::FONT_CACHE
+                        .with(|fc| fc.find_font(font_family, pixel_size));
+                    let clusters = font.visual_clusters(text);
+                    let x_for_position = |position: i32| {
+                        sixtyfps_corelib::font::x_position_for_offset(&clusters, position as usize)
+                    };
+                    let height = font.font_height();
+
+                    let selection_start = x_for_position((*cursor_position).min(*anchor_position));
+                    let selection_end = x_for_position((*cursor_position).max(*anchor_position));
+                    let selection_rect = Rect::new(
+                        Point::new(selection_start, 0.),
+                        Size::new(selection_end - selection_start, height),
+                    );
+                    let cursor_rect = Rect::new(
+                        Point::new(x_for_position(*cursor_position), 0.),
+                        Size::new(text_cursor_width.max(1.), height),
+                    );
+
+                    // The glyphs come last because on wasm they are rendered as a texture that
+                    // consumes the scaling rendering variables
+                    let mut primitives: SmallVec<_> =
+                        self.fill_rectangle(&selection_rect, 0.).into_iter().collect();
+                    primitives.extend(self.fill_rectangle(&cursor_rect, 0.));
+                    primitives.push(self.create_glyph_runs(
+                        text,
+                        font_family,
+                        pixel_size,
+                        *color,
+                        Point::new(0., 0.),
+                    ));
+                    primitives
                 }
                 HighLevelRenderingPrimitive::Path { width, height, elements, stroke_width } => {
                     let mut primitives = SmallVec::new();
@@ -574,11 +652,12 @@ impl GLRenderingPrimitivesBuilder {
         font_family: &str,
         pixel_size: f32,
         color: Color,
+        origin: Point,
     ) -> GLRenderingPrimitive {
         let cached_glyphs = self.platform_data.glyph_cache.find_font(font_family, pixel_size);
         let mut cached_glyphs = cached_glyphs.borrow_mut();
         let mut atlas = self.texture_atlas.borrow_mut();
-        let glyphs_runs = cached_glyphs.render_glyphs(&self.context, &mut atlas, text);
+        let glyphs_runs = cached_glyphs.render_glyphs(&self.context, &mut atlas, text, origin);
         GLRenderingPrimitive::GlyphRuns { glyph_runs: glyphs_runs, color }
     }
 
@@ -589,6 +668,7 @@ impl GLRenderingPrimitivesBuilder {
         font_family: &str,
         pixel_size: f32,
         color: Color,
+        origin: Point,
     ) -> GLRenderingPrimitive {
         let font =
             sixtyfps_corelib::font::FONT_CACHE.with(|fc| fc.find_font(font_family, pixel_size));
@@ -596,10 +676,8 @@ impl GLRenderingPrimitivesBuilder {
 
         let texture = Rc::new(GLTexture::new_from_canvas(&self.context, &text_canvas));
 
-        let rect = Rect::new(
-            Point::new(0.0, 0.0),
-            Size::new(text_canvas.width() as f32, text_canvas.height() as f32),
-        );
+        let rect =
+            Rect::new(origin, Size::new(text_canvas.width() as f32, text_canvas.height() as f32));
 
         let vertex1 = Vertex { _pos: [rect.min_x(), rect.min_y()] };
         let vertex2 = Vertex { _pos: [rect.max_x(), rect.min_y()] };
@@ -28,15 +28,34 @@ fn gen_corelib(include_dir: &Path) -> anyhow::Result<()> {
         "TouchArea",
         "Flickable",
         "Text",
+        "TextInput",
         "Path",
         "ComponentVTable",
         "Slice",
         "ComponentWindowOpaque",
         "PropertyAnimation",
+        "StateInfo",
         "EasingCurve",
         "TextHorizontalAlignment",
         "TextVerticalAlignment",
+        "TextWrap",
+        "TextOverflow",
+        "MouseCursor",
+        "PointerButton",
         "Window",
+        "FocusScope",
+        "KeyEvent",
+        "KeyEventResult",
+        "KeyEventType",
+        "KeyCode",
+        "KeyboardModifiers",
+        "FocusEvent",
+        "FocusEventResult",
+        "TimerItem",
+        "TimerMode",
+        "LayoutAlignment",
+        "CellAlignment",
+        "HeightForWidth",
     ]
     .iter()
     .map(|x| x.to_string())
@@ -58,6 +77,7 @@ fn gen_corelib(include_dir: &Path) -> anyhow::Result<()> {
         "sixtyfps_property_listener_scope_evaluate",
         "sixtyfps_property_listener_scope_is_dirty",
         "PropertyTrackerOpaque",
+        "ChangeTrackerOpaque",
         "SignalOpaque",
     ]
     .iter()
@@ -135,6 +155,14 @@ fn gen_corelib(include_dir: &Path) -> anyhow::Result<()> {
             "sixtyfps_component_window_get_scale_factor",
             "sixtyfps_component_window_set_scale_factor",
             "sixtyfps_component_window_free_graphics_resources",
+            "sixtyfps_component_window_dispatch_key_event",
+            "sixtyfps_component_window_set_focus_item",
+            "sixtyfps_component_window_set_focus_item_at",
+            "sixtyfps_timer_start",
+            "sixtyfps_timer_singleshot",
+            "sixtyfps_timer_destroy",
+            "sixtyfps_timer_stop",
+            "sixtyfps_timer_running",
             "sixtyfps_new_path_elements",
             "sixtyfps_new_path_events",
         ]
@@ -180,13 +208,24 @@ fn gen_corelib(include_dir: &Path) -> anyhow::Result<()> {
     config.export.body.insert(
         "EasingCurve".to_owned(),
         "    constexpr EasingCurve() : tag(Tag::Linear), cubic_bezier{{0,0,1,1}} {}
-    constexpr explicit EasingCurve(EasingCurve::Tag tag, float a, float b, float c, float d) : tag(tag), cubic_bezier{{a,b,c,d}} {}".into()
+    constexpr explicit EasingCurve(EasingCurve::Tag tag, float a, float b, float c, float d) : tag(tag), cubic_bezier{{a,b,c,d}} {}
+    constexpr explicit EasingCurve(EasingCurve::Tag tag) : tag(tag), cubic_bezier{{0,0,1,1}} {}
+    constexpr explicit EasingCurve(EasingCurve::Tag tag, uint32_t n) : tag(tag), steps{n} {}".into()
     );
     config
         .export
         .body
         .insert("Flickable".to_owned(), "    inline Flickable(); inline ~Flickable();".into());
     config.export.pre_body.insert("FlickableDataBox".to_owned(), "struct FlickableData;".into());
+    config.export.body.insert(
+        "TimerItem".to_owned(),
+        "    inline TimerItem(); inline ~TimerItem(); inline void init();".into(),
+    );
+    config.export.pre_body.insert("TimerItemDataBox".to_owned(), "struct TimerItemData;".into());
+    config.export.body.insert("TextInput".to_owned(), "    inline ~TextInput();".into());
+    // cbindgen drops the `()` from `Signal<()>`, and the C++ Signal is a template whose default
+    // argument is `void()`
+    config.export.rename.insert("Signal".into(), "Signal<>".into());
     cbindgen::Builder::new()
         .with_config(config)
         .with_src(crate_dir.join("lib.rs"))